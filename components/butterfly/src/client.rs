@@ -2,44 +2,63 @@
 //!
 //! This will connect to a given butterfly members `Pull` thread, and inject a rumor.
 
+use std::{io,
+          net::ToSocketAddrs};
+
 use habitat_core::{crypto::SymKey,
                    service::ServiceGroup};
-use zmq;
 
 use crate::{error::{Error,
                     Result},
             message,
+            ring_key::RingKeys,
             rumor::{departure::Departure,
+                    ring_key::RingKey,
                     service_config::ServiceConfig,
                     service_file::ServiceFile,
+                    Author,
                     Rumor},
-            ZMQ_CONTEXT};
+            transport::{GossipSender,
+                        GossipTransport,
+                        ZmqTransport},
+            trust::MemberKey};
 
-/// Holds a ZMQ Push socket, and an optional ring encryption key.
+/// Holds a connection to the server, an optional ring encryption key, and an optional member key
+/// to sign with.
 pub struct Client {
-    socket:   zmq::Socket,
-    ring_key: Option<SymKey>,
+    sender:     Box<dyn GossipSender>,
+    ring_keys:  RingKeys,
+    member_key: Option<MemberKey>,
 }
 
 impl Client {
-    /// Connect this client to the address, and optionally encrypt the traffic.
-    pub fn new(addr: &str, ring_key: Option<SymKey>) -> Result<Client> {
-        let socket = (**ZMQ_CONTEXT).as_mut()
-                                    .socket(zmq::PUSH)
-                                    .expect("Failure to create the ZMQ push socket");
-        socket.set_linger(-1)
-              .expect("Failure to set the ZMQ push socket to not linger");
-        socket.set_tcp_keepalive(0)
-              .expect("Failure to set the ZMQ push socket to not use keepalive");
-        socket.set_immediate(true)
-              .expect("Failure to set the ZMQ push socket to immediate");
-        socket.set_sndhwm(1000)
-              .expect("Failure to set the ZMQ push socket hwm");
-        socket.set_sndtimeo(500)
-              .expect("Failure to set the ZMQ send timeout");
-        let to_addr = format!("tcp://{}", addr);
-        socket.connect(&to_addr).map_err(Error::ZmqConnectError)?;
-        Ok(Client { socket, ring_key })
+    /// Connect this client to the address over ZeroMQ, and optionally encrypt and sign the
+    /// traffic.
+    pub fn new(addr: &str,
+               ring_key: Option<SymKey>,
+               member_key: Option<MemberKey>)
+               -> Result<Client> {
+        Self::with_transport(addr, &ZmqTransport, ring_key, member_key)
+    }
+
+    /// Connect this client to the address over `transport`, and optionally encrypt and sign the
+    /// traffic.
+    pub fn with_transport(addr: &str,
+                          transport: &dyn GossipTransport,
+                          ring_key: Option<SymKey>,
+                          member_key: Option<MemberKey>)
+                          -> Result<Client> {
+        let to_addr = match addr.to_socket_addrs().map(|mut iter| iter.next()) {
+            Ok(Some(to_addr)) => to_addr,
+            Ok(None) => {
+                let err = io::Error::new(io::ErrorKind::AddrNotAvailable, "No address discovered.");
+                return Err(Error::GossipConnect(addr.to_string(), err));
+            }
+            Err(e) => return Err(Error::GossipConnect(addr.to_string(), e)),
+        };
+        Ok(Client { sender: transport.connect(to_addr)?,
+                    ring_keys: RingKeys::new(ring_key),
+                    member_key })
     }
 
     /// Create a departure notification and send it to the server.
@@ -48,32 +67,42 @@ impl Client {
         self.send(&departure)
     }
 
-    /// Create a service configuration and send it to the server.
+    /// Send a new revision of the ring key to the server, for it to distribute to the ring.
+    pub fn send_ring_key(&mut self, ring_key: &SymKey) -> Result<()> {
+        let ring_key = RingKey::new("butterflyclient", ring_key)?;
+        self.send(&ring_key)
+    }
+
+    /// Create a service configuration, signed by `author` if given, and send it to the server.
     pub fn send_service_config(&mut self,
                                service_group: ServiceGroup,
                                incarnation: u64,
                                config: &[u8],
-                               encrypted: bool)
+                               encrypted: bool,
+                               author: Option<Author>)
                                -> Result<()> {
         let mut sc = ServiceConfig::new("butterflyclient", service_group, config.to_vec());
         sc.incarnation = incarnation;
         sc.encrypted = encrypted;
+        sc.author = author;
         self.send(&sc)
     }
 
-    /// Create a service file and send it to the server.
+    /// Create a service file, signed by `author` if given, and send it to the server.
     pub fn send_service_file<S>(&mut self,
                                 service_group: ServiceGroup,
                                 filename: S,
                                 incarnation: u64,
                                 body: &[u8],
-                                encrypted: bool)
+                                encrypted: bool,
+                                author: Option<Author>)
                                 -> Result<()>
         where S: Into<String>
     {
         let mut sf = ServiceFile::new("butterflyclient", service_group, filename, body.to_vec());
         sf.incarnation = incarnation;
         sf.encrypted = encrypted;
+        sf.author = author;
         self.send(&sf)
     }
 
@@ -82,7 +111,7 @@ impl Client {
         where T: Rumor
     {
         let bytes = rumor.write_to_bytes()?;
-        let wire_msg = message::generate_wire(bytes, self.ring_key.as_ref())?;
-        self.socket.send(&wire_msg, 0).map_err(Error::ZmqSendError)
+        let wire_msg = message::generate_wire(bytes, &self.ring_keys, self.member_key.as_ref())?;
+        self.sender.send(&wire_msg)
     }
 }
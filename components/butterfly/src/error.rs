@@ -21,6 +21,11 @@ pub enum Error {
     DatFileIO(PathBuf, io::Error),
     DecodeError(prost::DecodeError),
     EncodeError(prost::EncodeError),
+    GossipConnect(String, io::Error),
+    GossipFrameTooLarge(usize),
+    GossipHandshake(String, String),
+    GossipListenerClosed,
+    GossipSend(String, io::Error),
     HabitatCore(habitat_core::error::Error),
     IncarnationIO(PathBuf, io::Error),
     IncarnationParse(PathBuf, num::ParseIntError),
@@ -28,6 +33,7 @@ pub enum Error {
     InvalidRumorShareLimit,
     NonExistentRumor(String, String),
     ProtocolMismatch(&'static str),
+    RetiredRingKeysIO(PathBuf, io::Error),
     ServiceConfigDecode(String, toml::de::Error),
     ServiceConfigNotUtf8(String, str::Utf8Error),
     SocketCloneError,
@@ -35,7 +41,12 @@ pub enum Error {
     SocketSetWriteTimeout(io::Error),
     Timeout(String),
     UnknownMember(String),
+    UnknownRingKey(String),
+    UntrustedAuthor(String),
+    UntrustedSender(String, String),
+    ZmqBindError(zmq::Error),
     ZmqConnectError(zmq::Error),
+    ZmqRecvError(zmq::Error),
     ZmqSendError(zmq::Error),
     UnknownIOError(io::Error),
 }
@@ -62,6 +73,21 @@ impl fmt::Display for Error {
             Error::UnknownIOError(ref err) => format!("Error reading or writing: {}", err),
             Error::DecodeError(ref err) => format!("Failed to decode protocol message: {}", err),
             Error::EncodeError(ref err) => format!("Failed to encode protocol message: {}", err),
+            Error::GossipConnect(ref addr, ref err) => {
+                format!("Cannot connect to gossip at {}: {}", addr, err)
+            }
+            Error::GossipFrameTooLarge(len) => {
+                format!("Gossip message of {} bytes is larger than the transport allows",
+                        len)
+            }
+            Error::GossipHandshake(ref addr, ref reason) => {
+                format!("Cannot agree on a gossip protocol with {}: {}",
+                        addr, reason)
+            }
+            Error::GossipListenerClosed => "Gossip listener has stopped".to_string(),
+            Error::GossipSend(ref addr, ref err) => {
+                format!("Cannot send gossip to {}: {}", addr, err)
+            }
             Error::HabitatCore(ref err) => format!("{}", err),
             Error::IncarnationIO(ref path, ref err) => {
                 format!("Error reading or writing incarnation store file {}: {}",
@@ -88,6 +114,11 @@ impl fmt::Display for Error {
                 format!("Received an unsupported or bad protocol message. Missing field: {}",
                         field)
             }
+            Error::RetiredRingKeysIO(ref path, ref err) => {
+                format!("Error reading or writing retired ring keys file {}: {}",
+                        path.display(),
+                        err)
+            }
             Error::ServiceConfigDecode(ref sg, ref err) => {
                 format!("Cannot decode service config: group={}, {:?}", sg, err)
             }
@@ -103,7 +134,23 @@ impl fmt::Display for Error {
             }
             Error::Timeout(ref msg) => format!("Timed out {}", msg),
             Error::UnknownMember(ref member_id) => format!("Unknown member ID: {}", member_id),
+            Error::UnknownRingKey(ref name_with_rev) => {
+                format!("Message is encrypted with ring key {}, which is not held or was retired",
+                        name_with_rev)
+            }
+            Error::UntrustedAuthor(ref reason) => {
+                format!("Rejected rumor not signed by a trusted user key: {}",
+                        reason)
+            }
+            Error::UntrustedSender(ref signer, ref reason) => {
+                format!("Rejected message from untrusted member key {}: {}",
+                        signer, reason)
+            }
+            Error::ZmqBindError(ref err) => format!("Cannot bind ZMQ socket: {}", err),
             Error::ZmqConnectError(ref err) => format!("Cannot connect ZMQ socket: {}", err),
+            Error::ZmqRecvError(ref err) => {
+                format!("Cannot receive message from ZMQ socket: {}", err)
+            }
             Error::ZmqSendError(ref err) => {
                 format!("Cannot send message through ZMQ socket: {}", err)
             }
@@ -122,6 +169,11 @@ impl error::Error for Error {
             Error::UnknownIOError(_) => "Unknown I/O error",
             Error::DecodeError(ref err) => err.description(),
             Error::EncodeError(ref err) => err.description(),
+            Error::GossipConnect(..) => "Cannot connect to gossip",
+            Error::GossipFrameTooLarge(_) => "Gossip message is too large",
+            Error::GossipHandshake(..) => "Cannot agree on a gossip protocol",
+            Error::GossipListenerClosed => "Gossip listener has stopped",
+            Error::GossipSend(..) => "Cannot send gossip",
             Error::HabitatCore(_) => "Habitat core error",
             Error::IncarnationIO(..) => "Error reading or writing incarnation store file",
             Error::IncarnationParse(..) => "Error parsing value from incarnation store file",
@@ -133,6 +185,7 @@ impl error::Error for Error {
             Error::ProtocolMismatch(_) => {
                 "Received an unprocessable wire message from another Supervisor"
             }
+            Error::RetiredRingKeysIO(..) => "Error reading or writing retired ring keys file",
             Error::ServiceConfigDecode(..) => "Cannot decode service config into TOML",
             Error::ServiceConfigNotUtf8(..) => "Cannot read service config bytes to UTF-8",
             Error::SocketCloneError => "Cannot clone the underlying UDP socket",
@@ -140,7 +193,12 @@ impl error::Error for Error {
             Error::SocketSetWriteTimeout(_) => "Cannot set UDP socket write timeout",
             Error::Timeout(_) => "Timed out waiting",
             Error::UnknownMember(_) => "Unknown member",
+            Error::UnknownRingKey(_) => "Message is encrypted with a ring key that is not held",
+            Error::UntrustedAuthor(_) => "Rejected rumor not signed by a trusted user key",
+            Error::UntrustedSender(..) => "Rejected message not signed by a trusted member",
+            Error::ZmqBindError(_) => "Cannot bind ZMQ socket",
             Error::ZmqConnectError(_) => "Cannot connect ZMQ socket",
+            Error::ZmqRecvError(_) => "Cannot receive message from ZMQ socket",
             Error::ZmqSendError(_) => "Cannot send message through ZMQ socket",
         }
     }
@@ -7,4 +7,21 @@ pub struct Wire {
     pub nonce: ::std::option::Option<std::vec::Vec<u8>>,
     #[prost(bytes, optional, tag="3")]
     pub payload: ::std::option::Option<std::vec::Vec<u8>>,
+    /// The member key that signed the unencrypted payload, with its signature.
+    #[prost(string, optional, tag="4")]
+    pub signer: ::std::option::Option<std::string::String>,
+    #[prost(bytes, optional, tag="5")]
+    pub signature: ::std::option::Option<std::vec::Vec<u8>>,
+    /// Sent when the member key is endorsed by a certificate authority: the
+    /// member's public key, and the endorsement of it.
+    #[prost(bytes, optional, tag="6")]
+    pub signer_key: ::std::option::Option<std::vec::Vec<u8>>,
+    #[prost(bytes, optional, tag="7")]
+    pub endorsement: ::std::option::Option<std::vec::Vec<u8>>,
+    /// The revision of the ring key the payload is encrypted with, and every
+    /// revision the sender can decrypt.
+    #[prost(string, optional, tag="8")]
+    pub ring_key: ::std::option::Option<std::string::String>,
+    #[prost(string, repeated, tag="9")]
+    pub ring_keys: ::std::vec::Vec<std::string::String>,
 }
@@ -41,6 +41,32 @@ pub struct Service {
     pub cfg: ::std::option::Option<std::vec::Vec<u8>>,
     #[prost(message, optional, tag="12")]
     pub sys: ::std::option::Option<SysInfo>,
+    #[prost(message, optional, tag="13")]
+    pub canary: ::std::option::Option<CanaryRelease>,
+    /// Whether the member's ready hook (or, lacking one, its health
+    /// check) last passed. Unset by Supervisors that predate it.
+    #[prost(bool, optional, tag="14")]
+    pub ready: ::std::option::Option<bool>,
+}
+/// The verdict of a canary update performed by a member on behalf of
+/// its service group.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+pub struct CanaryRelease {
+    #[prost(string, optional, tag="1")]
+    pub pkg: ::std::option::Option<std::string::String>,
+    #[prost(enumeration="canary_release::Status", optional, tag="2")]
+    pub status: ::std::option::Option<i32>,
+}
+pub mod canary_release {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    #[derive(Serialize, Deserialize)]
+    pub enum Status {
+        Soaking = 1,
+        Passed = 2,
+        Failed = 3,
+    }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
@@ -53,6 +79,11 @@ pub struct ServiceConfig {
     pub encrypted: ::std::option::Option<bool>,
     #[prost(bytes, optional, tag="4")]
     pub config: ::std::option::Option<std::vec::Vec<u8>>,
+    /// The name of the user key the configuration was signed with.
+    #[prost(string, optional, tag="5")]
+    pub signer: ::std::option::Option<std::string::String>,
+    #[prost(bytes, optional, tag="6")]
+    pub signature: ::std::option::Option<std::vec::Vec<u8>>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
@@ -67,6 +98,11 @@ pub struct ServiceFile {
     pub filename: ::std::option::Option<std::string::String>,
     #[prost(bytes, optional, tag="5")]
     pub body: ::std::option::Option<std::vec::Vec<u8>>,
+    /// The name of the user key the file was signed with.
+    #[prost(string, optional, tag="6")]
+    pub signer: ::std::option::Option<std::string::String>,
+    #[prost(bytes, optional, tag="7")]
+    pub signature: ::std::option::Option<std::vec::Vec<u8>>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
@@ -94,6 +130,34 @@ pub struct Departure {
     #[prost(string, optional, tag="1")]
     pub member_id: ::std::option::Option<std::string::String>,
 }
+/// A new revision of the ring key, distributed to every member so the
+/// ring can move to it. Only ever sent encrypted with the current one.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+pub struct RingKey {
+    #[prost(string, optional, tag="1")]
+    pub name_with_rev: ::std::option::Option<std::string::String>,
+    #[prost(string, optional, tag="2")]
+    pub secret: ::std::option::Option<std::string::String>,
+}
+/// The member a rumor originated from, and the signature its member key
+/// made over the rumor as that member created it. Travels with the rumor
+/// however it is relayed.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+pub struct Origin {
+    #[prost(string, optional, tag="1")]
+    pub member_id: ::std::option::Option<std::string::String>,
+    #[prost(string, optional, tag="2")]
+    pub signer: ::std::option::Option<std::string::String>,
+    #[prost(bytes, optional, tag="3")]
+    pub signature: ::std::option::Option<std::vec::Vec<u8>>,
+    /// Sent when the member key is endorsed by a certificate authority.
+    #[prost(bytes, optional, tag="4")]
+    pub signer_key: ::std::option::Option<std::vec::Vec<u8>>,
+    #[prost(bytes, optional, tag="5")]
+    pub endorsement: ::std::option::Option<std::vec::Vec<u8>>,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
 pub struct Rumor {
@@ -103,7 +167,9 @@ pub struct Rumor {
     pub tag: ::std::vec::Vec<std::string::String>,
     #[prost(string, optional, tag="3")]
     pub from_id: ::std::option::Option<std::string::String>,
-    #[prost(oneof="rumor::Payload", tags="4, 5, 6, 7, 8, 9")]
+    #[prost(message, optional, tag="11")]
+    pub origin: ::std::option::Option<Origin>,
+    #[prost(oneof="rumor::Payload", tags="4, 5, 6, 7, 8, 9, 10")]
     pub payload: ::std::option::Option<rumor::Payload>,
 }
 pub mod rumor {
@@ -120,6 +186,7 @@ pub mod rumor {
         Fake2 = 7,
         ElectionUpdate = 8,
         Departure = 9,
+        RingKey = 10,
     }
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     #[allow(clippy::large_enum_variant)]
@@ -137,5 +204,7 @@ pub mod rumor {
         Election(super::Election),
         #[prost(message, tag="9")]
         Departure(super::Departure),
+        #[prost(message, tag="10")]
+        RingKey(super::RingKey),
     }
 }
@@ -40,6 +40,19 @@ pub struct PingReq {
     pub from: ::std::option::Option<Member>,
     #[prost(message, optional, tag="2")]
     pub target: ::std::option::Option<Member>,
+    /// Reply with a Nack if the target doesn't ack in time
+    #[prost(bool, optional, tag="3", default="false")]
+    pub nack: ::std::option::Option<bool>,
+}
+/// Sent back to the sender of a PingReq when the target didn't ack in time, to show that the
+/// member relaying the ping is still reachable.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+pub struct Nack {
+    #[prost(message, optional, tag="1")]
+    pub from: ::std::option::Option<Member>,
+    #[prost(message, optional, tag="2")]
+    pub target: ::std::option::Option<Member>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
@@ -48,6 +61,9 @@ pub struct Membership {
     pub member: ::std::option::Option<Member>,
     #[prost(enumeration="membership::Health", optional, tag="2")]
     pub health: ::std::option::Option<i32>,
+    /// The id of the member that most recently found this one suspect
+    #[prost(string, optional, tag="3")]
+    pub suspected_by: ::std::option::Option<std::string::String>,
 }
 pub mod membership {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
@@ -68,7 +84,7 @@ pub struct Swim {
     pub r#type: i32,
     #[prost(message, repeated, tag="5")]
     pub membership: ::std::vec::Vec<Membership>,
-    #[prost(oneof="swim::Payload", tags="2, 3, 4")]
+    #[prost(oneof="swim::Payload", tags="2, 3, 4, 6")]
     pub payload: ::std::option::Option<swim::Payload>,
 }
 pub mod swim {
@@ -79,6 +95,7 @@ pub mod swim {
         Ping = 1,
         Ack = 2,
         Pingreq = 3,
+        Nack = 4,
     }
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     #[derive(Serialize, Deserialize)]
@@ -89,5 +106,7 @@ pub mod swim {
         Ack(super::Ack),
         #[prost(message, tag="4")]
         Pingreq(super::PingReq),
+        #[prost(message, tag="6")]
+        Nack(super::Nack),
     }
 }
@@ -1,5 +1,5 @@
 //! Butterfly is the [SWIM](http://www.cs.cornell.edu/projects/Quicksilver/public_pdfs/SWIM.pdf)
-//! implementation for Habitat, along with a ZeroMQ based gossip protocol.
+//! implementation for Habitat, along with a gossip protocol carried over ZeroMQ or plain TCP.
 //!
 //! It implements SWIM+Susp+Inf. It uses Newscast-style "heat" tracking to share membership rumors,
 //! while trying to keep UDP packet sizes below 512 bytes. It has the following changes:
@@ -45,9 +45,12 @@ pub mod error;
 pub mod member;
 pub mod message;
 pub mod protocol;
+pub mod ring_key;
 pub mod rumor;
 pub mod server;
 pub mod swim;
+pub mod transport;
+pub mod trust;
 
 pub use crate::server::Server;
 use std::cell::UnsafeCell;
@@ -154,6 +154,21 @@ impl Member {
             }
         }
     }
+
+    /// Returns the socket address this member receives gossip on.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the address is un-parseable, like `swim_socket_address`.
+    pub fn gossip_socket_address(&self) -> SocketAddr {
+        let address_str = format!("{}:{}", self.address, self.gossip_port);
+        match address_str.parse() {
+            Ok(addr) => addr,
+            Err(e) => {
+                panic!("Cannot parse member {:?} address: {}", self, e);
+            }
+        }
+    }
 }
 
 impl Default for Member {
@@ -199,8 +214,10 @@ impl From<Member> for proto::Member {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Membership {
-    pub member: Member,
-    pub health: Health,
+    pub member:       Member,
+    pub health:       Health,
+    /// The id of the member that most recently found this one suspect, if it is and we know.
+    pub suspected_by: Option<String>,
 }
 
 impl Membership {
@@ -218,8 +235,9 @@ impl protocol::Message<proto::Membership> for Membership {}
 
 impl From<Membership> for proto::Membership {
     fn from(value: Membership) -> Self {
-        proto::Membership { member: Some(value.member.into()),
-                            health: Some(value.health as i32), }
+        proto::Membership { member:       Some(value.member.into()),
+                            health:       Some(value.health as i32),
+                            suspected_by: value.suspected_by, }
     }
 }
 
@@ -293,12 +311,13 @@ impl FromProto<proto::Member> for Member {
 
 impl FromProto<proto::Membership> for Membership {
     fn from_proto(proto: proto::Membership) -> Result<Self> {
-        Ok(Membership { member: proto.member
-                                     .ok_or(Error::ProtocolMismatch("member"))
-                                     .and_then(Member::from_proto)?,
-                        health: proto.health
-                                     .and_then(Health::from_i32)
-                                     .unwrap_or(Health::Alive), })
+        Ok(Membership { member:       proto.member
+                                           .ok_or(Error::ProtocolMismatch("member"))
+                                           .and_then(Member::from_proto)?,
+                        health:       proto.health
+                                           .and_then(Health::from_i32)
+                                           .unwrap_or(Health::Alive),
+                        suspected_by: proto.suspected_by, })
     }
 }
 
@@ -317,6 +336,14 @@ mod member_list {
         pub member:            super::Member,
         pub health:            super::Health,
         pub health_updated_at: super::SteadyTime,
+        /// The members that found this one suspect, in the order we heard from them. Empty
+        /// unless it is suspect.
+        pub suspected_by:      Vec<String>,
+    }
+
+    impl Entry {
+        /// How many members found this one suspect after the first did.
+        pub fn confirmations(&self) -> usize { self.suspected_by.len().saturating_sub(1) }
     }
 }
 
@@ -461,29 +488,53 @@ impl MemberList {
     /// | Departed  |       |           |           |           |
     // TODO (CM): why don't we just insert a membership record here?
     pub fn insert(&self, incoming_member: Member, incoming_health: Health) -> bool {
-        self.insert_membership(Membership { member: incoming_member,
-                                            health: incoming_health, })
+        self.insert_membership(Membership { member:       incoming_member,
+                                            health:       incoming_health,
+                                            suspected_by: None, })
     }
 
-    fn insert_membership(&self, incoming: Membership) -> bool {
+    /// Like `insert`, but also notes who found the member suspect, if it is. A suspicion that
+    /// matches the one we hold, but comes from a member we haven't heard it from, is accepted as
+    /// a confirmation of it, which doesn't restart the suspicion's timeout.
+    pub fn insert_membership(&self, incoming: Membership) -> bool {
+        let suspected_by = match incoming.health {
+            Health::Suspect => incoming.suspected_by.into_iter().collect(),
+            _ => Vec::new(),
+        };
         // Is this clone necessary, or can a key be a reference to a field contained in the value?
         // Maybe the members we store should not contain the ID to reduce the duplication?
         let modified = match self.write_entries().entry(incoming.member.id.clone()) {
             hash_map::Entry::Occupied(mut entry) => {
                 let val = entry.get_mut();
                 if incoming.newer_or_less_healthy_than(val.member.incarnation, val.health) {
-                    *val = member_list::Entry { member:            incoming.member,
-                                                health:            incoming.health,
-                                                health_updated_at: SteadyTime::now(), };
+                    *val = member_list::Entry { member: incoming.member,
+                                                health: incoming.health,
+                                                health_updated_at: SteadyTime::now(),
+                                                suspected_by };
                     true
                 } else {
-                    false
+                    if incoming.health == Health::Suspect
+                       && val.health == Health::Suspect
+                       && incoming.member.incarnation == val.member.incarnation
+                    {
+                        let mut confirmed = false;
+                        for id in suspected_by {
+                            if !val.suspected_by.contains(&id) {
+                                val.suspected_by.push(id);
+                                confirmed = true;
+                            }
+                        }
+                        confirmed
+                    } else {
+                        false
+                    }
                 }
             }
             hash_map::Entry::Vacant(entry) => {
-                entry.insert(member_list::Entry { member:            incoming.member,
-                                                  health:            incoming.health,
-                                                  health_updated_at: SteadyTime::now(), });
+                entry.insert(member_list::Entry { member: incoming.member,
+                                                  health: incoming.health,
+                                                  health_updated_at: SteadyTime::now(),
+                                                  suspected_by });
                 true
             }
         };
@@ -581,10 +632,14 @@ impl MemberList {
     pub fn membership_for(&self, member_id: &str) -> Option<Membership> {
         self.read_entries()
             .get(member_id)
-            .map(|member_list::Entry { member, health, .. }| {
-                Membership { member: member.clone(),
-                             health: *health, }
-            })
+            .map(|member_list::Entry { member,
+                                       health,
+                                       suspected_by,
+                                       .. }| {
+                     Membership { member:       member.clone(),
+                                  health:       *health,
+                                  suspected_by: suspected_by.last().cloned(), }
+                 })
     }
 
     /// Returns the number of entries.
@@ -604,6 +659,29 @@ impl MemberList {
         members
     }
 
+    /// The IDs of every member that is alive or suspected, other than `exclude_id`.
+    pub fn live_ids(&self, exclude_id: &str) -> Vec<String> {
+        self.read_entries()
+            .values()
+            .filter(|member_list::Entry { member, health, .. }| {
+                member.id != exclude_id && (*health == Health::Alive || *health == Health::Suspect)
+            })
+            .map(|member_list::Entry { member, .. }| member.id.clone())
+            .collect()
+    }
+
+    /// The IDs of every member that has not departed, other than `exclude_id`. Suspect and
+    /// confirmed members may still come back.
+    pub fn undeparted_ids(&self, exclude_id: &str) -> Vec<String> {
+        self.read_entries()
+            .values()
+            .filter(|member_list::Entry { member, health, .. }| {
+                member.id != exclude_id && *health != Health::Departed
+            })
+            .map(|member_list::Entry { member, .. }| member.id.clone())
+            .collect()
+    }
+
     /// Takes a function whose first argument is a member, and calls it for every pingreq target.
     pub fn with_pingreq_targets(&self,
                                 sending_member_id: &str,
@@ -651,10 +729,14 @@ impl MemberList {
         let mut ok = Ok(T::default());
         for membership in self.read_entries()
                               .values()
-                              .map(|member_list::Entry { member, health, .. }| {
-                                  Membership { member: member.clone(),
-                                               health: *health, }
-                              })
+                              .map(|member_list::Entry { member,
+                                                         health,
+                                                         suspected_by,
+                                                         .. }| {
+                                       Membership { member:       member.clone(),
+                                                    health:       *health,
+                                                    suspected_by: suspected_by.last().cloned(), }
+                                   })
         {
             ok = Ok(with_closure(membership)?);
         }
@@ -665,15 +747,19 @@ impl MemberList {
     /// have now expired to Confirmed. Health is updated
     /// appropriately, and a list of newly-Confirmed Member IDs is
     /// returned.
-    pub fn members_expired_to_confirmed(&self, timeout: Duration) -> Vec<String> {
-        self.members_expired_to(Health::Confirmed, timeout)
+    ///
+    /// How long a member may stay Suspect is given by `timeout`,
+    /// from the number of other members that have confirmed the
+    /// suspicion.
+    pub fn members_expired_to_confirmed(&self, timeout: impl Fn(usize) -> Duration) -> Vec<String> {
+        self.members_expired_to(Health::Confirmed, |entry| timeout(entry.confirmations()))
     }
 
     /// Query the list of aging Confirmed members to find those which
     /// have now expired to Departed. Health is updated appropriately,
     /// and a list of newly-Departed Member IDs is returned.
     pub fn members_expired_to_departed(&self, timeout: Duration) -> Vec<String> {
-        self.members_expired_to(Health::Departed, timeout)
+        self.members_expired_to(Health::Departed, |_| timeout)
     }
 
     /// Return the member IDs of all members that have "timed out" to
@@ -684,11 +770,14 @@ impl MemberList {
     ///   members_expired_to(Health::Departed, timeout)
     ///
     /// will return the IDs of those members that have been
-    /// `Confirmed` for longer than the given `timeout`.
+    /// `Confirmed` for longer than the `timeout` given for them.
     ///
     /// The newly-updated health status is recorded properly.
     // TODO (CM): Better return type than Vec<String>
-    fn members_expired_to(&self, expiring_to: Health, timeout: Duration) -> Vec<String> {
+    fn members_expired_to(&self,
+                          expiring_to: Health,
+                          timeout: impl Fn(&member_list::Entry) -> Duration)
+                          -> Vec<String> {
         let now = SteadyTime::now();
         let precursor_health = match expiring_to {
             Health::Confirmed => Health::Suspect,
@@ -700,12 +789,10 @@ impl MemberList {
             self.write_entries()
                 .iter_mut()
                 .filter_map(|(id, v)| {
-                    let member_list::Entry { health,
-                                             health_updated_at,
-                                             .. } = v;
-                    if *health == precursor_health && now >= *health_updated_at + timeout {
-                        *health = expiring_to;
-                        *health_updated_at = now;
+                    if v.health == precursor_health && now >= v.health_updated_at + timeout(v) {
+                        v.health = expiring_to;
+                        v.health_updated_at = now;
+                        v.suspected_by.clear();
                         Some(id.clone())
                     } else {
                         None
@@ -813,7 +900,8 @@ mod tests {
         fn encode_decode_roundtrip() {
             let member = Member::default();
             let membership = Membership { member,
-                                          health: Health::Suspect };
+                                          health: Health::Suspect,
+                                          suspected_by: Some(String::from("member_1")) };
 
             let bytes = membership.clone()
                                   .write_to_bytes()
@@ -823,6 +911,7 @@ mod tests {
 
             assert_eq!(&membership.member, &from_bytes.member);
             assert_eq!(&membership.health, &from_bytes.health);
+            assert_eq!(&membership.suspected_by, &from_bytes.suspected_by);
         }
     }
 
@@ -1308,7 +1397,8 @@ mod tests {
         mod timed_expiration {
             use crate::member::{Health,
                                 Member,
-                                MemberList};
+                                MemberList,
+                                Membership};
             use std::{thread,
                       time::Duration as StdDuration};
             use time::Duration;
@@ -1326,24 +1416,27 @@ mod tests {
                 let large_timeout =
                     Duration::from_std(StdDuration::from_secs(large_seconds)).unwrap();
 
-                assert!(ml.members_expired_to_confirmed(small_timeout).is_empty(),
+                assert!(ml.members_expired_to_confirmed(|_| small_timeout)
+                          .is_empty(),
                         "An empty MemberList shouldn't have anything that's timing out to being \
                          Confirmed");
 
                 assert!(ml.insert(member_one.clone(), Health::Alive));
 
-                assert!(ml.members_expired_to_confirmed(small_timeout).is_empty(),
+                assert!(ml.members_expired_to_confirmed(|_| small_timeout)
+                          .is_empty(),
                         "Should be no newly Confirmed members when they're all Alive");
 
                 assert!(ml.insert(member_one.clone(), Health::Suspect));
 
-                assert!(ml.members_expired_to_confirmed(large_timeout).is_empty(),
+                assert!(ml.members_expired_to_confirmed(|_| large_timeout)
+                          .is_empty(),
                         "Nothing should have timed out to Confirmed with a large timeout");
 
                 // Allow the Suspect to age
                 thread::sleep(StdDuration::from_secs(small_seconds));
 
-                let newly_confirmed = ml.members_expired_to_confirmed(small_timeout);
+                let newly_confirmed = ml.members_expired_to_confirmed(|_| small_timeout);
                 assert!(newly_confirmed.contains(&member_one.id),
                         "Member should be newly Confirmed after timing out");
 
@@ -1412,7 +1505,7 @@ mod tests {
 
                 let timeout = Duration::from_std(StdDuration::from_secs(2)).unwrap();
 
-                let newly_confirmed = ml.members_expired_to_confirmed(timeout);
+                let newly_confirmed = ml.members_expired_to_confirmed(|_| timeout);
                 assert!(newly_confirmed.contains(&member_1.id),
                         "Member 1 should be newly Confirmed after timing out");
                 assert!(newly_confirmed.contains(&member_2.id),
@@ -1432,6 +1525,47 @@ mod tests {
                             timed out yet");
             }
 
+            #[test]
+            fn confirmations_from_other_members_shorten_the_suspect_timeout() {
+                let ml = MemberList::new();
+                let member_1 = Member::default();
+                let member_2 = Member::default();
+                let suspect = |member: &Member, suspected_by: &str| {
+                    Membership { member:       member.clone(),
+                                 health:       Health::Suspect,
+                                 suspected_by: Some(suspected_by.to_string()), }
+                };
+
+                assert!(ml.insert_membership(suspect(&member_1, "a")));
+                assert!(ml.insert_membership(suspect(&member_2, "a")));
+                // Confirmations only count once per member
+                assert!(ml.insert_membership(suspect(&member_1, "b")));
+                assert!(!ml.insert_membership(suspect(&member_1, "b")));
+                assert!(!ml.insert_membership(suspect(&member_1, "a")));
+                assert!(ml.insert_membership(suspect(&member_1, "c")));
+                assert_eq!(ml.membership_for(&member_1.id).unwrap().suspected_by,
+                           Some(String::from("c")));
+
+                let timeout = |confirmations: usize| {
+                    if confirmations >= 2 {
+                        Duration::zero()
+                    } else {
+                        Duration::seconds(100_000)
+                    }
+                };
+                let newly_confirmed = ml.members_expired_to_confirmed(timeout);
+                assert_eq!(newly_confirmed, vec![member_1.id.clone()]);
+                assert_eq!(ml.health_of(&member_2), Some(Health::Suspect));
+
+                // A suspicion of a member that has since refuted it isn't a confirmation
+                let mut refuted = member_2.clone();
+                refuted.incarnation = refuted.incarnation + 1;
+                assert!(ml.insert(refuted.clone(), Health::Alive));
+                assert!(!ml.insert_membership(suspect(&member_2, "b")));
+                assert!(ml.insert_membership(suspect(&refuted, "c")));
+                assert!(ml.members_expired_to_confirmed(timeout).is_empty());
+            }
+
             #[test]
             fn confirmed_timeout_is_appropriately_selective() {
                 let ml = MemberList::new();
@@ -1,34 +1,38 @@
 use bytes::BytesMut;
-use habitat_core::crypto::SymKey;
 use prost::Message;
 
-use crate::{error::{Error,
-                    Result},
-            protocol::Wire};
+use crate::{error::Result,
+            protocol::Wire,
+            ring_key::{RingKeyUsage,
+                       RingKeys},
+            trust::{MemberKey,
+                    MemberTrust}};
 
-pub fn generate_wire(payload: Vec<u8>, ring_key: Option<&SymKey>) -> Result<Vec<u8>> {
+pub fn generate_wire(payload: Vec<u8>,
+                     ring_keys: &RingKeys,
+                     member_key: Option<&MemberKey>)
+                     -> Result<Vec<u8>> {
     let mut wire = Wire::default();
-    if let Some(ring_key) = ring_key {
-        wire.encrypted = Some(true);
-        let (nonce, encrypted_payload) = ring_key.encrypt(&payload)?;
-        wire.nonce = Some(nonce);
-        wire.payload = Some(encrypted_payload);
-    } else {
-        wire.payload = Some(payload);
+    if let Some(member_key) = member_key {
+        member_key.sign(&mut wire, &payload)?;
     }
+    ring_keys.encrypt(&mut wire, payload)?;
     let mut buf = BytesMut::with_capacity(wire.encoded_len());
     wire.encode(&mut buf)?;
     Ok(buf.to_vec())
 }
 
-pub fn unwrap_wire(payload: &[u8], ring_key: Option<&SymKey>) -> Result<Vec<u8>> {
+/// Unwrap a wire message, returning its payload, what it says about the sender's ring keys, if
+/// the ring is encrypted, and the member key that signed it, if the signature was verified.
+pub fn unwrap_wire(payload: &[u8],
+                   ring_keys: &RingKeys,
+                   member_trust: Option<&MemberTrust>)
+                   -> Result<(Vec<u8>, Option<RingKeyUsage>, Option<String>)> {
     let wire = Wire::decode(payload)?;
-    let payload = wire.payload
-                      .ok_or(Error::ProtocolMismatch("missing payload"))?;
-    if let Some(ring_key) = ring_key {
-        let nonce = wire.nonce.ok_or(Error::ProtocolMismatch("missing nonce"))?;
-        Ok(ring_key.decrypt(&nonce, &payload)?)
-    } else {
-        Ok(payload)
-    }
+    let (payload, ring_key_usage) = ring_keys.decrypt(&wire)?;
+    let signer = match member_trust {
+        Some(member_trust) => Some(member_trust.verify(&wire, &payload)?),
+        None => None,
+    };
+    Ok((payload, ring_key_usage, signer))
 }
@@ -3,6 +3,7 @@ use std::fmt;
 use crate::rumor::{departure::Departure as CDeparture,
                    election::{Election as CElection,
                               ElectionUpdate as CElectionUpdate},
+                   ring_key::RingKey as CRingKey,
                    service::Service as CService,
                    service_config::ServiceConfig as CServiceConfig,
                    service_file::ServiceFile as CServiceFile};
@@ -24,6 +25,7 @@ impl fmt::Display for RumorType {
             RumorType::Fake2 => "fake2",
             RumorType::ElectionUpdate => "election-update",
             RumorType::Departure => "departure",
+            RumorType::RingKey => "ring-key",
         };
 
         write!(f, "{}", value)
@@ -36,6 +38,7 @@ impl From<CDeparture> for Rumor {
         Rumor { r#type:  RumorType::Departure as i32,
                 tag:     Vec::default(),
                 from_id: Some("butterflyclient".to_string()),
+                origin:  value.origin.map(Into::into),
                 payload: Some(RumorPayload::Departure(payload)), }
     }
 }
@@ -51,6 +54,7 @@ impl From<CElection> for Rumor {
         Rumor { r#type:  RumorType::Election as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.member_id),
+                origin:  None,
                 payload: Some(RumorPayload::Election(payload)), }
     }
 }
@@ -66,6 +70,7 @@ impl From<CElectionUpdate> for Rumor {
         Rumor { r#type:  RumorType::ElectionUpdate as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.member_id.clone()),
+                origin:  None,
                 payload: Some(RumorPayload::Election(payload)), }
     }
 }
@@ -78,23 +83,30 @@ impl From<CService> for Rumor {
                                 initialized:   Some(value.initialized),
                                 pkg:           Some(value.pkg),
                                 cfg:           Some(value.cfg),
-                                sys:           Some(value.sys.into()), };
+                                sys:           Some(value.sys.into()),
+                                canary:        value.canary.map(Into::into),
+                                ready:         Some(value.ready), };
         Rumor { r#type:  RumorType::Service as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.member_id),
+                origin:  value.origin.map(Into::into),
                 payload: Some(RumorPayload::Service(payload)), }
     }
 }
 
 impl From<CServiceConfig> for Rumor {
     fn from(value: CServiceConfig) -> Self {
-        let payload = ServiceConfig { service_group: Some(value.service_group.to_string()),
-                                      incarnation:   Some(value.incarnation),
-                                      encrypted:     Some(value.encrypted),
-                                      config:        Some(value.config), };
+        let payload =
+            ServiceConfig { service_group: Some(value.service_group.to_string()),
+                            incarnation:   Some(value.incarnation),
+                            encrypted:     Some(value.encrypted),
+                            config:        Some(value.config),
+                            signer:        value.author.as_ref().map(|a| a.signer.clone()),
+                            signature:     value.author.map(|a| a.signature), };
         Rumor { r#type:  RumorType::ServiceConfig as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.from_id),
+                origin:  value.origin.map(Into::into),
                 payload: Some(RumorPayload::ServiceConfig(payload)), }
     }
 }
@@ -105,10 +117,23 @@ impl From<CServiceFile> for Rumor {
                                     incarnation:   Some(value.incarnation),
                                     encrypted:     Some(value.encrypted),
                                     filename:      Some(value.filename),
-                                    body:          Some(value.body), };
+                                    body:          Some(value.body),
+                                    signer:        value.author.as_ref().map(|a| a.signer.clone()),
+                                    signature:     value.author.map(|a| a.signature), };
         Rumor { r#type:  RumorType::ServiceFile as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.from_id),
+                origin:  value.origin.map(Into::into),
                 payload: Some(RumorPayload::ServiceFile(payload)), }
     }
 }
+
+impl From<CRingKey> for Rumor {
+    fn from(value: CRingKey) -> Self {
+        Rumor { r#type:  RumorType::RingKey as i32,
+                tag:     Vec::default(),
+                from_id: Some(value.from_id.clone()),
+                origin:  value.origin.clone().map(Into::into),
+                payload: Some(RumorPayload::RingKey(value.into())), }
+    }
+}
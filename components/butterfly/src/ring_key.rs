@@ -0,0 +1,393 @@
+//! Ring key revisions.
+//!
+//! A ring key can't be replaced everywhere at once, so each member holds every revision of the
+//! ring key it knows about. It decrypts a message with whichever revision the message names, and
+//! encrypts with the newest revision that every member yet to depart has said it can decrypt. Each
+//! message names the revision it was encrypted with and every revision its sender holds, which is
+//! how members learn what their peers accept.
+//!
+//! Once every member yet to depart has been seen encrypting with the newest revision, the older
+//! revisions are retired, and messages encrypted with them are refused from then on, including
+//! after a restart.
+//!
+//! A new revision reaches the ring as a rumor, and that rumor is encrypted with the revision its
+//! sender is encrypting with at the time, like all gossip. A member that only holds a retired
+//! revision can't decrypt it, so it has to be given the new revision by hand.
+
+use std::{collections::HashMap,
+          fs,
+          io,
+          path::PathBuf,
+          result};
+
+use habitat_core::{crypto::SymKey,
+                   fs::atomic_write};
+use serde::{ser::SerializeStruct,
+            Serialize,
+            Serializer};
+
+use crate::{error::{Error,
+                    Result},
+            protocol::Wire};
+
+/// What a member's messages say about the ring key revisions it holds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct RingKeyUsage {
+    /// The revision the member's last message was encrypted with.
+    pub using:   Option<String>,
+    /// Every revision the member can decrypt.
+    pub accepts: Vec<String>,
+}
+
+/// The revisions of the ring key a member holds, and what it knows of its peers' revisions. An
+/// unencrypted ring holds none.
+#[derive(Debug, Default)]
+pub struct RingKeys {
+    /// Held revisions, oldest first.
+    keys:         Vec<SymKey>,
+    /// The held revision messages are encrypted with.
+    encrypting:   Option<String>,
+    retired:      Vec<String>,
+    /// Where retired revisions are recorded, so they stay retired across restarts.
+    retired_path: Option<PathBuf>,
+    members:      HashMap<String, RingKeyUsage>,
+}
+
+impl RingKeys {
+    pub fn new(ring_key: Option<SymKey>) -> Self {
+        RingKeys { encrypting: ring_key.as_ref().map(SymKey::name_with_rev),
+                   keys: ring_key.into_iter().collect(),
+                   ..Default::default() }
+    }
+
+    /// The revision messages are encrypted with, if the ring is encrypted.
+    pub fn current(&self) -> Option<&SymKey> {
+        self.encrypting
+            .as_ref()
+            .and_then(|name_with_rev| self.get(name_with_rev))
+    }
+
+    /// Hold another revision of the ring key. Returns `false` if the ring is unencrypted, the
+    /// revision is of a different ring, or it is already held or retired.
+    ///
+    /// A new revision is only encrypted with once every member yet to depart accepts it, unless
+    /// the current one has been retired.
+    pub fn add(&mut self, ring_key: SymKey) -> bool {
+        let name_with_rev = ring_key.name_with_rev();
+        match self.current() {
+            Some(current) if current.name == ring_key.name => {}
+            _ => return false,
+        }
+        if self.get(&name_with_rev).is_some() || self.retired.contains(&name_with_rev) {
+            return false;
+        }
+        self.keys.push(ring_key);
+        self.keys.sort_by(|a, b| a.rev.cmp(&b.rev));
+        self.drop_retired();
+        true
+    }
+
+    /// Refuse the revisions retired before a restart, as recorded at `path`, and record the ones
+    /// retired from now on there too.
+    pub fn persist_retired(&mut self, path: PathBuf) -> Result<()> {
+        match fs::read_to_string(&path) {
+            Ok(retired) => {
+                for name_with_rev in retired.lines().map(str::trim) {
+                    if !name_with_rev.is_empty() && !self.retired.iter().any(|r| r == name_with_rev)
+                    {
+                        self.retired.push(name_with_rev.to_string());
+                    }
+                }
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(Error::RetiredRingKeysIO(path, err)),
+        }
+        self.retired_path = Some(path);
+        self.drop_retired();
+        Ok(())
+    }
+
+    /// Every held revision, oldest first.
+    pub fn accepts(&self) -> Vec<String> { self.keys.iter().map(SymKey::name_with_rev).collect() }
+
+    /// Encrypt `payload` into `wire` with the current revision, or leave it unencrypted if the ring
+    /// is.
+    pub fn encrypt(&self, wire: &mut Wire, payload: Vec<u8>) -> Result<()> {
+        match self.current() {
+            Some(ring_key) => {
+                let (nonce, encrypted_payload) = ring_key.encrypt(&payload)?;
+                wire.encrypted = Some(true);
+                wire.nonce = Some(nonce);
+                wire.payload = Some(encrypted_payload);
+                wire.ring_key = Some(ring_key.name_with_rev());
+                wire.ring_keys = self.accepts();
+            }
+            None => wire.payload = Some(payload),
+        }
+        Ok(())
+    }
+
+    /// Decrypt the payload of `wire`, along with what it says about its sender's revisions, if the
+    /// ring is encrypted.
+    ///
+    /// Members that predate ring key rotation don't name the revision they encrypt with, so each
+    /// held revision is tried in turn, newest first.
+    pub fn decrypt(&self, wire: &Wire) -> Result<(Vec<u8>, Option<RingKeyUsage>)> {
+        let payload = wire.payload
+                          .as_ref()
+                          .ok_or(Error::ProtocolMismatch("missing payload"))?;
+        if self.keys.is_empty() {
+            return Ok((payload.clone(), None));
+        }
+        let nonce = wire.nonce
+                        .as_ref()
+                        .ok_or(Error::ProtocolMismatch("missing nonce"))?;
+        if let Some(ref name_with_rev) = wire.ring_key {
+            let ring_key = self.get(name_with_rev)
+                               .ok_or_else(|| Error::UnknownRingKey(name_with_rev.clone()))?;
+            let usage = RingKeyUsage { using:   Some(name_with_rev.clone()),
+                                       accepts: wire.ring_keys.clone(), };
+            return Ok((ring_key.decrypt(nonce, payload)?, Some(usage)));
+        }
+        let mut last_err = None;
+        for ring_key in self.keys.iter().rev() {
+            match ring_key.decrypt(nonce, payload) {
+                Ok(payload) => {
+                    let using = ring_key.name_with_rev();
+                    let usage = RingKeyUsage { using:   Some(using.clone()),
+                                               accepts: vec![using], };
+                    return Ok((payload, Some(usage)));
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.expect("at least one ring key is held").into())
+    }
+
+    /// Note what a message from `member_id` said about its revisions.
+    pub fn record(&mut self, member_id: &str, usage: RingKeyUsage) {
+        self.members.insert(member_id.to_string(), usage);
+    }
+
+    /// Move to the newest revision every one of `members` accepts, then retire the revisions older
+    /// than the current one once none of them is encrypting with them. A member we haven't heard
+    /// from holds both back.
+    ///
+    /// `members` should include suspect and confirmed members as well as live ones: the new
+    /// revision is only gossiped encrypted with the old one, so a member that comes back after the
+    /// old one is retired can no longer learn the new one.
+    pub fn refresh<'a>(&mut self, members: impl IntoIterator<Item = &'a str>) {
+        let current = match self.encrypting {
+            Some(ref current) => current.clone(),
+            None => return,
+        };
+        let mut usages = Vec::new();
+        for member_id in members {
+            match self.members.get(member_id) {
+                Some(usage) => usages.push(usage),
+                None => return,
+            }
+        }
+
+        let newest_accepted =
+            self.keys
+                .iter()
+                .rev()
+                .map(SymKey::name_with_rev)
+                .take_while(|name_with_rev| *name_with_rev != current)
+                .find(|name_with_rev| usages.iter().all(|u| u.accepts.contains(name_with_rev)));
+        if let Some(newest) = newest_accepted {
+            info!("Encrypting with ring key {}, which every member accepts",
+                  newest);
+            self.encrypting = Some(newest);
+            return;
+        }
+
+        let older = self.keys
+                        .iter()
+                        .map(SymKey::name_with_rev)
+                        .take_while(|name_with_rev| *name_with_rev != current)
+                        .collect::<Vec<_>>();
+        if older.is_empty()
+           || !usages.iter().all(|u| {
+                                u.using
+                                 .as_ref()
+                                 .map_or(false, |using| !older.contains(using))
+                            })
+        {
+            return;
+        }
+        info!("Every member is encrypting with ring key {}, retiring {}",
+              current,
+              older.join(", "));
+        self.keys
+            .retain(|ring_key| !older.contains(&ring_key.name_with_rev()));
+        self.retired.extend(older);
+        if let Some(ref path) = self.retired_path {
+            if let Err(err) = atomic_write(path, self.retired.join("\n")) {
+                warn!("{}", Error::RetiredRingKeysIO(path.clone(), err));
+            }
+        }
+    }
+
+    /// Stop holding retired revisions, moving on to the oldest revision still accepted if the
+    /// current one has been retired. The current revision is kept until there is one to move to.
+    fn drop_retired(&mut self) {
+        let current_is_retired = self.encrypting
+                                     .as_ref()
+                                     .map_or(false, |current| self.retired.contains(current));
+        if current_is_retired {
+            let next = self.keys
+                           .iter()
+                           .map(SymKey::name_with_rev)
+                           .find(|name_with_rev| !self.retired.contains(name_with_rev));
+            if let Some(next) = next {
+                info!("Ring key {} has been retired, encrypting with {}",
+                      self.encrypting.as_ref().unwrap(),
+                      next);
+                self.encrypting = Some(next);
+            }
+        }
+        let (retired, encrypting) = (&self.retired, &self.encrypting);
+        self.keys.retain(|ring_key| {
+                     let name_with_rev = ring_key.name_with_rev();
+                     !retired.contains(&name_with_rev)
+                     || encrypting.as_ref() == Some(&name_with_rev)
+                 });
+    }
+
+    fn get(&self, name_with_rev: &str) -> Option<&SymKey> {
+        self.keys
+            .iter()
+            .find(|ring_key| ring_key.name_with_rev() == name_with_rev)
+    }
+}
+
+impl Serialize for RingKeys {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut strukt = serializer.serialize_struct("ring_keys", 4)?;
+        strukt.serialize_field("encrypting", &self.encrypting)?;
+        strukt.serialize_field("accepts", &self.accepts())?;
+        strukt.serialize_field("retired", &self.retired)?;
+        strukt.serialize_field("members", &self.members)?;
+        strukt.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use habitat_core::crypto::SymKey;
+    use mktemp::Temp;
+
+    use super::{RingKeyUsage,
+                RingKeys};
+    use crate::{error::Error,
+                protocol::Wire};
+
+    fn ring_key(rev: &str) -> SymKey {
+        let mut ring_key = SymKey::generate_pair_for_ring("ring").unwrap();
+        ring_key.rev = rev.to_string();
+        ring_key
+    }
+
+    fn usage(using: &str, accepts: &[&str]) -> RingKeyUsage {
+        RingKeyUsage { using:   Some(using.to_string()),
+                       accepts: accepts.iter().map(|a| a.to_string()).collect(), }
+    }
+
+    fn encrypted(ring_keys: &RingKeys, payload: &[u8]) -> Wire {
+        let mut wire = Wire::default();
+        ring_keys.encrypt(&mut wire, payload.to_vec())
+                 .expect("encrypt payload");
+        wire
+    }
+
+    #[test]
+    fn decrypts_with_any_held_revision() {
+        let (old, new) = (ring_key("20190101000000"), ring_key("20190201000000"));
+        let mut sender = RingKeys::new(Some(old.clone()));
+        let mut receiver = RingKeys::new(Some(old));
+        assert!(receiver.add(new.clone()));
+
+        let (payload, seen) = receiver.decrypt(&encrypted(&sender, b"rumor")).unwrap();
+        assert_eq!(payload, b"rumor");
+        assert_eq!(seen,
+                   Some(usage("ring-20190101000000", &["ring-20190101000000"])));
+
+        assert!(sender.add(new));
+        sender.refresh(Vec::new());
+        let (payload, _) = receiver.decrypt(&encrypted(&sender, b"rumor")).unwrap();
+        assert_eq!(payload, b"rumor");
+    }
+
+    #[test]
+    fn moves_to_a_new_revision_once_every_live_member_accepts_it() {
+        let mut ring_keys = RingKeys::new(Some(ring_key("20190101000000")));
+        assert!(ring_keys.add(ring_key("20190201000000")));
+        ring_keys.record("a",
+                         usage("ring-20190101000000",
+                               &["ring-20190101000000", "ring-20190201000000"]));
+        ring_keys.record("b", usage("ring-20190101000000", &["ring-20190101000000"]));
+
+        ring_keys.refresh(vec!["a", "b"]);
+        assert_eq!(ring_keys.current().unwrap().rev, "20190101000000");
+        ring_keys.refresh(vec!["a", "c"]);
+        assert_eq!(ring_keys.current().unwrap().rev, "20190101000000");
+
+        ring_keys.refresh(vec!["a"]);
+        assert_eq!(ring_keys.current().unwrap().rev, "20190201000000");
+        assert_eq!(ring_keys.accepts().len(), 2);
+    }
+
+    #[test]
+    fn retires_old_revisions_once_every_live_member_uses_the_new_one() {
+        let mut ring_keys = RingKeys::new(Some(ring_key("20190101000000")));
+        ring_keys.add(ring_key("20190201000000"));
+        ring_keys.refresh(Vec::new());
+        ring_keys.record("a",
+                         usage("ring-20190101000000",
+                               &["ring-20190101000000", "ring-20190201000000"]));
+
+        ring_keys.refresh(vec!["a"]);
+        assert_eq!(ring_keys.accepts().len(), 2);
+
+        ring_keys.record("a",
+                         usage("ring-20190201000000",
+                               &["ring-20190101000000", "ring-20190201000000"]));
+        ring_keys.refresh(vec!["a"]);
+        assert_eq!(ring_keys.accepts(), vec!["ring-20190201000000"]);
+        assert!(!ring_keys.add(ring_key("20190101000000")));
+
+        let mut wire = Wire::default();
+        RingKeys::new(Some(ring_key("20190101000000"))).encrypt(&mut wire, b"rumor".to_vec())
+                                                       .unwrap();
+        match ring_keys.decrypt(&wire) {
+            Err(Error::UnknownRingKey(name_with_rev)) => {
+                assert_eq!(name_with_rev, "ring-20190101000000")
+            }
+            other => panic!("Expected an unknown ring key, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn retired_revisions_stay_retired_across_restarts() {
+        let dir = Temp::new_dir().expect("Could not create temp dir");
+        let path = dir.as_ref().join("RETIRED_RING_KEYS");
+        let (old, new) = (ring_key("20190101000000"), ring_key("20190201000000"));
+        let mut ring_keys = RingKeys::new(Some(old.clone()));
+        ring_keys.persist_retired(path.clone()).unwrap();
+        ring_keys.add(new.clone());
+        ring_keys.refresh(Vec::new());
+        ring_keys.refresh(Vec::new());
+        assert_eq!(ring_keys.accepts(), vec!["ring-20190201000000"]);
+
+        let mut restarted = RingKeys::new(Some(old));
+        restarted.persist_retired(path).unwrap();
+        assert_eq!(restarted.current().unwrap().rev, "20190101000000");
+        assert!(restarted.add(new));
+        assert_eq!(restarted.current().unwrap().rev, "20190201000000");
+        assert_eq!(restarted.accepts(), vec!["ring-20190201000000"]);
+    }
+}
@@ -14,15 +14,23 @@ use crate::{error::{Error,
                        FromProto},
             rumor::{Rumor,
                     RumorPayload,
-                    RumorType}};
+                    RumorType},
+            trust::{Origin,
+                    OriginRumor}};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Departure {
     pub member_id: String,
+    /// The member that put the rumor into the ring, and its signature, if it signed it.
+    #[serde(skip_serializing)]
+    pub origin:    Option<Origin>,
 }
 
 impl Departure {
-    pub fn new(member_id: &str) -> Self { Departure { member_id: member_id.to_string(), } }
+    pub fn new(member_id: &str) -> Self {
+        Departure { member_id: member_id.to_string(),
+                    origin:    None, }
+    }
 }
 
 impl protocol::Message<ProtoRumor> for Departure {}
@@ -34,7 +42,8 @@ impl FromProto<ProtoRumor> for Departure {
             _ => panic!("from-bytes departure"),
         };
         Ok(Departure { member_id: payload.member_id
-                                         .ok_or(Error::ProtocolMismatch("member-id"))?, })
+                                         .ok_or(Error::ProtocolMismatch("member-id"))?,
+                       origin:    rumor.origin.map(Origin::from_proto).transpose()?, })
     }
 }
 
@@ -52,6 +61,12 @@ impl Rumor for Departure {
     fn key(&self) -> &str { "departure" }
 }
 
+impl OriginRumor for Departure {
+    fn origin(&self) -> Option<&Origin> { self.origin.as_ref() }
+
+    fn set_origin(&mut self, origin: Option<Origin>) { self.origin = origin; }
+}
+
 impl PartialOrd for Departure {
     fn partial_cmp(&self, other: &Departure) -> Option<Ordering> {
         if self.member_id != other.member_id {
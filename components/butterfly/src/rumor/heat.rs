@@ -144,6 +144,10 @@ impl RumorHeat {
         }
     }
 
+    /// The number of rumors being tracked, whether or not they have
+    /// cooled off for any member.
+    pub fn rumor_count(&self) -> usize { self.0.read().expect("RumorHeat lock poisoned").len() }
+
     /// When a member is considered "gone" (e.g., once it is
     /// considered Departed), we can get rid of all the "cooling"
     /// information, since we're not going to be sending anything
@@ -10,6 +10,7 @@ pub mod dat_file;
 pub mod departure;
 pub mod election;
 pub mod heat;
+pub mod ring_key;
 pub mod service;
 pub mod service_config;
 pub mod service_file;
@@ -36,6 +37,7 @@ use serde::{ser::{SerializeMap,
 pub use self::{departure::Departure,
                election::{Election,
                           ElectionUpdate},
+               ring_key::RingKey,
                service::Service,
                service_config::ServiceConfig,
                service_file::ServiceFile};
@@ -61,6 +63,7 @@ pub enum RumorKind {
     Election(Election),
     ElectionUpdate(ElectionUpdate),
     Membership(Membership),
+    RingKey(RingKey),
     Service(Box<Service>), // Boxed due to clippy::large_enum_variant
     ServiceConfig(ServiceConfig),
     ServiceFile(ServiceFile),
@@ -73,6 +76,7 @@ impl From<RumorKind> for RumorPayload {
             RumorKind::Election(election) => RumorPayload::Election(election.into()),
             RumorKind::ElectionUpdate(election) => RumorPayload::Election(election.into()),
             RumorKind::Membership(membership) => RumorPayload::Member(membership.into()),
+            RumorKind::RingKey(ring_key) => RumorPayload::RingKey(ring_key.into()),
             RumorKind::Service(service) => RumorPayload::Service((*service).into()),
             RumorKind::ServiceConfig(service_config) => {
                 RumorPayload::ServiceConfig(service_config.into())
@@ -82,6 +86,32 @@ impl From<RumorKind> for RumorPayload {
     }
 }
 
+/// The user key a `ServiceConfig` or `ServiceFile` was signed with, and the signature, which
+/// travel with the rumor however it is relayed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Author {
+    pub signer:    String,
+    #[serde(skip_serializing)]
+    pub signature: Vec<u8>,
+}
+
+impl Author {
+    pub fn new<S>(signer: S, signature: Vec<u8>) -> Self
+        where S: Into<String>
+    {
+        Author { signer: signer.into(),
+                 signature }
+    }
+
+    /// The author of a rumor carrying these fields, if it is signed.
+    pub fn from_parts(signer: Option<String>, signature: Option<Vec<u8>>) -> Option<Self> {
+        match (signer, signature) {
+            (Some(signer), Some(signature)) => Some(Author { signer, signature }),
+            _ => None,
+        }
+    }
+}
+
 /// The description of a `RumorKey`.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct RumorKey {
@@ -192,6 +222,26 @@ impl<'a> Serialize for RumorStoreProxy<'a, Departure> {
     }
 }
 
+impl<'a> Serialize for RumorStoreProxy<'a, RingKey> {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let map = self.0.list.read().expect("Rumor store lock poisoned");
+        let inner_map = map.get("ring_key");
+        let len = inner_map.map_or(0, HashMap::len);
+
+        let mut s = serializer.serialize_seq(Some(len))?;
+
+        if let Some(inner_map) = inner_map {
+            for k in inner_map.keys() {
+                s.serialize_element(k)?;
+            }
+        }
+
+        s.end()
+    }
+}
+
 impl<'a> Serialize for RumorStoreProxy<'a, Election> {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
         where S: Serializer
@@ -461,6 +511,7 @@ impl RumorEnvelope {
                 RumorKind::ElectionUpdate(ElectionUpdate::from_proto(proto)?)
             }
             RumorType::Member => RumorKind::Membership(Membership::from_proto(proto)?),
+            RumorType::RingKey => RumorKind::RingKey(RingKey::from_proto(proto)?),
             RumorType::Service => RumorKind::Service(Box::new(Service::from_proto(proto)?)),
             RumorType::ServiceConfig => RumorKind::ServiceConfig(ServiceConfig::from_proto(proto)?),
             RumorType::ServiceFile => RumorKind::ServiceFile(ServiceFile::from_proto(proto)?),
@@ -481,9 +532,20 @@ impl RumorEnvelope {
 
 impl From<RumorEnvelope> for ProtoRumor {
     fn from(value: RumorEnvelope) -> ProtoRumor {
+        let origin = match value.kind {
+            RumorKind::Departure(ref departure) => departure.origin.clone(),
+            RumorKind::RingKey(ref ring_key) => ring_key.origin.clone(),
+            RumorKind::Service(ref service) => service.origin.clone(),
+            RumorKind::ServiceConfig(ref service_config) => service_config.origin.clone(),
+            RumorKind::ServiceFile(ref service_file) => service_file.origin.clone(),
+            RumorKind::Election(_) | RumorKind::ElectionUpdate(_) | RumorKind::Membership(_) => {
+                None
+            }
+        };
         ProtoRumor { r#type:  value.r#type as i32,
                      tag:     vec![],
                      from_id: Some(value.from_id),
+                     origin:  origin.map(Into::into),
                      payload: Some(value.kind.into()), }
     }
 }
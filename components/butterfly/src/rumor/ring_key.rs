@@ -0,0 +1,138 @@
+//! The RingKey rumor.
+//!
+//! RingKey rumors carry a new revision of the ring key to every member, so the ring can move to it
+//! without restarting anyone. They travel encrypted with a revision the ring already holds, like
+//! every other rumor. The key itself is never serialized for display.
+
+use std::{cmp::Ordering,
+          fmt};
+
+use habitat_core::crypto::SymKey;
+
+use crate::{error::{Error,
+                    Result},
+            protocol::{self,
+                       newscast::{self,
+                                  Rumor as ProtoRumor},
+                       FromProto},
+            rumor::{Rumor,
+                    RumorPayload,
+                    RumorType},
+            trust::{Origin,
+                    OriginRumor}};
+
+#[derive(Clone, Serialize)]
+pub struct RingKey {
+    pub from_id:       String,
+    pub name_with_rev: String,
+    /// The contents of the ring key file.
+    #[serde(skip_serializing)]
+    pub secret:        String,
+    /// The member that put the rumor into the ring, and its signature, if it signed it.
+    #[serde(skip_serializing)]
+    pub origin:        Option<Origin>,
+}
+
+impl RingKey {
+    pub fn new<S>(member_id: S, ring_key: &SymKey) -> Result<Self>
+        where S: Into<String>
+    {
+        Ok(RingKey { from_id:       member_id.into(),
+                     name_with_rev: ring_key.name_with_rev(),
+                     secret:        ring_key.to_secret_string()?,
+                     origin:        None, })
+    }
+}
+
+impl fmt::Debug for RingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RingKey({})", self.name_with_rev)
+    }
+}
+
+impl protocol::Message<ProtoRumor> for RingKey {}
+
+impl FromProto<ProtoRumor> for RingKey {
+    fn from_proto(rumor: ProtoRumor) -> Result<Self> {
+        let payload = match rumor.payload.ok_or(Error::ProtocolMismatch("payload"))? {
+            RumorPayload::RingKey(payload) => payload,
+            _ => panic!("from-bytes ring key"),
+        };
+        Ok(RingKey { from_id:       rumor.from_id.ok_or(Error::ProtocolMismatch("from-id"))?,
+                     name_with_rev: payload.name_with_rev
+                                           .ok_or(Error::ProtocolMismatch("name-with-rev"))?,
+                     secret:        payload.secret.ok_or(Error::ProtocolMismatch("secret"))?,
+                     origin:        rumor.origin.map(Origin::from_proto).transpose()?, })
+    }
+}
+
+impl From<RingKey> for newscast::RingKey {
+    fn from(value: RingKey) -> Self {
+        newscast::RingKey { name_with_rev: Some(value.name_with_rev),
+                            secret:        Some(value.secret), }
+    }
+}
+
+impl Rumor for RingKey {
+    /// A revision of the ring key never changes.
+    fn merge(&mut self, other: RingKey) -> bool { *self < other }
+
+    fn kind(&self) -> RumorType { RumorType::RingKey }
+
+    fn id(&self) -> &str { &self.name_with_rev }
+
+    fn key(&self) -> &str { "ring_key" }
+}
+
+impl OriginRumor for RingKey {
+    fn origin(&self) -> Option<&Origin> { self.origin.as_ref() }
+
+    fn set_origin(&mut self, origin: Option<Origin>) { self.origin = origin; }
+}
+
+impl PartialOrd for RingKey {
+    fn partial_cmp(&self, other: &RingKey) -> Option<Ordering> {
+        if self.name_with_rev != other.name_with_rev {
+            None
+        } else {
+            Some(Ordering::Equal)
+        }
+    }
+}
+
+impl PartialEq for RingKey {
+    fn eq(&self, other: &RingKey) -> bool { self.name_with_rev == other.name_with_rev }
+}
+
+#[cfg(test)]
+mod tests {
+    use habitat_core::crypto::SymKey;
+
+    use super::RingKey;
+    use crate::{protocol::Message,
+                rumor::{Rumor,
+                        RumorStore}};
+
+    #[test]
+    fn revisions_are_stored_once() {
+        let ring_key = SymKey::generate_pair_for_ring("ring").unwrap();
+        let rs = RumorStore::default();
+        assert!(rs.insert(RingKey::new("member_1", &ring_key).unwrap()));
+        assert!(!rs.insert(RingKey::new("member_2", &ring_key).unwrap()));
+
+        let mut rumor = RingKey::new("member_1", &ring_key).unwrap();
+        assert!(!rumor.merge(RingKey::new("member_2", &ring_key).unwrap()));
+    }
+
+    #[test]
+    fn secret_survives_encoding_but_not_display() {
+        let ring_key = SymKey::generate_pair_for_ring("ring").unwrap();
+        let rumor = RingKey::new("member_1", &ring_key).unwrap();
+
+        let decoded = RingKey::from_bytes(&rumor.write_to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.secret, ring_key.to_secret_string().unwrap());
+        assert!(!serde_json::to_string(&decoded).unwrap().contains("secret"));
+        assert_eq!(format!("{:?}", decoded),
+                   format!("RingKey({})", ring_key.name_with_rev()));
+    }
+}
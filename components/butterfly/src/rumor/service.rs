@@ -15,6 +15,7 @@ use toml;
 use habitat_core::{package::Identifiable,
                    service::ServiceGroup};
 
+pub use crate::protocol::newscast::canary_release::Status as CanaryStatus;
 use crate::{error::{Error,
                     Result},
             protocol::{self,
@@ -22,7 +23,9 @@ use crate::{error::{Error,
                        FromProto},
             rumor::{Rumor,
                     RumorPayload,
-                    RumorType}};
+                    RumorType},
+            trust::{Origin,
+                    OriginRumor}};
 
 #[derive(Debug, Clone)]
 pub struct Service {
@@ -33,6 +36,14 @@ pub struct Service {
     pub pkg:           String,
     pub cfg:           Vec<u8>,
     pub sys:           SysInfo,
+    /// The verdict of the most recent canary update this member
+    /// performed for its service group, if any.
+    pub canary:        Option<CanaryRelease>,
+    /// Whether this member's ready hook (or, lacking one, its health
+    /// check) last passed, i.e. whether it should receive traffic.
+    pub ready:         bool,
+    /// The member's signature over the rumor, if it signed it.
+    pub origin:        Option<Origin>,
 }
 
 // Ensures that `cfg` is rendered as a map, and not an array of bytes
@@ -40,7 +51,7 @@ impl Serialize for Service {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
         where S: Serializer
     {
-        let mut strukt = serializer.serialize_struct("service", 7)?;
+        let mut strukt = serializer.serialize_struct("service", 8)?;
         let cfg: toml::value::Table = toml::from_slice(&self.cfg).unwrap_or_default();
         strukt.serialize_field("member_id", &self.member_id)?;
         strukt.serialize_field("service_group", &self.service_group)?;
@@ -49,6 +60,7 @@ impl Serialize for Service {
         strukt.serialize_field("cfg", &cfg)?;
         strukt.serialize_field("sys", &self.sys)?;
         strukt.serialize_field("initialized", &self.initialized)?;
+        strukt.serialize_field("ready", &self.ready)?;
         strukt.end()
     }
 }
@@ -94,6 +106,9 @@ impl Service {
                   initialized: false,
                   pkg: package.to_string(),
                   sys,
+                  canary: None,
+                  ready: false,
+                  origin: None,
                   cfg: cfg.map(|v| {
                               // Directly serializing a toml::value::Table can lead to an error
                               // Wrapping it in a toml::value::Value makes this operation safe
@@ -125,7 +140,12 @@ impl FromProto<newscast::Rumor> for Service {
                      cfg:           payload.cfg.unwrap_or_default(),
                      sys:           payload.sys
                                            .ok_or(Error::ProtocolMismatch("sys"))
-                                           .and_then(SysInfo::from_proto)?, })
+                                           .and_then(SysInfo::from_proto)?,
+                     canary:        payload.canary.map(CanaryRelease::from_proto).transpose()?,
+                     // Supervisors that don't report readiness treat
+                     // every running service as ready.
+                     ready:         payload.ready.unwrap_or(true),
+                     origin:        rumor.origin.map(Origin::from_proto).transpose()?, })
     }
 }
 
@@ -137,7 +157,9 @@ impl From<Service> for newscast::Service {
                             initialized:   Some(value.initialized),
                             pkg:           Some(value.pkg),
                             cfg:           Some(value.cfg),
-                            sys:           Some(value.sys.into()), }
+                            sys:           Some(value.sys.into()),
+                            canary:        value.canary.map(Into::into),
+                            ready:         Some(value.ready), }
     }
 }
 
@@ -160,6 +182,48 @@ impl Rumor for Service {
     fn key(&self) -> &str { self.service_group.as_ref() }
 }
 
+impl OriginRumor for Service {
+    fn origin(&self) -> Option<&Origin> { self.origin.as_ref() }
+
+    fn set_origin(&mut self, origin: Option<Origin>) { self.origin = origin; }
+
+    /// Only a member can speak for its own services.
+    fn origin_member_id(&self) -> Option<&str> { Some(&self.member_id) }
+}
+
+/// A package a member installed ahead of the rest of its service
+/// group, along with how that release has fared so far.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CanaryRelease {
+    pub pkg:    String,
+    pub status: CanaryStatus,
+}
+
+impl CanaryRelease {
+    pub fn new<T>(package: &T, status: CanaryStatus) -> Self
+        where T: Identifiable
+    {
+        CanaryRelease { pkg: package.to_string(),
+                        status }
+    }
+}
+
+impl FromProto<newscast::CanaryRelease> for CanaryRelease {
+    fn from_proto(proto: newscast::CanaryRelease) -> Result<Self> {
+        Ok(CanaryRelease { pkg:    proto.pkg.ok_or(Error::ProtocolMismatch("pkg"))?,
+                           status: proto.status
+                                        .and_then(CanaryStatus::from_i32)
+                                        .ok_or(Error::ProtocolMismatch("status"))?, })
+    }
+}
+
+impl From<CanaryRelease> for newscast::CanaryRelease {
+    fn from(value: CanaryRelease) -> Self {
+        newscast::CanaryRelease { pkg:    Some(value.pkg),
+                                  status: Some(value.status as i32), }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SysInfo {
     pub ip:                String,
@@ -222,8 +286,11 @@ mod tests {
                        service::ServiceGroup};
 
     use super::Service;
-    use crate::rumor::{service::SysInfo,
-                       Rumor};
+    use crate::{protocol::Message,
+                rumor::{service::{CanaryRelease,
+                                  CanaryStatus,
+                                  SysInfo},
+                         Rumor}};
 
     fn create_service(member_id: &str) -> Service {
         let pkg = PackageIdent::from_str("core/neurosis/1.2.3/20161208121212").unwrap();
@@ -309,6 +376,25 @@ mod tests {
         assert_eq!(s1, s1_check);
     }
 
+    #[test]
+    fn canary_release_survives_a_round_trip() {
+        let mut s1 = create_service("adam");
+        let release = PackageIdent::from_str("core/neurosis/1.2.4/20161209121212").unwrap();
+        s1.canary = Some(CanaryRelease::new(&release, CanaryStatus::Failed));
+        let bytes = s1.write_to_bytes().unwrap();
+        let s2 = Service::from_bytes(&bytes).unwrap();
+        assert_eq!(s2.canary, s1.canary);
+    }
+
+    #[test]
+    fn readiness_survives_a_round_trip() {
+        let mut s1 = create_service("adam");
+        s1.ready = true;
+        let bytes = s1.write_to_bytes().unwrap();
+        let s2 = Service::from_bytes(&bytes).unwrap();
+        assert!(s2.ready);
+    }
+
     #[test]
     #[should_panic]
     fn service_package_name_mismatch() {
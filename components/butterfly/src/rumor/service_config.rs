@@ -8,9 +8,12 @@ use crate::{error::{Error,
                        newscast::{self,
                                   Rumor as ProtoRumor},
                        FromProto},
-            rumor::{Rumor,
+            rumor::{Author,
+                    Rumor,
                     RumorPayload,
-                    RumorType}};
+                    RumorType},
+            trust::{Origin,
+                    OriginRumor}};
 use habitat_core::{crypto::{keys::box_key_pair::WrappedSealedBox,
                             BoxKeyPair},
                    service::ServiceGroup};
@@ -28,6 +31,11 @@ pub struct ServiceConfig {
     pub incarnation:   u64,
     pub encrypted:     bool,
     pub config:        Vec<u8>, // TODO: make this a String
+    /// Who signed the rumor, if anyone did.
+    pub author:        Option<Author>,
+    /// The member that put the rumor into the ring, and its signature, if it signed it.
+    #[serde(skip_serializing)]
+    pub origin:        Option<Origin>,
 }
 
 impl PartialOrd for ServiceConfig {
@@ -58,7 +66,9 @@ impl ServiceConfig {
                         service_group,
                         incarnation: 0,
                         encrypted: false,
-                        config }
+                        config,
+                        author: None,
+                        origin: None }
     }
 
     pub fn encrypt(&mut self, user_pair: &BoxKeyPair, service_pair: &BoxKeyPair) -> Result<()> {
@@ -115,7 +125,9 @@ impl FromProto<ProtoRumor> for ServiceConfig {
                                       })?,
                            incarnation:   payload.incarnation.unwrap_or(0),
                            encrypted:     payload.encrypted.unwrap_or(false),
-                           config:        payload.config.unwrap_or_default(), })
+                           config:        payload.config.unwrap_or_default(),
+                           author:        Author::from_parts(payload.signer, payload.signature),
+                           origin:        rumor.origin.map(Origin::from_proto).transpose()?, })
     }
 }
 
@@ -124,7 +136,9 @@ impl From<ServiceConfig> for newscast::ServiceConfig {
         newscast::ServiceConfig { service_group: Some(value.service_group.to_string()),
                                   incarnation:   Some(value.incarnation),
                                   encrypted:     Some(value.encrypted),
-                                  config:        Some(value.config), }
+                                  config:        Some(value.config),
+                                  signer:        value.author.as_ref().map(|a| a.signer.clone()),
+                                  signature:     value.author.map(|a| a.signature), }
     }
 }
 
@@ -147,6 +161,12 @@ impl Rumor for ServiceConfig {
     fn key(&self) -> &str { &self.service_group }
 }
 
+impl OriginRumor for ServiceConfig {
+    fn origin(&self) -> Option<&Origin> { self.origin.as_ref() }
+
+    fn set_origin(&mut self, origin: Option<Origin>) { self.origin = origin; }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{cmp::Ordering,
@@ -156,8 +176,10 @@ mod tests {
     use toml;
 
     use super::ServiceConfig;
-    use crate::rumor::{Rumor,
-                       RumorStore};
+    use crate::{protocol::Message,
+                rumor::{Author,
+                        Rumor,
+                        RumorStore}};
 
     fn create_rumor_store() -> RumorStore<ServiceConfig> { RumorStore::default() }
 
@@ -256,4 +278,17 @@ mod tests {
         assert_eq!(s1.config(&mock_cache_key_path).unwrap(),
                    toml::from_str::<toml::value::Table>("yep=1").unwrap());
     }
+
+    #[test]
+    fn author_survives_encoding() {
+        let mut s1 = create_service_config("adam", "yep=1");
+        s1.author = Some(Author::new("alice-20190101000000", vec![1, 2, 3]));
+        let bytes = s1.write_to_bytes().unwrap();
+        let s2 = ServiceConfig::from_bytes(&bytes).unwrap();
+        assert_eq!(s2.author, s1.author);
+
+        let unsigned = create_service_config("adam", "yep=1");
+        let bytes = unsigned.write_to_bytes().unwrap();
+        assert_eq!(ServiceConfig::from_bytes(&bytes).unwrap().author, None);
+    }
 }
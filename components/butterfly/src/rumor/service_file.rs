@@ -8,9 +8,12 @@ use crate::{error::{Error,
                        newscast::{self,
                                   Rumor as ProtoRumor},
                        FromProto},
-            rumor::{Rumor,
+            rumor::{Author,
+                    Rumor,
                     RumorPayload,
-                    RumorType}};
+                    RumorType},
+            trust::{Origin,
+                    OriginRumor}};
 use habitat_core::{crypto::{keys::box_key_pair::WrappedSealedBox,
                             BoxKeyPair},
                    service::ServiceGroup};
@@ -27,6 +30,11 @@ pub struct ServiceFile {
     pub encrypted:     bool,
     pub filename:      String,
     pub body:          Vec<u8>, // TODO: make this a String
+    /// Who signed the rumor, if anyone did.
+    pub author:        Option<Author>,
+    /// The member that put the rumor into the ring, and its signature, if it signed it.
+    #[serde(skip_serializing)]
+    pub origin:        Option<Origin>,
 }
 
 impl PartialOrd for ServiceFile {
@@ -64,7 +72,9 @@ impl ServiceFile {
                       incarnation: 0,
                       encrypted: false,
                       filename: filename.into(),
-                      body }
+                      body,
+                      author: None,
+                      origin: None }
     }
 
     /// Encrypt the contents of the service file
@@ -108,7 +118,9 @@ impl FromProto<ProtoRumor> for ServiceFile {
                          encrypted:     payload.encrypted.unwrap_or(false),
                          filename:      payload.filename
                                                .ok_or(Error::ProtocolMismatch("filename"))?,
-                         body:          payload.body.unwrap_or_default(), })
+                         body:          payload.body.unwrap_or_default(),
+                         author:        Author::from_parts(payload.signer, payload.signature),
+                         origin:        rumor.origin.map(Origin::from_proto).transpose()?, })
     }
 }
 
@@ -118,7 +130,9 @@ impl From<ServiceFile> for newscast::ServiceFile {
                                 incarnation:   Some(value.incarnation),
                                 encrypted:     Some(value.encrypted),
                                 filename:      Some(value.filename),
-                                body:          Some(value.body), }
+                                body:          Some(value.body),
+                                signer:        value.author.as_ref().map(|a| a.signer.clone()),
+                                signature:     value.author.map(|a| a.signature), }
     }
 }
 
@@ -141,6 +155,12 @@ impl Rumor for ServiceFile {
     fn key(&self) -> &str { &self.service_group }
 }
 
+impl OriginRumor for ServiceFile {
+    fn origin(&self) -> Option<&Origin> { self.origin.as_ref() }
+
+    fn set_origin(&mut self, origin: Option<Origin>) { self.origin = origin; }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{cmp::Ordering,
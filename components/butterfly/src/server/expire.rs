@@ -1,13 +1,17 @@
 //! Periodically check membership rumors to automatically "time out"
 //! `Suspect` rumors to `Confirmed`, and `Confirmed` rumors to
 //! `Departed`.
+//!
+//! How long a member may stay `Suspect` shrinks as other members
+//! confirm the suspicion; see `lifeguard::SuspicionTimeout`.
 
 use std::{thread,
           time::Duration};
 
 use crate::{rumor::{RumorKey,
                     RumorType},
-            server::{timing::Timing,
+            server::{lifeguard::SuspicionTimeout,
+                     timing::Timing,
                      Server}};
 
 const LOOP_DELAY_MS: u64 = 500;
@@ -24,10 +28,16 @@ impl Expire {
         loop {
             habitat_common::sync::mark_thread_alive();
 
-            let newly_confirmed_members =
-                self.server
-                    .member_list
-                    .members_expired_to_confirmed(self.timing.suspicion_timeout_duration());
+            let live_members = self.server
+                                   .member_list
+                                   .live_ids(self.server.member_id())
+                                   .len();
+            let suspicion_timeout = SuspicionTimeout::new(&self.timing, live_members);
+            let newly_confirmed_members = self.server
+                                              .member_list
+                                              .members_expired_to_confirmed(|confirmations| {
+                                                  suspicion_timeout.after(confirmations)
+                                              });
 
             for id in newly_confirmed_members {
                 self.server
@@ -10,18 +10,27 @@ use std::{net::{SocketAddr,
 use habitat_core::util::ToI64;
 use prometheus::{IntCounterVec,
                  IntGaugeVec};
+use time::{Duration as TimeDuration,
+           SteadyTime};
 
-use super::AckSender;
-use crate::{member::Health,
+use super::ReplySender;
+use crate::{error::Error,
+            member::{Health,
+                     Member},
             server::{outbound,
+                     timing::Timing,
                      Server},
             swim::{Ack,
+                   Nack,
                    Ping,
                    PingReq,
                    Swim,
                    SwimKind},
             trace::TraceKind};
 
+/// How long to wait for a message when no nacks are due.
+pub(super) const RECV_TIMEOUT: Duration = Duration::from_millis(1000);
+
 lazy_static! {
     static ref SWIM_MESSAGES_RECEIVED: IntCounterVec =
         register_int_counter_vec!("hab_butterfly_swim_messages_received_total",
@@ -33,23 +42,47 @@ lazy_static! {
                                 &["type", "mode"]).unwrap();
 }
 
-/// Takes the Server and a channel to send received Acks to the outbound thread.
+/// A reply to one of our probes, passed from the inbound thread to the outbound thread.
+pub enum ProbeReply {
+    Ack(Ack),
+    Nack(Nack),
+}
+
+/// A ping we sent on behalf of a PingReq whose sender wants a Nack if the target doesn't ack
+/// by the deadline.
+struct PendingNack {
+    requester: Member,
+    target:    Member,
+    deadline:  SteadyTime,
+}
+
+/// Takes the Server and a channel to send received Acks and Nacks to the outbound thread.
 pub struct Inbound {
     pub server:      Server,
     pub socket:      UdpSocket,
-    pub tx_outbound: AckSender,
+    pub tx_outbound: ReplySender,
+    pub timing:      Timing,
+    pending_nacks:   Vec<PendingNack>,
+    read_timeout:    Duration,
 }
 
 impl Inbound {
     /// Create a new Inbound.
-    pub fn new(server: Server, socket: UdpSocket, tx_outbound: AckSender) -> Inbound {
+    pub fn new(server: Server,
+               socket: UdpSocket,
+               tx_outbound: ReplySender,
+               timing: Timing)
+               -> Inbound {
         Inbound { server,
                   socket,
-                  tx_outbound }
+                  tx_outbound,
+                  timing,
+                  pending_nacks: Vec::new(),
+                  read_timeout: RECV_TIMEOUT }
     }
 
     /// Run the thread. Listens for messages up to 1k in size, and then processes them accordingly.
-    pub fn run(&self) {
+    pub fn run(&mut self) {
         let mut recv_buffer: Vec<u8> = vec![0; 1024];
 
         loop {
@@ -60,21 +93,38 @@ impl Inbound {
                 continue;
             }
 
+            self.send_due_nacks();
+            self.update_read_timeout();
+
             match self.socket.recv_from(&mut recv_buffer[..]) {
                 Ok((length, addr)) => {
-                    let swim_payload = match self.server.unwrap_wire(&recv_buffer[0..length]) {
-                        Ok(swim_payload) => swim_payload,
-                        Err(e) => {
-                            // NOTE: In the future, we might want to block people who send us
-                            // garbage all the time.
-                            error!("Error unwrapping protocol message, {}", e);
-                            let label_values = &["unwrap_wire", "failure"];
-                            SWIM_BYTES_RECEIVED.with_label_values(label_values)
-                                               .set(length.to_i64());
-                            SWIM_MESSAGES_RECEIVED.with_label_values(label_values).inc();
-                            continue;
-                        }
-                    };
+                    let (swim_payload, ring_key_usage, signer) =
+                        match self.server.unwrap_wire(&recv_buffer[0..length]) {
+                            Ok(unwrapped) => unwrapped,
+                            Err(Error::UntrustedSender(signer, reason)) => {
+                                warn!("Rejecting SWIM message from {} signed by {}: {}",
+                                      addr, signer, reason);
+                                trace_it!(UNTRUSTED: &self.server,
+                                          TraceKind::RecvUntrusted,
+                                          &signer,
+                                          &reason);
+                                let label_values = &["untrusted", "failure"];
+                                SWIM_BYTES_RECEIVED.with_label_values(label_values)
+                                                   .set(length.to_i64());
+                                SWIM_MESSAGES_RECEIVED.with_label_values(label_values).inc();
+                                continue;
+                            }
+                            Err(e) => {
+                                // NOTE: In the future, we might want to block people who send us
+                                // garbage all the time.
+                                error!("Error unwrapping protocol message, {}", e);
+                                let label_values = &["unwrap_wire", "failure"];
+                                SWIM_BYTES_RECEIVED.with_label_values(label_values)
+                                                   .set(length.to_i64());
+                                SWIM_MESSAGES_RECEIVED.with_label_values(label_values).inc();
+                                continue;
+                            }
+                        };
 
                     let bytes_received = swim_payload.len();
                     let msg = match Swim::decode(&swim_payload) {
@@ -91,6 +141,34 @@ impl Inbound {
                         }
                     };
 
+                    // A forwarded ack is signed by the member that forwarded it
+                    let sender = match msg.kind {
+                        SwimKind::Ping(ref ping) => Some(&ping.from.id),
+                        SwimKind::Ack(ref ack) if ack.forward_to.is_none() => Some(&ack.from.id),
+                        SwimKind::Ack(_) => None,
+                        SwimKind::PingReq(ref pingreq) => Some(&pingreq.from.id),
+                        SwimKind::Nack(ref nack) => Some(&nack.from.id),
+                    };
+                    if let Some(sender) = sender {
+                        if let Err(e) =
+                            self.server
+                                .verify_sender(sender, signer.as_ref().map(String::as_str))
+                        {
+                            warn!("Rejecting SWIM message from {}: {}", addr, e);
+                            if let Error::UntrustedSender(signer, reason) = e {
+                                trace_it!(UNTRUSTED: &self.server,
+                                          TraceKind::RecvUntrusted,
+                                          &signer,
+                                          &reason);
+                            }
+                            let label_values = &["untrusted", "failure"];
+                            SWIM_BYTES_RECEIVED.with_label_values(label_values)
+                                               .set(bytes_received.to_i64());
+                            SWIM_MESSAGES_RECEIVED.with_label_values(label_values).inc();
+                            continue;
+                        }
+                    }
+
                     // Setting a label_values variable here throws errors about moving borrowed
                     // content that I couldn't solve w/o clones. Leaving this for now. I'm sure
                     // there's a better way.
@@ -107,6 +185,8 @@ impl Inbound {
                                        ping.from.id);
                                 continue;
                             }
+                            self.server
+                                .record_ring_key_usage(&ping.from.id, ring_key_usage);
                             self.process_ping(addr, ping);
                         }
                         SwimKind::Ack(ack) => {
@@ -117,6 +197,10 @@ impl Inbound {
                                        ack.from.id);
                                 continue;
                             }
+                            if ack.forward_to.is_none() {
+                                self.server
+                                    .record_ring_key_usage(&ack.from.id, ring_key_usage);
+                            }
                             self.process_ack(addr, ack);
                         }
                         SwimKind::PingReq(pingreq) => {
@@ -125,8 +209,20 @@ impl Inbound {
                                        pingreq.from.id);
                                 continue;
                             }
+                            self.server
+                                .record_ring_key_usage(&pingreq.from.id, ring_key_usage);
                             self.process_pingreq(addr, pingreq);
                         }
+                        SwimKind::Nack(nack) => {
+                            if self.server.is_member_blocked(&nack.from.id) {
+                                debug!("Not processing message from {} - it is blocked",
+                                       nack.from.id);
+                                continue;
+                            }
+                            self.server
+                                .record_ring_key_usage(&nack.from.id, ring_key_usage);
+                            self.process_nack(addr, nack);
+                        }
                     }
                 }
                 Err(e) => {
@@ -150,28 +246,62 @@ impl Inbound {
         }
     }
 
+    /// Send the Nacks that are due, for the pings we sent on behalf of a PingReq that the target
+    /// hasn't acked.
+    fn send_due_nacks(&mut self) {
+        let now = SteadyTime::now();
+        let (due, pending): (Vec<_>, Vec<_>) = self.pending_nacks
+                                                   .drain(..)
+                                                   .partition(|p| p.deadline <= now);
+        self.pending_nacks = pending;
+        for PendingNack { requester, target, .. } in due {
+            outbound::nack(&self.server, &self.socket, &requester, &target);
+        }
+    }
+
+    /// Wait for messages no longer than until the next Nack is due.
+    fn update_read_timeout(&mut self) {
+        let timeout = match self.pending_nacks.iter().map(|p| p.deadline).min() {
+            Some(deadline) => {
+                let wait_ms = (deadline - SteadyTime::now()).num_milliseconds().max(1);
+                Duration::from_millis(wait_ms as u64).min(RECV_TIMEOUT)
+            }
+            None => RECV_TIMEOUT,
+        };
+        if timeout != self.read_timeout {
+            match self.socket.set_read_timeout(Some(timeout)) {
+                Ok(()) => self.read_timeout = timeout,
+                Err(e) => error!("Failed to set the SWIM socket read timeout: {}", e),
+            }
+        }
+    }
+
     /// Process pingreq messages.
-    fn process_pingreq(&self, addr: SocketAddr, mut msg: PingReq) {
+    fn process_pingreq(&mut self, addr: SocketAddr, mut msg: PingReq) {
         trace_it!(SWIM: &self.server, TraceKind::RecvPingReq, &msg.from.id, addr, &msg);
         msg.from.address = addr.ip().to_string();
-        let id = msg.target.id.clone(); // TODO: see if we can eliminate this clone
-        self.server.member_list.with_member(&id, |target| {
-                                   if let Some(target) = target {
-                                       // Set the route-back address to the one we received the
-                                       // pingreq from
-                                       outbound::ping(&self.server,
-                                                      &self.socket,
-                                                      target,
-                                                      target.swim_socket_address(),
-                                                      Some(&msg.from));
-                                   } else {
-                                       error!("PingReq request {:?} for invalid target", msg);
-                                   }
-                               });
+        match self.server.member_list.get_cloned(&msg.target.id) {
+            Some(target) => {
+                // Set the route-back address to the one we received the pingreq from
+                outbound::ping(&self.server,
+                               &self.socket,
+                               &target,
+                               target.swim_socket_address(),
+                               Some(&msg.from));
+                if msg.nack {
+                    let deadline =
+                        SteadyTime::now() + TimeDuration::milliseconds(self.timing.ping_ms);
+                    self.pending_nacks.push(PendingNack { requester: msg.from,
+                                                          target,
+                                                          deadline });
+                }
+            }
+            None => error!("PingReq request {:?} for invalid target", msg),
+        }
     }
 
     /// Process ack messages; forwards to the outbound thread.
-    fn process_ack(&self, addr: SocketAddr, mut msg: Ack) {
+    fn process_ack(&mut self, addr: SocketAddr, mut msg: Ack) {
         trace_it!(SWIM: &self.server, TraceKind::RecvAck, &msg.from.id, addr, &msg);
         trace!("Ack from {}@{}", msg.from.id, addr);
         if msg.forward_to.is_some() && *self.server.member_id != msg.forward_to.as_ref().unwrap().id
@@ -195,21 +325,32 @@ impl Inbound {
                 (forward_to_addr, addr.ip().to_string())
             };
             msg.from.address = from_addr;
+            let requester_id = &msg.forward_to.as_ref().unwrap().id;
+            self.pending_nacks
+                .retain(|p| p.target.id != msg.from.id || p.requester.id != *requester_id);
             outbound::forward_ack(&self.server, &self.socket, forward_to_addr, msg);
             return;
         }
         let memberships = msg.membership.clone();
-        match self.tx_outbound.send((addr, msg)) {
+        match self.tx_outbound.send((addr, ProbeReply::Ack(msg))) {
             Ok(()) => {
                 for membership in memberships {
-                    self.server
-                        .insert_member_from_rumor(membership.member, membership.health);
+                    self.server.insert_member_from_rumor(membership);
                 }
             }
             Err(e) => panic!("Outbound thread has died - this shouldn't happen: #{:?}", e),
         }
     }
 
+    /// Process nack messages; forwards to the outbound thread.
+    fn process_nack(&self, addr: SocketAddr, msg: Nack) {
+        trace_it!(SWIM: &self.server, TraceKind::RecvNack, &msg.from.id, addr, &msg);
+        trace!("Nack from {}@{} for {}", msg.from.id, addr, msg.target.id);
+        if let Err(e) = self.tx_outbound.send((addr, ProbeReply::Nack(msg))) {
+            panic!("Outbound thread has died - this shouldn't happen: #{:?}", e);
+        }
+    }
+
     /// Process ping messages.
     fn process_ping(&self, addr: SocketAddr, mut msg: Ping) {
         trace_it!(SWIM: &self.server, TraceKind::RecvPing, &msg.from.id, addr, &msg);
@@ -223,8 +364,7 @@ impl Inbound {
             self.server.insert_member(msg.from, Health::Alive);
         }
         for membership in msg.membership {
-            self.server
-                .insert_member_from_rumor(membership.member, membership.health);
+            self.server.insert_member_from_rumor(membership);
         }
     }
 }
@@ -0,0 +1,160 @@
+//! Lifeguard extensions to the SWIM protocol, which cut down on healthy members being found
+//! suspect and confirmed when they, or the members probing them, are slow to respond.
+//!
+//! * Local health: a member that can't keep up with its own probes is a poor judge of the health of
+//!   others. Each member keeps a score of how badly its probes have been going, and stretches its
+//!   probe timeouts and protocol period by it.
+//! * Dynamic suspicion timeouts: a suspect member starts with a long time to refute the suspicion,
+//!   which shrinks as other members independently find it suspect too.
+//! * Nacks: a member relaying a PingReq replies with a Nack when the target doesn't ack in time, so
+//!   the requester can tell a target that is down from its own trouble hearing its peers.
+//!
+//! See "Lifeguard: Local Health Awareness for More Accurate Failure Detection",
+//! https://arxiv.org/abs/1707.00788
+
+use std::sync::atomic::{AtomicUsize,
+                        Ordering};
+
+use habitat_core::util::ToI64;
+use prometheus::IntGauge;
+use time::Duration as TimeDuration;
+
+use crate::server::timing::Timing;
+
+/// The worst local health score; probe timeouts are stretched up to one more times than this.
+const MAX_LOCAL_HEALTH: usize = 8;
+/// The most confirmations a suspicion needs before it times out as soon as it can.
+const MAX_EXPECTED_CONFIRMATIONS: usize = 3;
+
+lazy_static! {
+    static ref LOCAL_HEALTH: IntGauge =
+        register_int_gauge!(opts!("hab_butterfly_local_health",
+                                  "How badly the Supervisor's own probes have been going, from \
+                                   0 (healthy) to 8")).unwrap();
+}
+
+/// How badly this member's probes have been going, from 0 (healthy) to `MAX_LOCAL_HEALTH`.
+///
+/// The score goes up when a probe fails without the members relaying it saying the target is
+/// down, or when another member finds us suspect, and goes down with each probe that is acked.
+#[derive(Debug, Default)]
+pub struct LocalHealth(AtomicUsize);
+
+impl LocalHealth {
+    pub fn score(&self) -> usize { self.0.load(Ordering::SeqCst) }
+
+    /// Raise the score by `delta`, or lower it if `delta` is negative.
+    pub fn apply(&self, delta: isize) {
+        let mut current = self.score();
+        loop {
+            let score = ((current as isize + delta).max(0) as usize).min(MAX_LOCAL_HEALTH);
+            match self.0
+                      .compare_exchange(current, score, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => {
+                    LOCAL_HEALTH.set(score.to_i64());
+                    return;
+                }
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// `timing`, with its probe timeouts stretched by the score.
+    pub fn stretch(&self, timing: &Timing) -> Timing { timing.stretched(self.score() as i64 + 1) }
+}
+
+/// How long a suspect member has to refute the suspicion, given how many other members have
+/// confirmed it.
+#[derive(Debug)]
+pub struct SuspicionTimeout {
+    min:                    TimeDuration,
+    max:                    TimeDuration,
+    expected_confirmations: usize,
+}
+
+impl SuspicionTimeout {
+    /// `live_members` is the number of other members that are alive or suspect; any of them but
+    /// the suspect itself can confirm a suspicion.
+    pub fn new(timing: &Timing, live_members: usize) -> Self {
+        SuspicionTimeout { min:                    timing.suspicion_timeout_duration(),
+                           max:                    timing.max_suspicion_timeout_duration(),
+                           expected_confirmations: live_members.saturating_sub(1)
+                                                               .min(MAX_EXPECTED_CONFIRMATIONS), }
+    }
+
+    /// Starts at the maximum timeout, and falls logarithmically with each confirmation to the
+    /// minimum, which it reaches once as many have arrived as can be expected. If no other member
+    /// can confirm the suspicion, it is always the minimum.
+    pub fn after(&self, confirmations: usize) -> TimeDuration {
+        if self.expected_confirmations == 0 {
+            return self.min;
+        }
+        let confirmations = confirmations.min(self.expected_confirmations);
+        let fraction =
+            (confirmations as f64 + 1.0).ln() / (self.expected_confirmations as f64 + 1.0).ln();
+        let (min_ms, max_ms) = (self.min.num_milliseconds(), self.max.num_milliseconds());
+        let timeout_ms = max_ms - ((max_ms - min_ms) as f64 * fraction).floor() as i64;
+        TimeDuration::milliseconds(timeout_ms.max(min_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalHealth,
+                SuspicionTimeout,
+                MAX_LOCAL_HEALTH};
+    use crate::server::timing::Timing;
+
+    #[test]
+    fn local_health_stays_in_bounds() {
+        let health = LocalHealth::default();
+        health.apply(-1);
+        assert_eq!(health.score(), 0);
+        health.apply(3);
+        assert_eq!(health.score(), 3);
+        health.apply(MAX_LOCAL_HEALTH as isize);
+        assert_eq!(health.score(), MAX_LOCAL_HEALTH);
+    }
+
+    #[test]
+    fn local_health_stretches_probe_timeouts() {
+        let timing = Timing::default();
+        let health = LocalHealth::default();
+        assert_eq!(health.stretch(&timing).protocol_period_ms(),
+                   timing.protocol_period_ms());
+
+        health.apply(2);
+        let stretched = health.stretch(&timing);
+        assert_eq!(stretched.ping_ms, timing.ping_ms * 3);
+        assert_eq!(stretched.pingreq_ms, timing.pingreq_ms * 3);
+        assert_eq!(stretched.suspicion_timeout_duration(),
+                   timing.suspicion_timeout_duration() * 3);
+    }
+
+    #[test]
+    fn suspicion_timeout_shrinks_with_confirmations() {
+        let timing = Timing::default();
+        let (min, max) =
+            (timing.suspicion_timeout_duration(), timing.max_suspicion_timeout_duration());
+        let timeout = SuspicionTimeout::new(&timing, 10);
+
+        assert_eq!(timeout.after(0), max);
+        assert!(timeout.after(1) < max);
+        assert!(timeout.after(2) < timeout.after(1));
+        assert!(timeout.after(2) > min);
+        assert_eq!(timeout.after(3), min);
+        assert_eq!(timeout.after(10), min);
+    }
+
+    #[test]
+    fn suspicion_timeout_is_the_minimum_when_nobody_can_confirm() {
+        let timing = Timing::default();
+        assert_eq!(SuspicionTimeout::new(&timing, 1).after(0),
+                   timing.suspicion_timeout_duration());
+
+        let timeout = SuspicionTimeout::new(&timing, 2);
+        assert_eq!(timeout.after(0), timing.max_suspicion_timeout_duration());
+        assert_eq!(timeout.after(1), timing.suspicion_timeout_duration());
+    }
+}
@@ -8,26 +8,33 @@
 mod expire;
 mod inbound;
 mod incarnation_store;
+mod lifeguard;
 mod outbound;
 mod pull;
 mod push;
 pub mod timing;
 
-use self::incarnation_store::IncarnationStore;
+use self::{inbound::ProbeReply,
+           incarnation_store::IncarnationStore,
+           lifeguard::LocalHealth};
 use crate::{error::{Error,
                     Result},
             member::{Health,
                      Incarnation,
                      Member,
                      MemberList,
-                     MemberListProxy},
+                     MemberListProxy,
+                     Membership},
             message,
+            ring_key::{RingKeyUsage,
+                       RingKeys},
             rumor::{dat_file::DatFile,
                     departure::Departure,
                     election::{Election,
                                ElectionRumor,
                                ElectionUpdate},
                     heat::RumorHeat,
+                    ring_key::RingKey,
                     service::Service,
                     service_config::ServiceConfig,
                     service_file::ServiceFile,
@@ -36,9 +43,14 @@ use crate::{error::{Error,
                     RumorStore,
                     RumorStoreProxy,
                     RumorType},
-            swim::Ack,
             trace::{Trace,
-                    TraceKind}};
+                    TraceKind},
+            transport::{GossipTransport,
+                        ZmqTransport},
+            trust::{MemberBindings,
+                    MemberKey,
+                    MemberTrust,
+                    OriginRumor}};
 use habitat_common::FeatureFlag;
 use habitat_core::crypto::SymKey;
 use prometheus::{HistogramTimer,
@@ -94,13 +106,21 @@ impl fmt::Debug for ElectionTimer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "An election timer!") }
 }
 
-type AckReceiver = mpsc::Receiver<(SocketAddr, Ack)>;
-type AckSender = mpsc::Sender<(SocketAddr, Ack)>;
+type ReplyReceiver = mpsc::Receiver<(SocketAddr, ProbeReply)>;
+type ReplySender = mpsc::Sender<(SocketAddr, ProbeReply)>;
 
 pub trait Suitability: Debug + Send + Sync {
     fn get(&self, service_group: &str) -> u64;
 }
 
+/// Decides whose configuration and files a service group accepts. Rumors it rejects are neither
+/// stored nor passed on, so they can't displace what a trusted author wrote.
+pub trait AuthorCheck: Debug + Send + Sync {
+    fn check_service_config(&self, service_config: &ServiceConfig) -> Result<()>;
+
+    fn check_service_file(&self, service_file: &ServiceFile) -> Result<()>;
+}
+
 /// Encapsulate a `Member` with the added understanding that this
 /// represents the identity of this Butterfly Server.
 ///
@@ -233,7 +253,12 @@ pub struct Server {
     // depends on it being so. Refactor so it can be private.
     pub member:               Arc<RwLock<Myself>>,
     pub member_list:          Arc<MemberList>,
-    ring_key:                 Arc<Option<SymKey>>,
+    ring_keys:                Arc<RwLock<RingKeys>>,
+    member_key:               Arc<Option<MemberKey>>,
+    member_trust:             Arc<Option<MemberTrust>>,
+    member_bindings:          Arc<MemberBindings>,
+    author_check:             Arc<Option<Box<dyn AuthorCheck>>>,
+    local_health:             Arc<LocalHealth>,
     rumor_heat:               RumorHeat,
     pub service_store:        RumorStore<Service>,
     pub service_config_store: RumorStore<ServiceConfig>,
@@ -241,8 +266,10 @@ pub struct Server {
     pub election_store:       RumorStore<Election>,
     pub update_store:         RumorStore<ElectionUpdate>,
     pub departure_store:      RumorStore<Departure>,
+    pub ring_key_store:       RumorStore<RingKey>,
     swim_addr:                SocketAddr,
     gossip_addr:              SocketAddr,
+    gossip_transport:         Arc<Box<dyn GossipTransport>>,
     suitability_lookup:       Arc<Box<dyn Suitability>>,
     data_path:                Option<PathBuf>,
     dat_file:                 Option<Arc<Mutex<DatFile>>>,
@@ -263,7 +290,12 @@ impl Clone for Server {
                  member_id:            self.member_id.clone(),
                  member:               self.member.clone(),
                  member_list:          self.member_list.clone(),
-                 ring_key:             self.ring_key.clone(),
+                 ring_keys:            self.ring_keys.clone(),
+                 member_key:           self.member_key.clone(),
+                 member_trust:         self.member_trust.clone(),
+                 member_bindings:      self.member_bindings.clone(),
+                 author_check:         self.author_check.clone(),
+                 local_health:         self.local_health.clone(),
                  rumor_heat:           self.rumor_heat.clone(),
                  service_store:        self.service_store.clone(),
                  service_config_store: self.service_config_store.clone(),
@@ -271,8 +303,10 @@ impl Clone for Server {
                  election_store:       self.election_store.clone(),
                  update_store:         self.update_store.clone(),
                  departure_store:      self.departure_store.clone(),
+                 ring_key_store:       self.ring_key_store.clone(),
                  swim_addr:            self.swim_addr,
                  gossip_addr:          self.gossip_addr,
+                 gossip_transport:     self.gossip_transport.clone(),
                  suitability_lookup:   self.suitability_lookup.clone(),
                  data_path:            self.data_path.clone(),
                  dat_file:             self.dat_file.clone(),
@@ -327,7 +361,12 @@ impl Server {
                             member_id:            Arc::new(member_id),
                             member:               Arc::new(RwLock::new(myself)),
                             member_list:          Arc::new(MemberList::new()),
-                            ring_key:             Arc::new(ring_key),
+                            ring_keys:            Arc::new(RwLock::new(RingKeys::new(ring_key))),
+                            member_key:           Arc::new(None),
+                            member_trust:         Arc::new(None),
+                            member_bindings:      Arc::new(MemberBindings::default()),
+                            author_check:         Arc::new(None),
+                            local_health:         Arc::new(LocalHealth::default()),
                             rumor_heat:           RumorHeat::default(),
                             service_store:        RumorStore::default(),
                             service_config_store: RumorStore::default(),
@@ -335,8 +374,10 @@ impl Server {
                             election_store:       RumorStore::default(),
                             update_store:         RumorStore::default(),
                             departure_store:      RumorStore::default(),
+                            ring_key_store:       RumorStore::default(),
                             swim_addr:            swim_socket_addr,
                             gossip_addr:          gossip_socket_addr,
+                            gossip_transport:     Arc::new(Box::new(ZmqTransport)),
                             suitability_lookup:   Arc::new(suitability_lookup),
                             data_path:            data_path.as_ref().map(|p| p.into()),
                             dat_file:             None,
@@ -440,18 +481,24 @@ impl Server {
                 me.incarnation_store = Some(store);
                 me.sync_incarnation()?;
             }
+
+            self.ring_keys
+                .write()
+                .expect("Ring keys lock is poisoned")
+                .persist_retired(path.join("RETIRED_RING_KEYS"))?;
         }
 
         let socket = match UdpSocket::bind(self.swim_addr) {
             Ok(socket) => socket,
             Err(e) => return Err(Error::CannotBind(e)),
         };
-        socket.set_read_timeout(Some(Duration::from_millis(1000)))
+        socket.set_read_timeout(Some(inbound::RECV_TIMEOUT))
               .map_err(Error::SocketSetReadTimeout)?;
         socket.set_write_timeout(Some(Duration::from_millis(1000)))
               .map_err(Error::SocketSetReadTimeout)?;
 
         let server_a = self.clone();
+        let timing_a = timing.clone();
         let socket_a = match socket.try_clone() {
             Ok(socket_a) => socket_a,
             Err(_) => return Err(Error::SocketCloneError),
@@ -462,12 +509,14 @@ impl Server {
         };
         self.socket = Some(socket_expire);
 
-        let _ =
-            thread::Builder::new().name(format!("inbound-{}", self.name()))
-                                  .spawn(move || {
-                                      inbound::Inbound::new(server_a, socket_a, tx_outbound).run();
-                                      panic!("You should never, ever get here, judy");
-                                  });
+        let _ = thread::Builder::new().name(format!("inbound-{}", self.name()))
+                                      .spawn(move || {
+                                          inbound::Inbound::new(server_a,
+                                                                socket_a,
+                                                                tx_outbound,
+                                                                timing_a).run();
+                                          panic!("You should never, ever get here, judy");
+                                      });
 
         let server_b = self.clone();
         let socket_b = match socket.try_clone() {
@@ -518,6 +567,103 @@ impl Server {
 
     pub fn need_peer_seeding(&self) -> bool { self.member_list.is_empty() }
 
+    /// Sign every message this server sends with `member_key`. Must be called before `start`.
+    pub fn set_member_key(&mut self, member_key: MemberKey) {
+        self.member_key = Arc::new(Some(member_key));
+    }
+
+    /// Carry gossip over `transport` rather than ZeroMQ. Must be called before `start`.
+    pub fn set_gossip_transport(&mut self, transport: Box<dyn GossipTransport>) {
+        self.gossip_transport = Arc::new(transport);
+    }
+
+    /// The transport gossip is carried over.
+    pub fn gossip_transport(&self) -> &dyn GossipTransport { &**self.gossip_transport }
+
+    /// Only process messages signed by a member key that `member_trust` accepts. Must be called
+    /// before `start`.
+    pub fn set_member_trust(&mut self, member_trust: MemberTrust) {
+        self.member_trust = Arc::new(Some(member_trust));
+    }
+
+    /// Only store and spread the configuration and files `author_check` accepts. Must be called
+    /// before `start`.
+    pub fn set_author_check(&mut self, author_check: Box<dyn AuthorCheck>) {
+        self.author_check = Arc::new(Some(author_check));
+    }
+
+    /// Sign `rumor` as originating from this member, if it has a member key.
+    pub(crate) fn sign_origin<T>(&self, rumor: &mut T)
+        where T: OriginRumor
+    {
+        if let Some(ref member_key) = *self.member_key {
+            if let Err(e) = member_key.sign_origin(self.member_id(), rumor) {
+                error!("Could not sign {} rumor: {}", rumor.kind(), e);
+            }
+        }
+    }
+
+    /// If only some member keys are trusted, check that one of them signed `rumor` as the member
+    /// it originated from created it, and that the key speaks for that member.
+    pub(crate) fn verify_origin<T>(&self, rumor: &T) -> Result<()>
+        where T: OriginRumor
+    {
+        match *self.member_trust {
+            Some(ref member_trust) => {
+                let origin = member_trust.verify_origin(rumor)?;
+                self.member_bindings.bind(&origin.member_id, &origin.signer)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Check that `signer`, the verified signer of a message from `member_id`, speaks for it.
+    pub(crate) fn verify_sender(&self, member_id: &str, signer: Option<&str>) -> Result<()> {
+        match signer {
+            Some(signer) => self.member_bindings.bind(member_id, signer),
+            None => Ok(()),
+        }
+    }
+
+    /// Also decrypt with `ring_key`, another revision of the ring key, and move to it once every
+    /// member yet to depart accepts it. Returns `false` if the revision is not of this ring, or is
+    /// already held or retired.
+    pub fn add_ring_key(&self, ring_key: SymKey) -> bool {
+        self.ring_keys
+            .write()
+            .expect("Ring keys lock is poisoned")
+            .add(ring_key)
+    }
+
+    /// The revision of the ring key messages are currently encrypted with, if the ring is
+    /// encrypted.
+    pub fn ring_key(&self) -> Option<SymKey> {
+        self.ring_keys
+            .read()
+            .expect("Ring keys lock is poisoned")
+            .current()
+            .cloned()
+    }
+
+    fn record_ring_key_usage(&self, member_id: &str, usage: Option<RingKeyUsage>) {
+        if let Some(usage) = usage {
+            self.ring_keys
+                .write()
+                .expect("Ring keys lock is poisoned")
+                .record(member_id, usage);
+        }
+    }
+
+    /// Move to a newer revision of the ring key, or retire older ones, based on what every member
+    /// yet to depart has been seen using.
+    fn refresh_ring_keys(&self) {
+        let members = self.member_list.undeparted_ids(&self.member_id);
+        self.ring_keys
+            .write()
+            .expect("Ring keys lock is poisoned")
+            .refresh(members.iter().map(String::as_str));
+    }
+
     /// Persistently block a given address, causing no traffic to be seen.
     pub fn add_to_block_list(&self, member_id: String) {
         let mut block_list = self.block_list
@@ -545,6 +691,9 @@ impl Server {
     /// Stop the outbound and inbound threads from processing work.
     pub fn pause(&mut self) { self.pause.compare_and_swap(false, true, Ordering::Relaxed); }
 
+    /// Let the outbound and inbound threads process work again after a `pause`.
+    pub fn unpause(&mut self) { self.pause.compare_and_swap(true, false, Ordering::Relaxed); }
+
     /// Whether this server is currently paused.
     pub fn paused(&self) -> bool { self.pause.load(Ordering::Relaxed) }
 
@@ -566,16 +715,51 @@ impl Server {
     /// Return the name of this server.
     pub fn name(&self) -> &str { &self.name }
 
+    /// How badly this server's own probes have been going, from 0 (healthy) up; see
+    /// `lifeguard::LocalHealth`.
+    pub fn local_health(&self) -> usize { self.local_health.score() }
+
+    /// For each member of the ring, the number of rumors that are
+    /// still "hot" for it, i.e., that will be gossiped to it again.
+    pub fn hot_rumor_counts(&self) -> Vec<(String, usize)> {
+        let mut member_ids = Vec::new();
+        self.member_list
+            .with_members(|member| member_ids.push(member.id.clone()));
+        member_ids.into_iter()
+                  .map(|id| {
+                      let hot = self.rumor_heat.currently_hot_rumors(&id).len();
+                      (id, hot)
+                  })
+                  .collect()
+    }
+
+    /// The number of rumors this server is gossiping.
+    pub fn rumor_count(&self) -> usize { self.rumor_heat.rumor_count() }
+
     /// Insert a member to the `MemberList`, and update its `RumorKey` appropriately.
     pub fn insert_member(&self, member: Member, health: Health) {
-        let rk: RumorKey = RumorKey::from(&member);
+        self.insert_membership(Membership { member,
+                                            health,
+                                            suspected_by: None })
+    }
+
+    /// Mark a member suspect after it failed one of our probes.
+    fn suspect_member(&self, member: Member) {
+        self.insert_membership(Membership { member,
+                                            health: Health::Suspect,
+                                            suspected_by: Some(self.member_id().to_string()) })
+    }
+
+    fn insert_membership(&self, membership: Membership) {
+        let rk: RumorKey = RumorKey::from(&membership.member);
         // NOTE: This sucks so much right here. Check out how we allocate no matter what, because
         // of just how the logic goes. The value of the trace is really high, though, so we deal
         // with it as best we can, with our head held high.
-        let member_id = member.id.clone();
-        let trace_incarnation = member.incarnation;
+        let member_id = membership.member.id.clone();
+        let trace_incarnation = membership.member.incarnation;
+        let health = membership.health;
         let trace_health = health;
-        if self.member_list.insert(member, health) {
+        if self.member_list.insert_membership(membership) {
             trace_it!(MEMBERSHIP: self,
                       TraceKind::MemberUpdate,
                       member_id,
@@ -640,24 +824,30 @@ impl Server {
         }
     }
 
-    /// Given a membership record and some health, insert it into the Member List.
-    fn insert_member_from_rumor(&self, member: Member, mut health: Health) {
-        let rk: RumorKey = RumorKey::from(&member);
-        if member.id == self.member_id() && health != Health::Alive {
+    /// Given a membership record, insert it into the Member List.
+    ///
+    /// Having to refute a rumor that we are not alive is taken as a sign that we are slow to
+    /// respond to our peers, and counts against our local health.
+    fn insert_member_from_rumor(&self, mut membership: Membership) {
+        let rk: RumorKey = RumorKey::from(&membership.member);
+        if membership.member.id == self.member_id() && membership.health != Health::Alive {
             let mut me = self.member.write().expect("Member lock is poisoned");
-            if member.incarnation >= me.incarnation() {
-                me.refute_incarnation(member.incarnation);
-                health = Health::Alive;
+            if membership.member.incarnation >= me.incarnation() {
+                self.local_health.apply(1);
+                me.refute_incarnation(membership.member.incarnation);
+                membership.health = Health::Alive;
+                membership.suspected_by = None;
             }
         }
         // NOTE: This sucks so much right here. Check out how we allocate no matter what, because
         // of just how the logic goes. The value of the trace is really high, though, so we carry
         // on, knowing life is still worth living.
-        let member_id = member.id.clone();
-        let trace_incarnation = member.incarnation;
+        let member_id = membership.member.id.clone();
+        let trace_incarnation = membership.member.incarnation;
+        let health = membership.health;
         let trace_health = health;
 
-        if self.member_list.insert(member, health) {
+        if self.member_list.insert_membership(membership) {
             trace_it!(MEMBERSHIP: self,
                       TraceKind::MemberUpdate,
                       member_id,
@@ -682,7 +872,12 @@ impl Server {
     ///
     /// See https://github.com/habitat-sh/habitat/issues/1994
     /// See Server::check_quorum
-    pub fn insert_service(&self, service: Service) {
+    ///
+    /// This member's own services are signed as originating from it.
+    pub fn insert_service(&self, mut service: Service) {
+        if service.member_id == self.member_id() {
+            self.sign_origin(&mut service);
+        }
         Self::insert_service_impl(service,
                                   &self.service_store,
                                   &self.member_list,
@@ -721,16 +916,33 @@ impl Server {
         }
     }
 
-    /// Insert a service config rumor into the service store.
+    /// Insert a service config rumor into the service store, if its author is accepted.
     pub fn insert_service_config(&self, service_config: ServiceConfig) {
+        if let Some(ref author_check) = *self.author_check {
+            if let Err(e) = author_check.check_service_config(&service_config) {
+                warn!("Ignoring configuration version {} for {}: {}",
+                      service_config.incarnation, service_config.service_group, e);
+                return;
+            }
+        }
         let rk = RumorKey::from(&service_config);
         if self.service_config_store.insert(service_config) {
             self.rumor_heat.start_hot_rumor(rk);
         }
     }
 
-    /// Insert a service file rumor into the service file store.
+    /// Insert a service file rumor into the service file store, if its author is accepted.
     pub fn insert_service_file(&self, service_file: ServiceFile) {
+        if let Some(ref author_check) = *self.author_check {
+            if let Err(e) = author_check.check_service_file(&service_file) {
+                warn!("Ignoring service file {} version {} for {}: {}",
+                      service_file.filename,
+                      service_file.incarnation,
+                      service_file.service_group,
+                      e);
+                return;
+            }
+        }
         let rk = RumorKey::from(&service_file);
         if self.service_file_store.insert(service_file) {
             self.rumor_heat.start_hot_rumor(rk);
@@ -756,6 +968,15 @@ impl Server {
         }
     }
 
+    /// Insert a new revision of the ring key into the ring key store. Adding it to the ring keys
+    /// is left to the caller, who knows where to keep it.
+    pub fn insert_ring_key(&self, ring_key: RingKey) {
+        let rk = RumorKey::from(&ring_key);
+        if self.ring_key_store.insert(ring_key) {
+            self.rumor_heat.start_hot_rumor(rk);
+        }
+    }
+
     /// Get all the Member ID's who are present in a given service group, and eligible to vote
     /// (alive)
     fn get_electorate(&self, key: &str) -> Vec<String> {
@@ -1119,11 +1340,17 @@ impl Server {
     }
 
     fn generate_wire(&self, payload: Vec<u8>) -> Result<Vec<u8>> {
-        message::generate_wire(payload, (*self.ring_key).as_ref())
+        message::generate_wire(payload,
+                               &self.ring_keys.read().expect("Ring keys lock is poisoned"),
+                               (*self.member_key).as_ref())
     }
 
-    fn unwrap_wire(&self, payload: &[u8]) -> Result<Vec<u8>> {
-        message::unwrap_wire(payload, (*self.ring_key).as_ref())
+    fn unwrap_wire(&self,
+                   payload: &[u8])
+                   -> Result<(Vec<u8>, Option<RingKeyUsage>, Option<String>)> {
+        message::unwrap_wire(payload,
+                             &self.ring_keys.read().expect("Ring keys lock is poisoned"),
+                             (*self.member_trust).as_ref())
     }
 
     pub fn persist_data(&self) {
@@ -1145,7 +1372,7 @@ impl Serialize for Server {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
         where S: Serializer
     {
-        let mut strukt = serializer.serialize_struct("butterfly", 7)?;
+        let mut strukt = serializer.serialize_struct("butterfly", 8)?;
         strukt.serialize_field("member", &self.member_list)?;
         strukt.serialize_field("service", &self.service_store)?;
         strukt.serialize_field("service_config", &self.service_config_store)?;
@@ -1153,6 +1380,8 @@ impl Serialize for Server {
         strukt.serialize_field("election", &self.election_store)?;
         strukt.serialize_field("election_update", &self.update_store)?;
         strukt.serialize_field("departure", &self.departure_store)?;
+        strukt.serialize_field("ring_keys",
+                               &*self.ring_keys.read().expect("Ring keys lock is poisoned"))?;
         strukt.end()
     }
 }
@@ -1213,7 +1442,7 @@ impl<'a> Serialize for ServerProxy<'a> {
         let sfsp = RumorStoreProxy::new(&self.0.service_file_store);
         let mlp = MemberListProxy::new(&self.0.member_list);
 
-        let mut strukt = serializer.serialize_struct("butterfly_server", 7)?;
+        let mut strukt = serializer.serialize_struct("butterfly_server", 15)?;
         strukt.serialize_field("member", &self.0.member_list)?;
         strukt.serialize_field("membership", &mlp)?;
         strukt.serialize_field("service", &self.0.service_store)?;
@@ -1228,6 +1457,8 @@ impl<'a> Serialize for ServerProxy<'a> {
         strukt.serialize_field("latest_election_update", &eusp)?;
         strukt.serialize_field("departure", &self.0.departure_store)?;
         strukt.serialize_field("departed_members", &dsp)?;
+        strukt.serialize_field("ring_keys",
+                               &*self.0.ring_keys.read().expect("Ring keys lock is poisoned"))?;
         strukt.end()
     }
 }
@@ -1311,7 +1542,10 @@ mod tests {
                   initialized:   Default::default(),
                   pkg:           Default::default(),
                   cfg:           Default::default(),
-                  sys:           Default::default(), }
+                  sys:           Default::default(),
+                  canary:        Default::default(),
+                  ready:         Default::default(),
+                  origin:        Default::default(), }
     }
 
     #[test]
@@ -2,7 +2,8 @@
 //!
 //! This module handles the implementation of the swim probe protocol.
 
-use std::{fmt,
+use std::{collections::HashSet,
+          fmt,
           net::{SocketAddr,
                 UdpSocket},
           sync::mpsc,
@@ -16,7 +17,8 @@ use prometheus::{HistogramTimer,
                  IntGaugeVec};
 use time::SteadyTime;
 
-use super::AckReceiver;
+use super::{inbound::ProbeReply,
+            ReplyReceiver};
 use crate::{member::{Health,
                      Member},
             rumor::{RumorKey,
@@ -24,6 +26,7 @@ use crate::{member::{Health,
             server::{timing::Timing,
                      Server},
             swim::{Ack,
+                   Nack,
                    Ping,
                    PingReq,
                    Swim},
@@ -70,23 +73,34 @@ impl fmt::Display for AckFrom {
 pub struct Outbound {
     pub server:     Server,
     pub socket:     UdpSocket,
-    pub rx_inbound: AckReceiver,
+    pub rx_inbound: ReplyReceiver,
     pub timing:     Timing,
+    /// Members that have sent us a Nack, and so can be expected to send one whenever a ping they
+    /// send for us goes unanswered. Members that predate Nacks never do.
+    nack_senders:   HashSet<String>,
+    /// The members that have sent a Nack for the member being probed.
+    nacks:          HashSet<String>,
 }
 
 impl Outbound {
     /// Creates a new Outbound struct.
     pub fn new(server: Server,
                socket: UdpSocket,
-               rx_inbound: AckReceiver,
+               rx_inbound: ReplyReceiver,
                timing: Timing)
                -> Outbound {
         Outbound { server,
                    socket,
                    rx_inbound,
-                   timing }
+                   timing,
+                   nack_senders: HashSet::new(),
+                   nacks: HashSet::new() }
     }
 
+    /// Our timing, stretched by our local health; the worse our own probes have been going, the
+    /// longer we give members to respond to them.
+    fn timing(&self) -> Timing { self.server.local_health.stretch(&self.timing) }
+
     /// Run the outbound thread. Gets a list of members to ping, then walks the list, probing each
     /// member.
     ///
@@ -123,8 +137,9 @@ impl Outbound {
             }
 
             self.server.update_swim_round();
+            self.server.refresh_ring_keys();
 
-            let long_wait = self.timing.next_protocol_period();
+            let long_wait = self.timing().next_protocol_period();
 
             let check_list = self.server.member_list.check_list(&self.server.member_id);
 
@@ -133,7 +148,7 @@ impl Outbound {
                     // This is the timeout for the next protocol period - if we
                     // complete faster than this, we want to wait in the end
                     // until this timer expires.
-                    let next_protocol_period = self.timing.next_protocol_period();
+                    let next_protocol_period = self.timing().next_protocol_period();
 
                     self.probe(member);
 
@@ -172,11 +187,16 @@ impl Outbound {
     /// PING_RECV_QUEUE_EMPTY_SLEEP_MS, and try again.
     ///
     /// If we don't receive anything at all in the Ping/PingReq loop, we mark the member as Suspect.
+    ///
+    /// Each probe also updates our local health. An ack to our ping is a sign we are keeping up,
+    /// while a probe that fails is a sign we may not be, unless the members we sent PingReqs to
+    /// sent back Nacks to show that we can still hear them.
     fn probe(&mut self, member: Member) {
         let pa_timer = SWIM_PROBE_DURATION.with_label_values(&["ping/ack"])
                                           .start_timer();
         let mut pr_timer: Option<HistogramTimer> = None;
         let addr = member.swim_socket_address();
+        self.nacks.clear();
 
         trace_it!(PROBE: &self.server, TraceKind::ProbeBegin, &member.id, addr);
 
@@ -189,9 +209,11 @@ impl Outbound {
             trace_it!(PROBE: &self.server, TraceKind::ProbeComplete, &member.id, addr);
             SWIM_PROBES_SENT.with_label_values(&["ack"]).inc();
             pa_timer.observe_duration();
+            self.server.local_health.apply(-1);
             return;
         }
 
+        let mut expected_nacks = Vec::new();
         self.server.member_list.with_pingreq_targets(
             self.server.member_id(),
             &member.id,
@@ -206,6 +228,9 @@ impl Outbound {
                         .with_label_values(&["pingreq/ack"])
                         .start_timer(),
                 );
+                if self.nack_senders.contains(&pingreq_target.id) {
+                    expected_nacks.push(pingreq_target.id.clone());
+                }
                 pingreq(&self.server, &self.socket, pingreq_target, &member);
             },
         );
@@ -220,7 +245,18 @@ impl Outbound {
             warn!("Marking {} as Suspect", &member.id);
             trace_it!(PROBE: &self.server, TraceKind::ProbeSuspect, &member.id, addr);
             trace_it!(PROBE: &self.server, TraceKind::ProbeComplete, &member.id, addr);
-            self.server.insert_member(member, Health::Suspect);
+            // A persistent member we have already confirmed is expected to fail its probes.
+            if !self.server.member_list.persistent_and_confirmed(&member) {
+                let missed_nacks = expected_nacks.iter()
+                                                 .filter(|id| !self.nacks.contains(*id))
+                                                 .count();
+                if expected_nacks.is_empty() {
+                    self.server.local_health.apply(1);
+                } else {
+                    self.server.local_health.apply(missed_nacks as isize);
+                }
+            }
+            self.server.suspect_member(member);
             SWIM_PROBES_SENT.with_label_values(&["pingreq/failure"])
                             .inc();
         }
@@ -230,15 +266,21 @@ impl Outbound {
         }
     }
 
-    /// Listen for an ack from the `Inbound` thread.
+    /// Listen for an ack from the `Inbound` thread, noting any Nacks that arrive meanwhile.
     fn recv_ack(&mut self, member: &Member, addr: SocketAddr, ack_from: AckFrom) -> bool {
         let timeout = match ack_from {
-            AckFrom::Ping => self.timing.ping_timeout(),
-            AckFrom::PingReq => self.timing.pingreq_timeout(),
+            AckFrom::Ping => self.timing().ping_timeout(),
+            AckFrom::PingReq => self.timing().pingreq_timeout(),
         };
         loop {
             match self.rx_inbound.try_recv() {
-                Ok((real_addr, mut ack)) => {
+                Ok((_, ProbeReply::Nack(nack))) => {
+                    self.nack_senders.insert(nack.from.id.clone());
+                    if nack.target.id == member.id {
+                        self.nacks.insert(nack.from.id);
+                    }
+                }
+                Ok((real_addr, ProbeReply::Ack(mut ack))) => {
                     // If this was forwarded to us, we want to retain the address of the member who
                     // sent the ack, not the one we received on the socket.
                     if ack.forward_to.is_none() {
@@ -315,7 +357,8 @@ pub fn populate_membership_rumors(server: &Server, target: &Member, swim: &mut S
 pub fn pingreq(server: &Server, socket: &UdpSocket, pingreq_target: &Member, target: &Member) {
     let pingreq = PingReq { membership: vec![],
                             from:       server.member.read().unwrap().as_member(),
-                            target:     target.clone(), };
+                            target:     target.clone(),
+                            nack:       true, };
     let mut swim: Swim = pingreq.into();
     let addr = pingreq_target.swim_socket_address();
     populate_membership_rumors(server, target, &mut swim);
@@ -434,6 +477,44 @@ pub fn forward_ack(server: &Server, socket: &UdpSocket, addr: SocketAddr, msg: A
     }
 }
 
+/// Send a Nack, telling the member that sent us a PingReq for `target` that it didn't ack in
+/// time.
+pub fn nack(server: &Server, socket: &UdpSocket, requester: &Member, target: &Member) {
+    let nack = Nack { membership: vec![],
+                      from:       server.member.read().unwrap().as_member(),
+                      target:     target.clone(), };
+    let addr = requester.swim_socket_address();
+    let swim: Swim = nack.into();
+    let bytes = match swim.clone().encode() {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Generating protocol message failed: {}", e);
+            return;
+        }
+    };
+    let payload = match server.generate_wire(bytes) {
+        Ok(payload) => payload,
+        Err(e) => {
+            error!("Generating protocol message failed: {}", e);
+            return;
+        }
+    };
+    match socket.send_to(&payload, addr) {
+        Ok(_s) => {
+            let label_values = &["nack"];
+            SWIM_MESSAGES_SENT.with_label_values(label_values).inc();
+            SWIM_BYTES_SENT.with_label_values(label_values)
+                           .set(payload.len().to_i64());
+            trace!("Sent nack to {}@{} for {}", &requester.id, addr, &target.id);
+        }
+        Err(e) => {
+            error!("Failed nack to {}@{} for {}: {}",
+                   &requester.id, addr, &target.id, e)
+        }
+    }
+    trace_it!(SWIM: server, TraceKind::SendNack, &requester.id, addr, &swim);
+}
+
 /// Send an Ack.
 pub fn ack(server: &Server,
            socket: &UdpSocket,
@@ -1,6 +1,6 @@
 //! The pull thread.
 //!
-//! This module handles pulling all the pushed rumors from every member off a ZMQ socket.
+//! This module handles pulling all the pushed rumors from every member off the gossip transport.
 
 use std::{thread,
           time::Duration};
@@ -8,13 +8,18 @@ use std::{thread,
 use habitat_core::util::ToI64;
 use prometheus::{IntCounterVec,
                  IntGaugeVec};
-use zmq;
 
-use crate::{rumor::{RumorEnvelope,
-                    RumorKind},
+use crate::{error::{Error,
+                    Result},
+            rumor::{RumorEnvelope,
+                    RumorKind,
+                    RumorType},
             server::Server,
             trace::TraceKind,
-            ZMQ_CONTEXT};
+            trust::OriginRumor};
+
+/// The member ID a `Client` sends its rumors with.
+const CLIENT_ID: &str = "butterflyclient";
 
 lazy_static! {
     static ref GOSSIP_MESSAGES_RECEIVED: IntCounterVec =
@@ -36,24 +41,22 @@ impl Pull {
     /// Create a new Pull
     pub fn new(server: Server) -> Pull { Pull { server } }
 
-    /// Run this thread. Creates a socket, binds to the `gossip_addr`, then processes messages as
-    /// they are received. Uses a ZMQ pull socket, so inbound messages are fair-queued.
+    /// Run this thread. Binds the gossip transport to the `gossip_addr`, then processes messages
+    /// as they are received from every member.
     pub fn run(&mut self) {
         habitat_core::env_config_int!(RecvTimeoutMillis, i32, HAB_PULL_RECV_TIMEOUT_MS, 5_000);
 
-        let socket = (**ZMQ_CONTEXT).as_mut()
-                                    .socket(zmq::PULL)
-                                    .expect("Failure to create the ZMQ pull socket");
-        socket.set_linger(0)
-              .expect("Failure to set the ZMQ Pull socket to not linger");
-        socket.set_tcp_keepalive(0)
-              .expect("Failure to set the ZMQ Pull socket to not use keepalive");
-        socket.set_rcvtimeo(RecvTimeoutMillis::configured_value().into())
-              .expect("Failure to set the ZMQ Pull socket receive timeout");
-        socket.bind(&format!("tcp://{}", self.server.gossip_addr()))
-              .expect("Failure to bind the ZMQ Pull socket to the port");
+        // A negative timeout waits forever
+        let recv_timeout = match i32::from(RecvTimeoutMillis::configured_value()) {
+            millis if millis < 0 => None,
+            millis => Some(Duration::from_millis(millis as u64)),
+        };
+        let mut listener = self.server
+                               .gossip_transport()
+                               .listen(*self.server.gossip_addr(), recv_timeout)
+                               .expect("Failure to bind the gossip listener to the port");
         'recv: loop {
-            if let Ok(-1) = socket.get_rcvtimeo() {
+            if recv_timeout.is_none() {
                 trace!("Skipping thread liveliness checks due to infinite recv timeout");
             } else {
                 habitat_common::sync::mark_thread_alive();
@@ -64,20 +67,32 @@ impl Pull {
                 continue;
             }
 
-            let msg = match socket.recv_msg(0) {
-                Ok(msg) => msg,
+            let msg = match listener.recv() {
+                Ok(Some(msg)) => msg,
+                // We intentionally set a timeout above so that `mark_thread_alive` can be used to
+                // show this thread is alive even when there's no data to receive.
+                Ok(None) => continue 'recv,
                 Err(e) => {
-                    // We intentionally set a timeout above so that `mark_thread_alive` can be
-                    // used to show this thread is alive even when there's no data to receive.
-                    if e != zmq::Error::EAGAIN {
-                        error!("Error receiving message: {:?}", e);
-                    }
+                    error!("Error receiving message: {}", e);
                     continue 'recv;
                 }
             };
 
-            let payload = match self.server.unwrap_wire(&msg) {
-                Ok(payload) => payload,
+            let (payload, ring_key_usage, signer) = match self.server.unwrap_wire(&msg) {
+                Ok(unwrapped) => unwrapped,
+                Err(Error::UntrustedSender(signer, reason)) => {
+                    warn!("Rejecting rumor signed by {}: {}", signer, reason);
+                    trace_it!(UNTRUSTED: &self.server,
+                              TraceKind::RecvUntrusted,
+                              &signer,
+                              &reason);
+                    let label_values = &["untrusted", "failure", "unknown"];
+                    GOSSIP_BYTES_RECEIVED.with_label_values(label_values)
+                                         .set(msg.len().to_i64());
+                    GOSSIP_MESSAGES_RECEIVED.with_label_values(label_values)
+                                            .inc();
+                    continue;
+                }
                 Err(e) => {
                     // NOTE: In the future, we might want to block people who send us
                     // garbage all the time.
@@ -120,17 +135,32 @@ impl Pull {
             }
 
             trace_it!(GOSSIP: &self.server, TraceKind::RecvRumor, &proto.from_id, &proto);
+            self.server
+                .record_ring_key_usage(&proto.from_id, ring_key_usage);
             match proto.kind {
                 RumorKind::Membership(membership) => {
-                    self.server
-                        .insert_member_from_rumor(membership.member, membership.health);
+                    // Membership rumors are always sent by the member they are from
+                    let sender = signer.as_ref().map(String::as_str);
+                    if self.accept(RumorType::Member,
+                                   self.server.verify_sender(&proto.from_id, sender))
+                    {
+                        self.server.insert_member_from_rumor(membership);
+                    }
                 }
-                RumorKind::Service(service) => self.server.insert_service(*service),
-                RumorKind::ServiceConfig(service_config) => {
-                    self.server.insert_service_config(service_config);
+                RumorKind::Service(mut service) => {
+                    if self.accept_origin(&proto.from_id, &mut *service) {
+                        self.server.insert_service(*service);
+                    }
+                }
+                RumorKind::ServiceConfig(mut service_config) => {
+                    if self.accept_origin(&proto.from_id, &mut service_config) {
+                        self.server.insert_service_config(service_config);
+                    }
                 }
-                RumorKind::ServiceFile(service_file) => {
-                    self.server.insert_service_file(service_file);
+                RumorKind::ServiceFile(mut service_file) => {
+                    if self.accept_origin(&proto.from_id, &mut service_file) {
+                        self.server.insert_service_file(service_file);
+                    }
                 }
                 RumorKind::Election(election) => {
                     self.server.insert_election(election);
@@ -138,10 +168,47 @@ impl Pull {
                 RumorKind::ElectionUpdate(election) => {
                     self.server.insert_update_election(election);
                 }
-                RumorKind::Departure(departure) => {
-                    self.server.insert_departure(departure);
+                RumorKind::Departure(mut departure) => {
+                    if self.accept_origin(&proto.from_id, &mut departure) {
+                        self.server.insert_departure(departure);
+                    }
+                }
+                RumorKind::RingKey(mut ring_key) => {
+                    if self.accept_origin(&proto.from_id, &mut ring_key) {
+                        self.server.insert_ring_key(ring_key);
+                    }
                 }
             }
         }
     }
+
+    /// Sign a rumor a client put into the ring through this member as originating from this
+    /// member, and check where any other rumor originated. Returns whether to insert the rumor.
+    fn accept_origin<T>(&self, from_id: &str, rumor: &mut T) -> bool
+        where T: OriginRumor
+    {
+        if from_id == CLIENT_ID && rumor.origin().is_none() {
+            self.server.sign_origin(rumor);
+            return true;
+        }
+        self.accept(rumor.kind(), self.server.verify_origin(rumor))
+    }
+
+    fn accept(&self, kind: RumorType, verified: Result<()>) -> bool {
+        match verified {
+            Ok(()) => true,
+            Err(Error::UntrustedSender(signer, reason)) => {
+                warn!("Rejecting {} rumor signed by {}: {}", kind, signer, reason);
+                trace_it!(UNTRUSTED: &self.server,
+                          TraceKind::RecvUntrusted,
+                          &signer,
+                          &reason);
+                false
+            }
+            Err(e) => {
+                error!("Could not verify {} rumor: {}", kind, e);
+                false
+            }
+        }
+    }
 }
@@ -10,18 +10,15 @@ use habitat_core::util::ToI64;
 use prometheus::{IntCounterVec,
                  IntGaugeVec};
 use time::SteadyTime;
-use zmq;
 
-use crate::{member::{Member,
-                     Membership},
+use crate::{member::Member,
             rumor::{RumorEnvelope,
                     RumorKey,
                     RumorKind,
                     RumorType},
             server::{timing::Timing,
                      Server},
-            trace::TraceKind,
-            ZMQ_CONTEXT};
+            trace::TraceKind};
 
 const FANOUT: usize = 5;
 
@@ -138,33 +135,22 @@ impl PushWorker {
     /// Create a new PushWorker.
     pub fn new(server: Server) -> PushWorker { PushWorker { server } }
 
-    /// Send the list of rumors to a given member. This method creates an outbound socket and then
-    /// closes the connection as soon as we are done sending rumors. ZeroMQ may choose to keep the
-    /// connection and socket open for 1 second longer - so it is possible, but unlikely, that this
-    /// method can lose messages.
+    /// Send the list of rumors to a given member over the gossip transport. With ZeroMQ, the
+    /// socket may stay open for 1 second after we are done sending rumors, so it is possible, but
+    /// unlikely, that this method can lose messages.
     // If we ever need to modify this function, it would be an excellent opportunity to
     // simplify the redundant aspects and remove this allow(clippy::cyclomatic_complexity),
     // but changing it in the absence of other necessity seems like too much risk for the
     // expected reward.
     fn send_rumors(&self, member: &Member, rumors: &[RumorKey]) {
-        let socket = (**ZMQ_CONTEXT).as_mut()
-                                    .socket(zmq::PUSH)
-                                    .expect("Failure to create the ZMQ push socket");
-        socket.set_linger(1000)
-              .expect("Failure to set the ZMQ push socket to not linger");
-        socket.set_tcp_keepalive(0)
-              .expect("Failure to set the ZMQ push socket to not use keepalive");
-        socket.set_immediate(true)
-              .expect("Failure to set the ZMQ push socket to immediate");
-        socket.set_sndhwm(1000)
-              .expect("Failure to set the ZMQ push socket hwm");
-        socket.set_sndtimeo(500)
-              .expect("Failure to set the ZMQ send timeout");
-        let to_addr = format!("{}:{}", member.address, member.gossip_port);
-        match socket.connect(&format!("tcp://{}", to_addr)) {
-            Ok(()) => debug!("Connected push socket to {:?}", member),
+        let to_addr = member.gossip_socket_address();
+        let mut sender = match self.server.gossip_transport().connect(to_addr) {
+            Ok(sender) => {
+                debug!("Connected to {:?}", member);
+                sender
+            }
             Err(e) => {
-                error!("Cannot connect push socket to {:?}: {:?}", member, e);
+                error!("Cannot connect to {:?}: {}", member, e);
                 let label_values = &["socket_connect", "failure"];
                 GOSSIP_MESSAGES_SENT.with_label_values(label_values).inc();
                 GOSSIP_BYTES_SENT.with_label_values(label_values).set(0);
@@ -275,6 +261,23 @@ impl PushWorker {
                         }
                     }
                 }
+                RumorType::RingKey => {
+                    match self.server
+                              .ring_key_store
+                              .encode(&rumor_key.key, &rumor_key.id)
+                    {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            error!("Could not write our own rumor to bytes; abandoning sending \
+                                    rumor: {:?}",
+                                   e);
+                            let label_values = &["ring_key_rumor_encode", "failure"];
+                            GOSSIP_MESSAGES_SENT.with_label_values(label_values).inc();
+                            GOSSIP_BYTES_SENT.with_label_values(label_values).set(0);
+                            continue 'rumorlist;
+                        }
+                    }
+                }
                 RumorType::Election => {
                     // trace_it!(GOSSIP: &self.server,
                     //           TraceKind::SendRumor,
@@ -330,7 +333,7 @@ impl PushWorker {
                     continue 'rumorlist;
                 }
             };
-            match socket.send(&payload, 0) {
+            match sender.send(&payload) {
                 Ok(()) => {
                     GOSSIP_MESSAGES_SENT.with_label_values(&[&rumor_key.kind.to_string(),
                                                              "success"])
@@ -340,8 +343,12 @@ impl PushWorker {
                     debug!("Sent rumor {:?} to {:?}", rumor_key, member);
                 }
                 Err(e) => {
-                    warn!("Could not send rumor to {:?} @ {:?}; ZMQ said: {:?}",
-                          member.id, to_addr, e)
+                    warn!("Could not send rumor to {:?} @ {:?}: {}",
+                          member.id, to_addr, e);
+                    let label_values = &["send", "failure"];
+                    GOSSIP_MESSAGES_SENT.with_label_values(label_values).inc();
+                    GOSSIP_BYTES_SENT.with_label_values(label_values)
+                                     .set(payload.len().to_i64());
                 }
             }
         }
@@ -350,12 +357,7 @@ impl PushWorker {
 
     /// Given a rumorkey, creates a protobuf rumor for sharing.
     fn create_member_rumor(&self, rumor_key: &RumorKey) -> Option<RumorEnvelope> {
-        let member = self.server.member_list.get_cloned(&rumor_key.key())?;
-        let payload = Membership { member,
-                                   health: self.server
-                                               .member_list
-                                               .health_of_by_id(&rumor_key.key())
-                                               .unwrap() };
+        let payload = self.server.member_list.membership_for(&rumor_key.key())?;
         let rumor = RumorEnvelope { r#type:  RumorType::Member,
                                     from_id: self.server.member_id().to_string(),
                                     kind:    RumorKind::Membership(payload), };
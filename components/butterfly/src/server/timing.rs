@@ -5,8 +5,11 @@ use time::{Duration as TimeDuration,
 const PING_TIMING_DEFAULT_MS: i64 = 1000;
 /// How long to wait for an Ack after we PingReq - should be at least 2x the PING_TIMING_DEFAULT_MS
 const PINGREQ_TIMING_DEFAULT_MS: i64 = 2100;
-/// How many protocol periods before a suspect member is marked as confirmed.
+/// How many protocol periods before a suspect member is marked as confirmed, once enough other
+/// members have confirmed the suspicion.
 const SUSPICION_TIMEOUT_DEFAULT_PROTOCOL_PERIODS: i64 = 3;
+/// How many times longer than that a suspect member has when no other member confirms it
+const SUSPICION_MAX_TIMEOUT_DEFAULT_MULTIPLIER: i64 = 4;
 /// How long is the gossip period
 const GOSSIP_PERIOD_DEFAULT_MS: i64 = 1000;
 /// How long before we set a confirmed member to a departed member, removing them from quorums
@@ -20,6 +23,7 @@ pub struct Timing {
     pub pingreq_ms: i64,
     pub gossip_period_ms: i64,
     pub suspicion_timeout_protocol_periods: i64,
+    pub suspicion_max_timeout_multiplier: i64,
     pub departure_timeout_ms: i64,
 }
 
@@ -29,6 +33,7 @@ impl Default for Timing {
                  pingreq_ms: PINGREQ_TIMING_DEFAULT_MS,
                  gossip_period_ms: GOSSIP_PERIOD_DEFAULT_MS,
                  suspicion_timeout_protocol_periods: SUSPICION_TIMEOUT_DEFAULT_PROTOCOL_PERIODS,
+                 suspicion_max_timeout_multiplier: SUSPICION_MAX_TIMEOUT_DEFAULT_MULTIPLIER,
                  departure_timeout_ms: DEPARTURE_TIMEOUT_DEFAULT_MS, }
     }
 }
@@ -39,15 +44,25 @@ impl Timing {
                pingreq_ms: i64,
                gossip_period_ms: i64,
                suspicion_timeout_protocol_periods: i64,
+               suspicion_max_timeout_multiplier: i64,
                departure_timeout_ms: i64)
                -> Timing {
         Timing { ping_ms,
                  pingreq_ms,
                  gossip_period_ms,
                  suspicion_timeout_protocol_periods,
+                 suspicion_max_timeout_multiplier,
                  departure_timeout_ms }
     }
 
+    /// This timing with the ping and pingreq timeouts, and so the protocol period, `factor` times
+    /// as long.
+    pub fn stretched(&self, factor: i64) -> Timing {
+        Timing { ping_ms: self.ping_ms * factor,
+                 pingreq_ms: self.pingreq_ms * factor,
+                 ..self.clone() }
+    }
+
     /// When should this gossip period expire
     pub fn gossip_timeout(&self) -> SteadyTime {
         SteadyTime::now() + TimeDuration::milliseconds(self.gossip_period_ms)
@@ -77,6 +92,13 @@ impl Timing {
                                    * self.suspicion_timeout_protocol_periods)
     }
 
+    /// How long before this suspect entry times out, if no other member confirms it
+    pub fn max_suspicion_timeout_duration(&self) -> TimeDuration {
+        TimeDuration::milliseconds(self.protocol_period_ms()
+                                   * self.suspicion_timeout_protocol_periods
+                                   * self.suspicion_max_timeout_multiplier)
+    }
+
     pub fn departure_timeout_duration(&self) -> TimeDuration {
         TimeDuration::milliseconds(self.departure_timeout_ms)
     }
@@ -22,11 +22,14 @@ pub enum TraceKind {
     ProbeDeparted,
     ProbePingReq,
     RecvAck,
+    RecvNack,
     RecvPing,
     RecvPingReq,
     RecvRumor,
+    RecvUntrusted,
     SendAck,
     SendForwardAck,
+    SendNack,
     SendPing,
     SendPingReq,
     SendRumor,
@@ -45,11 +48,14 @@ impl fmt::Display for TraceKind {
             TraceKind::ProbeDeparted => write!(f, "ProbeDeparted"),
             TraceKind::ProbePingReq => write!(f, "ProbePingReq"),
             TraceKind::RecvAck => write!(f, "RecvAck"),
+            TraceKind::RecvNack => write!(f, "RecvNack"),
             TraceKind::RecvPing => write!(f, "RecvPing"),
             TraceKind::RecvPingReq => write!(f, "RecvPingReq"),
             TraceKind::RecvRumor => write!(f, "RecvRumor"),
+            TraceKind::RecvUntrusted => write!(f, "RecvUntrusted"),
             TraceKind::SendAck => write!(f, "SendAck"),
             TraceKind::SendForwardAck => write!(f, "SendForwardAck"),
+            TraceKind::SendNack => write!(f, "SendNack"),
             TraceKind::SendPing => write!(f, "SendPing"),
             TraceKind::SendPingReq => write!(f, "SendPingReq"),
             TraceKind::SendRumor => write!(f, "SendRumor"),
@@ -283,6 +289,25 @@ macro_rules! trace_it {
             trace.write(&tw);
         }
     }};
+    (UNTRUSTED: $server:expr, $msg_type:expr, $signer:expr, $reason:expr) => {{
+        let trace_on = $server.trace.read().expect("Trace lock is poisoned").on();
+        if trace_on {
+            use crate::trace::TraceWrite;
+            let mut trace = $server.trace.write().expect("Trace lock is poisoned");
+            trace.init($server);
+            let thread = thread::current();
+            let thread_name = thread.name().unwrap_or("undefined");
+            let member_id = $server.member_id();
+            let server_name = $server.name();
+
+            let mut tw = TraceWrite::new($msg_type, module_path!(), line!(), thread_name);
+            tw.server_name = Some(&server_name);
+            tw.member_id = Some(member_id);
+            tw.to_member_id = Some($signer);
+            tw.rumor = Some($reason);
+            trace.write(&tw);
+        }
+    }};
     (SWIM: $server:expr, $msg_type:expr, $to_member_id:expr, $to_addr:expr, $payload:expr) => {{
         let trace_on = $server.trace.read().expect("Trace lock is poisoned").on();
         if trace_on {
@@ -367,6 +392,7 @@ macro_rules! trace_it {
                     election.votes
                 ),
                 rumor::RumorKind::Departure(ref departure) => format!("{}", departure.member_id),
+                rumor::RumorKind::RingKey(ref ring_key) => ring_key.name_with_rev.to_string(),
             };
 
             let mut tw = TraceWrite::new($msg_type, module_path!(), line!(), thread_name);
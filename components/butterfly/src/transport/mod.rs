@@ -0,0 +1,49 @@
+//! Gossip transports.
+//!
+//! Rumors are pushed to members over a transport, and pulled off a listener bound to the gossip
+//! port. Two transports are provided:
+//!
+//! * `ZmqTransport`, which uses ZeroMQ PUSH and PULL sockets. Every Supervisor can speak it.
+//! * `TcpTransport`, which sends length-prefixed frames over plain TCP connections. It reuses
+//!   connections between gossip rounds, applies backpressure to senders when the receiver falls
+//!   behind, and reports connection and send failures as errors. It also accepts ZeroMQ traffic on
+//!   the same port, and falls back to ZeroMQ when sending to members that don't speak it, so a ring
+//!   can move to it one Supervisor at a time.
+
+mod tcp;
+mod zeromq;
+
+pub use self::{tcp::TcpTransport,
+               zeromq::ZmqTransport};
+
+use std::{fmt::Debug,
+          net::SocketAddr,
+          time::Duration};
+
+use crate::error::Result;
+
+/// A way of carrying gossip between members.
+pub trait GossipTransport: Debug + Send + Sync {
+    /// Bind to `addr` and receive the gossip sent there. `recv_timeout` bounds how long a call to
+    /// `GossipListener::recv` waits for a message; `None` waits forever.
+    fn listen(&self,
+              addr: SocketAddr,
+              recv_timeout: Option<Duration>)
+              -> Result<Box<dyn GossipListener>>;
+
+    /// Connect to the member gossiping at `addr`. The connection may be reused from an earlier
+    /// call.
+    fn connect(&self, addr: SocketAddr) -> Result<Box<dyn GossipSender>>;
+}
+
+/// Gossip received on a bound port, from every member sending to it.
+pub trait GossipListener: Send {
+    /// The next message received, or `None` if none arrived before the receive timeout.
+    fn recv(&mut self) -> Result<Option<Vec<u8>>>;
+}
+
+/// A connection to a single member.
+pub trait GossipSender: Send {
+    /// Send a message, failing if the member doesn't accept it in time.
+    fn send(&mut self, msg: &[u8]) -> Result<()>;
+}
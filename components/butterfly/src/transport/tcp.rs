@@ -0,0 +1,501 @@
+//! The native TCP gossip transport.
+//!
+//! A sender opens a connection by writing `MAGIC` followed by the highest protocol version it
+//! speaks; the listener answers with the version both will use. After that, each message is a
+//! 4-byte big-endian length followed by that many bytes.
+//!
+//! Members that only speak ZeroMQ open their connections with the ZMTP greeting instead, which
+//! never starts with `MAGIC`. The listener hands those connections to a ZeroMQ PULL socket bound
+//! to the loopback interface, so they are received alongside native ones. When sending, a member
+//! that answers the handshake with a ZMTP greeting, or hangs up, is sent to with ZeroMQ instead.
+//!
+//! Every connection is read on its own thread, into a queue of at most `RECV_QUEUE_DEPTH`
+//! messages. When the queue is full, those threads stop reading, and senders' writes time out
+//! after `WRITE_TIMEOUT`. At most `MAX_CONNECTIONS` connections are read at once; any more are
+//! closed as soon as they are accepted. Messages are read `READ_CHUNK_BYTES` at a time, so a
+//! sender can't make the listener hold more memory than it has actually sent.
+
+use std::{collections::HashMap,
+          io::{self,
+               Read,
+               Write},
+          net::{Shutdown,
+                SocketAddr,
+                TcpListener,
+                TcpStream},
+          result,
+          sync::{atomic::{AtomicUsize,
+                          Ordering},
+                 mpsc::{self,
+                        Receiver,
+                        RecvTimeoutError,
+                        SyncSender},
+                 Arc,
+                 Mutex},
+          thread,
+          time::{Duration,
+                 Instant}};
+
+use byteorder::{BigEndian,
+                ByteOrder};
+
+use super::{zeromq::ZmqTransport,
+            GossipListener,
+            GossipSender,
+            GossipTransport};
+use crate::error::{Error,
+                   Result};
+
+/// Opens every native connection. It can't be mistaken for a ZMTP greeting, which starts with
+/// 0xFF.
+const MAGIC: &[u8; 4] = b"HGSP";
+/// The highest protocol version spoken.
+const VERSION: u8 = 1;
+/// The first byte of a ZMTP 3 greeting.
+const ZMTP_SIGNATURE: u8 = 0xFF;
+/// Room for the largest configuration or file a rumor carries, 64 KiB, along with the rest of
+/// the rumor, its signatures and its encryption.
+const MAX_FRAME_BYTES: usize = 80 * 1024;
+const READ_CHUNK_BYTES: usize = 8 * 1024;
+/// The most connections a listener reads from at once. Each takes a thread, and ZeroMQ senders
+/// hold theirs open.
+const MAX_CONNECTIONS: usize = 512;
+const RECV_QUEUE_DEPTH: usize = 1000;
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(1000);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(1000);
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+/// How long a listener keeps a connection nothing is sent on.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long a sender keeps an unused connection for reuse. It is shorter than `IDLE_TIMEOUT`, so
+/// the listener is never the one to close it.
+const REUSE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to keep sending to a member with ZeroMQ before checking whether it has been upgraded.
+const ZMQ_PEER_RECHECK: Duration = Duration::from_secs(60);
+
+type IdleConnections = Arc<Mutex<HashMap<SocketAddr, Vec<(TcpStream, Instant)>>>>;
+
+/// Sends gossip over plain TCP connections, falling back to ZeroMQ for members that don't speak
+/// it.
+#[derive(Debug, Default)]
+pub struct TcpTransport {
+    idle:      IdleConnections,
+    /// Members that answered the handshake as ZeroMQ, and when they did.
+    zmq_peers: Mutex<HashMap<SocketAddr, Instant>>,
+    zmq:       ZmqTransport,
+}
+
+impl TcpTransport {
+    fn speaks_zmq(&self, addr: SocketAddr) -> bool {
+        let mut zmq_peers = self.zmq_peers.lock().expect("ZMQ peers lock is poisoned");
+        match zmq_peers.get(&addr) {
+            Some(since) if since.elapsed() < ZMQ_PEER_RECHECK => true,
+            Some(_) => {
+                zmq_peers.remove(&addr);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// An idle connection to `addr` the member hasn't closed, if there is one.
+    fn reuse(&self, addr: SocketAddr) -> Option<TcpStream> {
+        let mut idle = self.idle.lock().expect("Idle connections lock is poisoned");
+        let streams = idle.get_mut(&addr)?;
+        while let Some((stream, since)) = streams.pop() {
+            if since.elapsed() < REUSE_TIMEOUT && is_open(&stream) {
+                return Some(stream);
+            }
+        }
+        None
+    }
+}
+
+impl GossipTransport for TcpTransport {
+    fn listen(&self,
+              addr: SocketAddr,
+              recv_timeout: Option<Duration>)
+              -> Result<Box<dyn GossipListener>> {
+        let listener = TcpListener::bind(addr).map_err(Error::CannotBind)?;
+        let (tx, rx) = mpsc::sync_channel(RECV_QUEUE_DEPTH);
+
+        // Receives the ZeroMQ connections handed over by the listener. It waits forever; this
+        // thread has no liveness to report.
+        let mut zmq_listener = ZmqTransport::pull("127.0.0.1:*", None)?;
+        let zmq_endpoint = zmq_listener.endpoint()?;
+        let zmq_tx = tx.clone();
+        thread::Builder::new().name(String::from("pull-zmq"))
+                              .spawn(move || {
+                                  loop {
+                                      match zmq_listener.recv() {
+                                          Ok(Some(msg)) => {
+                                              if zmq_tx.send(msg).is_err() {
+                                                  break;
+                                              }
+                                          }
+                                          Ok(None) => {}
+                                          Err(e) => error!("Error receiving message: {}", e),
+                                      }
+                                  }
+                              })?;
+
+        thread::Builder::new().name(String::from("pull-accept"))
+                              .spawn(move || accept(&listener, &tx, &zmq_endpoint))?;
+
+        Ok(Box::new(TcpGossipListener { rx, recv_timeout }))
+    }
+
+    fn connect(&self, addr: SocketAddr) -> Result<Box<dyn GossipSender>> {
+        if self.speaks_zmq(addr) {
+            return self.zmq.connect(addr);
+        }
+        if let Some(stream) = self.reuse(addr) {
+            return Ok(Box::new(TcpSender { addr,
+                                           stream: Some(stream),
+                                           idle: self.idle.clone() }));
+        }
+
+        let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+            .map_err(|e| Error::GossipConnect(addr.to_string(), e))?;
+        match handshake(&stream) {
+            Ok(Some(version)) => {
+                debug!("Speaking gossip protocol version {} with {}", version, addr);
+                Ok(Box::new(TcpSender { addr,
+                                        stream: Some(stream),
+                                        idle: self.idle.clone() }))
+            }
+            Ok(None) => {
+                debug!("{} only speaks ZeroMQ gossip", addr);
+                self.zmq_peers
+                    .lock()
+                    .expect("ZMQ peers lock is poisoned")
+                    .insert(addr, Instant::now());
+                self.zmq.connect(addr)
+            }
+            Err(e) => Err(Error::GossipHandshake(addr.to_string(), e)),
+        }
+    }
+}
+
+/// Offer `VERSION` to the listener. Returns the version it chose, or `None` if it only speaks
+/// ZeroMQ.
+fn handshake(mut stream: &TcpStream) -> result::Result<Option<u8>, String> {
+    stream.set_nodelay(true).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))
+          .map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))
+          .map_err(|e| e.to_string())?;
+    let mut hello = MAGIC.to_vec();
+    hello.push(VERSION);
+    stream.write_all(&hello).map_err(|e| e.to_string())?;
+
+    let mut reply = [0; 1];
+    match stream.read(&mut reply) {
+        Ok(0) => Ok(None),
+        Ok(_) if reply[0] == ZMTP_SIGNATURE => Ok(None),
+        Ok(_) if (1..=VERSION).contains(&reply[0]) => Ok(Some(reply[0])),
+        Ok(_) => Err(format!("unsupported protocol version {}", reply[0])),
+        Err(ref e) if e.kind() == io::ErrorKind::ConnectionReset => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Whether the other end of an idle connection is still there. It never sends anything, so
+/// anything but a read that would block means it's gone.
+fn is_open(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return false;
+    }
+    let open = match stream.peek(&mut [0; 1]) {
+        Err(ref e) => e.kind() == io::ErrorKind::WouldBlock,
+        Ok(_) => false,
+    };
+    open && stream.set_nonblocking(false).is_ok()
+}
+
+struct TcpSender {
+    addr:   SocketAddr,
+    /// Taken if a send fails, so the connection isn't reused.
+    stream: Option<TcpStream>,
+    idle:   IdleConnections,
+}
+
+impl GossipSender for TcpSender {
+    fn send(&mut self, msg: &[u8]) -> Result<()> {
+        if msg.len() > MAX_FRAME_BYTES {
+            return Err(Error::GossipFrameTooLarge(msg.len()));
+        }
+        let mut stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => {
+                return Err(Error::GossipSend(self.addr.to_string(),
+                                             io::ErrorKind::NotConnected.into()));
+            }
+        };
+        let mut frame = vec![0; 4];
+        BigEndian::write_u32(&mut frame, msg.len() as u32);
+        frame.extend_from_slice(msg);
+        stream.write_all(&frame)
+              .map_err(|e| Error::GossipSend(self.addr.to_string(), e))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+}
+
+impl Drop for TcpSender {
+    fn drop(&mut self) {
+        if let Some(stream) = self.stream.take() {
+            if let Ok(mut idle) = self.idle.lock() {
+                idle.entry(self.addr)
+                    .or_insert_with(Vec::new)
+                    .push((stream, Instant::now()));
+            }
+        }
+    }
+}
+
+struct TcpGossipListener {
+    rx:           Receiver<Vec<u8>>,
+    recv_timeout: Option<Duration>,
+}
+
+impl GossipListener for TcpGossipListener {
+    fn recv(&mut self) -> Result<Option<Vec<u8>>> {
+        match self.recv_timeout {
+            Some(timeout) => {
+                match self.rx.recv_timeout(timeout) {
+                    Ok(msg) => Ok(Some(msg)),
+                    Err(RecvTimeoutError::Timeout) => Ok(None),
+                    Err(RecvTimeoutError::Disconnected) => Err(Error::GossipListenerClosed),
+                }
+            }
+            None => {
+                self.rx
+                    .recv()
+                    .map(Some)
+                    .map_err(|_| Error::GossipListenerClosed)
+            }
+        }
+    }
+}
+
+/// Counts a connection being read from until it is dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn take(open: &Arc<AtomicUsize>) -> Option<Self> {
+        if open.fetch_add(1, Ordering::SeqCst) < MAX_CONNECTIONS {
+            Some(ConnectionSlot(open.clone()))
+        } else {
+            open.fetch_sub(1, Ordering::SeqCst);
+            None
+        }
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) { self.0.fetch_sub(1, Ordering::SeqCst); }
+}
+
+fn accept(listener: &TcpListener, tx: &SyncSender<Vec<u8>>, zmq_endpoint: &str) {
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("Error accepting gossip connection: {}", e);
+                continue;
+            }
+        };
+        let slot = match ConnectionSlot::take(&open) {
+            Some(slot) => slot,
+            None => {
+                warn!("Closing a gossip connection, {} connections are already open",
+                      MAX_CONNECTIONS);
+                let _ = stream.shutdown(Shutdown::Both);
+                continue;
+            }
+        };
+        let tx = tx.clone();
+        let zmq_endpoint = zmq_endpoint.to_string();
+        let spawned = thread::Builder::new().name(String::from("pull-connection"))
+                                            .spawn(move || {
+                                                let _slot = slot;
+                                                if let Err(e) = receive(stream, &tx, &zmq_endpoint)
+                                                {
+                                                    debug!("Gossip connection closed: {}", e);
+                                                }
+                                            });
+        if let Err(e) = spawned {
+            error!("Could not spawn thread: {}", e);
+        }
+    }
+}
+
+/// Receive every message sent on a connection, or hand it to ZeroMQ if it opened with anything
+/// but `MAGIC`.
+fn receive(mut stream: TcpStream, tx: &SyncSender<Vec<u8>>, zmq_endpoint: &str) -> Result<()> {
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let mut first = [0; 1];
+    stream.read_exact(&mut first)?;
+    if first[0] != MAGIC[0] {
+        return proxy(stream, first[0], zmq_endpoint);
+    }
+
+    let mut hello = [0; 4];
+    stream.read_exact(&mut hello)?;
+    if hello[..3] != MAGIC[1..] || hello[3] == 0 {
+        let _ = stream.shutdown(Shutdown::Both);
+        return Err(Error::ProtocolMismatch("gossip handshake"));
+    }
+    stream.write_all(&[hello[3].min(VERSION)])?;
+
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    let mut len = [0; 4];
+    loop {
+        match stream.read_exact(&mut len) {
+            Ok(()) => {}
+            // The sender closed the connection between messages
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+        let len = BigEndian::read_u32(&len) as usize;
+        if len > MAX_FRAME_BYTES {
+            return Err(Error::GossipFrameTooLarge(len));
+        }
+        let msg = read_frame(&mut stream, len)?;
+        // Blocks while the queue is full, which stops us reading from the sender
+        if tx.send(msg).is_err() {
+            return Err(Error::GossipListenerClosed);
+        }
+    }
+}
+
+/// Read a `len`-byte message, growing it only as its bytes arrive.
+fn read_frame(stream: &mut TcpStream, len: usize) -> io::Result<Vec<u8>> {
+    let mut msg = Vec::with_capacity(len.min(READ_CHUNK_BYTES));
+    let mut chunk = [0; READ_CHUNK_BYTES];
+    while msg.len() < len {
+        let chunk = &mut chunk[..(len - msg.len()).min(READ_CHUNK_BYTES)];
+        stream.read_exact(chunk)?;
+        msg.extend_from_slice(chunk);
+    }
+    Ok(msg)
+}
+
+/// Relay a ZeroMQ connection to the PULL socket at `zmq_endpoint`, starting with the byte
+/// already read from it.
+fn proxy(stream: TcpStream, first: u8, zmq_endpoint: &str) -> Result<()> {
+    stream.set_read_timeout(None)?;
+    let mut upstream = TcpStream::connect(zmq_endpoint)?;
+    upstream.write_all(&[first])?;
+
+    let mut from_upstream = upstream.try_clone()?;
+    let mut to_downstream = stream.try_clone()?;
+    thread::Builder::new().name(String::from("pull-zmq-relay"))
+                          .spawn(move || {
+                              let _ = io::copy(&mut from_upstream, &mut to_downstream);
+                              let _ = to_downstream.shutdown(Shutdown::Write);
+                          })?;
+
+    let mut from_downstream = stream;
+    io::copy(&mut from_downstream, &mut upstream)?;
+    let _ = upstream.shutdown(Shutdown::Write);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::{SocketAddr,
+                    TcpListener},
+              time::Duration};
+
+    use super::{TcpTransport,
+                MAX_FRAME_BYTES,
+                READ_CHUNK_BYTES};
+    use crate::{error::Error,
+                transport::{GossipTransport,
+                            ZmqTransport}};
+
+    /// A port nothing is listening on yet.
+    fn free_addr() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0").unwrap()
+                                        .local_addr()
+                                        .unwrap()
+    }
+
+    fn recv_timeout() -> Option<Duration> { Some(Duration::from_millis(5000)) }
+
+    #[test]
+    fn reuses_connections() {
+        let addr = free_addr();
+        let transport = TcpTransport::default();
+        let mut listener = transport.listen(addr, recv_timeout()).unwrap();
+
+        for msg in &[&b"first"[..], &b"second"[..]] {
+            let mut sender = transport.connect(addr).unwrap();
+            sender.send(msg).unwrap();
+            assert_eq!(listener.recv().unwrap().unwrap(), msg.to_vec());
+        }
+        let idle = transport.idle.lock().unwrap();
+        assert_eq!(idle[&addr].len(), 1);
+    }
+
+    #[test]
+    fn accepts_zmq_senders() {
+        let addr = free_addr();
+        let mut listener = TcpTransport::default().listen(addr, recv_timeout())
+                                                  .unwrap();
+
+        let mut sender = ZmqTransport.connect(addr).unwrap();
+        sender.send(b"rumor").unwrap();
+        assert_eq!(listener.recv().unwrap().unwrap(), b"rumor".to_vec());
+    }
+
+    #[test]
+    fn falls_back_to_zmq_listeners() {
+        let addr = free_addr();
+        let mut listener = ZmqTransport.listen(addr, recv_timeout()).unwrap();
+        let transport = TcpTransport::default();
+
+        let mut sender = transport.connect(addr).unwrap();
+        sender.send(b"rumor").unwrap();
+        assert_eq!(listener.recv().unwrap().unwrap(), b"rumor".to_vec());
+        assert!(transport.speaks_zmq(addr));
+    }
+
+    #[test]
+    fn reports_connection_failures() {
+        let addr = free_addr();
+        match TcpTransport::default().connect(addr) {
+            Err(Error::GossipConnect(..)) => {}
+            Err(e) => panic!("Expected a connection error, got {}", e),
+            Ok(_) => panic!("Expected a connection error"),
+        }
+    }
+
+    #[test]
+    fn receives_messages_larger_than_a_chunk() {
+        let addr = free_addr();
+        let transport = TcpTransport::default();
+        let mut listener = transport.listen(addr, recv_timeout()).unwrap();
+
+        let msg = (0..READ_CHUNK_BYTES * 2 + 1).map(|i| i as u8)
+                                               .collect::<Vec<_>>();
+        let mut sender = transport.connect(addr).unwrap();
+        sender.send(&msg).unwrap();
+        assert_eq!(listener.recv().unwrap().unwrap(), msg);
+    }
+
+    #[test]
+    fn refuses_oversized_messages() {
+        let addr = free_addr();
+        let transport = TcpTransport::default();
+        let _listener = transport.listen(addr, recv_timeout()).unwrap();
+
+        let mut sender = transport.connect(addr).unwrap();
+        match sender.send(&vec![0; MAX_FRAME_BYTES + 1]) {
+            Err(Error::GossipFrameTooLarge(_)) => {}
+            other => panic!("Expected an oversized message error, got {:?}", other.err()),
+        }
+    }
+}
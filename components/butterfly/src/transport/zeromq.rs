@@ -0,0 +1,98 @@
+//! The ZeroMQ gossip transport.
+
+use std::{net::SocketAddr,
+          time::Duration};
+
+use super::{GossipListener,
+            GossipSender,
+            GossipTransport};
+use crate::{error::{Error,
+                    Result},
+            ZMQ_CONTEXT};
+
+/// Pushes gossip through ZeroMQ PUSH sockets, and pulls it off a ZeroMQ PULL socket, which
+/// fair-queues inbound messages from every member.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ZmqTransport;
+
+impl ZmqTransport {
+    /// Bind a PULL socket to `endpoint`, which may be any ZeroMQ TCP endpoint.
+    pub(crate) fn pull(endpoint: &str, recv_timeout: Option<Duration>) -> Result<ZmqListener> {
+        let socket = (**ZMQ_CONTEXT).as_mut()
+                                    .socket(zmq::PULL)
+                                    .expect("Failure to create the ZMQ pull socket");
+        socket.set_linger(0)
+              .expect("Failure to set the ZMQ Pull socket to not linger");
+        socket.set_tcp_keepalive(0)
+              .expect("Failure to set the ZMQ Pull socket to not use keepalive");
+        let recv_timeout = recv_timeout.map_or(-1, |t| t.as_millis() as i32);
+        socket.set_rcvtimeo(recv_timeout)
+              .expect("Failure to set the ZMQ Pull socket receive timeout");
+        socket.bind(&format!("tcp://{}", endpoint))
+              .map_err(Error::ZmqBindError)?;
+        Ok(ZmqListener(socket))
+    }
+}
+
+impl GossipTransport for ZmqTransport {
+    fn listen(&self,
+              addr: SocketAddr,
+              recv_timeout: Option<Duration>)
+              -> Result<Box<dyn GossipListener>> {
+        Ok(Box::new(Self::pull(&addr.to_string(), recv_timeout)?))
+    }
+
+    /// Connects a new PUSH socket. ZeroMQ connects in the background, so an unreachable member
+    /// only shows up as a send timing out.
+    fn connect(&self, addr: SocketAddr) -> Result<Box<dyn GossipSender>> {
+        let socket = (**ZMQ_CONTEXT).as_mut()
+                                    .socket(zmq::PUSH)
+                                    .expect("Failure to create the ZMQ push socket");
+        socket.set_linger(1000)
+              .expect("Failure to set the ZMQ push socket to not linger");
+        socket.set_tcp_keepalive(0)
+              .expect("Failure to set the ZMQ push socket to not use keepalive");
+        socket.set_immediate(true)
+              .expect("Failure to set the ZMQ push socket to immediate");
+        socket.set_sndhwm(1000)
+              .expect("Failure to set the ZMQ push socket hwm");
+        socket.set_sndtimeo(500)
+              .expect("Failure to set the ZMQ send timeout");
+        socket.connect(&format!("tcp://{}", addr))
+              .map_err(Error::ZmqConnectError)?;
+        Ok(Box::new(ZmqSender(socket)))
+    }
+}
+
+pub(crate) struct ZmqListener(zmq::Socket);
+
+impl ZmqListener {
+    /// The endpoint the socket is bound to, which differs from the one it was bound with if that
+    /// asked for an ephemeral port.
+    pub(crate) fn endpoint(&self) -> Result<String> {
+        let endpoint = self.0
+                           .get_last_endpoint()
+                           .map_err(Error::ZmqBindError)?
+                           .unwrap_or_default();
+        Ok(endpoint.trim_start_matches("tcp://").to_string())
+    }
+}
+
+impl GossipListener for ZmqListener {
+    fn recv(&mut self) -> Result<Option<Vec<u8>>> {
+        match self.0.recv_bytes(0) {
+            Ok(msg) => Ok(Some(msg)),
+            // The receive timeout passed
+            Err(zmq::Error::EAGAIN) => Ok(None),
+            Err(e) => Err(Error::ZmqRecvError(e)),
+        }
+    }
+}
+
+struct ZmqSender(zmq::Socket);
+
+impl GossipSender for ZmqSender {
+    fn send(&mut self, msg: &[u8]) -> Result<()> {
+        self.0.send(msg, 0).map_err(Error::ZmqSendError)
+    }
+}
@@ -0,0 +1,419 @@
+//! Member identity for gossip.
+//!
+//! A ring key only proves that a message came from someone holding the ring key. To know which
+//! Supervisor is speaking, each Supervisor can sign with its own member key, a sig key pair. A
+//! receiver with a `MemberTrust` then only accepts signatures that check out against a member key
+//! it trusts: either one on an allow-list, or one endorsed by a certificate authority it trusts.
+//!
+//! Two things are signed:
+//!
+//! * Every message, by the Supervisor that sends it. This is all that vouches for SWIM messages,
+//!   membership rumors and elections, which every member amends as it learns about the ring.
+//! * Rumors that one member originates and the rest only relay (services, service configuration and
+//!   files, departures and ring keys), by the member they originated from. The signature covers the
+//!   rumor as that member created it, including the member's ID and the rumor's incarnation, and
+//!   travels with the rumor as its `Origin`. A relayer can neither alter it nor pass an old
+//!   incarnation off as a new one. Departures and ring keys have no incarnation, as each names
+//!   something that only ever happens once.
+//!
+//! The first member key seen speaking for a member ID is bound to it, and anything signed for
+//! that member ID by another member key is rejected after that.
+
+use std::{collections::HashMap,
+          fmt,
+          sync::RwLock};
+
+use bytes::BytesMut;
+use prost::Message as ProstMessage;
+
+use habitat_core::crypto::{keys::parse_name_with_rev,
+                           SigKeyPair};
+
+use crate::{error::{Error,
+                    Result},
+            protocol::{newscast,
+                       FromProto,
+                       Wire},
+            rumor::{ProtoRumor,
+                    Rumor}};
+
+/// The key a Supervisor signs with, and the endorsement it presents alongside its signatures, if
+/// any.
+#[derive(Clone)]
+pub struct MemberKey {
+    pair:        SigKeyPair,
+    endorsement: Option<Vec<u8>>,
+}
+
+impl MemberKey {
+    pub fn new(pair: SigKeyPair, endorsement: Option<Vec<u8>>) -> Self {
+        MemberKey { pair, endorsement }
+    }
+
+    pub fn name_with_rev(&self) -> String { self.pair.name_with_rev() }
+
+    /// Sign `payload`, the unencrypted contents of `wire`.
+    pub fn sign(&self, wire: &mut Wire, payload: &[u8]) -> Result<()> {
+        wire.signer = Some(self.pair.name_with_rev());
+        wire.signature = Some(self.pair.sign_detached(payload)?);
+        if let Some(ref endorsement) = self.endorsement {
+            wire.signer_key = Some(self.pair.public()?.as_ref().to_vec());
+            wire.endorsement = Some(endorsement.clone());
+        }
+        Ok(())
+    }
+
+    /// Sign `rumor` as originating from `member_id`, replacing any origin it had.
+    pub fn sign_origin<T>(&self, member_id: &str, rumor: &mut T) -> Result<()>
+        where T: OriginRumor
+    {
+        let payload = origin_payload(rumor, member_id)?;
+        let signer_key = match self.endorsement {
+            Some(_) => Some(self.pair.public()?.as_ref().to_vec()),
+            None => None,
+        };
+        rumor.set_origin(Some(Origin { member_id: member_id.to_string(),
+                                       signer: self.pair.name_with_rev(),
+                                       signature: self.pair.sign_detached(&payload)?,
+                                       signer_key,
+                                       endorsement: self.endorsement.clone() }));
+        Ok(())
+    }
+}
+
+impl fmt::Debug for MemberKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MemberKey({})", self.pair.name_with_rev())
+    }
+}
+
+/// The member keys whose signatures are accepted.
+#[derive(Clone)]
+pub enum MemberTrust {
+    /// Only these member keys are trusted.
+    AllowList(Vec<SigKeyPair>),
+    /// Any member key endorsed by one of these keys is trusted.
+    Endorsed(Vec<SigKeyPair>),
+}
+
+impl MemberTrust {
+    /// Check that `payload`, the unencrypted contents of `wire`, was signed by a trusted member
+    /// key, returning the name of the key.
+    pub fn verify(&self, wire: &Wire, payload: &[u8]) -> Result<String> {
+        let (signer, signature) = match (&wire.signer, &wire.signature) {
+            (Some(signer), Some(signature)) => (signer, signature),
+            _ => return Err(untrusted("unsigned", "message is not signed")),
+        };
+        self.verify_signature(signer,
+                              signature,
+                              wire.signer_key.as_ref().map(Vec::as_slice),
+                              wire.endorsement.as_ref().map(Vec::as_slice),
+                              payload)?;
+        Ok(signer.to_string())
+    }
+
+    /// Check that `rumor` is exactly as the member it claims to originate from created it, and
+    /// that a trusted member key signed it, returning its origin.
+    pub fn verify_origin<'a, T>(&self, rumor: &'a T) -> Result<&'a Origin>
+        where T: OriginRumor
+    {
+        let origin = rumor.origin()
+                          .ok_or_else(|| untrusted("unsigned", "rumor has no origin"))?;
+        if let Some(member_id) = rumor.origin_member_id() {
+            if member_id != origin.member_id {
+                return Err(untrusted(&origin.signer,
+                                     &format!("rumor about member {} originates \
+                                               from member {}",
+                                              member_id, origin.member_id)));
+            }
+        }
+        let payload = origin_payload(rumor, &origin.member_id)?;
+        self.verify_signature(&origin.signer,
+                              &origin.signature,
+                              origin.signer_key.as_ref().map(Vec::as_slice),
+                              origin.endorsement.as_ref().map(Vec::as_slice),
+                              &payload)?;
+        Ok(origin)
+    }
+
+    fn verify_signature(&self,
+                        signer: &str,
+                        signature: &[u8],
+                        signer_key: Option<&[u8]>,
+                        endorsement: Option<&[u8]>,
+                        payload: &[u8])
+                        -> Result<()> {
+        let verified = match *self {
+            MemberTrust::AllowList(ref keys) => {
+                match keys.iter().find(|key| key.name_with_rev() == signer) {
+                    Some(key) => key.verify_detached(payload, signature),
+                    None => return Err(untrusted(signer, "member key is not on the allow-list")),
+                }
+            }
+            MemberTrust::Endorsed(ref authorities) => {
+                let key = match (signer_key, endorsement) {
+                    (Some(key), Some(endorsement)) => {
+                        let key = SigKeyPair::from_public_key_bytes(signer, key)
+                            .map_err(|e| untrusted(signer, &e.to_string()))?;
+                        if !authorities.iter()
+                                       .any(|ca| ca.verify_endorsement(&key, endorsement).is_ok())
+                        {
+                            return Err(untrusted(signer,
+                                                 "member key is not endorsed by a trusted \
+                                                  authority"));
+                        }
+                        key
+                    }
+                    _ => return Err(untrusted(signer, "member key is not endorsed")),
+                };
+                key.verify_detached(payload, signature)
+            }
+        };
+        verified.map_err(|e| untrusted(signer, &e.to_string()))
+    }
+}
+
+impl fmt::Debug for MemberTrust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, keys) = match *self {
+            MemberTrust::AllowList(ref keys) => ("AllowList", keys),
+            MemberTrust::Endorsed(ref keys) => ("Endorsed", keys),
+        };
+        let names = keys.iter()
+                        .map(SigKeyPair::name_with_rev)
+                        .collect::<Vec<_>>();
+        write!(f, "{}({})", kind, names.join(", "))
+    }
+}
+
+/// Which member key speaks for each member ID, learned from the first signature seen for it.
+#[derive(Debug, Default)]
+pub struct MemberBindings(RwLock<HashMap<String, String>>);
+
+impl MemberBindings {
+    /// Bind `member_id` to the member key `signer`, unless it is already bound to another member
+    /// key. Every revision of a member key speaks for the same members.
+    pub fn bind(&self, member_id: &str, signer: &str) -> Result<()> {
+        let (name, _) = parse_name_with_rev(signer).map_err(|e| untrusted(signer, &e.to_string()))?;
+        let mut bindings = self.0.write().expect("Member bindings lock is poisoned");
+        match bindings.get(member_id) {
+            Some(bound) if *bound != name => {
+                Err(untrusted(signer,
+                              &format!("member {} is bound to member key {}",
+                                       member_id, bound)))
+            }
+            Some(_) => Ok(()),
+            None => {
+                bindings.insert(member_id.to_string(), name);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The member a rumor originated from, and its member key's signature over the rumor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Origin {
+    pub member_id:   String,
+    pub signer:      String,
+    pub signature:   Vec<u8>,
+    pub signer_key:  Option<Vec<u8>>,
+    pub endorsement: Option<Vec<u8>>,
+}
+
+impl FromProto<newscast::Origin> for Origin {
+    fn from_proto(proto: newscast::Origin) -> Result<Self> {
+        Ok(Origin { member_id:   proto.member_id
+                                      .ok_or(Error::ProtocolMismatch("origin-member-id"))?,
+                    signer:      proto.signer
+                                      .ok_or(Error::ProtocolMismatch("origin-signer"))?,
+                    signature:   proto.signature
+                                      .ok_or(Error::ProtocolMismatch("origin-signature"))?,
+                    signer_key:  proto.signer_key,
+                    endorsement: proto.endorsement, })
+    }
+}
+
+impl From<Origin> for newscast::Origin {
+    fn from(value: Origin) -> Self {
+        newscast::Origin { member_id:   Some(value.member_id),
+                           signer:      Some(value.signer),
+                           signature:   Some(value.signature),
+                           signer_key:  value.signer_key,
+                           endorsement: value.endorsement, }
+    }
+}
+
+/// A rumor that one member originates and the rest only relay, which carries its origin with it.
+pub trait OriginRumor: Rumor {
+    fn origin(&self) -> Option<&Origin>;
+
+    fn set_origin(&mut self, origin: Option<Origin>);
+
+    /// The member this rumor can only originate from, if its content names one.
+    fn origin_member_id(&self) -> Option<&str> { None }
+}
+
+/// What the origin of `rumor` signs: the rumor, without its origin, as `member_id` sends it.
+fn origin_payload<T>(rumor: &T, member_id: &str) -> Result<Vec<u8>>
+    where T: OriginRumor
+{
+    let mut rumor = rumor.clone();
+    rumor.set_origin(None);
+    let mut proto: ProtoRumor = rumor.into();
+    proto.from_id = Some(member_id.to_string());
+    let mut buf = BytesMut::with_capacity(proto.encoded_len());
+    proto.encode(&mut buf)?;
+    Ok(buf.to_vec())
+}
+
+fn untrusted(signer: &str, reason: &str) -> Error {
+    Error::UntrustedSender(signer.to_string(), reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use habitat_core::{crypto::SigKeyPair,
+                       package::PackageIdent,
+                       service::ServiceGroup};
+
+    use super::{MemberBindings,
+                MemberKey,
+                MemberTrust};
+    use crate::{error::Error,
+                protocol::{Message,
+                           Wire},
+                rumor::{service::SysInfo,
+                        Service,
+                        ServiceConfig}};
+
+    fn signed(member_key: &MemberKey, payload: &[u8]) -> Wire {
+        let mut wire = Wire::default();
+        member_key.sign(&mut wire, payload).expect("sign payload");
+        wire
+    }
+
+    #[test]
+    fn allow_list_accepts_listed_keys_only() {
+        let trusted = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        let stranger = SigKeyPair::generate_pair_for_origin("web-2").unwrap();
+        let trust = MemberTrust::AllowList(vec![trusted.clone()]);
+
+        let wire = signed(&MemberKey::new(trusted, None), b"rumor");
+        trust.verify(&wire, b"rumor").expect("trusted member");
+
+        let wire = signed(&MemberKey::new(stranger, None), b"rumor");
+        match trust.verify(&wire, b"rumor") {
+            Err(Error::UntrustedSender(signer, _)) => assert!(signer.starts_with("web-2-")),
+            other => panic!("Expected an untrusted sender, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn forged_payload_is_rejected() {
+        let pair = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        let trust = MemberTrust::AllowList(vec![pair.clone()]);
+        let wire = signed(&MemberKey::new(pair, None), b"rumor");
+
+        assert!(trust.verify(&wire, b"forged rumor").is_err());
+        assert!(trust.verify(&Wire::default(), b"rumor").is_err());
+    }
+
+    #[test]
+    fn endorsed_keys_are_trusted() {
+        let ca = SigKeyPair::generate_pair_for_origin("gossip-ca").unwrap();
+        let member = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        let endorsement = ca.endorse(&member).unwrap();
+        let trust = MemberTrust::Endorsed(vec![ca]);
+
+        let wire = signed(&MemberKey::new(member.clone(), Some(endorsement.clone())),
+                          b"rumor");
+        trust.verify(&wire, b"rumor").expect("endorsed member");
+
+        let wire = signed(&MemberKey::new(member, None), b"rumor");
+        assert!(trust.verify(&wire, b"rumor").is_err());
+
+        let impostor = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        let wire = signed(&MemberKey::new(impostor, Some(endorsement)), b"rumor");
+        assert!(trust.verify(&wire, b"rumor").is_err());
+    }
+
+    fn service_config(member_key: &MemberKey, member_id: &str) -> ServiceConfig {
+        let mut config = ServiceConfig::new("butterflyclient",
+                                            ServiceGroup::from_str("web.default").unwrap(),
+                                            b"port = 80".to_vec());
+        config.incarnation = 2;
+        member_key.sign_origin(member_id, &mut config)
+                  .expect("sign rumor");
+        config
+    }
+
+    #[test]
+    fn origin_survives_relaying() {
+        let pair = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        let trust = MemberTrust::AllowList(vec![pair.clone()]);
+        let config = service_config(&MemberKey::new(pair, None), "member-a");
+
+        let relayed = ServiceConfig::from_bytes(&config.write_to_bytes().unwrap()).unwrap();
+        let origin = trust.verify_origin(&relayed).expect("signed rumor");
+        assert_eq!(origin.member_id, "member-a");
+    }
+
+    #[test]
+    fn altered_rumors_are_rejected() {
+        let pair = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        let trust = MemberTrust::AllowList(vec![pair.clone()]);
+        let config = service_config(&MemberKey::new(pair, None), "member-a");
+
+        let mut replayed = config.clone();
+        replayed.incarnation += 1;
+        assert!(trust.verify_origin(&replayed).is_err());
+
+        let mut reattributed = config.clone();
+        reattributed.origin.as_mut().unwrap().member_id = "member-b".to_string();
+        assert!(trust.verify_origin(&reattributed).is_err());
+
+        let mut unsigned = config;
+        unsigned.origin = None;
+        assert!(trust.verify_origin(&unsigned).is_err());
+    }
+
+    #[test]
+    fn members_only_originate_their_own_services() {
+        let pair = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        let trust = MemberTrust::AllowList(vec![pair.clone()]);
+        let member_key = MemberKey::new(pair, None);
+        let mut service =
+            Service::new("member-b",
+                         &PackageIdent::from_str("core/web/1.0.0/20190101000000").unwrap(),
+                         ServiceGroup::from_str("web.default").unwrap(),
+                         SysInfo::default(),
+                         None);
+
+        member_key.sign_origin("member-a", &mut service)
+                  .expect("sign rumor");
+        assert!(trust.verify_origin(&service).is_err());
+
+        member_key.sign_origin("member-b", &mut service)
+                  .expect("sign rumor");
+        trust.verify_origin(&service).expect("member's own service");
+    }
+
+    #[test]
+    fn member_ids_stay_bound_to_their_first_member_key() {
+        let bindings = MemberBindings::default();
+        bindings.bind("member-a", "web-1-20190101000000")
+                .expect("new member");
+        bindings.bind("member-a", "web-1-20190202000000")
+                .expect("another revision of its key");
+        bindings.bind("member-b", "web-2-20190101000000")
+                .expect("another member");
+
+        match bindings.bind("member-a", "web-2-20190101000000") {
+            Err(Error::UntrustedSender(signer, _)) => assert_eq!(signer, "web-2-20190101000000"),
+            other => panic!("Expected an untrusted sender, got {:?}", other),
+        }
+    }
+}
@@ -11,7 +11,9 @@ use habitat_butterfly::{error::Error,
                                  Server,
                                  Suitability},
                         trace::Trace,
-                        trace_it};
+                        trace_it,
+                        transport::{GossipTransport,
+                                    ZmqTransport}};
 use habitat_core::{crypto::keys::sym_key::SymKey,
                    package::{Identifiable,
                              PackageIdent},
@@ -26,7 +28,8 @@ use std::{net::{IpAddr,
           sync::Mutex,
           thread,
           time::Duration};
-use time::SteadyTime;
+use time::{Duration as TimeDuration,
+           SteadyTime};
 
 lazy_static::lazy_static! {
     static ref SERVER_PORT: Mutex<u16> = Mutex::new(6666);
@@ -39,6 +42,14 @@ impl Suitability for NSuitability {
 }
 
 pub fn start_server(name: &str, ring_key: Option<SymKey>, suitability: u64) -> Server {
+    start_server_with_transport(name, ring_key, suitability, Box::new(ZmqTransport))
+}
+
+pub fn start_server_with_transport(name: &str,
+                                   ring_key: Option<SymKey>,
+                                   suitability: u64,
+                                   transport: Box<dyn GossipTransport>)
+                                   -> Server {
     let swim_port;
     let gossip_port;
     {
@@ -61,6 +72,7 @@ pub fn start_server(name: &str, ring_key: Option<SymKey>, suitability: u64) -> S
                                  Some(String::from(name)),
                                  None,
                                  Box::new(NSuitability(suitability))).unwrap();
+    server.set_gossip_transport(transport);
     server.start(Timing::default())
           .expect("Cannot start server");
     server
@@ -121,6 +133,18 @@ impl SwimNet {
         SwimNet { members }
     }
 
+    pub fn new_with_transports(transports: Vec<Box<dyn GossipTransport>>) -> SwimNet {
+        SwimNet { members: transports.into_iter()
+                                     .enumerate()
+                                     .map(|(x, transport)| {
+                                         start_server_with_transport(&format!("{}", x),
+                                                                     None,
+                                                                     0,
+                                                                     transport)
+                                     })
+                                     .collect(), }
+    }
+
     pub fn connect(&mut self, from_entry: usize, to_entry: usize) {
         let to = member_from_server(&self.members[to_entry]);
         trace_it!(TEST: &self.members[from_entry], format!("Connected {} {}", self.members[to_entry].name(), self.members[to_entry].member_id()));
@@ -352,6 +376,27 @@ impl SwimNet {
         }
     }
 
+    /// Wait up to `timeout` for `from_entry` to see `to_check` as `health`. Unlike
+    /// `wait_for_health_of`, this does not depend on the network making progress, so it can be
+    /// used to check that a health is never reached.
+    pub fn wait_for_health_of_within(&self,
+                                     from_entry: usize,
+                                     to_check: usize,
+                                     health: Health,
+                                     timeout: TimeDuration)
+                                     -> bool {
+        let deadline = SteadyTime::now() + timeout;
+        loop {
+            if self.health_of(from_entry, to_check) == Some(health) {
+                return true;
+            }
+            if SteadyTime::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
     pub fn wait_for_health_of(&self, from_entry: usize, to_check: usize, health: Health) -> bool {
         let rounds_in = self.rounds_in(self.max_rounds());
         loop {
@@ -398,6 +443,28 @@ impl SwimNet {
         }
     }
 
+    pub fn local_health(&self, entry: usize) -> usize {
+        self.members
+            .get(entry)
+            .expect("Asked for a network member who is out of bounds")
+            .local_health()
+    }
+
+    /// Wait for a member's local health score to reach at least `score`.
+    pub fn wait_for_local_health_of(&self, entry: usize, score: usize) -> bool {
+        let rounds_in = self.rounds_in(self.max_rounds());
+        loop {
+            if self.local_health(entry) >= score {
+                return true;
+            }
+            if self.check_rounds(&rounds_in) {
+                println!("Failed local health check for {:#?}", self.members[entry]);
+                return false;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
     #[allow(dead_code)]
     pub fn wait_protocol_period(&self) {
         let timing = Timing::default();
@@ -15,3 +15,31 @@ fn symmetric_encryption_of_wire_payloads() {
     assert!(net[1].service_store
                   .contains_rumor("beast.prod", net[0].member_id()));
 }
+
+#[test]
+fn ring_key_rotation_keeps_the_ring_talking() {
+    let mut old = SymKey::generate_pair_for_ring("wolverine").expect("Failed to generate an in \
+                                                                      memory symkey");
+    let mut new = SymKey::generate_pair_for_ring("wolverine").expect("Failed to generate an in \
+                                                                      memory symkey");
+    old.rev = "20190101000000".to_string();
+    new.rev = "20190201000000".to_string();
+    let mut net = btest::SwimNet::new_ring_encryption(2, &old);
+    net.connect(0, 1);
+    assert_wait_for_health_of!(net, [0..2, 0..2], Health::Alive);
+
+    assert!(net[0].add_ring_key(new.clone()));
+    net.wait_for_rounds(2);
+    assert_eq!(net[0].ring_key().unwrap().rev, old.rev);
+
+    assert!(net[1].add_ring_key(new.clone()));
+    net.wait_for_rounds(3);
+    assert_eq!(net[0].ring_key().unwrap().rev, new.rev);
+    assert_eq!(net[1].ring_key().unwrap().rev, new.rev);
+
+    assert_wait_for_health_of!(net, [0..2, 0..2], Health::Alive);
+    net.add_service(0, "core/beast/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    assert!(net[1].service_store
+                  .contains_rumor("beast.prod", net[0].member_id()));
+}
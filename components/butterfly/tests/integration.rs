@@ -1,7 +1,9 @@
 #[macro_use]
 mod common;
 mod encryption;
+mod lifeguard;
 mod rumor;
+mod transport;
 
 use common as btest;
 use habitat_butterfly::{self,
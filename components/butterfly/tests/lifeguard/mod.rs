@@ -0,0 +1,80 @@
+use habitat_butterfly::{member::Health,
+                        server::timing::Timing};
+
+use std::{thread,
+          time::Duration};
+use time::SteadyTime;
+
+use crate::btest;
+
+#[test]
+fn members_that_get_nacks_for_a_paused_member_stay_healthy() {
+    let mut net = btest::SwimNet::new(3);
+    net.mesh();
+    assert_wait_for_health_of!(net, [0..3, 0..3], Health::Alive);
+
+    net[0].pause();
+    assert_wait_for_health_of!(net, 0, Health::Confirmed);
+    // Only the first failed probe, before either member has seen a Nack from the other, counts
+    // against them.
+    assert!(net.local_health(1) <= 1);
+    assert!(net.local_health(2) <= 1);
+}
+
+#[test]
+fn a_member_that_cannot_hear_its_peers_loses_local_health() {
+    let mut net = btest::SwimNet::new(3);
+    net.mesh();
+    assert_wait_for_health_of!(net, [0..3, 0..3], Health::Alive);
+
+    net.partition(0..1, 1..3);
+    assert!(net.wait_for_local_health_of(0, 2),
+            "Member 0 should have lost local health");
+    assert_wait_for_health_of!(net, 0, Health::Confirmed);
+    assert!(net.local_health(1) <= 1);
+    assert!(net.local_health(2) <= 1);
+}
+
+#[test]
+fn confirmations_from_other_members_shorten_the_suspicion_timeout() {
+    let timing = Timing::default();
+    let mut net = btest::SwimNet::new(4);
+    net.mesh();
+    assert_wait_for_health_of!(net, [0..4, 0..4], Health::Alive);
+
+    net[0].pause();
+    assert_wait_for_health_of!(net, 1, 0, Health::Suspect);
+    // Members 2 and 3 suspect member 0 too, so member 1 should not wait out the maximum timeout.
+    assert!(net.wait_for_health_of_within(1,
+                                          0,
+                                          Health::Confirmed,
+                                          timing.max_suspicion_timeout_duration() / 2),
+            "Member 1 should have confirmed member 0 well before the maximum suspicion timeout");
+}
+
+#[test]
+fn a_member_paused_briefly_is_never_confirmed() {
+    let timing = Timing::default();
+    let mut net = btest::SwimNet::new(3);
+    net.mesh();
+    assert_wait_for_health_of!(net, [0..3, 0..3], Health::Alive);
+
+    let paused_at = SteadyTime::now();
+    net[0].pause();
+    assert_wait_for_health_of!(net, 1, 0, Health::Suspect);
+    net[0].unpause();
+    assert!(SteadyTime::now() - paused_at < timing.max_suspicion_timeout_duration());
+
+    // Watch both observers at once; a refuted confirmation could otherwise come and go unseen.
+    let deadline = SteadyTime::now() + timing.max_suspicion_timeout_duration();
+    while SteadyTime::now() < deadline {
+        for observer in 1..3 {
+            assert_ne!(net.health_of(observer, 0),
+                       Some(Health::Confirmed),
+                       "Member {} confirmed member 0 after it resumed",
+                       observer);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert_wait_for_health_of!(net, 0, Health::Alive);
+}
@@ -0,0 +1,62 @@
+use habitat_butterfly::{client::Client,
+                        member::Health,
+                        transport::{GossipTransport,
+                                    TcpTransport,
+                                    ZmqTransport}};
+use habitat_core::service::ServiceGroup;
+
+use crate::btest;
+
+fn tcp() -> Box<dyn GossipTransport> { Box::new(TcpTransport::default()) }
+
+fn zmq() -> Box<dyn GossipTransport> { Box::new(ZmqTransport) }
+
+#[test]
+fn tcp_members_share_rumors() {
+    let mut net = btest::SwimNet::new_with_transports(vec![tcp(), tcp()]);
+    net.mesh();
+    assert_wait_for_health_of!(net, [0..2, 0..2], Health::Alive);
+    net.add_service(0, "core/beast/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    assert!(net[1].service_store
+                  .contains_rumor("beast.prod", net[0].member_id()));
+}
+
+#[test]
+fn tcp_and_zmq_members_share_rumors() {
+    let mut net = btest::SwimNet::new_with_transports(vec![tcp(), zmq(), tcp()]);
+    net.mesh();
+    assert_wait_for_health_of!(net, [0..3, 0..3], Health::Alive);
+    net.add_service(0, "core/beast/1.2.3/20161208121212");
+    net.add_service(1, "core/witcher/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    for member in 0..3 {
+        assert!(net[member].service_store
+                           .contains_rumor("beast.prod", net[0].member_id()));
+        assert!(net[member].service_store
+                           .contains_rumor("witcher.prod", net[1].member_id()));
+    }
+}
+
+#[test]
+fn clients_of_either_transport_reach_a_tcp_member() {
+    let mut net = btest::SwimNet::new_with_transports(vec![tcp(), tcp()]);
+    net.mesh();
+    net.wait_for_gossip_rounds(1);
+
+    let addr = net[0].gossip_addr().to_string();
+    let service_group = ServiceGroup::new(None, "witcher", "prod", None).unwrap();
+    let mut zmq_client = Client::new(&addr, None, None).expect("Cannot create Butterfly Client");
+    zmq_client.send_service_config(service_group.clone(), 0, b"tcp-backlog = 128", false, None)
+              .expect("Cannot send the service configuration");
+    let mut tcp_client = Client::with_transport(&addr, &TcpTransport::default(), None, None)
+        .expect("Cannot create Butterfly Client");
+    tcp_client.send_service_file(service_group, "witcher.conf", 0, b"tokyo", false, None)
+              .expect("Cannot send the service file");
+
+    net.wait_for_gossip_rounds(1);
+    assert!(net[1].service_config_store
+                  .contains_rumor("witcher.prod", "service_config"));
+    assert!(net[1].service_file_store
+                  .contains_rumor("witcher.prod", "witcher.conf"));
+}
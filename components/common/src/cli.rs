@@ -10,7 +10,9 @@ use habitat_core::{env as henv,
                    fs::{cache_key_path,
                         CACHE_KEY_PATH},
                    os::process::{ShutdownSignal,
-                                 ShutdownTimeout}};
+                                 ShutdownTimeout},
+                   service::{self,
+                             HealthCheck}};
 use std::path::PathBuf;
 
 pub const GOSSIP_DEFAULT_IP: &str = "0.0.0.0";
@@ -94,3 +96,19 @@ pub fn cache_key_path_from_matches(matches: &ArgMatches<'_>) -> PathBuf {
         val => PathBuf::from(val),
     }
 }
+
+/// The health check given with `HEALTH_CHECK`, and the `HEALTH_CHECK_STATUS`,
+/// `HEALTH_CHECK_BODY` and `HEALTH_CHECK_TIMEOUT` settings that go with it. The values are
+/// validated at the clap layer, so any that don't parse are never seen here.
+pub fn health_check_from_matches(matches: &ArgMatches<'_>) -> Option<HealthCheck> {
+    let mut check = HealthCheck::new(matches.value_of("HEALTH_CHECK")?.parse().ok()?);
+    check.status = matches.value_of("HEALTH_CHECK_STATUS")
+                          .and_then(|v| service::parse_health_check_status(v).ok());
+    check.body = matches.value_of("HEALTH_CHECK_BODY").map(str::to_string);
+    if let Some(timeout) = matches.value_of("HEALTH_CHECK_TIMEOUT")
+                                  .and_then(|v| service::parse_health_check_timeout(v).ok())
+    {
+        check.timeout = timeout;
+    }
+    Some(check)
+}
@@ -56,6 +56,8 @@ use retry::retry;
 
 use crate::{error::{Error,
                     Result},
+            service_log::{HookLog,
+                          LogConfig},
             templating::{self,
                          hooks::{Hook,
                                  InstallHook},
@@ -404,7 +406,8 @@ fn run_install_hook<T>(ui: &mut T, package: &PackageInstall) -> Result<()>
         templating::compile_for_package_install(package)?;
         if !hook.run(&package.ident().name,
                      &Pkg::from_install(package)?,
-                     None::<&str>)
+                     None::<&str>,
+                     &HookLog::new(LogConfig::default()))
         {
             return Err(Error::InstallHookFailed(package.ident().clone()));
         }
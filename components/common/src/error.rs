@@ -23,6 +23,8 @@ pub enum Error {
     BadEnvConfig(String),
     BadGlyphStyle(String),
     CantUploadGossipToml,
+    CfgSchemaInvalid(PathBuf, String),
+    CfgSchemaViolation(Vec<String>),
     ChannelNotFound,
     CryptoKeyError(String),
     DownloadFailed(String),
@@ -34,8 +36,10 @@ pub enum Error {
     GossipFileRelativePath(String),
     HabitatCore(hcore::Error),
     InstallHookFailed(PackageIdent),
+    InvalidEventSink(String),
     InvalidEventStreamToken(String),
     InvalidInstallHookMode(String),
+    InvalidLogFormat(String),
     /// Occurs when making lower level IO calls.
     IO(io::Error),
     /// Errors when joining paths :)
@@ -88,6 +92,13 @@ impl fmt::Display for Error {
             Error::CantUploadGossipToml => {
                 "Can't upload gossip.toml, it's a reserved file name".to_string()
             }
+            Error::CfgSchemaInvalid(ref path, ref e) => {
+                format!("Invalid configuration schema {}: {}", path.display(), e)
+            }
+            Error::CfgSchemaViolation(ref errors) => {
+                format!("Configuration does not match the package's schema:\n  {}",
+                        errors.join("\n  "))
+            }
             Error::ChannelNotFound => "Channel not found".to_string(),
             Error::CryptoKeyError(ref s) => format!("Missing or invalid key: {}", s),
             Error::DownloadFailed(ref msg) => msg.to_string(),
@@ -106,12 +117,19 @@ impl fmt::Display for Error {
             Error::InstallHookFailed(ref ident) => {
                 format!("Install hook exited unsuccessfully: {}", ident)
             }
+            Error::InvalidEventSink(ref s) => {
+                format!("Invalid event sink '{}', expected file:PATH, webhook:URL or unix:PATH",
+                        s)
+            }
             Error::InvalidEventStreamToken(ref s) => {
                 format!("Invalid event stream token provided: '{}'", s)
             }
             Error::InvalidInstallHookMode(ref e) => {
                 format!("Invalid InstallHookMode conversion from {}", e)
             }
+            Error::InvalidLogFormat(ref format) => {
+                format!("Unknown log format '{}', expected plain or json", format)
+            }
             Error::IO(ref err) => format!("{}", err),
             Error::JoinPathsError(ref err) => format!("{}", err),
             Error::NetParseError(ref err) => format!("{}", err),
@@ -160,6 +178,8 @@ impl error::Error for Error {
             Error::BadEnvConfig(_) => "Unknown syntax in Env Configuration",
             Error::BadGlyphStyle(_) => "Unknown symbol style",
             Error::CantUploadGossipToml => "Can't upload gossip.toml, it's a reserved filename",
+            Error::CfgSchemaInvalid(..) => "Invalid configuration schema",
+            Error::CfgSchemaViolation(_) => "Configuration does not match the package's schema",
             Error::ChannelNotFound => "Channel not found",
             Error::CryptoKeyError(_) => "Missing or invalid key",
             Error::DownloadFailed(_) => "Failed to download from remote",
@@ -172,8 +192,10 @@ impl error::Error for Error {
             }
             Error::HabitatCore(ref err) => err.description(),
             Error::InstallHookFailed(_) => "Install hook exited unsuccessfully",
+            Error::InvalidEventSink(_) => "Invalid event sink",
             Error::InvalidEventStreamToken(_) => "Invalid event stream token provided",
             Error::InvalidInstallHookMode(_) => "Invalid InstallHookMode",
+            Error::InvalidLogFormat(_) => "Unknown log format",
             Error::IO(ref err) => err.description(),
             Error::JoinPathsError(ref err) => err.description(),
             Error::MissingCLIInputError(_) => "Missing required CLI argument!",
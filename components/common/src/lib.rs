@@ -30,6 +30,7 @@ pub mod error;
 pub mod locked_env_var;
 pub mod output;
 pub mod package_graph;
+pub mod service_log;
 pub mod templating;
 pub mod types;
 pub mod ui;
@@ -86,6 +87,7 @@ bitflags::bitflags! {
         const EVENT_STREAM       = 0b0001_0000_0000;
         const TRIGGER_ELECTION   = 0b0010_0000_0000;
         const CONFIGURE_SHUTDOWN = 0b0100_0000_0000;
+        const TCP_GOSSIP         = 0b1000_0000_0000;
     }
 }
 
@@ -100,7 +102,8 @@ lazy_static! {
                            (FeatureFlag::IGNORE_LOCAL, "HAB_FEAT_IGNORE_LOCAL"),
                            (FeatureFlag::EVENT_STREAM, "HAB_FEAT_EVENT_STREAM"),
                            (FeatureFlag::TRIGGER_ELECTION, "HAB_FEAT_TRIGGER_ELECTION"),
-                           (FeatureFlag::CONFIGURE_SHUTDOWN, "HAB_FEAT_CONFIGURE_SHUTDOWN")];
+                           (FeatureFlag::CONFIGURE_SHUTDOWN, "HAB_FEAT_CONFIGURE_SHUTDOWN"),
+                           (FeatureFlag::TCP_GOSSIP, "HAB_FEAT_TCP_GOSSIP")];
         HashMap::from_iter(mapping)
     };
 }
@@ -0,0 +1,708 @@
+//! Captures the output of supervised services in log files under each
+//! service's `logs` directory, and reads it back out again.
+//!
+//! The Launcher writes every line a service prints to `service.log`.
+//! Once that file grows too large, or has been written to for too
+//! long, it is rotated: it becomes `service.log.1` (compressed to
+//! `service.log.1.gz` if configured), the previously rotated files
+//! each move up by one, and the oldest beyond the configured number of
+//! files is removed.
+//!
+//! The output of the service's hooks, which the Supervisor runs
+//! itself, is written to `hooks.log` beside it, and rotated the same
+//! way. Every run of every hook shares one `HookLog`, so that hooks
+//! running at the same time don't rotate the file out from under each
+//! other.
+//!
+//! Lines are written either as the Supervisor would print them
+//! (`redis.default(O): Ready to accept connections`), or as JSON
+//! objects carrying a timestamp, the service group and the stream the
+//! line came from (as well as the hook it came from, for hook output).
+
+use crate::error::{Error,
+                   Result};
+use flate2::{read::GzDecoder,
+             write::GzEncoder,
+             Compression};
+use serde_json;
+use std::{fmt,
+          fs::{self,
+               File,
+               OpenOptions},
+          io::{self,
+               BufRead,
+               BufReader,
+               Read,
+               Seek,
+               SeekFrom,
+               Write},
+          path::{Path,
+                 PathBuf},
+          str::FromStr,
+          sync::{Arc,
+                 Mutex},
+          time::{Duration,
+                 SystemTime}};
+
+/// The file a service's output is currently written to.
+pub const LOG_FILE_NAME: &str = "service.log";
+/// The file the output of a service's hooks is currently written to.
+pub const HOOK_LOG_FILE_NAME: &str = "hooks.log";
+/// The extension given to rotated log files when they are compressed.
+const COMPRESSED_EXTENSION: &str = "gz";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Lines are written as the Supervisor prints them.
+    Plain,
+    /// Each line is a JSON object with a timestamp, the service group
+    /// and the stream it was read from.
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self { LogFormat::Plain }
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_ref() {
+            "plain" => Ok(LogFormat::Plain),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(Error::InvalidLogFormat(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match *self {
+            LogFormat::Plain => "plain",
+            LogFormat::Json => "json",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// Which of a service's output streams a line was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl LogStream {
+    /// The log key the Supervisor prints the stream's lines with.
+    fn logkey(self) -> &'static str {
+        match self {
+            LogStream::Stdout => "O",
+            LogStream::Stderr => "E",
+        }
+    }
+
+    fn from_logkey(logkey: &str) -> Option<LogStream> {
+        match logkey {
+            "O" => Some(LogStream::Stdout),
+            "E" => Some(LogStream::Stderr),
+            _ => None,
+        }
+    }
+}
+
+/// How a service's output is written and rotated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogConfig {
+    pub format:    LogFormat,
+    /// The log file is rotated once it has grown to this many bytes.
+    pub max_bytes: u64,
+    /// The log file is rotated once it has been written to for this
+    /// long, regardless of its size. `None` only rotates on size.
+    pub max_age:   Option<Duration>,
+    /// How many rotated log files are kept.
+    pub max_files: u32,
+    /// Whether rotated log files are compressed with gzip.
+    pub compress:  bool,
+}
+
+impl LogConfig {
+    pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+    pub const DEFAULT_MAX_FILES: u32 = 5;
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig { format:    LogFormat::default(),
+                    max_bytes: Self::DEFAULT_MAX_BYTES,
+                    max_age:   None,
+                    max_files: Self::DEFAULT_MAX_FILES,
+                    compress:  true, }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    timestamp:     String,
+    service_group: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hook:          Option<&'a str>,
+    stream:        LogStream,
+    line:          &'a str,
+}
+
+/// Format a line of output for the log file, including its trailing
+/// newline. `hook` is the name of the hook the output came from, if
+/// it didn't come from the service itself.
+pub fn format_line(format: LogFormat,
+                   service_group: &str,
+                   hook: Option<&str>,
+                   stream: LogStream,
+                   line: &str)
+                   -> String {
+    match format {
+        LogFormat::Plain => {
+            match hook {
+                Some(hook) => {
+                    format!("{} hook[{}]({}): {}\n",
+                            service_group,
+                            hook,
+                            stream.logkey(),
+                            line)
+                }
+                None => format!("{}({}): {}\n", service_group, stream.logkey(), line),
+            }
+        }
+        LogFormat::Json => {
+            let entry = JsonLine { timestamp: time::now_utc().rfc3339().to_string(),
+                                   service_group,
+                                   hook,
+                                   stream,
+                                   line };
+            // unwrap is safe, as a `JsonLine` is always serializable
+            format!("{}\n", serde_json::to_string(&entry).unwrap())
+        }
+    }
+}
+
+/// The stream a line read back out of a log file was written from, in
+/// either format.
+pub fn line_stream(line: &str) -> Option<LogStream> {
+    #[derive(Deserialize)]
+    struct JsonStream {
+        stream: LogStream,
+    }
+
+    if line.starts_with('{') {
+        serde_json::from_str::<JsonStream>(line).ok()
+                                                .map(|l| l.stream)
+    } else {
+        // Service groups never contain parentheses, so the first one
+        // opens the log key.
+        let start = line.find('(')? + 1;
+        let end = start + line[start..].find("): ")?;
+        LogStream::from_logkey(&line[start..end])
+    }
+}
+
+/// Writes a service's output to its log file, rotating it as
+/// configured.
+#[derive(Debug)]
+pub struct LogWriter {
+    file:          LogFile,
+    service_group: String,
+}
+
+impl LogWriter {
+    /// Open the log file in the given directory for appending,
+    /// creating the directory if needed.
+    pub fn open<P>(dir: P, service_group: &str, config: LogConfig) -> io::Result<LogWriter>
+        where P: AsRef<Path>
+    {
+        Ok(LogWriter { file:          LogFile::open(dir, LOG_FILE_NAME, config)?,
+                       service_group: service_group.to_string(), })
+    }
+
+    pub fn write_line(&mut self, stream: LogStream, line: &str) -> io::Result<()> {
+        let entry = format_line(self.file.config.format,
+                                &self.service_group,
+                                None,
+                                stream,
+                                line);
+        self.file.write_entry(&entry)
+    }
+}
+
+/// Writes the output of a service's hooks to its hook log, rotating it
+/// as configured. Clones share the open log file.
+#[derive(Clone, Debug)]
+pub struct HookLog {
+    config: LogConfig,
+    /// Opened by the first line written, as only the hooks know the
+    /// directory their output goes to.
+    file:   Arc<Mutex<Option<LogFile>>>,
+}
+
+impl HookLog {
+    pub fn new(config: LogConfig) -> Self {
+        HookLog { config,
+                  file: Arc::new(Mutex::new(None)) }
+    }
+
+    /// Append a line of the named hook's output to the hook log in the
+    /// given directory.
+    pub fn write_line<P>(&self,
+                         dir: P,
+                         service_group: &str,
+                         hook: &str,
+                         stream: LogStream,
+                         line: &str)
+                         -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let mut file = self.file.lock().expect("Hook log lock is poisoned");
+        if file.is_none() {
+            *file = Some(LogFile::open(dir, HOOK_LOG_FILE_NAME, self.config)?);
+        }
+        let entry = format_line(self.config.format, service_group, Some(hook), stream, line);
+        file.as_mut()
+            .expect("hook log was just opened")
+            .write_entry(&entry)
+    }
+}
+
+/// A log file of entries that are already formatted, rotated as
+/// configured.
+#[derive(Debug)]
+pub struct LogFile {
+    path:    PathBuf,
+    config:  LogConfig,
+    file:    File,
+    /// The size of the current log file.
+    size:    u64,
+    /// When the current log file was started, which may have been
+    /// before it was opened.
+    started: SystemTime,
+}
+
+impl LogFile {
+    /// Open the named log file in the given directory for appending,
+    /// creating the directory if needed.
+    pub fn open<P>(dir: P, file_name: &str, config: LogConfig) -> io::Result<LogFile>
+        where P: AsRef<Path>
+    {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let path = dir.join(file_name);
+        let file = open_for_append(&path)?;
+        let metadata = file.metadata()?;
+        // Not every platform records when a file was created, in which
+        // case its age is taken from when it was last written to.
+        let started = metadata.created()
+                              .or_else(|_| metadata.modified())
+                              .unwrap_or_else(|_| SystemTime::now());
+        Ok(LogFile { path,
+                     config,
+                     file,
+                     size: metadata.len(),
+                     started })
+    }
+
+    /// Append an entry, including its trailing newline, as it is.
+    pub fn write_entry(&mut self, entry: &str) -> io::Result<()> {
+        if self.rotation_due() {
+            self.rotate()?;
+        }
+        self.file.write_all(entry.as_bytes())?;
+        self.size += entry.len() as u64;
+        Ok(())
+    }
+
+    fn rotation_due(&self) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        self.size >= self.config.max_bytes
+        || self.config.max_age.map_or(false, |max_age| {
+                                  self.started.elapsed().map_or(false, |age| age >= max_age)
+                              })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let max_files = self.config.max_files;
+        if max_files == 0 {
+            // Nothing is kept, so just start over.
+            self.file.set_len(0)?;
+        } else {
+            // The oldest file is removed whether or not it was
+            // compressed, as the setting may have changed since.
+            for compressed in &[false, true] {
+                let oldest = rotated_path(&self.path, max_files, *compressed);
+                if oldest.exists() {
+                    fs::remove_file(oldest)?;
+                }
+            }
+            for n in (1..max_files).rev() {
+                for compressed in &[false, true] {
+                    let from = rotated_path(&self.path, n, *compressed);
+                    if from.exists() {
+                        fs::rename(&from, rotated_path(&self.path, n + 1, *compressed))?;
+                    }
+                }
+            }
+            let rotated = rotated_path(&self.path, 1, false);
+            fs::rename(&self.path, &rotated)?;
+            if self.config.compress {
+                compress(&rotated)?;
+            }
+        }
+        self.file = open_for_append(&self.path)?;
+        self.size = 0;
+        self.started = SystemTime::now();
+        Ok(())
+    }
+}
+
+fn open_for_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// The `n`th most recently rotated log file.
+fn rotated_path(path: &Path, n: u32, compressed: bool) -> PathBuf {
+    let mut name = path.file_name()
+                       .expect("log file has a file name")
+                       .to_os_string();
+    name.push(format!(".{}", n));
+    if compressed {
+        name.push(format!(".{}", COMPRESSED_EXTENSION));
+    }
+    path.with_file_name(name)
+}
+
+/// Replace the file at `path` with a gzipped copy.
+fn compress(path: &Path) -> io::Result<()> {
+    let mut compressed_name = path.as_os_str().to_os_string();
+    compressed_name.push(format!(".{}", COMPRESSED_EXTENSION));
+    let mut encoder = GzEncoder::new(File::create(&compressed_name)?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+/// The last `count` lines of the log file at `path`, oldest first.
+/// Lines from the most recently rotated log file are included when the
+/// current one has fewer than `count`.
+pub fn tail<P>(path: P, count: usize) -> io::Result<Vec<String>>
+    where P: AsRef<Path>
+{
+    tail_matching(path, count, |_| true)
+}
+
+/// Like `tail`, but only counting the lines that `matches` accepts.
+pub fn tail_matching<P, F>(path: P, count: usize, matches: F) -> io::Result<Vec<String>>
+    where P: AsRef<Path>,
+          F: Fn(&str) -> bool
+{
+    let path = path.as_ref();
+    let mut lines = match File::open(path) {
+        Ok(file) => read_lines(file, &matches)?,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    if lines.len() < count {
+        let previous = if let Ok(file) = File::open(rotated_path(path, 1, false)) {
+            read_lines(file, &matches)?
+        } else if let Ok(file) = File::open(rotated_path(path, 1, true)) {
+            read_lines(GzDecoder::new(file), &matches)?
+        } else {
+            Vec::new()
+        };
+        let mut all = previous;
+        all.append(&mut lines);
+        lines = all;
+    }
+    let skip = lines.len().saturating_sub(count);
+    Ok(lines.split_off(skip))
+}
+
+fn read_lines<R, F>(reader: R, matches: F) -> io::Result<Vec<String>>
+    where R: Read,
+          F: Fn(&str) -> bool
+{
+    let mut lines = Vec::new();
+    for line in BufReader::new(reader).split(b'\n') {
+        let line = lossy_line(&line?);
+        if matches(&line) {
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+/// A line of a log file without its line ending. Services may print
+/// anything, so bytes that aren't valid UTF-8 are replaced rather than
+/// failing the whole read.
+fn lossy_line(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end_matches(|c| c == '\n' || c == '\r')
+                                  .to_string()
+}
+
+/// Follows a log file as it is written to, like `tail -f`,
+/// carrying on into the new file whenever the log is rotated.
+#[derive(Debug)]
+pub struct LogFollower {
+    path:    PathBuf,
+    file:    Option<File>,
+    /// A line that has only been partially written so far.
+    partial: Vec<u8>,
+}
+
+impl LogFollower {
+    /// Start following the log file at `path` from its current end.
+    pub fn new<P>(path: P) -> LogFollower
+        where P: AsRef<Path>
+    {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path).and_then(|mut f| f.seek(SeekFrom::End(0)).map(|_| f))
+                                    .ok();
+        LogFollower { path,
+                      file,
+                      partial: Vec::new() }
+    }
+
+    /// The complete lines written since the last call.
+    pub fn read_lines(&mut self) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        if let Some(ref mut file) = self.file {
+            Self::read_available(file, &mut self.partial, &mut lines)?;
+        }
+        if self.rotated() {
+            // Everything left in the old file has been read above, so
+            // the new one can be read from its start.
+            self.file = File::open(&self.path).ok();
+            self.partial.clear();
+            if let Some(ref mut file) = self.file {
+                Self::read_available(file, &mut self.partial, &mut lines)?;
+            }
+        }
+        Ok(lines)
+    }
+
+    fn read_available(file: &mut File,
+                      partial: &mut Vec<u8>,
+                      lines: &mut Vec<String>)
+                      -> io::Result<()> {
+        file.read_to_end(partial)?;
+        while let Some(end) = partial.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = partial.drain(..=end).collect();
+            lines.push(lossy_line(&line));
+        }
+        Ok(())
+    }
+
+    /// Whether the file at the log's path is no longer the one being
+    /// read.
+    fn rotated(&self) -> bool {
+        let current = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(_) => return false,
+        };
+        match self.file.as_ref().and_then(|f| f.metadata().ok()) {
+            Some(open) => !same_file(&open, &current),
+            None => true,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+// Without inode numbers to go on, a file shorter than the one being
+// read must be a new one.
+#[cfg(windows)]
+fn same_file(open: &fs::Metadata, current: &fs::Metadata) -> bool { current.len() >= open.len() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(max_bytes: u64, max_files: u32, compress: bool) -> LogConfig {
+        LogConfig { max_bytes,
+                    max_files,
+                    compress,
+                    ..LogConfig::default() }
+    }
+
+    #[test]
+    fn plain_lines_look_like_supervisor_output() {
+        assert_eq!(format_line(LogFormat::Plain,
+                               "redis.default",
+                               None,
+                               LogStream::Stderr,
+                               "oops"),
+                   "redis.default(E): oops\n");
+        assert_eq!(format_line(LogFormat::Plain,
+                               "redis.default",
+                               Some("init"),
+                               LogStream::Stdout,
+                               "done"),
+                   "redis.default hook[init](O): done\n");
+    }
+
+    #[test]
+    fn json_lines_carry_their_origin() {
+        let line = format_line(LogFormat::Json,
+                               "redis.default",
+                               None,
+                               LogStream::Stdout,
+                               "ready");
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["service_group"], "redis.default");
+        assert_eq!(value["stream"], "stdout");
+        assert_eq!(value["line"], "ready");
+        assert!(value["timestamp"].is_string());
+        assert!(value.get("hook").is_none());
+
+        let line = format_line(LogFormat::Json,
+                               "redis.default",
+                               Some("health-check"),
+                               LogStream::Stderr,
+                               "oops");
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["hook"], "health-check");
+    }
+
+    #[test]
+    fn the_stream_of_a_line_can_be_read_back() {
+        for format in &[LogFormat::Plain, LogFormat::Json] {
+            for stream in &[LogStream::Stdout, LogStream::Stderr] {
+                let line = format_line(*format, "redis.default", None, *stream, "a (b): c");
+                assert_eq!(line_stream(line.trim_end()), Some(*stream));
+            }
+        }
+        assert_eq!(line_stream("redis.default: not a service line"), None);
+    }
+
+    #[test]
+    fn log_is_rotated_once_it_is_too_large() {
+        let dir = TempDir::new().unwrap();
+        let mut writer =
+            LogWriter::open(dir.path(), "redis.default", config(20, 2, false)).unwrap();
+        for n in 0..5 {
+            writer.write_line(LogStream::Stdout, &format!("line {}", n))
+                  .unwrap();
+        }
+        // Each line is longer than the limit, so every line after the
+        // first rotates the log, and only the two most recent rotations are
+        // kept.
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("service.log"), "redis.default(O): line 4\n");
+        assert_eq!(read("service.log.1"), "redis.default(O): line 3\n");
+        assert_eq!(read("service.log.2"), "redis.default(O): line 2\n");
+        assert!(!dir.path().join("service.log.3").exists());
+    }
+
+    #[test]
+    fn hook_runs_rotate_one_shared_hook_log() {
+        let dir = TempDir::new().unwrap();
+        let hook_log = HookLog::new(config(60, 5, false));
+        let other_run = hook_log.clone();
+        hook_log.write_line(dir.path(),
+                            "redis.default",
+                            "init",
+                            LogStream::Stdout,
+                            "first")
+                .unwrap();
+        other_run.write_line(dir.path(),
+                             "redis.default",
+                             "health-check",
+                             LogStream::Stdout,
+                             "second")
+                 .unwrap();
+        other_run.write_line(dir.path(),
+                             "redis.default",
+                             "health-check",
+                             LogStream::Stdout,
+                             "third")
+                 .unwrap();
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("hooks.log.1"),
+                   "redis.default hook[init](O): first\nredis.default hook[health-check](O): \
+                    second\n");
+        assert_eq!(read("hooks.log"),
+                   "redis.default hook[health-check](O): third\n");
+    }
+
+    #[test]
+    fn rotated_logs_can_be_compressed() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = LogWriter::open(dir.path(), "redis.default", config(1, 5, true)).unwrap();
+        writer.write_line(LogStream::Stdout, "first").unwrap();
+        writer.write_line(LogStream::Stdout, "second").unwrap();
+
+        assert!(!dir.path().join("service.log.1").exists());
+        assert!(dir.path().join("service.log.1.gz").exists());
+        assert_eq!(tail(dir.path().join(LOG_FILE_NAME), 10).unwrap(),
+                   vec!["redis.default(O): first", "redis.default(O): second"]);
+    }
+
+    #[test]
+    fn tail_returns_the_most_recent_lines() {
+        let dir = TempDir::new().unwrap();
+        assert!(tail(dir.path().join(LOG_FILE_NAME), 10).unwrap().is_empty());
+
+        let mut writer =
+            LogWriter::open(dir.path(), "redis.default", LogConfig::default()).unwrap();
+        for n in 0..5 {
+            writer.write_line(LogStream::Stdout, &format!("line {}", n))
+                  .unwrap();
+        }
+        assert_eq!(tail(dir.path().join(LOG_FILE_NAME), 2).unwrap(),
+                   vec!["redis.default(O): line 3", "redis.default(O): line 4"]);
+        assert_eq!(tail_matching(dir.path().join(LOG_FILE_NAME), 2, |l| {
+                       !l.ends_with('4')
+                   }).unwrap(),
+                   vec!["redis.default(O): line 2", "redis.default(O): line 3"]);
+    }
+
+    #[test]
+    fn follower_carries_on_across_rotation() {
+        let dir = TempDir::new().unwrap();
+        let mut writer =
+            LogWriter::open(dir.path(), "redis.default", config(40, 5, false)).unwrap();
+        writer.write_line(LogStream::Stdout, "before").unwrap();
+
+        let mut follower = LogFollower::new(dir.path().join(LOG_FILE_NAME));
+        assert!(follower.read_lines().unwrap().is_empty());
+
+        writer.write_line(LogStream::Stdout, "old file").unwrap();
+        writer.write_line(LogStream::Stderr, "new file").unwrap();
+        assert_eq!(follower.read_lines().unwrap(),
+                   vec!["redis.default(O): old file", "redis.default(E): new file"]);
+        assert!(follower.read_lines().unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced_rather_than_failing_the_read() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(LOG_FILE_NAME);
+        fs::write(&path, b"before\n").unwrap();
+        let mut follower = LogFollower::new(&path);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"bad \xff byte\r\nhalf a \xe2").unwrap();
+        assert_eq!(follower.read_lines().unwrap(), vec!["bad \u{fffd} byte"]);
+        file.write_all(b"\x82\xac line\n").unwrap();
+        assert_eq!(follower.read_lines().unwrap(), vec!["half a \u{20ac} line"]);
+
+        assert_eq!(tail(&path, 2).unwrap(),
+                   vec!["bad \u{fffd} byte", "half a \u{20ac} line"]);
+    }
+}
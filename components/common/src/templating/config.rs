@@ -4,7 +4,8 @@ use crate::{error::{Error,
             hcore::{self,
                     crypto,
                     fs::{self,
-                         USER_CONFIG_FILE}},
+                         USER_CONFIG_FILE},
+                    package::metadata::MetaFile},
             outputln,
             templating::{package::Pkg,
                          TemplateRenderer}};
@@ -19,8 +20,11 @@ use std::{self,
           io::prelude::*,
           path::{Path,
                  PathBuf},
-          result};
+          result,
+          sync::Arc};
 use toml;
+use url::Url;
+use valico::json_schema;
 
 static LOGKEY: &'static str = "CF";
 static ENV_VAR_PREFIX: &'static str = "HAB";
@@ -92,6 +96,9 @@ pub struct Cfg {
     pub gossip: Option<toml::value::Table>,
     /// Environment level configuration loaded by the Supervisor's process environment
     pub environment: Option<toml::value::Table>,
+    /// Schema describing the configuration the package accepts, loaded from its
+    /// `config.schema.json`
+    pub schema: Option<CfgSchema>,
     /// Source of the user configuration
     pub user_config_path: UserConfigPath,
     /// Last known incarnation number of the census group's service config
@@ -106,12 +113,12 @@ impl Cfg {
         where P: PackageConfigPaths
     {
         let override_config_dir = config_from.and_then(|c| Some(c.clone()));
-        let default = {
+        let (default, schema) = {
             let pkg_root = match override_config_dir {
                 Some(ref path) => Cow::Borrowed(path),
                 None => Cow::Owned(package.default_config_dir()),
             };
-            Self::load_default(pkg_root.as_ref())?
+            (Self::load_default(pkg_root.as_ref())?, CfgSchema::load(pkg_root.as_ref())?)
         };
         let user_config_path = Self::determine_user_config_path(package);
         let user = Self::load_user(user_config_path.get_path())?;
@@ -120,6 +127,7 @@ impl Cfg {
                   user,
                   gossip: None,
                   environment,
+                  schema,
                   gossip_incarnation: 0,
                   user_config_path,
                   override_config_dir })
@@ -168,29 +176,55 @@ impl Cfg {
     ///
     /// Note that if you're using `config_from`, then changes in the
     /// incoming packages won't be reflected.
+    ///
+    /// The new release may also ship a different schema, which the
+    /// current configuration must match. If it does not, the previous
+    /// defaults and schema are kept, and the violation is returned.
     pub fn update_defaults_from_package<P>(&mut self, package: &P) -> Result<bool>
         where P: PackageConfigPaths
     {
-        let incoming_defaults = {
+        let (incoming_defaults, incoming_schema) = {
             let pkg_root = match self.override_config_dir {
                 Some(ref path) => Cow::Borrowed(path),
                 None => Cow::Owned(package.default_config_dir()),
             };
-            Self::load_default(pkg_root.as_ref())?
+            (Self::load_default(pkg_root.as_ref())?, CfgSchema::load(pkg_root.as_ref())?)
         };
+        let previous_schema = std::mem::replace(&mut self.schema, incoming_schema);
+        let previous_defaults = std::mem::replace(&mut self.default, incoming_defaults);
+        if let Err(err) = self.validate_schema() {
+            self.schema = previous_schema;
+            self.default = previous_defaults;
+            return Err(err);
+        }
 
-        if incoming_defaults != self.default {
-            self.default = incoming_defaults;
-            Ok(true)
-        } else {
-            Ok(false)
+        Ok(self.default != previous_defaults)
+    }
+
+    /// Validates the merged configuration against the package's schema, if it ships one.
+    pub fn validate_schema(&self) -> Result<()> {
+        match self.schema {
+            Some(ref schema) => {
+                let cfg = serde_json::to_value(self).map_err(Error::RenderContextSerialization)?;
+                schema.validate(&cfg)
+            }
+            None => Ok(()),
         }
     }
 
     /// Updates the service configuration with data from a census group
-    pub fn set_gossip(&mut self, incarnation: u64, gossip: toml::value::Table) {
+    ///
+    /// If the gossiped configuration does not match the package's schema, the previous gossiped
+    /// configuration is kept. Its incarnation is recorded either way, so that the same
+    /// configuration is not applied again.
+    pub fn set_gossip(&mut self, incarnation: u64, gossip: toml::value::Table) -> Result<()> {
         self.gossip_incarnation = incarnation;
-        self.gossip = Some(gossip);
+        let previous = std::mem::replace(&mut self.gossip, Some(gossip));
+        if let Err(err) = self.validate_schema() {
+            self.gossip = previous;
+            return Err(err);
+        }
+        Ok(())
     }
 
     /// Returns a subset of the overall configuration which intersects with the given package
@@ -292,9 +326,16 @@ impl Cfg {
     }
 
     /// Reloads the user configuration file.
+    ///
+    /// If the reloaded configuration does not match the package's schema, the previous user
+    /// configuration is kept.
     pub fn reload_user(&mut self) -> Result<()> {
         let user = Self::load_user(self.user_config_path.get_path())?;
-        self.user = user;
+        let previous = std::mem::replace(&mut self.user, user);
+        if let Err(err) = self.validate_schema() {
+            self.user = previous;
+            return Err(err);
+        }
         Ok(())
     }
 
@@ -388,6 +429,70 @@ impl Serialize for Cfg {
     }
 }
 
+/// A JSON Schema describing the keys and types of the configuration a package accepts.
+#[derive(Clone, Debug)]
+pub struct CfgSchema {
+    path:  PathBuf,
+    /// The schema is compiled once, when it is loaded, into this scope, where it is found again
+    /// by its `id` whenever configuration is validated.
+    scope: Arc<json_schema::Scope>,
+    id:    Url,
+}
+
+impl CfgSchema {
+    /// Load the schema from a package's `config.schema.json`, if it ships one.
+    pub fn load<T>(config_from: T) -> Result<Option<Self>>
+        where T: AsRef<Path>
+    {
+        let path = config_from.as_ref()
+                              .join(MetaFile::ConfigSchema.to_string());
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                debug!("Failed to open '{}', {}", path.display(), e);
+                return Ok(None);
+            }
+        };
+        let schema: serde_json::Value = serde_json::from_reader(file).map_err(|e| {
+                                            Error::CfgSchemaInvalid(path.clone(), e.to_string())
+                                        })?;
+        // Compiling up front reports a broken schema when the package is loaded, rather than
+        // every time configuration is validated.
+        let mut scope = json_schema::Scope::new();
+        // NOTE: using `false` instead of `true` allows schemas to use
+        // keywords valico does not know about, such as `$comment`.
+        let id = scope.compile(schema, false)
+                      .map_err(|e| Error::CfgSchemaInvalid(path.clone(), format!("{:?}", e)))?;
+        Ok(Some(CfgSchema { path,
+                            scope: Arc::new(scope),
+                            id }))
+    }
+
+    /// Validate a configuration, rendered as JSON, against the schema.
+    ///
+    /// Each error names the path of the offending value, e.g., `/http/port`.
+    pub fn validate(&self, cfg: &serde_json::Value) -> Result<()> {
+        let schema = self.scope
+                         .resolve(&self.id)
+                         .expect("A compiled schema is found by the id it was compiled with");
+        let state = schema.validate(cfg);
+        if state.is_valid() {
+            return Ok(());
+        }
+        let mut errors = Vec::new();
+        for error in state.errors.iter() {
+            let path = match error.get_path() {
+                "" => "/",
+                path => path,
+            };
+            errors.push(format!("{}: {}",
+                                path,
+                                error.get_detail().unwrap_or_else(|| error.get_title())));
+        }
+        Err(Error::CfgSchemaViolation(errors))
+    }
+}
+
 #[derive(Debug)]
 /// Renders configuration templates into config files.
 pub struct CfgRenderer(TemplateRenderer);
@@ -407,6 +512,21 @@ impl CfgRenderer {
         }
     }
 
+    /// Render all configuration files without writing them.
+    ///
+    /// Returns the path each file compiles to in `render_path`, and its content.
+    pub fn render<P, T>(&self, render_path: P, ctx: &T) -> Result<Vec<(PathBuf, String)>>
+        where P: AsRef<Path>,
+              T: Serialize
+    {
+        let mut rendered = Vec::new();
+        for template in self.0.get_templates().keys() {
+            let compiled = self.0.render(&template, ctx)?;
+            rendered.push((render_path.as_ref().join(&template), compiled));
+        }
+        Ok(rendered)
+    }
+
     /// Compile and write all configuration files to the configuration directory.
     ///
     /// Returns `true` if the configuration has changed.
@@ -932,6 +1052,109 @@ mod test {
         assert_eq!(cfg.user, Some(toml_from_str(toml)));
     }
 
+    const PORT_SCHEMA: &str = r#"{
+        "type": "object",
+        "properties": {
+            "http": {
+                "type": "object",
+                "properties": { "port": { "type": "integer" } }
+            }
+        },
+        "additionalProperties": false
+    }"#;
+
+    #[test]
+    fn schema_errors_name_the_offending_path() {
+        let cfg_data = CfgTestData::new();
+        write_toml(&cfg_data.pkg
+                            .default_config_dir()
+                            .join(MetaFile::ConfigSchema.to_string()),
+                   PORT_SCHEMA);
+        write_toml(&cfg_data.rucp, "prot = 80\n[http]\nport = \"eighty\"");
+        let cfg = Cfg::new(&cfg_data.pkg, None).expect("create config");
+
+        match cfg.validate_schema() {
+            Err(Error::CfgSchemaViolation(errors)) => {
+                assert_eq!(errors.len(), 2);
+                assert!(errors.iter().any(|e| e.starts_with("/http/port: ")));
+                assert!(errors.iter().any(|e| e.contains("prot")));
+            }
+            other => panic!("Expected a schema violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reload_keeps_previous_user_toml_if_it_violates_the_schema() {
+        let cfg_data = CfgTestData::new();
+        write_toml(&cfg_data.pkg
+                            .default_config_dir()
+                            .join(MetaFile::ConfigSchema.to_string()),
+                   PORT_SCHEMA);
+        let toml = "[http]\nport = 80";
+        write_toml(&cfg_data.rucp, toml);
+        let mut cfg = Cfg::new(&cfg_data.pkg, None).expect("create config");
+        cfg.validate_schema().expect("valid user config");
+
+        write_toml(&cfg_data.rucp, "[http]\nport = \"eighty\"");
+        assert!(cfg.reload_user().is_err());
+        assert_eq!(cfg.user, Some(toml_from_str(toml)));
+    }
+
+    #[test]
+    fn updated_defaults_keep_the_previous_schema_if_the_config_violates_the_new_one() {
+        let cfg_data = CfgTestData::new();
+        write_toml(&cfg_data.rucp, "[http]\nport = \"eighty\"");
+        let mut cfg = Cfg::new(&cfg_data.pkg, None).expect("create config");
+        assert!(cfg.schema.is_none());
+
+        write_toml(&cfg_data.pkg
+                            .default_config_dir()
+                            .join(MetaFile::ConfigSchema.to_string()),
+                   PORT_SCHEMA);
+        match cfg.update_defaults_from_package(&cfg_data.pkg) {
+            Err(Error::CfgSchemaViolation(_)) => (),
+            other => panic!("Expected a schema violation, got {:?}", other),
+        }
+        assert!(cfg.schema.is_none());
+
+        write_toml(&cfg_data.rucp, "[http]\nport = 80");
+        cfg.reload_user().expect("reload user config");
+        cfg.update_defaults_from_package(&cfg_data.pkg)
+           .expect("config matches the new schema");
+        assert!(cfg.schema.is_some());
+    }
+
+    #[test]
+    fn set_gossip_keeps_previous_gossip_if_it_violates_the_schema() {
+        let cfg_data = CfgTestData::new();
+        write_toml(&cfg_data.pkg
+                            .default_config_dir()
+                            .join(MetaFile::ConfigSchema.to_string()),
+                   PORT_SCHEMA);
+        let mut cfg = Cfg::new(&cfg_data.pkg, None).expect("create config");
+        let gossip = toml_from_str("[http]\nport = 80");
+        cfg.set_gossip(1, gossip.clone()).expect("valid gossip");
+
+        assert!(cfg.set_gossip(2, toml_from_str("[http]\nport = \"eighty\""))
+                   .is_err());
+        assert_eq!(cfg.gossip, Some(gossip));
+        assert_eq!(cfg.gossip_incarnation, 2);
+    }
+
+    #[test]
+    fn malformed_schema_is_reported_on_load() {
+        let cfg_data = CfgTestData::new();
+        write_toml(&cfg_data.pkg
+                            .default_config_dir()
+                            .join(MetaFile::ConfigSchema.to_string()),
+                   "{ \"type\": ");
+
+        match Cfg::new(&cfg_data.pkg, None) {
+            Err(Error::CfgSchemaInvalid(..)) => (),
+            other => panic!("Expected an invalid schema, got {:?}", other),
+        }
+    }
+
     #[test]
     fn serialize_config() {
         let concrete_path = TempDir::new().expect("create temp dir");
@@ -1160,4 +1383,34 @@ mod test {
         assert_eq!(file_content(deep_output_dir.join("config.txt")),
                    "config message is Hello");
     }
+
+    #[test]
+    fn test_render_does_not_write_files() {
+        let root = TempDir::new().expect("create temp dir").into_path();
+
+        let pkg_dir = root.join("pkg/testing/test");
+        fs::create_dir_all(&pkg_dir).expect("create pkg dir");
+        let pg_id = PackageIdent::new("testing", "test", Some("1.0.0"), Some("20170712000000"));
+        let pkg_install = PackageInstall::new_from_parts(pg_id.clone(),
+                                                         pkg_dir.clone(),
+                                                         pkg_dir.clone(),
+                                                         pkg_dir.clone());
+        create_with_content(pkg_dir.join("default.toml"), "message = \"Hello\"");
+        let config_dir = pkg_dir.join("config");
+        fs::create_dir_all(&config_dir).expect("create config dir");
+        create_with_content(config_dir.join("config.txt"),
+                            "config message is {{cfg.message}}");
+
+        let output_dir = root.join("output");
+        let pkg = Pkg::from_install(&pkg_install).unwrap();
+        let cfg = Cfg::new(&pkg, None).unwrap();
+        let ctx = RenderContext::new(&pkg, &cfg);
+
+        let renderer = CfgRenderer::new(&config_dir).expect("create cfg renderer");
+        let rendered = renderer.render(&output_dir, &ctx).expect("render");
+
+        assert_eq!(rendered,
+                   vec![(output_dir.join("config.txt"), "config message is Hello".to_string())]);
+        assert!(!output_dir.exists());
+    }
 }
@@ -6,7 +6,8 @@ use handlebars::{Handlebars,
                  RenderContext,
                  RenderError,
                  Renderable};
-use serde_json::Value as Json;
+use serde_json::{Map,
+                 Value as Json};
 
 use super::{super::RenderResult,
             to_json,
@@ -19,6 +20,10 @@ impl HelperDef for EachAliveHelper {
     fn call(&self, h: &Helper<'_>, r: &Handlebars, rc: &mut RenderContext<'_>) -> RenderResult<()> {
         let value = h.param(0)
                      .ok_or_else(|| RenderError::new("Param not found for helper \"eachAlive\""))?;
+        // `ready=true` further narrows the members to those whose ready
+        // hook (or, lacking one, health check) last passed.
+        let ready_only = h.hash_get("ready")
+                          .map_or(false, |ready| ready.value().is_truthy());
         if let Some(template) = h.template() {
             rc.promote_local_vars();
             let local_path_root = value.path_root()
@@ -28,7 +33,7 @@ impl HelperDef for EachAliveHelper {
                     let alive_members: Vec<Json> = list.iter()
                                                        .filter_map(|m| {
                                                            m.as_object().and_then(|m| {
-                                if m.contains_key("alive") && m["alive"].as_bool().unwrap() {
+                                if is_selected(m, ready_only) {
                                     Some(to_json(&m))
                                 } else {
                                     None
@@ -59,7 +64,7 @@ impl HelperDef for EachAliveHelper {
                 }
                 (true, &Json::Object(ref obj)) => {
                     let mut first: bool = true;
-                    if !obj.contains_key("alive") || !obj["alive"].as_bool().unwrap() {
+                    if !is_selected(obj, ready_only) {
                         return Ok(());
                     }
                     for k in obj.keys() {
@@ -119,4 +124,11 @@ impl HelperDef for EachAliveHelper {
     }
 }
 
+/// Whether a census member is alive and, if `ready_only` is set, also
+/// ready.
+fn is_selected(member: &Map<String, Json>, ready_only: bool) -> bool {
+    let flag = |name: &str| member.get(name).and_then(Json::as_bool).unwrap_or(false);
+    flag("alive") && (!ready_only || flag("ready"))
+}
+
 pub static EACH_ALIVE: EachAliveHelper = EachAliveHelper;
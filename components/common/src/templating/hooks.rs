@@ -2,7 +2,9 @@ use super::{package::Pkg,
             TemplateRenderer};
 use crate::{error::{Error,
                     Result},
-            outputln};
+            outputln,
+            service_log::{HookLog,
+                          LogStream}};
 #[cfg(windows)]
 use habitat_core::os::process::windows_child::{Child,
                                                ExitStatus};
@@ -122,9 +124,7 @@ pub trait Hook: fmt::Debug + Sized + Send {
     fn compile<T>(&self, service_group: &str, ctx: &T) -> Result<bool>
         where T: Serialize
     {
-        let content = self.renderer().render(Self::file_name(), ctx)?;
-        // We make sure we don't use a deprecated file name
-        let path = self.path().with_file_name(Self::file_name());
+        let (path, content) = self.render(ctx)?;
         if write_hook(&content, &path)? {
             outputln!(preamble service_group,
                       "Modified hook content in {}",
@@ -139,6 +139,18 @@ pub trait Hook: fmt::Debug + Sized + Send {
         }
     }
 
+    /// Render a hook without writing it.
+    ///
+    /// Returns the path the hook compiles to, and its content.
+    fn render<T>(&self, ctx: &T) -> Result<(PathBuf, String)>
+        where T: Serialize
+    {
+        let content = self.renderer().render(Self::file_name(), ctx)?;
+        // We make sure we don't use a deprecated file name
+        let path = self.path().with_file_name(Self::file_name());
+        Ok((path, content))
+    }
+
     #[cfg(not(windows))]
     fn set_permissions<T: AsRef<Path>>(path: T) -> habitat_core::error::Result<()> {
         use habitat_core::util::posix_perm;
@@ -178,30 +190,50 @@ pub trait Hook: fmt::Debug + Sized + Send {
                 Habitat core developers");
     }
 
-    /// Run a compiled hook.
+    /// Run a compiled hook, appending its output to the service's
+    /// hook log.
     fn run<T>(&self,
               service_group: &str,
               pkg: &Pkg,
-              svc_encrypted_password: Option<T>)
+              svc_encrypted_password: Option<T>,
+              hook_log: &HookLog)
               -> Self::ExitValue
         where T: ToString
+    {
+        self.run_with_exit_code(service_group, pkg, svc_encrypted_password, hook_log)
+            .0
+    }
+
+    /// Run a compiled hook, also returning the exit code of its
+    /// process. The exit code is `None` if the hook could not be run,
+    /// or was terminated by a signal.
+    fn run_with_exit_code<T>(&self,
+                             service_group: &str,
+                             pkg: &Pkg,
+                             svc_encrypted_password: Option<T>,
+                             hook_log: &HookLog)
+                             -> (Self::ExitValue, Option<i32>)
+        where T: ToString
     {
         let mut child = match Self::exec(self.path(), &pkg, svc_encrypted_password) {
             Ok(child) => child,
             Err(err) => {
                 outputln!(preamble service_group,
                     "Hook failed to run, {}, {}", Self::file_name(), err);
-                return Self::ExitValue::default();
+                return (Self::ExitValue::default(), None);
             }
         };
         let mut hook_output = HookOutput::new(self.stdout_log_path(), self.stderr_log_path());
-        hook_output.stream_output::<Self>(service_group, &mut child);
+        hook_output.stream_output::<Self>(service_group, &mut child, hook_log);
         match child.wait() {
-            Ok(status) => self.handle_exit(pkg, &hook_output, status),
+            Ok(status) => {
+                let exit_code = status.code();
+                (self.handle_exit(pkg, &hook_output, status), exit_code)
+            }
             Err(err) => {
                 outputln!(preamble service_group,
                     "Hook failed to run, {}, {}", Self::file_name(), err);
-                Self::ExitValue::default()
+                (Self::ExitValue::default(), None)
             }
         }
     }
@@ -448,12 +480,19 @@ impl<'a> HookOutput<'a> {
         }
     }
 
-    fn stream_output<H: Hook>(&mut self, service_group: &str, process: &mut Child) {
+    fn stream_output<H: Hook>(&mut self,
+                              service_group: &str,
+                              process: &mut Child,
+                              hook_log: &HookLog) {
         let mut stdout_log =
             File::create(&self.stdout_log_file).expect("couldn't create log output file");
         let mut stderr_log =
             File::create(&self.stderr_log_file).expect("couldn't create log output file");
 
+        // Each hook's latest output is kept in its own files, while the
+        // output of every run is also appended to the service's
+        // rotating hook log.
+
         let preamble_str = self.stream_preamble::<H>(service_group);
         if let Some(ref mut stdout) = process.stdout {
             for line in BufReader::new(stdout).lines() {
@@ -461,6 +500,7 @@ impl<'a> HookOutput<'a> {
                     outputln!(preamble preamble_str, l);
                     stdout_log.write_fmt(format_args!("{}\n", l))
                               .expect("couldn't write line");
+                    self.log_line::<H>(hook_log, service_group, LogStream::Stdout, l);
                 }
             }
         }
@@ -470,11 +510,24 @@ impl<'a> HookOutput<'a> {
                     outputln!(preamble preamble_str, l);
                     stderr_log.write_fmt(format_args!("{}\n", l))
                               .expect("couldn't write line");
+                    self.log_line::<H>(hook_log, service_group, LogStream::Stderr, l);
                 }
             }
         }
     }
 
+    fn log_line<H: Hook>(&self,
+                         hook_log: &HookLog,
+                         service_group: &str,
+                         stream: LogStream,
+                         line: &str) {
+        if let Some(dir) = self.stdout_log_file.parent() {
+            if let Err(e) = hook_log.write_line(dir, service_group, H::file_name(), stream, line) {
+                debug!("Unable to write to hook log in {}, {}", dir.display(), e);
+            }
+        }
+    }
+
     fn stream_preamble<H: Hook>(&self, service_group: &str) -> String {
         format!("{} hook[{}]:", service_group, H::file_name())
     }
@@ -483,10 +536,11 @@ impl<'a> HookOutput<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::templating::{config::Cfg,
-                            context::RenderContext,
-                            package::Pkg,
-                            test_helpers::*};
+    use crate::{service_log::LogConfig,
+                templating::{config::Cfg,
+                             context::RenderContext,
+                             package::Pkg,
+                             test_helpers::*}};
     use habitat_core::{package::{PackageIdent,
                                  PackageInstall},
                        service::ServiceGroup};
@@ -759,7 +813,9 @@ echo "The message is Hello"
             ServiceGroup::new(None, "dummy", "service", None).expect("couldn't create \
                                                                       ServiceGroup");
 
-        hook_output.stream_output::<InstallHook>(&service_group, &mut child);
+        hook_output.stream_output::<InstallHook>(&service_group,
+                                                 &mut child,
+                                                 &HookLog::new(LogConfig::default()));
 
         let mut stdout = String::new();
         hook_output.stdout()
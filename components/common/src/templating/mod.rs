@@ -402,6 +402,26 @@ test: something"#
         assert_eq!(each_alive_render, each_if_render);
     }
 
+    #[test]
+    fn each_alive_helper_with_ready_filter() {
+        let mut renderer = TemplateRenderer::new();
+        // template using the `eachAlive` helper with `ready=true`
+        renderer.register_template_file("each_ready", templates().join("each_ready.txt"))
+                .unwrap();
+
+        // template using an each block with nested if blocks filtering on `alive` and `ready`
+        renderer.register_template_file("ready_members", templates().join("ready_members.txt"))
+                .unwrap();
+
+        let data = service_config_json_from_toml_file("multiple_supervisors_config.toml");
+
+        let each_ready_render = renderer.render("each_ready", &data).unwrap();
+        let each_if_render = renderer.render("ready_members", &data).unwrap();
+
+        assert_eq!(each_ready_render, each_if_render);
+        assert_eq!(each_ready_render.matches("Member ID").count(), 1);
+    }
+
     #[test]
     fn render_package_install() {
         let root = TempDir::new().expect("create temp dir").into_path();
@@ -2,8 +2,10 @@ mod listen_ctl_addr;
 pub use self::listen_ctl_addr::ListenCtlAddr;
 use crate::error::Error;
 use clap::ArgMatches;
+use hyper::Url;
 use std::{collections::HashMap,
           fmt,
+          path::PathBuf,
           result,
           str::FromStr};
 
@@ -138,6 +140,78 @@ impl fmt::Display for AutomateAuthToken {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "{}", self.0) }
 }
 
+/// A destination for the Supervisor's events, besides a NATS
+/// Streaming server. Each event is delivered as a single line of
+/// JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventSinkConfig {
+    /// Append events to a file, given as `file:PATH`.
+    File(PathBuf),
+    /// `POST` each event to an HTTP or HTTPS URL, given as
+    /// `webhook:URL`.
+    Webhook(Url),
+    /// Write events to a Unix domain socket, given as `unix:PATH`.
+    UnixSocket(PathBuf),
+}
+
+impl EventSinkConfig {
+    /// The name of the Clap argument we'll use for arguments of this type.
+    pub const ARG_NAME: &'static str = "EVENT_SINK";
+
+    /// Ensure that user input from Clap names a sink.
+    ///
+    /// Note: this validates each value given by the user, not all the
+    /// values given at once.
+    #[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+    pub fn validate(value: String) -> result::Result<(), String> {
+        value.parse::<Self>().map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    /// Create all the sinks given in validated user input.
+    pub fn from_matches(m: &ArgMatches) -> Vec<Self> {
+        m.values_of(Self::ARG_NAME)
+         .unwrap_or_default()
+         .map(|s| {
+             s.parse()
+              .expect("EVENT_SINK should be validated at this point")
+         })
+         .collect()
+    }
+}
+
+impl FromStr for EventSinkConfig {
+    type Err = Error;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        let invalid = || Error::InvalidEventSink(s.to_string());
+        let mut parts = s.splitn(2, ':');
+        let kind = parts.next().unwrap_or_default();
+        let target = parts.next().filter(|t| !t.is_empty()).ok_or_else(invalid)?;
+        match kind {
+            "file" => Ok(EventSinkConfig::File(PathBuf::from(target))),
+            "webhook" => {
+                let url = Url::parse(target).map_err(|_| invalid())?;
+                match url.scheme() {
+                    "http" | "https" => Ok(EventSinkConfig::Webhook(url)),
+                    _ => Err(invalid()),
+                }
+            }
+            "unix" => Ok(EventSinkConfig::UnixSocket(PathBuf::from(target))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for EventSinkConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EventSinkConfig::File(ref path) => write!(f, "file:{}", path.display()),
+            EventSinkConfig::Webhook(ref url) => write!(f, "webhook:{}", url),
+            EventSinkConfig::UnixSocket(ref path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -150,4 +224,41 @@ mod test {
 
     }
 
+    mod event_sink {
+        use super::*;
+
+        #[test]
+        fn parses_each_kind_of_sink() {
+            assert_eq!("file:/hab/sup/default/events.log".parse::<EventSinkConfig>()
+                                                         .unwrap(),
+                       EventSinkConfig::File(PathBuf::from("/hab/sup/default/events.log")));
+            assert_eq!("webhook:https://example.com/hooks/hab".parse::<EventSinkConfig>()
+                                                               .unwrap(),
+                       EventSinkConfig::Webhook(Url::parse("https://example.com/hooks/hab").unwrap()));
+            assert_eq!("unix:/run/events.sock".parse::<EventSinkConfig>().unwrap(),
+                       EventSinkConfig::UnixSocket(PathBuf::from("/run/events.sock")));
+        }
+
+        #[test]
+        fn rejects_invalid_sinks() {
+            for sink in &["",
+                          "file",
+                          "file:",
+                          "nats:localhost",
+                          "webhook:ftp://example.com",
+                          "webhook:not a url"]
+            {
+                assert!(sink.parse::<EventSinkConfig>().is_err(),
+                        "{} should be invalid",
+                        sink);
+            }
+        }
+
+        #[test]
+        fn displays_as_parsed() {
+            let sink = "webhook:http://10.0.0.1:8080/events";
+            assert_eq!(sink.parse::<EventSinkConfig>().unwrap().to_string(), sink);
+        }
+    }
+
 }
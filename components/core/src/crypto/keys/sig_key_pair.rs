@@ -10,6 +10,8 @@ use sodiumoxide::{crypto::sign::{self,
                   randombytes::randombytes};
 
 use super::{super::{hash,
+                    ENDORSEMENT_SUFFIX,
+                    MEMBER_ENDORSEMENT_VERSION,
                     PUBLIC_KEY_SUFFIX,
                     PUBLIC_SIG_KEY_VERSION,
                     SECRET_SIG_KEY_SUFFIX,
@@ -98,6 +100,88 @@ impl SigKeyPair {
         }
     }
 
+    /// Builds a public-only pair from the raw bytes of a public key, such as the ones gossip
+    /// members present along with an endorsement.
+    pub fn from_public_key_bytes(name_with_rev: &str, bytes: &[u8]) -> Result<Self> {
+        let (name, rev) = parse_name_with_rev(name_with_rev)?;
+        match SigPublicKey::from_slice(bytes) {
+            Some(pk) => Ok(Self::new(name, rev, Some(pk), None)),
+            None => {
+                let msg = format!("Can't read sig public key for {}", name_with_rev);
+                Err(Error::CryptoError(msg))
+            }
+        }
+    }
+
+    /// Signs `data` with the secret key, returning the signature on its own rather than
+    /// prepended to `data`.
+    ///
+    /// # Errors
+    ///
+    /// * If the secret key component of the `SigKeyPair` is not present
+    pub fn sign_detached(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(sign::sign_detached(data, self.secret()?).as_ref().to_vec())
+    }
+
+    /// Verifies a signature made over `data` by `sign_detached`.
+    ///
+    /// # Errors
+    ///
+    /// * If the public key component of the `SigKeyPair` is not present
+    /// * If the signature is not the required size
+    /// * If the signature was not made over `data` with this pair's secret key
+    pub fn verify_detached(&self, data: &[u8], signature: &[u8]) -> Result<()> {
+        let signature = match sign::Signature::from_slice(signature) {
+            Some(signature) => signature,
+            None => return Err(Error::CryptoError("Invalid size of signature".to_string())),
+        };
+        if sign::verify_detached(&signature, data, self.public()?) {
+            Ok(())
+        } else {
+            Err(Error::CryptoError(format!("Signature not made by {}", self.name_with_rev())))
+        }
+    }
+
+    /// Endorses the public key of `member` with this pair's secret key, returning the
+    /// endorsement.
+    pub fn endorse(&self, member: &SigKeyPair) -> Result<Vec<u8>> {
+        self.sign_detached(&Self::endorsement_payload(member)?)
+    }
+
+    /// Verifies that `endorsement` was made for `member` with this pair's secret key.
+    pub fn verify_endorsement(&self, member: &SigKeyPair, endorsement: &[u8]) -> Result<()> {
+        self.verify_detached(&Self::endorsement_payload(member)?, endorsement)
+    }
+
+    /// Endorses `member`, writing the endorsement to the key cache next to its keys.
+    ///
+    /// The return is a `Result` of the path of the endorsement file.
+    pub fn write_endorsement<P: AsRef<Path> + ?Sized>(&self,
+                                                      member: &SigKeyPair,
+                                                      cache_key_path: &P)
+                                                      -> Result<PathBuf> {
+        let path = mk_key_filename(cache_key_path.as_ref(),
+                                   member.name_with_rev(),
+                                   ENDORSEMENT_SUFFIX);
+        let content = format!("{}\n{}\n\n{}",
+                              MEMBER_ENDORSEMENT_VERSION,
+                              self.name_with_rev(),
+                              base64::encode(&self.endorse(member)?));
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// Reads the endorsement of this pair written by `write_endorsement`.
+    pub fn get_endorsement<P: AsRef<Path> + ?Sized>(&self, cache_key_path: &P) -> Result<Vec<u8>> {
+        let path = mk_key_filename(cache_key_path.as_ref(),
+                                   self.name_with_rev(),
+                                   ENDORSEMENT_SUFFIX);
+        if !path.is_file() {
+            return Err(Error::CryptoError(format!("No endorsement found at {}", path.display())));
+        }
+        read_key_bytes(&path)
+    }
+
     pub fn get_public_key_path<P: AsRef<Path> + ?Sized>(key_with_rev: &str,
                                                         cache_key_path: &P)
                                                         -> Result<PathBuf> {
@@ -290,6 +374,12 @@ impl SigKeyPair {
                             Some(self.to_secret_string()?))
     }
 
+    fn endorsement_payload(member: &SigKeyPair) -> Result<Vec<u8>> {
+        let mut payload = format!("{}\n", member.name_with_rev()).into_bytes();
+        payload.extend_from_slice(member.public()?.as_ref());
+        Ok(payload)
+    }
+
     fn get_public_key(key_with_rev: &str, cache_key_path: &Path) -> Result<SigPublicKey> {
         let public_keyfile = mk_key_filename(cache_key_path, key_with_rev, PUBLIC_KEY_SUFFIX);
         let bytes = read_key_bytes(&public_keyfile)?;
@@ -533,6 +623,43 @@ mod test {
         SigKeyPair::get_secret_key_path(VALID_NAME_WITH_REV, cache.path()).unwrap();
     }
 
+    #[test]
+    fn sign_and_verify_detached() {
+        let pair = SigKeyPair::generate_pair_for_origin("unicorn").unwrap();
+        let signature = pair.sign_detached(b"Guess who?").unwrap();
+
+        pair.verify_detached(b"Guess who?", &signature).unwrap();
+        assert!(pair.verify_detached(b"Guess what?", &signature).is_err());
+        assert!(pair.verify_detached(b"Guess who?", &signature[1..])
+                    .is_err());
+    }
+
+    #[test]
+    fn verify_detached_with_another_key() {
+        let pair = SigKeyPair::generate_pair_for_origin("unicorn").unwrap();
+        let other = SigKeyPair::generate_pair_for_origin("dragon").unwrap();
+        let signature = other.sign_detached(b"Guess who?").unwrap();
+
+        assert!(pair.verify_detached(b"Guess who?", &signature).is_err());
+    }
+
+    #[test]
+    fn endorsement_round_trip() {
+        let cache = Builder::new().prefix("key_cache").tempdir().unwrap();
+        let ca = SigKeyPair::generate_pair_for_origin("gossip-ca").unwrap();
+        let member = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        ca.write_endorsement(&member, cache.path()).unwrap();
+
+        let endorsement = member.get_endorsement(cache.path()).unwrap();
+        let presented =
+            SigKeyPair::from_public_key_bytes(&member.name_with_rev(),
+                                              member.public().unwrap().as_ref()).unwrap();
+        ca.verify_endorsement(&presented, &endorsement).unwrap();
+
+        let impostor = SigKeyPair::generate_pair_for_origin("web-1").unwrap();
+        assert!(ca.verify_endorsement(&impostor, &endorsement).is_err());
+    }
+
     #[test]
     fn write_file_from_str_secret() {
         let cache = Builder::new().prefix("key_cache").tempdir().unwrap();
@@ -209,6 +209,25 @@
 //!
 //! <symkey_base64>
 //! ```
+//!
+//! ## Member endorsements
+//!
+//! A gossip certificate authority (an ordinary sig key pair) endorses a Supervisor's member key
+//! by signing the member key's name with revision and public key. The endorsement is written
+//! next to the member key as `<member key name with revision>.endorsement`, with the same 4
+//! line layout as a key:
+//!
+//! 1. The endorsement format version
+//! 1. The name, including revision, of the endorsing key
+//! 1. An empty line
+//! 1. The detached signature, which is Bas64-encoded
+//!
+//! ```text
+//! MEMBER-ENDORSEMENT-1
+//! gossip-ca-20160405144945
+//!
+//! <signature_base64>
+//! ```
 
 use crypto;
 use std::path::{Path,
@@ -230,6 +249,8 @@ pub static SECRET_SIG_KEY_SUFFIX: &'static str = "sig.key";
 pub static SECRET_BOX_KEY_SUFFIX: &'static str = "box.key";
 /// The suffix on the end of a secret symmetric key file
 pub static SECRET_SYM_KEY_SUFFIX: &'static str = "sym.key";
+/// The suffix on the end of a member key endorsement file
+pub static ENDORSEMENT_SUFFIX: &'static str = "endorsement";
 /// The hashing function we're using during sign/verify
 /// See also: https://download.libsodium.org/doc/hashing/generic_hashing.html
 pub static SIG_HASH_TYPE: &'static str = "BLAKE2b";
@@ -248,6 +269,7 @@ pub const SECRET_SIG_KEY_VERSION: &str = "SIG-SEC-1";
 pub const PUBLIC_BOX_KEY_VERSION: &str = "BOX-PUB-1";
 pub const SECRET_BOX_KEY_VERSION: &str = "BOX-SEC-1";
 pub const SECRET_SYM_KEY_VERSION: &str = "SYM-SEC-1";
+pub const MEMBER_ENDORSEMENT_VERSION: &str = "MEMBER-ENDORSEMENT-1";
 
 pub mod artifact;
 #[cfg(windows)]
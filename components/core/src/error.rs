@@ -93,6 +93,12 @@ pub enum Error {
     InvalidPackageType(String),
     /// Occurs when a service group string cannot be successfully parsed.
     InvalidServiceGroup(String),
+    /// Occurs when an update window string cannot be successfully parsed.
+    InvalidUpdateWindow(String),
+    /// Occurs when a resource limit cannot be successfully parsed.
+    InvalidResourceLimit(String),
+    /// Occurs when a declarative health check cannot be parsed
+    InvalidHealthCheck(String),
     /// Occurs when an origin is in an invalid format
     InvalidOrigin(String),
     /// Occurs when an OsString path cannot be converted to a String
@@ -279,6 +285,13 @@ impl fmt::Display for Error {
                          service.group (example: redis.production)",
                         e)
             }
+            Error::InvalidResourceLimit(ref e) => format!("Invalid resource limit: {}", e),
+            Error::InvalidHealthCheck(ref e) => format!("Invalid health check: {}", e),
+            Error::InvalidUpdateWindow(ref e) => {
+                format!("Invalid update window: {}. A valid update window is a cron-style \
+                         schedule followed by a duration (example: '0 2 * * sat,sun 4h')",
+                        e)
+            }
             Error::InvalidOrigin(ref origin) => {
                 format!("Invalid origin: {}. Origins must begin with a lowercase letter or \
                          number. Allowed characters include lowercase letters, numbers, -, and _. \
@@ -440,6 +453,12 @@ impl error::Error for Error {
                 "Service group strings must be in service.group[@organization] format (example: \
                  redis.production or foo.default@bazcorp)"
             }
+            Error::InvalidResourceLimit(_) => "Resource limits could not be parsed",
+            Error::InvalidHealthCheck(_) => "Health check could not be parsed",
+            Error::InvalidUpdateWindow(_) => {
+                "Update windows must be a cron-style schedule followed by a duration (example: \
+                 '0 2 * * sat,sun 4h')"
+            }
             Error::InvalidOrigin(_) => {
                 "Origins must begin with a lowercase letter or number.  Allowed characters include \
                  a - z, 0 - 9, _, and -. No more than 255 characters."
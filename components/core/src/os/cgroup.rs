@@ -0,0 +1,99 @@
+//! Each supervised service runs in its own cgroup v2 slice beneath
+//! `habitat.slice`. The Launcher creates the slice and applies the
+//! service's resource limits to it; the Supervisor reads the service's
+//! resource usage back out of it.
+
+use serde_derive::Serialize;
+use std::{fs,
+          path::{Path,
+                 PathBuf}};
+
+/// Where the unified cgroup hierarchy is mounted.
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+/// The cgroup beneath which every service's slice is created.
+pub const HABITAT_SLICE: &str = "habitat.slice";
+
+/// Whether the unified (v2) cgroup hierarchy is mounted.
+pub fn is_v2() -> bool { Path::new(CGROUP_ROOT).join("cgroup.controllers").is_file() }
+
+/// The cgroup that the service with the given Launcher id (its
+/// service group) runs in.
+pub fn service_cgroup(id: &str) -> PathBuf {
+    Path::new(CGROUP_ROOT).join(HABITAT_SLICE)
+                          .join(format!("{}.slice", id))
+}
+
+/// A snapshot of the resources a service's processes are using.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct ResourceUsage {
+    /// Total CPU time consumed, in microseconds.
+    pub cpu_usec:     u64,
+    pub memory_bytes: u64,
+    pub pids:         u64,
+    /// How many processes have been killed for exceeding the memory
+    /// limit since the cgroup was created.
+    pub oom_kills:    u64,
+}
+
+impl ResourceUsage {
+    /// Read the usage of the cgroup at `path`, or `None` if there is no
+    /// such cgroup.
+    pub fn read(path: &Path) -> Option<ResourceUsage> {
+        if !path.is_dir() {
+            return None;
+        }
+        Some(ResourceUsage { cpu_usec:     read_keyed(&path.join("cpu.stat"), "usage_usec"),
+                             memory_bytes: read_value(&path.join("memory.current")),
+                             pids:         read_value(&path.join("pids.current")),
+                             oom_kills:    oom_kills(path), })
+    }
+}
+
+/// How many processes in the cgroup at `path` have been killed for
+/// exceeding its memory limit.
+pub fn oom_kills(path: &Path) -> u64 { read_keyed(&path.join("memory.events"), "oom_kill") }
+
+/// Read a file holding a single number. Controllers that are not
+/// enabled for the cgroup have no files, and so read as zero.
+fn read_value(file: &Path) -> u64 {
+    fs::read_to_string(file).ok()
+                            .and_then(|body| body.trim().parse().ok())
+                            .unwrap_or(0)
+}
+
+/// Read one entry of a file of `key value` lines.
+fn read_keyed(file: &Path, key: &str) -> u64 {
+    fs::read_to_string(file).ok()
+                            .and_then(|body| keyed_value(&body, key))
+                            .unwrap_or(0)
+}
+
+fn keyed_value(body: &str, key: &str) -> Option<u64> {
+    body.lines().find_map(|line| {
+                    let mut parts = line.split_whitespace();
+                    if parts.next() == Some(key) {
+                        parts.next().and_then(|v| v.parse().ok())
+                    } else {
+                        None
+                    }
+                })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keyed_value_finds_the_key() {
+        let body = "low 0\nhigh 0\nmax 12\noom 3\noom_kill 2\n";
+        assert_eq!(keyed_value(body, "oom_kill"), Some(2));
+        assert_eq!(keyed_value(body, "oom"), Some(3));
+        assert_eq!(keyed_value(body, "oom_group_kill"), None);
+    }
+
+    #[test]
+    fn service_cgroup_is_beneath_the_habitat_slice() {
+        assert_eq!(service_cgroup("redis.default"),
+                   PathBuf::from("/sys/fs/cgroup/habitat.slice/redis.default.slice"));
+    }
+}
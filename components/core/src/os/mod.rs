@@ -1,3 +1,5 @@
+#[cfg(target_os = "linux")]
+pub mod cgroup;
 pub mod ffi;
 pub mod filesystem;
 pub mod net;
@@ -9,7 +9,9 @@ use super::{list::package_list_for_ident,
             PackageIdent};
 use crate::{error::{Error,
                     Result},
-            fs};
+            fs,
+            service::{HealthCheck,
+                      ResourceLimits}};
 use serde_derive::{Deserialize,
                    Serialize};
 use std::{cmp::{Ordering,
@@ -359,6 +361,35 @@ impl PackageInstall {
         }
     }
 
+    /// The resource limits the package declares for its service, if any.
+    pub fn resource_limits(&self) -> Result<ResourceLimits> {
+        match self.read_metafile(MetaFile::ResourceLimits) {
+            Ok(body) => {
+                let values = parse_key_value(&body).map_err(|_| {
+                                 Error::MetaFileMalformed(MetaFile::ResourceLimits)
+                             })?;
+                ResourceLimits::from_key_values(&values)
+            }
+            Err(Error::MetaFileNotFound(MetaFile::ResourceLimits)) => Ok(ResourceLimits::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The health check the package declares for its service, if any.
+    pub fn health_check(&self) -> Result<Option<HealthCheck>> {
+        match self.read_metafile(MetaFile::HealthCheck) {
+            Ok(body) => {
+                let values =
+                    parse_key_value(&body).map_err(|_| {
+                                              Error::MetaFileMalformed(MetaFile::HealthCheck)
+                                          })?;
+                HealthCheck::from_key_values(&values).map(Some)
+            }
+            Err(Error::MetaFileNotFound(MetaFile::HealthCheck)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     /// A vector of ports we expose
     pub fn exposes(&self) -> Result<Vec<String>> {
         match self.read_metafile(MetaFile::Exposes) {
@@ -143,17 +143,20 @@ pub enum MetaFile {
     BuildTDeps,
     CFlags,
     Config,
+    ConfigSchema,
     Deps,
     Environment,
     EnvironmentSep,
     Exports,
     Exposes,
+    HealthCheck,
     Ident,
     LdFlags,
     LdRunPath,
     Manifest,
     Path,
     ResolvedServices, // Composite-only
+    ResourceLimits,
     RuntimeEnvironment,
     RuntimePath,
     Services, // Composite-only
@@ -174,17 +177,20 @@ impl fmt::Display for MetaFile {
             MetaFile::BuildTDeps => "BUILD_TDEPS",
             MetaFile::CFlags => "CFLAGS",
             MetaFile::Config => "default.toml",
+            MetaFile::ConfigSchema => "config.schema.json",
             MetaFile::Deps => "DEPS",
             MetaFile::Environment => "ENVIRONMENT",
             MetaFile::EnvironmentSep => "ENVIRONMENT_SEP",
             MetaFile::Exports => "EXPORTS",
             MetaFile::Exposes => "EXPOSES",
+            MetaFile::HealthCheck => "HEALTH_CHECK",
             MetaFile::Ident => "IDENT",
             MetaFile::LdFlags => "LDFLAGS",
             MetaFile::LdRunPath => "LD_RUN_PATH",
             MetaFile::Manifest => "MANIFEST",
             MetaFile::Path => "PATH",
             MetaFile::ResolvedServices => "RESOLVED_SERVICES",
+            MetaFile::ResourceLimits => "RESOURCE_LIMITS",
             MetaFile::RuntimeEnvironment => "RUNTIME_ENVIRONMENT",
             MetaFile::RuntimePath => "RUNTIME_PATH",
             MetaFile::Services => "SERVICES",
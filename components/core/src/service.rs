@@ -1,15 +1,20 @@
-use crate::error::{Error,
-                   Result};
+use crate::{error::{Error,
+                    Result},
+            util::{deserialize_using_from_str,
+                   serialize_using_to_string}};
 use regex::Regex;
 use serde_derive::{Deserialize,
                    Serialize};
-use std::{fmt,
+use std::{collections::HashMap,
+          fmt,
           num::ParseIntError,
           ops::{Deref,
                 DerefMut},
           result,
           str::FromStr,
           time::Duration};
+use time::{Timespec,
+           Tm};
 
 lazy_static::lazy_static! {
     static ref SG_FROM_STR_RE: Regex =
@@ -408,6 +413,517 @@ impl From<HealthCheckInterval> for Duration {
     fn from(h: HealthCheckInterval) -> Self { h.0 }
 }
 
+/// Limits on the resources a service's processes may use. The
+/// Launcher enforces them on Linux, where each service runs in its own
+/// cgroup; elsewhere they are ignored.
+#[derive(Clone,
+         Copy,
+         Debug,
+         Default,
+         Deserialize,
+         Eq,
+         Hash,
+         PartialEq,
+         Serialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// In thousandths of a CPU.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_millis:   Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pids:         Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Read limits from the `key=value` pairs of a package's
+    /// `RESOURCE_LIMITS` metafile. The keys are `cpu`, `memory` and
+    /// `pids`, with values as accepted by `parse_cpu_limit`,
+    /// `parse_memory_limit` and `parse_pids_limit`.
+    pub fn from_key_values(values: &HashMap<String, String>) -> Result<Self> {
+        let mut limits = ResourceLimits::default();
+        for (key, value) in values {
+            match key.as_str() {
+                "cpu" => limits.cpu_millis = Some(parse_cpu_limit(value)?),
+                "memory" => limits.memory_bytes = Some(parse_memory_limit(value)?),
+                "pids" => limits.pids = Some(parse_pids_limit(value)?),
+                _ => {
+                    return Err(Error::InvalidResourceLimit(format!("'{}' is not a known \
+                                                                    resource",
+                                                                   key)));
+                }
+            }
+        }
+        Ok(limits)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cpu_millis.is_none() && self.memory_bytes.is_none() && self.pids.is_none()
+    }
+
+    /// Fill in any limit not set here with the one from `defaults`.
+    pub fn or(self, defaults: ResourceLimits) -> ResourceLimits {
+        ResourceLimits { cpu_millis:   self.cpu_millis.or(defaults.cpu_millis),
+                         memory_bytes: self.memory_bytes.or(defaults.memory_bytes),
+                         pids:         self.pids.or(defaults.pids), }
+    }
+}
+
+/// Parse a CPU limit, given either as a number of CPUs (`2`, `0.5`) or
+/// in thousandths of a CPU (`500m`), into thousandths of a CPU.
+pub fn parse_cpu_limit(value: &str) -> Result<u32> {
+    let invalid = || Error::InvalidResourceLimit(format!("'{}' is not a valid CPU limit", value));
+    let millis = if value.ends_with('m') {
+        value[..value.len() - 1].parse::<u32>()
+                                .map_err(|_| invalid())?
+    } else {
+        let cpus = value.parse::<f64>().map_err(|_| invalid())?;
+        if !cpus.is_finite() || cpus < 0.0 || cpus * 1000.0 > f64::from(u32::max_value()) {
+            return Err(invalid());
+        }
+        (cpus * 1000.0).round() as u32
+    };
+    if millis == 0 {
+        return Err(invalid());
+    }
+    Ok(millis)
+}
+
+/// Parse a memory limit, given in bytes with an optional `K`, `M`, `G`
+/// or `T` suffix (powers of 1024), into bytes.
+pub fn parse_memory_limit(value: &str) -> Result<u64> {
+    let invalid =
+        || Error::InvalidResourceLimit(format!("'{}' is not a valid memory limit", value));
+    let (amount, multiplier) = match value.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&value[..value.len() - 1], 1 << 10),
+        Some('M') => (&value[..value.len() - 1], 1 << 20),
+        Some('G') => (&value[..value.len() - 1], 1 << 30),
+        Some('T') => (&value[..value.len() - 1], 1 << 40),
+        _ => (value, 1),
+    };
+    amount.parse::<u64>()
+          .ok()
+          .and_then(|a| a.checked_mul(multiplier))
+          .filter(|bytes| *bytes > 0)
+          .ok_or_else(invalid)
+}
+
+/// Parse a limit on the number of processes (and threads) a service
+/// may run at once.
+pub fn parse_pids_limit(value: &str) -> Result<u64> {
+    value.parse::<u64>()
+         .ok()
+         .filter(|pids| *pids > 0)
+         .ok_or_else(|| {
+             Error::InvalidResourceLimit(format!("'{}' is not a valid process limit", value))
+         })
+}
+
+/// What a declarative health check probes.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HealthCheckProbe {
+    /// An HTTP GET of `path` from `host:port`.
+    Http {
+        host: String,
+        port: u16,
+        path: String,
+    },
+    /// A TCP connection to `host:port`.
+    Tcp { host: String, port: u16 },
+    /// A command, run with the service's environment and as its user,
+    /// whose exit code is read as a `health-check` hook's would be.
+    Exec { command: String },
+}
+
+impl FromStr for HealthCheckProbe {
+    type Err = Error;
+
+    /// Parse a probe written as `http://HOST:PORT/PATH`,
+    /// `tcp://HOST:PORT` or `exec:COMMAND`.
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || Error::InvalidHealthCheck(format!("'{}' is not a valid probe", value));
+        if value.starts_with("http://") {
+            let rest = &value["http://".len()..];
+            let (authority, path) = match rest.find('/') {
+                Some(i) => (&rest[..i], &rest[i..]),
+                None => (rest, "/"),
+            };
+            let (host, port) = parse_host_port(authority).ok_or_else(invalid)?;
+            Ok(HealthCheckProbe::Http { host,
+                                        port,
+                                        path: path.to_string() })
+        } else if value.starts_with("tcp://") {
+            let (host, port) = parse_host_port(&value["tcp://".len()..]).ok_or_else(invalid)?;
+            Ok(HealthCheckProbe::Tcp { host, port })
+        } else if value.starts_with("exec:") && !value["exec:".len()..].trim().is_empty() {
+            Ok(HealthCheckProbe::Exec { command: value["exec:".len()..].to_string(), })
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+impl fmt::Display for HealthCheckProbe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HealthCheckProbe::Http { ref host,
+                                     port,
+                                     ref path, } => write!(f, "http://{}:{}{}", host, port, path),
+            HealthCheckProbe::Tcp { ref host, port } => write!(f, "tcp://{}:{}", host, port),
+            HealthCheckProbe::Exec { ref command } => write!(f, "exec:{}", command),
+        }
+    }
+}
+
+/// Split `HOST:PORT` (where an IPv6 host is written in brackets) into
+/// its host and non-zero port.
+fn parse_host_port(authority: &str) -> Option<(String, u16)> {
+    let i = authority.rfind(':')?;
+    let (host, port) = (&authority[..i], &authority[i + 1..]);
+    if host.is_empty() {
+        return None;
+    }
+    port.parse::<u16>()
+        .ok()
+        .filter(|port| *port > 0)
+        .map(|port| (host.to_string(), port))
+}
+
+/// A health check the Supervisor performs itself, so that a service
+/// doesn't need a `health-check` hook just to poll a port.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct HealthCheck {
+    #[serde(deserialize_with = "deserialize_using_from_str",
+            serialize_with = "serialize_using_to_string")]
+    pub probe:   HealthCheckProbe,
+    /// For HTTP probes, the status the response must have. Without
+    /// one, any 2xx status passes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status:  Option<u16>,
+    /// For HTTP probes, text the response body must contain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body:    Option<String>,
+    /// How long, in seconds, the probe may take before the check
+    /// fails.
+    #[serde(default = "HealthCheck::default_timeout",
+            deserialize_with = "deserialize_health_check_timeout")]
+    pub timeout: u64,
+}
+
+impl HealthCheck {
+    pub const DEFAULT_TIMEOUT: u64 = 10;
+    /// Probes are run once per health check interval, so there is no
+    /// use in letting one take longer than this.
+    pub const MAX_TIMEOUT: u64 = 60 * 60;
+
+    pub fn new(probe: HealthCheckProbe) -> Self {
+        HealthCheck { probe,
+                      status: None,
+                      body: None,
+                      timeout: Self::DEFAULT_TIMEOUT }
+    }
+
+    /// Read a health check from the `key=value` pairs of a package's
+    /// `HEALTH_CHECK` metafile. The `probe` key is required; `status`,
+    /// `body` and `timeout` are optional.
+    pub fn from_key_values(values: &HashMap<String, String>) -> Result<Self> {
+        let probe = values.get("probe")
+                          .ok_or_else(|| Error::InvalidHealthCheck("no probe given".to_string()))?;
+        let mut check = HealthCheck::new(probe.parse()?);
+        for (key, value) in values {
+            match key.as_str() {
+                "probe" => {}
+                "status" => check.status = Some(parse_health_check_status(value)?),
+                "body" => check.body = Some(value.to_string()),
+                "timeout" => check.timeout = parse_health_check_timeout(value)?,
+                _ => {
+                    return Err(Error::InvalidHealthCheck(format!("'{}' is not a known \
+                                                                  setting",
+                                                                 key)));
+                }
+            }
+        }
+        Ok(check)
+    }
+
+    pub fn timeout(&self) -> Duration { Duration::from_secs(self.timeout) }
+
+    fn default_timeout() -> u64 { Self::DEFAULT_TIMEOUT }
+}
+
+/// Parse the HTTP status a health check expects.
+pub fn parse_health_check_status(value: &str) -> Result<u16> {
+    value.parse::<u16>()
+         .ok()
+         .filter(|status| *status >= 100 && *status <= 599)
+         .ok_or_else(|| {
+             Error::InvalidHealthCheck(format!("'{}' is not a valid HTTP status", value))
+         })
+}
+
+/// Parse a health check timeout, in seconds.
+pub fn parse_health_check_timeout(value: &str) -> Result<u64> {
+    value.parse::<u64>()
+         .map_err(|_| Error::InvalidHealthCheck(format!("'{}' is not a valid timeout", value)))
+         .and_then(validate_health_check_timeout)
+}
+
+/// Check that a health check timeout, in seconds, is at least one
+/// second and no more than `HealthCheck::MAX_TIMEOUT`.
+pub fn validate_health_check_timeout(timeout: u64) -> Result<u64> {
+    if timeout > 0 && timeout <= HealthCheck::MAX_TIMEOUT {
+        return Ok(timeout);
+    }
+    let msg = format!("'{}' is not a valid timeout; it must be between 1 and {} seconds",
+                      timeout,
+                      HealthCheck::MAX_TIMEOUT);
+    Err(Error::InvalidHealthCheck(msg))
+}
+
+fn deserialize_health_check_timeout<'de, D>(d: D) -> result::Result<u64, D::Error>
+    where D: serde::Deserializer<'de>
+{
+    let timeout: u64 = serde::Deserialize::deserialize(d)?;
+    validate_health_check_timeout(timeout).map_err(serde::de::Error::custom)
+}
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+/// The longest an update window may stay open.
+const MAX_UPDATE_WINDOW_MINUTES: i64 = 7 * MINUTES_PER_DAY;
+/// How far ahead to look for the next opening of an update window.
+/// Any schedule that can match at all (e.g. on February 29th) will
+/// match within this many days.
+const UPDATE_WINDOW_SEARCH_DAYS: i64 = 4 * 366;
+/// The most days each month can have, counting February 29th.
+const DAYS_IN_MONTH: [i32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+const MONTH_NAMES: &[&str] =
+    &["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const DAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A recurring period of time during which updates may be applied.
+///
+/// An update window is written as a cron-style schedule of when the
+/// window opens, followed by how long it stays open. For example,
+/// `0 2 * * sat,sun 4h` opens at 02:00 UTC every Saturday and Sunday
+/// and closes at 06:00 UTC.
+///
+/// The five schedule fields are the minute (0-59), hour (0-23), day of
+/// the month (1-31), month (1-12 or jan-dec) and day of the week (0-7
+/// or sun-sat; both 0 and 7 are Sunday). Each field is `*`, a value, a
+/// range (`1-5`), or a comma-separated list of those, optionally
+/// followed by a step (`*/15`, `0-30/10`). As in cron, if both the day
+/// of the month and the day of the week are restricted, a day matches
+/// if either of them does. The duration is a number of minutes (`30m`),
+/// hours (`4h`) or days (`1d`). All times are in UTC.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UpdateWindow {
+    raw:              String,
+    minutes:          u64,
+    hours:            u64,
+    days_of_month:    u64,
+    months:           u64,
+    days_of_week:     u64,
+    any_day_of_month: bool,
+    any_day_of_week:  bool,
+    duration_minutes: i64,
+}
+
+impl UpdateWindow {
+    /// Is the window open right now?
+    pub fn is_open(&self) -> bool { self.is_open_at(time::get_time()) }
+
+    /// Is the window open at the given time?
+    pub fn is_open_at(&self, at: Timespec) -> bool {
+        let now = at.sec / 60;
+        let earliest = now - self.duration_minutes + 1;
+        let days = earliest / MINUTES_PER_DAY..=now / MINUTES_PER_DAY;
+        days.flat_map(|day| self.openings_on(day))
+            .any(|o| o >= earliest && o <= now)
+    }
+
+    /// The next time the window opens, if it ever does.
+    pub fn next_opening(&self) -> Option<Timespec> { self.next_opening_after(time::get_time()) }
+
+    /// The first time after `after` that the window opens, if it ever
+    /// does.
+    pub fn next_opening_after(&self, after: Timespec) -> Option<Timespec> {
+        let first = after.sec / 60 + 1;
+        let first_day = first / MINUTES_PER_DAY;
+        (first_day..first_day + UPDATE_WINDOW_SEARCH_DAYS).flat_map(|day| self.openings_on(day))
+                                                          .find(|o| *o >= first)
+                                                          .map(|o| Timespec::new(o * 60, 0))
+    }
+
+    /// The minutes (counted from the epoch) at which the window opens
+    /// on the given day (counted from the epoch). The minutes of a day
+    /// are only looked at if the window opens on that day at all.
+    fn openings_on(&self, day: i64) -> impl Iterator<Item = i64> + '_ {
+        let start = day * MINUTES_PER_DAY;
+        let minutes = if self.matches_day(&time::at_utc(Timespec::new(start * 60, 0))) {
+            0..MINUTES_PER_DAY
+        } else {
+            0..0
+        };
+        minutes.filter(move |m| {
+                   has_bit(self.hours, (m / 60) as i32) && has_bit(self.minutes, (m % 60) as i32)
+               })
+               .map(move |m| start + m)
+    }
+
+    fn matches_day(&self, tm: &Tm) -> bool {
+        let day_of_month = has_bit(self.days_of_month, tm.tm_mday);
+        let day_of_week = has_bit(self.days_of_week, tm.tm_wday);
+        let day = match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        };
+        day && has_bit(self.months, tm.tm_mon + 1)
+    }
+}
+
+impl FromStr for UpdateWindow {
+    type Err = Error;
+
+    fn from_str(value: &str) -> result::Result<Self, Self::Err> {
+        let invalid =
+            |reason: String| Error::InvalidUpdateWindow(format!("'{}' ({})", value, reason));
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() != 6 {
+            return Err(invalid("expected 5 schedule fields followed by a duration".to_string()));
+        }
+        let (schedule, duration) = (&fields[..5], fields[5]);
+
+        let mut days_of_week = parse_schedule_field(schedule[4], 0, 7, DAY_NAMES).map_err(invalid)?;
+        // Both 0 and 7 are Sunday
+        if has_bit(days_of_week, 7) {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        let minutes = parse_schedule_field(schedule[0], 0, 59, &[]).map_err(invalid)?;
+        let hours = parse_schedule_field(schedule[1], 0, 23, &[]).map_err(invalid)?;
+        let days_of_month = parse_schedule_field(schedule[2], 1, 31, &[]).map_err(invalid)?;
+        let months = parse_schedule_field(schedule[3], 1, 12, MONTH_NAMES).map_err(invalid)?;
+        let duration_minutes = parse_window_duration(duration).map_err(invalid)?;
+        let any_day_of_month = schedule[2] == "*";
+        let any_day_of_week = schedule[4] == "*";
+        // Only the day of the month can rule out every day, and only
+        // when the day of the week doesn't match days of its own.
+        if !any_day_of_month && any_day_of_week && !days_occur(days_of_month, months) {
+            return Err(invalid("none of those months has any of those days".to_string()));
+        }
+
+        Ok(UpdateWindow { raw: fields.join(" "),
+                          minutes,
+                          hours,
+                          days_of_month,
+                          months,
+                          days_of_week,
+                          any_day_of_month,
+                          any_day_of_week,
+                          duration_minutes })
+    }
+}
+
+impl fmt::Display for UpdateWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.raw) }
+}
+
+impl serde::Serialize for UpdateWindow {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for UpdateWindow {
+    fn deserialize<D>(deserializer: D) -> result::Result<Self, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        let raw = <String as serde::Deserialize>::deserialize(deserializer)?;
+        UpdateWindow::from_str(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+fn has_bit(mask: u64, bit: i32) -> bool { mask & (1 << bit) != 0 }
+
+/// Whether any of the days of the month occur in any of the months.
+fn days_occur(days_of_month: u64, months: u64) -> bool {
+    (1..=12).filter(|month| has_bit(months, *month))
+            .any(|month| {
+                (1..=DAYS_IN_MONTH[month as usize - 1]).any(|day| has_bit(days_of_month, day))
+            })
+}
+
+/// Parse one field of a cron-style schedule into a bitmask of the
+/// values it matches. `names`, if given, are accepted in place of the
+/// values starting at `min`.
+fn parse_schedule_field(field: &str,
+                        min: u32,
+                        max: u32,
+                        names: &[&str])
+                        -> result::Result<u64, String> {
+    let value = |s: &str| -> result::Result<u32, String> {
+        if let Some(i) = names.iter().position(|n| n.eq_ignore_ascii_case(s)) {
+            return Ok(min + i as u32);
+        }
+        s.parse::<u32>()
+         .map_err(|_| format!("'{}' is not a valid value", s))
+    };
+
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(i) => {
+                let step = part[i + 1..].parse::<u32>()
+                                        .ok()
+                                        .filter(|s| *s > 0)
+                                        .ok_or_else(|| format!("'{}' is not a valid step", part))?;
+                (&part[..i], step)
+            }
+            None => (part, 1),
+        };
+        let (low, high) = if range == "*" {
+            (min, max)
+        } else if let Some(i) = range.find('-') {
+            (value(&range[..i])?, value(&range[i + 1..])?)
+        } else {
+            let v = value(range)?;
+            // `5/10` means "every 10, starting at 5"
+            (v, if step > 1 { max } else { v })
+        };
+        if low < min || high > max || low > high {
+            return Err(format!("'{}' is outside of {}-{}", part, min, max));
+        }
+        for v in (low..=high).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+/// Parse the duration of an update window, in minutes.
+fn parse_window_duration(duration: &str) -> result::Result<i64, String> {
+    let invalid = || format!("'{}' is not a valid duration", duration);
+    let (amount, unit) = match duration.char_indices().last() {
+        Some((unit_start, _)) => duration.split_at(unit_start),
+        None => return Err(invalid()),
+    };
+    let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+    let minutes = match unit {
+        "m" => Some(amount),
+        "h" => amount.checked_mul(60),
+        "d" => amount.checked_mul(MINUTES_PER_DAY),
+        _ => return Err(invalid()),
+    };
+    match minutes {
+        Some(minutes) if minutes > 0 && minutes <= MAX_UPDATE_WINDOW_MINUTES => Ok(minutes),
+        _ => Err(format!("'{}' must be between 1 minute and 7 days", duration)),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
@@ -740,4 +1256,229 @@ mod test {
         assert_eq!("(5s)".to_owned(),
                    format!("{}", HealthCheckInterval::from_str("5").unwrap()));
     }
+
+    /// 2019-06-01T00:00:00Z was a Saturday
+    const SATURDAY_MIDNIGHT: i64 = 1_559_347_200;
+
+    fn at(offset_minutes: i64) -> Timespec {
+        Timespec::new(SATURDAY_MIDNIGHT + offset_minutes * 60, 0)
+    }
+
+    #[test]
+    fn update_window_round_trips_through_str() {
+        let window = UpdateWindow::from_str("0 2 * * sat,sun 4h").unwrap();
+        assert_eq!(window.to_string(), "0 2 * * sat,sun 4h");
+    }
+
+    #[test]
+    fn update_window_requires_a_schedule_and_a_duration() {
+        assert!(UpdateWindow::from_str("0 2 * * sat").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * sat 4h extra").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * sat 4x").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * sat 0m").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * sat 8d").is_err());
+        assert!(UpdateWindow::from_str("60 2 * * sat 4h").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * funday 4h").is_err());
+        assert!(UpdateWindow::from_str("*/0 2 * * sat 4h").is_err());
+    }
+
+    #[test]
+    fn update_window_rejects_durations_it_cannot_represent() {
+        assert!(UpdateWindow::from_str("0 2 * * sat 4é").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * sat é").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * sat 9223372036854775807d").is_err());
+        assert!(UpdateWindow::from_str("0 2 * * sat 9223372036854775807h").is_err());
+    }
+
+    #[test]
+    fn update_window_is_open_for_its_duration() {
+        let window = UpdateWindow::from_str("0 2 * * sat,sun 4h").unwrap();
+        assert!(!window.is_open_at(at(119)));
+        assert!(window.is_open_at(at(120)));
+        assert!(window.is_open_at(at(359)));
+        assert!(!window.is_open_at(at(360)));
+        // Sunday
+        assert!(window.is_open_at(at(MINUTES_PER_DAY + 180)));
+        // Monday
+        assert!(!window.is_open_at(at(2 * MINUTES_PER_DAY + 180)));
+    }
+
+    #[test]
+    fn update_window_can_span_midnight() {
+        let window = UpdateWindow::from_str("0 23 * * fri 2h").unwrap();
+        assert!(window.is_open_at(at(30)));
+        assert!(!window.is_open_at(at(60)));
+    }
+
+    #[test]
+    fn update_window_treats_seven_as_sunday() {
+        let window = UpdateWindow::from_str("0 0 * * 7 1h").unwrap();
+        assert!(window.is_open_at(at(MINUTES_PER_DAY)));
+    }
+
+    #[test]
+    fn update_window_matches_either_restricted_day_field() {
+        // The 1st of the month, or any Monday
+        let window = UpdateWindow::from_str("0 0 1 * mon 1h").unwrap();
+        // Saturday June 1st
+        assert!(window.is_open_at(at(0)));
+        // Monday June 3rd
+        assert!(window.is_open_at(at(2 * MINUTES_PER_DAY)));
+        // Tuesday June 4th
+        assert!(!window.is_open_at(at(3 * MINUTES_PER_DAY)));
+    }
+
+    #[test]
+    fn update_window_supports_ranges_and_steps() {
+        let window = UpdateWindow::from_str("*/15 9-17 * jun mon-fri 5m").unwrap();
+        let monday = 2 * MINUTES_PER_DAY;
+        assert!(window.is_open_at(at(monday + 9 * 60 + 45)));
+        assert!(!window.is_open_at(at(monday + 9 * 60 + 50)));
+        assert!(!window.is_open_at(at(monday + 18 * 60)));
+    }
+
+    #[test]
+    fn update_window_next_opening() {
+        let window = UpdateWindow::from_str("30 4 * * mon 1h").unwrap();
+        assert_eq!(window.next_opening_after(at(0)),
+                   Some(at(2 * MINUTES_PER_DAY + 4 * 60 + 30)));
+        // While open, the next opening is the following week's
+        assert_eq!(window.next_opening_after(at(2 * MINUTES_PER_DAY + 4 * 60 + 30)),
+                   Some(at(9 * MINUTES_PER_DAY + 4 * 60 + 30)));
+    }
+
+    #[test]
+    fn update_window_rejects_days_that_never_occur() {
+        assert!(UpdateWindow::from_str("0 0 31 feb * 1h").is_err());
+        assert!(UpdateWindow::from_str("0 3 31 2 * 1h").is_err());
+        assert!(UpdateWindow::from_str("0 0 30,31 feb * 1h").is_err());
+        assert!(UpdateWindow::from_str("0 0 31 apr,jun,sep,nov * 1h").is_err());
+        // Any Sunday would still match
+        assert!(UpdateWindow::from_str("0 0 31 feb sun 1h").is_ok());
+    }
+
+    #[test]
+    fn update_window_opens_on_leap_days() {
+        let window = UpdateWindow::from_str("0 0 29 feb * 1h").unwrap();
+        // Saturday February 29th 2020
+        assert_eq!(window.next_opening_after(at(0)),
+                   Some(Timespec::new(1_582_934_400, 0)));
+    }
+
+    #[test]
+    fn cpu_limits() {
+        assert_eq!(parse_cpu_limit("2").unwrap(), 2000);
+        assert_eq!(parse_cpu_limit("0.5").unwrap(), 500);
+        assert_eq!(parse_cpu_limit("250m").unwrap(), 250);
+        assert!(parse_cpu_limit("0").is_err());
+        assert!(parse_cpu_limit("-1").is_err());
+        assert!(parse_cpu_limit("lots").is_err());
+    }
+
+    #[test]
+    fn memory_limits() {
+        assert_eq!(parse_memory_limit("4096").unwrap(), 4096);
+        assert_eq!(parse_memory_limit("512M").unwrap(), 512 * 1024 * 1024);
+        assert_eq!(parse_memory_limit("2g").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert!(parse_memory_limit("0K").is_err());
+        assert!(parse_memory_limit("M").is_err());
+        assert!(parse_memory_limit("99999999999T").is_err());
+    }
+
+    #[test]
+    fn resource_limits_from_key_values() {
+        let mut values = HashMap::new();
+        values.insert("cpu".to_string(), "1.5".to_string());
+        values.insert("memory".to_string(), "1G".to_string());
+        let limits = ResourceLimits::from_key_values(&values).unwrap();
+        assert_eq!(limits,
+                   ResourceLimits { cpu_millis:   Some(1500),
+                                    memory_bytes: Some(1 << 30),
+                                    pids:         None, });
+
+        values.insert("disk".to_string(), "1G".to_string());
+        assert!(ResourceLimits::from_key_values(&values).is_err());
+    }
+
+    #[test]
+    fn resource_limits_fall_back_to_defaults() {
+        let spec = ResourceLimits { memory_bytes: Some(1024),
+                                    ..Default::default() };
+        let package = ResourceLimits { cpu_millis:   Some(500),
+                                       memory_bytes: Some(2048),
+                                       pids:         None, };
+        assert_eq!(spec.or(package),
+                   ResourceLimits { cpu_millis:   Some(500),
+                                    memory_bytes: Some(1024),
+                                    pids:         None, });
+    }
+
+    #[test]
+    fn health_check_probes_round_trip() {
+        for probe in &["http://localhost:8080/health",
+                       "http://[::1]:80/",
+                       "tcp://10.0.0.1:5432",
+                       "exec:pg_isready -q"]
+        {
+            assert_eq!(probe.parse::<HealthCheckProbe>().unwrap().to_string(),
+                       *probe);
+        }
+        assert_eq!("http://localhost:8080".parse::<HealthCheckProbe>().unwrap(),
+                   HealthCheckProbe::Http { host: "localhost".to_string(),
+                                            port: 8080,
+                                            path: "/".to_string(), });
+    }
+
+    #[test]
+    fn invalid_health_check_probes() {
+        for probe in &["https://localhost:8443/",
+                       "http://localhost/health",
+                       "http://:8080/",
+                       "tcp://localhost:0",
+                       "tcp://localhost:99999",
+                       "exec: ",
+                       "localhost:8080"]
+        {
+            assert!(probe.parse::<HealthCheckProbe>().is_err(),
+                    "{} parsed",
+                    probe);
+        }
+    }
+
+    #[test]
+    fn health_check_from_key_values() {
+        let mut values = HashMap::new();
+        values.insert("probe".to_string(),
+                      "http://localhost:8080/health".to_string());
+        values.insert("status".to_string(), "204".to_string());
+        values.insert("timeout".to_string(), "3".to_string());
+        let check = HealthCheck::from_key_values(&values).unwrap();
+        assert_eq!(check.status, Some(204));
+        assert_eq!(check.body, None);
+        assert_eq!(check.timeout(), Duration::from_secs(3));
+
+        values.insert("status".to_string(), "42".to_string());
+        assert!(HealthCheck::from_key_values(&values).is_err());
+
+        values.remove("probe");
+        values.remove("status");
+        assert!(HealthCheck::from_key_values(&values).is_err());
+    }
+
+    #[test]
+    fn health_check_timeouts_must_be_in_range() {
+        assert_eq!(parse_health_check_timeout("1").unwrap(), 1);
+        assert_eq!(parse_health_check_timeout("3600").unwrap(),
+                   HealthCheck::MAX_TIMEOUT);
+        for timeout in &["0", "3601", "18446744073709551615", "-1", "ten"] {
+            assert!(parse_health_check_timeout(timeout).is_err(),
+                    "{} parsed",
+                    timeout);
+        }
+
+        let probe = "probe = \"tcp://localhost:6379\"\n";
+        let check: HealthCheck = toml::from_str(probe).unwrap();
+        assert_eq!(check.timeout, HealthCheck::DEFAULT_TIMEOUT);
+        assert!(toml::from_str::<HealthCheck>(&format!("{}timeout = 0", probe)).is_err());
+    }
 }
@@ -15,6 +15,7 @@ use habitat_common::{cli::{BINLINK_DIR_ENVVAR,
                            SHUTDOWN_SIGNAL_DEFAULT,
                            SHUTDOWN_TIMEOUT_DEFAULT},
                      types::{AutomateAuthToken,
+                             EventSinkConfig,
                              EventStreamMetadata,
                              ListenCtlAddr},
                      FeatureFlag};
@@ -26,8 +27,11 @@ use habitat_core::{crypto::{keys::PairType,
                              Identifiable,
                              PackageIdent,
                              PackageTarget},
-                   service::{HealthCheckInterval,
-                             ServiceGroup},
+                   service::{self,
+                             HealthCheckInterval,
+                             HealthCheckProbe,
+                             ServiceGroup,
+                             UpdateWindow},
                    ChannelIdent};
 use habitat_sup_protocol;
 use std::{net::SocketAddr,
@@ -100,6 +104,9 @@ pub fn get(feature_flags: FeatureFlag) -> App<'static, 'static> {
                     "A version number (positive integer) for this configuration (ex: 42)")
                 (@arg FILE: +required {file_exists} "Path to local file on disk")
                 (@arg USER: -u --user +takes_value "Name of the user key")
+                (@arg SIGNING_KEY: --sign +takes_value
+                    "Name of a signing key to sign the file with, for Supervisors run with \
+                    --author-trust")
                 (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
                     "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
                 (arg: arg_cache_key_path("Path to search for encryption keys. \
@@ -666,6 +673,28 @@ pub fn get(feature_flags: FeatureFlag) -> App<'static, 'static> {
                         Default value is hab/cache/keys if root and .hab/cache/keys under the home \
                         directory otherwise."))
                 )
+                (@subcommand rotate =>
+                    (about: "Generates a new revision of a ring key and distributes it to the \
+                        ring through a Supervisor; the ring moves to it once every member that \
+                        hasn't departed holds it")
+                    (aliases: &["r", "ro", "rot", "rota", "rotat"])
+                    (@arg RING: +required +takes_value "Ring key name")
+                    (arg: arg_cache_key_path("Path to store generated keys. \
+                        Default value is hab/cache/keys if root and .hab/cache/keys under the home \
+                        directory otherwise."))
+                    (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
+                        "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                )
+            )
+            (@subcommand endorse =>
+                (about: "Endorses a Supervisor's member key, so that Supervisors run with \
+                    --member-ca trust its gossip")
+                (aliases: &["en", "end", "endo", "endor", "endors"])
+                (@arg MEMBER_KEY: +required +takes_value "Member key name")
+                (@arg AUTHORITY_KEY: +required +takes_value "Name of the key to endorse it with")
+                (arg: arg_cache_key_path("Path to search for keys, and to store the endorsement. \
+                    Default value is hab/cache/keys if root and .hab/cache/keys under the home \
+                    directory otherwise."))
             )
         )
         (subcommand: sup_commands(feature_flags))
@@ -689,6 +718,7 @@ pub fn get(feature_flags: FeatureFlag) -> App<'static, 'static> {
                 )
             )
             (subcommand: sub_svc_load().aliases(&["l", "lo", "loa"]))
+            (subcommand: sub_svc_logs().aliases(&["log"]))
             (subcommand: sub_svc_start().aliases(&["star"]))
             (subcommand: sub_svc_status().aliases(&["stat", "statu"]))
             (subcommand: sub_svc_stop(feature_flags).aliases(&["sto"]))
@@ -781,6 +811,7 @@ pub fn sup_commands(feature_flags: FeatureFlag) -> App<'static, 'static> {
     (@setting VersionlessSubcommands)
     (@setting SubcommandRequiredElseHelp)
     (subcommand: sub_sup_bash().aliases(&["b", "ba", "bas"]))
+    (subcommand: sub_sup_debug().aliases(&["deb", "debu"]))
     (subcommand: sub_sup_depart().aliases(&["d", "de", "dep", "depa", "depart"]))
     (subcommand: sub_sup_run(feature_flags).aliases(&["r", "ru"]))
     (subcommand: sub_sup_secret().aliases(&["sec", "secr"]))
@@ -911,6 +942,12 @@ fn sub_config_apply() -> App<'static, 'static> {
     (@arg FILE: {file_exists_or_stdin}
         "Path to local file on disk (ex: /tmp/config.toml, default: <stdin>)")
     (@arg USER: -u --user +takes_value "Name of a user key to use for encryption")
+    (@arg SIGNING_KEY: --sign +takes_value
+        "Name of a signing key to sign the configuration with, for Supervisors run with \
+        --author-trust")
+    (@arg DRY_RUN: --("dry-run")
+        "Show the changes to the service's rendered configuration files and hooks, and which \
+        hooks would run, without applying the configuration")
     (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
         "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
     (arg: arg_cache_key_path("Path to search for encryption keys. \
@@ -932,6 +969,19 @@ pub fn sub_sup_depart() -> App<'static, 'static> {
     )
 }
 
+pub fn sub_sup_debug() -> App<'static, 'static> {
+    clap_app!(@subcommand debug =>
+        (about: "Show the internal state of a Supervisor running on this host")
+        (@arg TOPIC: +required +takes_value
+            possible_value[heat elections updaters watchers]
+            "What to show: gossip rumor heat, pending elections, update workers or file \
+            watchers")
+        (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
+            "Address to the Supervisor's Control Gateway; it must be on this host \
+            [default: 127.0.0.1:9632]")
+    )
+}
+
 pub fn sub_sup_secret() -> App<'static, 'static> {
     clap_app!(@subcommand secret =>
         (about: "Commands relating to a Habitat Supervisor's Control Gateway secret")
@@ -991,6 +1041,20 @@ pub fn sub_sup_run(feature_flags: FeatureFlag) -> App<'static, 'static> {
              foo-20181113185935 \
 
                   GCrBOW6CCN75LMl0j2V5QqQ6nNzWm6and9hkKBSUFPI=')")
+                            (@arg MEMBER_KEY: --("member-key") +takes_value {non_empty}
+                             "The name of the sig key pair the Supervisor signs its gossip with \
+                              (ex: hab sup run --member-key web-1)")
+                            (@arg MEMBER_TRUST: --("member-trust") +takes_value +multiple requires[MEMBER_KEY]
+                             conflicts_with("MEMBER_CA") {non_empty}
+                             "Only accept gossip signed by the member keys with these names, including \
+                              this Supervisor's own")
+                            (@arg MEMBER_CA: --("member-ca") +takes_value +multiple requires[MEMBER_KEY]
+                             conflicts_with("MEMBER_TRUST") {non_empty}
+                             "Only accept gossip signed by member keys endorsed by the keys with these \
+                              names (see hab ring endorse)")
+                            (@arg AUTHOR_TRUST: --("author-trust") +takes_value +multiple {non_empty}
+                             "Only apply configuration and files signed by the user keys with these \
+                              names (see hab config apply --sign)")
                             (@arg CHANNEL: --channel +takes_value default_value[stable]
                              "Receive Supervisor updates from the specified release channel")
                             (@arg BLDR_URL: -u --url +takes_value {valid_url}
@@ -1002,6 +1066,10 @@ pub fn sub_sup_run(feature_flags: FeatureFlag) -> App<'static, 'static> {
                              "Use package config from this path, rather than the package itself")
                             (@arg AUTO_UPDATE: --("auto-update") -A "Enable automatic updates for the Supervisor \
                                                                      itself")
+                            (@arg AUTO_UPDATE_WINDOW: --("auto-update-window") +takes_value {valid_update_window}
+                             requires[AUTO_UPDATE]
+                             "Only apply automatic updates to the Supervisor itself during this window, given \
+                              as a cron-style schedule in UTC followed by a duration (ex: '0 2 * * sat,sun 4h')")
                             (@arg KEY_FILE: --key +takes_value {file_exists} requires[CERT_FILE]
                              "Used for enabling TLS for the HTTP gateway. Read private key from KEY_FILE. \
                               This should be a RSA private key or PKCS8-encoded private key, in PEM format.")
@@ -1012,6 +1080,38 @@ pub fn sub_sup_run(feature_flags: FeatureFlag) -> App<'static, 'static> {
                             (@arg CA_CERT_FILE: --("ca-certs") +takes_value {file_exists} requires[CERT_FILE] requires[KEY_FILE]
                              "Used for enabling client-authentication with TLS for the HTTP gateway. Read CA certificate from CA_CERT_FILE. \
                               This should contain PEM-format certificate that can be used to validate client requests.")
+                            (@arg CTL_KEY_FILE: --("ctl-key") +takes_value {file_exists} requires[CTL_CERT_FILE]
+                             "Used for enabling TLS for the Control Gateway. Read private key from CTL_KEY_FILE. \
+                              This should be a RSA private key in PEM format.")
+                            (@arg CTL_CERT_FILE: --("ctl-certs") +takes_value {file_exists} requires[CTL_KEY_FILE]
+                             "Used for enabling TLS for the Control Gateway. Read server certificates from CTL_CERT_FILE. \
+                              This should contain PEM-format certificates in the right order (the first certificate \
+                              should certify CTL_KEY_FILE, the last should be a root CA).")
+                            (@arg CTL_CA_CERT_FILE: --("ctl-ca-certs") +takes_value {file_exists} requires[CTL_CERT_FILE] requires[CTL_KEY_FILE]
+                             "Used for enabling client-authentication with TLS for the Control Gateway. Read CA certificate \
+                              from CTL_CA_CERT_FILE. This should contain PEM-format certificate that can be used to validate \
+                              clients such as `hab svc` commands run with --remote-sup.")
+                            (@arg SERVICE_LOG_FORMAT: --("service-log-format") +takes_value possible_value[plain json]
+                             "How lines of service output are written to each service's service.log file \
+                              [default: plain]")
+                            (@arg SERVICE_LOG_MAX_SIZE: --("service-log-max-size") +takes_value {valid_log_max_size}
+                             "Rotate a service's log file once it reaches this size, in bytes or with a K, M, \
+                              G or T suffix [default: 10M]")
+                            (@arg SERVICE_LOG_MAX_AGE: --("service-log-max-age") +takes_value {valid_numeric::<u64>}
+                             "Rotate a service's log file once it has been written to for this long \
+                              (seconds); 0 only rotates on size [default: 0]")
+                            (@arg SERVICE_LOG_MAX_FILES: --("service-log-max-files") +takes_value {valid_numeric::<u32>}
+                             "How many rotated log files to keep for each service [default: 5]")
+                            (@arg SERVICE_LOG_NO_COMPRESS: --("service-log-no-compress")
+                             "Don't gzip rotated service log files")
+                            (@arg EVENT_SINK: --("event-sink") +takes_value +multiple {valid_event_sink}
+                             "Also send the Supervisor's events, as JSON lines, to a file (file:PATH), an HTTP \
+                              webhook (webhook:URL) or a Unix domain socket (unix:PATH). May be given more \
+                              than once.")
+                            (@arg EVENT_SINK_BUFFER_SIZE: --("event-sink-buffer-size") +takes_value
+                             {valid_numeric::<usize>}
+                             "How many events to hold for each event sink while it is unavailable before \
+                              dropping the oldest [default: 1000]")
                             // === Optional arguments to additionally load an initial service for the Supervisor
                             (@arg PKG_IDENT_OR_ARTIFACT: +takes_value "Load the given Habitat package as part of \
                                                                        the Supervisor startup specified by a package identifier \
@@ -1026,7 +1126,7 @@ pub fn sub_sup_run(feature_flags: FeatureFlag) -> App<'static, 'static> {
                             (@arg TOPOLOGY: --topology -t +takes_value possible_value[standalone leader]
                              "Service topology; [default: none]")
                             (@arg STRATEGY: --strategy -s +takes_value {valid_update_strategy}
-                             "The update strategy; [default: none] [values: none, at-once, rolling]")
+                             "The update strategy; [default: none] [values: none, at-once, rolling, canary]")
                             (@arg BIND: --bind +takes_value +multiple
                              "One or more service groups to bind to a configuration")
                             (@arg BINDING_MODE: --("binding-mode") +takes_value {valid_binding_mode}
@@ -1038,6 +1138,39 @@ pub fn sub_sup_run(feature_flags: FeatureFlag) -> App<'static, 'static> {
                                                             Implies NO_COLOR")
                             (@arg HEALTH_CHECK_INTERVAL: --("health-check-interval") -i +takes_value {valid_health_check_interval}
                              "The interval (seconds) on which to run health checks [default: 30]")
+                            (@arg UPDATE_WINDOW: --("update-window") +takes_value {valid_update_window}
+                             "Only apply updates to the service during this window, given as a cron-style \
+                              schedule in UTC followed by a duration (ex: '0 2 * * sat,sun 4h') [default: \
+                              always]")
+                            (@arg RESTART_POLICY: --("restart-policy") +takes_value {valid_restart_policy}
+                             "When to restart the service after its process exits [default: always] \
+                              [values: always, on-failure, never]")
+                            (@arg MAX_RESTARTS: --("max-restarts") +takes_value {valid_numeric::<u32>}
+                             "How many restarts within the restart window before the service is \
+                              reported as crash-looping; 0 never reports it [default: 5]")
+                            (@arg RESTART_WINDOW: --("restart-window") +takes_value {valid_numeric::<u64>}
+                             "The period (seconds) over which restarts are counted [default: 300]")
+                            (@arg MAX_RESTART_BACKOFF: --("max-restart-backoff") +takes_value {valid_numeric::<u64>}
+                             "The longest time (seconds) to wait between restarts [default: 60]")
+                            (@arg CPU_LIMIT: --("cpu-limit") +takes_value {valid_cpu_limit}
+                             "Limit the service to this many CPUs, e.g. 2, 0.5 or 500m (Linux only)")
+                            (@arg MEMORY_LIMIT: --("memory-limit") +takes_value {valid_memory_limit}
+                             "Limit the service's memory, in bytes or with a K, M, G or T suffix \
+                              (Linux only)")
+                            (@arg PIDS_LIMIT: --("pids-limit") +takes_value {valid_pids_limit}
+                             "Limit the number of processes the service may run (Linux only)")
+                            (@arg HEALTH_CHECK: --("health-check") +takes_value {valid_health_check}
+                             "Check the service's health without a health-check hook, by probing \
+                              http://HOST:PORT/PATH, tcp://HOST:PORT or exec:COMMAND")
+                            (@arg HEALTH_CHECK_STATUS: --("health-check-status") +takes_value
+                             requires[HEALTH_CHECK] {valid_health_check_status}
+                             "The status an HTTP health check's response must have [default: any 2xx]")
+                            (@arg HEALTH_CHECK_BODY: --("health-check-body") +takes_value
+                             requires[HEALTH_CHECK]
+                             "Text an HTTP health check's response body must contain")
+                            (@arg HEALTH_CHECK_TIMEOUT: --("health-check-timeout") +takes_value
+                             requires[HEALTH_CHECK] {valid_health_check_timeout}
+                             "How long (seconds) a health check may take before it fails [default: 10]")
     );
 
     if feature_flags.contains(FeatureFlag::EVENT_STREAM) {
@@ -1067,6 +1200,23 @@ pub fn sub_sup_term() -> App<'static, 'static> {
     )
 }
 
+fn sub_svc_logs() -> App<'static, 'static> {
+    clap_app!(@subcommand logs =>
+        (about: "Show the most recent output of a loaded Habitat service")
+        (@arg PKG_IDENT: +required +takes_value {valid_ident}
+            "A Habitat package identifier (ex: core/redis)")
+        (@arg LINES: -n --lines +takes_value {valid_numeric::<u32>}
+            "The number of most recent lines to show [default: 10]")
+        (@arg FOLLOW: -f --follow
+            "Keep showing new output as the service writes it")
+        (@arg STREAM: -s --stream +takes_value +multiple number_of_values(1)
+            possible_value[stdout stderr hooks]
+            "Only show output from this stream; may be given more than once [default: all streams]")
+        (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
+            "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+    )
+}
+
 fn sub_svc_start() -> App<'static, 'static> {
     clap_app!(@subcommand start =>
         (about: "Start a loaded, but stopped, Habitat service.")
@@ -1121,7 +1271,7 @@ fn sub_svc_load() -> App<'static, 'static> {
         (@arg TOPOLOGY: --topology -t +takes_value possible_value[standalone leader]
             "Service topology; [default: none]")
         (@arg STRATEGY: --strategy -s +takes_value {valid_update_strategy}
-            "The update strategy; [default: none] [values: none, at-once, rolling]")
+            "The update strategy; [default: none] [values: none, at-once, rolling, canary]")
         (@arg BIND: --bind +takes_value +multiple
             "One or more service groups to bind to a configuration")
         (@arg BINDING_MODE: --("binding-mode") +takes_value {valid_binding_mode}
@@ -1133,6 +1283,36 @@ fn sub_svc_load() -> App<'static, 'static> {
             "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
         (@arg HEALTH_CHECK_INTERVAL: --("health-check-interval") -i +takes_value {valid_health_check_interval}
             "The interval (seconds) on which to run health checks [default: 30]")
+        (@arg UPDATE_WINDOW: --("update-window") +takes_value {valid_update_window}
+            "Only apply updates to the service during this window, given as a cron-style \
+             schedule in UTC followed by a duration (ex: '0 2 * * sat,sun 4h') [default: always]")
+        (@arg RESTART_POLICY: --("restart-policy") +takes_value {valid_restart_policy}
+            "When to restart the service after its process exits [default: always] \
+             [values: always, on-failure, never]")
+        (@arg MAX_RESTARTS: --("max-restarts") +takes_value {valid_numeric::<u32>}
+            "How many restarts within the restart window before the service is reported as \
+             crash-looping; 0 never reports it [default: 5]")
+        (@arg RESTART_WINDOW: --("restart-window") +takes_value {valid_numeric::<u64>}
+            "The period (seconds) over which restarts are counted [default: 300]")
+        (@arg MAX_RESTART_BACKOFF: --("max-restart-backoff") +takes_value {valid_numeric::<u64>}
+            "The longest time (seconds) to wait between restarts [default: 60]")
+        (@arg CPU_LIMIT: --("cpu-limit") +takes_value {valid_cpu_limit}
+            "Limit the service to this many CPUs, e.g. 2, 0.5 or 500m (Linux only)")
+        (@arg MEMORY_LIMIT: --("memory-limit") +takes_value {valid_memory_limit}
+            "Limit the service's memory, in bytes or with a K, M, G or T suffix (Linux only)")
+        (@arg PIDS_LIMIT: --("pids-limit") +takes_value {valid_pids_limit}
+            "Limit the number of processes the service may run (Linux only)")
+        (@arg HEALTH_CHECK: --("health-check") +takes_value {valid_health_check}
+            "Check the service's health without a health-check hook, by probing \
+             http://HOST:PORT/PATH, tcp://HOST:PORT or exec:COMMAND")
+        (@arg HEALTH_CHECK_STATUS: --("health-check-status") +takes_value requires[HEALTH_CHECK]
+            {valid_health_check_status}
+            "The status an HTTP health check's response must have [default: any 2xx]")
+        (@arg HEALTH_CHECK_BODY: --("health-check-body") +takes_value requires[HEALTH_CHECK]
+            "Text an HTTP health check's response body must contain")
+        (@arg HEALTH_CHECK_TIMEOUT: --("health-check-timeout") +takes_value requires[HEALTH_CHECK]
+            {valid_health_check_timeout}
+            "How long (seconds) a health check may take before it fails [default: 10]")
     );
 
     if cfg!(windows) {
@@ -1196,6 +1376,10 @@ fn add_event_stream_options(app: App<'static, 'static>) -> App<'static, 'static>
                                                .long("event-stream-site")
                                                .takes_value(true)
                                                .validator(non_empty))
+       .arg(Arg::with_name("EVENT_STREAM_AUDIT").help("Also send each record written to the \
+                                                       Supervisor's audit log to the event \
+                                                       stream.")
+                                                .long("event-stream-audit"))
        .arg(Arg::with_name(AutomateAuthToken::ARG_NAME).help("An authentication token for \
                                                               streaming events to an messaging \
                                                               server.")
@@ -1305,6 +1489,67 @@ fn valid_health_check_interval(val: String) -> result::Result<(), String> {
     }
 }
 
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_update_window(val: String) -> result::Result<(), String> {
+    match UpdateWindow::from_str(&val) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_restart_policy(val: String) -> result::Result<(), String> {
+    match habitat_sup_protocol::types::RestartPolicy::from_str(&val) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Restart policy: '{}' is not valid", &val)),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_cpu_limit(val: String) -> result::Result<(), String> {
+    service::parse_cpu_limit(&val).map(|_| ())
+                                  .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_memory_limit(val: String) -> result::Result<(), String> {
+    service::parse_memory_limit(&val).map(|_| ())
+                                     .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_pids_limit(val: String) -> result::Result<(), String> {
+    service::parse_pids_limit(&val).map(|_| ())
+                                   .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_health_check(val: String) -> result::Result<(), String> {
+    HealthCheckProbe::from_str(&val).map(|_| ())
+                                    .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_health_check_status(val: String) -> result::Result<(), String> {
+    service::parse_health_check_status(&val).map(|_| ())
+                                            .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_health_check_timeout(val: String) -> result::Result<(), String> {
+    service::parse_health_check_timeout(&val).map(|_| ())
+                                             .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_log_max_size(val: String) -> result::Result<(), String> {
+    service::parse_memory_limit(&val).map(|_| ())
+                                     .map_err(|_| format!("'{}' is not a valid log size", &val))
+}
+
+#[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
+fn valid_event_sink(val: String) -> result::Result<(), String> { EventSinkConfig::validate(val) }
+
 #[allow(clippy::needless_pass_by_value)] // Signature required by CLAP
 fn valid_update_strategy(val: String) -> result::Result<(), String> {
     match habitat_sup_protocol::types::UpdateStrategy::from_str(&val) {
@@ -1438,6 +1683,98 @@ mod tests {
             assert_eq!(run_matches.value_of("PEER"), Some("1.1.1.1"));
         }
 
+        #[test]
+        fn sup_subcommand_run_ctl_key_requires_ctl_certs() {
+            let key_file = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+            let r = get(no_feature_flags()).get_matches_from_safe(vec!["hab",
+                                                                       "sup",
+                                                                       "run",
+                                                                       "--ctl-key",
+                                                                       key_file]);
+            assert_eq!(r.unwrap_err().kind, ErrorKind::MissingRequiredArgument);
+
+            let r = get(no_feature_flags()).get_matches_from_safe(vec!["hab",
+                                                                       "sup",
+                                                                       "run",
+                                                                       "--ctl-key",
+                                                                       key_file,
+                                                                       "--ctl-certs",
+                                                                       key_file]);
+            assert!(r.is_ok());
+        }
+
+        #[test]
+        fn sup_subcommand_debug_requires_known_topic() {
+            let r =
+                get(no_feature_flags()).get_matches_from_safe(vec!["hab", "sup", "debug", "heat"]);
+            assert!(r.is_ok());
+
+            let r = get(no_feature_flags()).get_matches_from_safe(vec!["hab", "sup", "debug",
+                                                                       "rumors"]);
+            assert_eq!(r.unwrap_err().kind, ErrorKind::InvalidValue);
+        }
+
+    }
+
+    mod svc_commands {
+        use super::*;
+
+        #[test]
+        fn svc_logs_accepts_multiple_streams() {
+            let r = sub_svc_logs().get_matches_from_safe(vec!["logs",
+                                                              "core/redis",
+                                                              "--stream",
+                                                              "stderr",
+                                                              "-s",
+                                                              "hooks"]);
+            let matches = r.expect("Error while getting matches");
+            let streams: Vec<&str> = matches.values_of("STREAM")
+                                            .expect("Error while getting streams")
+                                            .collect();
+            assert_eq!(streams, vec!["stderr", "hooks"]);
+        }
+
+        #[test]
+        fn svc_logs_rejects_unknown_stream() {
+            let r = sub_svc_logs().get_matches_from_safe(vec!["logs",
+                                                              "core/redis",
+                                                              "--stream",
+                                                              "stdin"]);
+            assert_eq!(r.unwrap_err().kind, clap::ErrorKind::InvalidValue);
+        }
+
+        #[test]
+        fn svc_load_accepts_health_check() {
+            let r = sub_svc_load().get_matches_from_safe(vec!["load",
+                                                              "core/redis",
+                                                              "--health-check",
+                                                              "tcp://localhost:6379",
+                                                              "--health-check-timeout",
+                                                              "3"]);
+            let matches = r.expect("Error while getting matches");
+            assert_eq!(matches.value_of("HEALTH_CHECK"),
+                       Some("tcp://localhost:6379"));
+            assert_eq!(matches.value_of("HEALTH_CHECK_TIMEOUT"), Some("3"));
+        }
+
+        #[test]
+        fn svc_load_health_check_options_require_a_probe() {
+            let r = sub_svc_load().get_matches_from_safe(vec!["load",
+                                                              "core/redis",
+                                                              "--health-check-status",
+                                                              "200"]);
+            assert_eq!(r.unwrap_err().kind,
+                       clap::ErrorKind::MissingRequiredArgument);
+        }
+
+        #[test]
+        fn svc_load_rejects_invalid_health_check() {
+            let r = sub_svc_load().get_matches_from_safe(vec!["load",
+                                                              "core/redis",
+                                                              "--health-check",
+                                                              "https://localhost:8443/"]);
+            assert_eq!(r.unwrap_err().kind, clap::ErrorKind::ValueValidation);
+        }
     }
 
     mod event_stream_feature {
@@ -1801,4 +2138,29 @@ mod tests {
         }
 
     }
+
+    mod event_sinks {
+        use super::*;
+
+        #[test]
+        fn sinks_do_not_require_event_stream_feature() {
+            let args = vec!["run",
+                            "--event-sink",
+                            "file:/tmp/events.log",
+                            "--event-sink",
+                            "unix:/tmp/events.sock"];
+            let matches = sub_sup_run(no_feature_flags()).get_matches_from_safe(args)
+                                                         .expect("Error while getting matches");
+            assert_eq!(EventSinkConfig::from_matches(&matches).len(), 2);
+        }
+
+        #[test]
+        fn invalid_sinks_are_rejected() {
+            let matches =
+                sub_sup_run(no_feature_flags()).get_matches_from_safe(vec!["run",
+                                                                           "--event-sink",
+                                                                           "nats:localhost"]);
+            assert_eq!(matches.unwrap_err().kind, clap::ErrorKind::ValueValidation);
+        }
+    }
 }
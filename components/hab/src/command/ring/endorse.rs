@@ -0,0 +1,19 @@
+use std::path::Path;
+
+use crate::{common::ui::{UIWriter,
+                         UI},
+            hcore::crypto::{keys::PairType,
+                            SigKeyPair}};
+
+use crate::error::Result;
+
+pub fn start(ui: &mut UI, member: &str, authority: &str, cache: &Path) -> Result<()> {
+    ui.begin(format!("Endorsing member key {} with {}", member, authority))?;
+    let member = SigKeyPair::get_latest_pair_for(member, cache, Some(&PairType::Public))?;
+    let authority = SigKeyPair::get_latest_pair_for(authority, cache, Some(&PairType::Secret))?;
+    let path = authority.write_endorsement(&member, cache)?;
+    ui.end(format!("Endorsed member key {}, written to {}.",
+                   member.name_with_rev(),
+                   path.display()))?;
+    Ok(())
+}
@@ -1 +1,2 @@
+pub mod endorse;
 pub mod key;
@@ -37,6 +37,10 @@ pub mod scaffolding;
 pub const PRODUCT: &str = "hab";
 pub const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/VERSION"));
 pub const CTL_SECRET_ENVVAR: &str = "HAB_CTL_SECRET";
+pub const CTL_TLS_CA_CERT_ENVVAR: &str = "HAB_CTL_TLS_CA_CERT";
+pub const CTL_TLS_CERT_ENVVAR: &str = "HAB_CTL_TLS_CERT";
+pub const CTL_TLS_KEY_ENVVAR: &str = "HAB_CTL_TLS_KEY";
+pub const CTL_TLS_SERVER_NAME_ENVVAR: &str = "HAB_CTL_TLS_SERVER_NAME";
 pub const ORIGIN_ENVVAR: &str = "HAB_ORIGIN";
 pub const BLDR_URL_ENVVAR: &str = "HAB_BLDR_URL";
 
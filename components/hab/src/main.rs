@@ -7,6 +7,7 @@ extern crate lazy_static;
 #[macro_use]
 extern crate log;
 
+use chrono::NaiveDateTime;
 use clap::{ArgMatches,
            Shell};
 use env_logger;
@@ -24,11 +25,16 @@ use hab::{analytics,
           AUTH_TOKEN_ENVVAR,
           BLDR_URL_ENVVAR,
           CTL_SECRET_ENVVAR,
+          CTL_TLS_CA_CERT_ENVVAR,
+          CTL_TLS_CERT_ENVVAR,
+          CTL_TLS_KEY_ENVVAR,
+          CTL_TLS_SERVER_NAME_ENVVAR,
           ORIGIN_ENVVAR,
           PRODUCT,
           VERSION};
 use habitat_common::{self as common,
                      cli::{cache_key_path_from_matches,
+                           health_check_from_matches,
                            FS_ROOT},
                      command::package::install::{InstallHookMode,
                                                  InstallMode,
@@ -46,7 +52,8 @@ use habitat_core::crypto::dpapi::encrypt;
 use habitat_core::{crypto::{init,
                             keys::PairType,
                             BoxKeyPair,
-                            SigKeyPair},
+                            SigKeyPair,
+                            SymKey},
                    env as henv,
                    env::Config as EnvConfig,
                    fs::{cache_analytics_path,
@@ -57,14 +64,19 @@ use habitat_core::{crypto::{init,
                    package::{target,
                              PackageIdent,
                              PackageTarget},
-                   service::{HealthCheckInterval,
+                   service::{self,
+                             HealthCheckInterval,
+                             ResourceLimits,
                              ServiceGroup},
                    url::{bldr_url_from_env,
                          default_bldr_url},
                    ChannelIdent};
 use habitat_sup_client::{SrvClient,
-                         SrvClientError};
+                         SrvClientError,
+                         TlsConfig};
 use habitat_sup_protocol::{self as sup_proto,
+                           butterfly::{service_config_signed_bytes,
+                                       service_file_signed_bytes},
                            codec::*,
                            ctl::ServiceBindList,
                            net::ErrCode,
@@ -274,9 +286,11 @@ fn start(ui: &mut UI, feature_flags: FeatureFlag) -> Result<()> {
                         ("export", Some(sc)) => sub_ring_key_export(sc)?,
                         ("import", Some(sc)) => sub_ring_key_import(ui, sc)?,
                         ("generate", Some(sc)) => sub_ring_key_generate(ui, sc)?,
+                        ("rotate", Some(sc)) => sub_ring_key_rotate(sc)?,
                         _ => unreachable!(),
                     }
                 }
+                ("endorse", Some(m)) => sub_ring_endorse(ui, m)?,
                 _ => unreachable!(),
             }
         }
@@ -289,6 +303,7 @@ fn start(ui: &mut UI, feature_flags: FeatureFlag) -> Result<()> {
                     }
                 }
                 ("load", Some(m)) => sub_svc_load(m)?,
+                ("logs", Some(m)) => sub_svc_logs(m)?,
                 ("unload", Some(m)) => sub_svc_unload(m, feature_flags)?,
                 ("start", Some(m)) => sub_svc_start(m)?,
                 ("stop", Some(m)) => sub_svc_stop(m, feature_flags)?,
@@ -298,6 +313,7 @@ fn start(ui: &mut UI, feature_flags: FeatureFlag) -> Result<()> {
         }
         ("sup", Some(m)) => {
             match m.subcommand() {
+                ("debug", Some(m)) => sub_sup_debug(m)?,
                 ("depart", Some(m)) => sub_sup_depart(m)?,
                 ("secret", Some(m)) => {
                     match m.subcommand() {
@@ -894,6 +910,7 @@ fn sub_svc_set(m: &ArgMatches<'_>) -> Result<()> {
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
     let service_group = ServiceGroup::from_str(m.value_of("SERVICE_GROUP").unwrap())?;
     let mut ui = ui();
     let mut validate = sup_proto::ctl::SvcValidateCfg::default();
@@ -911,6 +928,12 @@ fn sub_svc_set(m: &ArgMatches<'_>) -> Result<()> {
                          sup_proto::butterfly::MAX_SVC_CFG_SIZE))?;
         process::exit(1);
     }
+    if m.is_present("DRY_RUN") {
+        let mut dry_run = sup_proto::ctl::SvcDryRunCfg::default();
+        dry_run.service_group = Some(service_group.into());
+        dry_run.cfg = Some(buf);
+        return sub_svc_set_dry_run(m, dry_run);
+    }
     validate.cfg = Some(buf.clone());
     let cache = cache_key_path_from_matches(&m);
     let mut set = sup_proto::ctl::SvcSetCfg::default();
@@ -927,8 +950,19 @@ fn sub_svc_set(m: &ArgMatches<'_>) -> Result<()> {
         }
         _ => set.cfg = Some(buf.to_vec()),
     }
+    let version = value_t!(m, "VERSION_NUMBER", u64).unwrap();
+    set.version = Some(version);
+    if let Some(name) = m.value_of("SIGNING_KEY") {
+        let pair = SigKeyPair::get_latest_pair_for(name, &cache, Some(&PairType::Secret))?;
+        ui.status(Status::Signing, format!("TOML as {}", pair.name_with_rev()))?;
+        let signed = service_config_signed_bytes(&service_group,
+                                                 version,
+                                                 set.is_encrypted.unwrap_or(false),
+                                                 set.cfg.as_ref().unwrap());
+        set.signer = Some(pair.name_with_rev());
+        set.signature = Some(pair.sign_detached(&signed)?);
+    }
     set.service_group = Some(service_group.into());
-    set.version = Some(value_t!(m, "VERSION_NUMBER", u64).unwrap());
     ui.begin(format!("Setting new configuration version {} for {}",
                      set.version
                         .as_ref()
@@ -939,34 +973,28 @@ fn sub_svc_set(m: &ArgMatches<'_>) -> Result<()> {
                         .map(ToString::to_string)
                         .unwrap_or_else(|| "UNKNOWN".to_string()),))?;
     ui.status(Status::Creating, "service configuration")?;
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(validate)
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+                                                                              conn.call(validate)
                 .for_each(|reply| match reply.message_id() {
                     "NetOk" => Ok(()),
                     "NetErr" => {
                         let m = reply
                             .parse::<sup_proto::net::NetErr>()
                             .map_err(SrvClientError::Decode)?;
-                        match ErrCode::from_i32(m.code) {
-                            Some(ErrCode::InvalidPayload) => {
-                                ui.warn(m)?;
-                                Ok(())
-                            }
-                            _ => Err(SrvClientError::from(m)),
-                        }
+                        Err(SrvClientError::from(m))
                     }
                     _ => Err(SrvClientError::from(io::Error::from(
                         io::ErrorKind::UnexpectedEof,
                     ))),
                 })
-                                                     })
-                                                     .wait()?;
+                                                                          })
+                                                                          .wait()?;
     ui.status(Status::Applying, format!("via peer {}", listen_ctl_addr))?;
     // JW: We should not need to make two connections here. I need a way to return the
     // SrvClient from a for_each iterator so we can chain upon a successful stream but I don't
     // know if it's possible with this version of futures.
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(set).for_each(|reply| {
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        conn.call(set).for_each(|reply| {
                           match reply.message_id() {
                 "NetOk" => Ok(()),
                 "NetErr" => {
@@ -980,21 +1008,61 @@ fn sub_svc_set(m: &ArgMatches<'_>) -> Result<()> {
                 ))),
             }
                       })
-                                                     })
-                                                     .wait()?;
+    })
+    .wait()?;
     ui.end("Applied configuration")?;
     Ok(())
 }
 
+/// Show what applying a configuration would change, without applying it.
+fn sub_svc_set_dry_run(m: &ArgMatches<'_>, msg: sup_proto::ctl::SvcDryRunCfg) -> Result<()> {
+    let cfg = config::load()?;
+    let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        conn.call(msg).for_each(|reply| {
+                          match reply.message_id() {
+                "SvcCfgDryRun" => {
+                    let m = reply
+                        .parse::<sup_proto::ctl::SvcCfgDryRun>()
+                        .map_err(SrvClientError::Decode)?;
+                    for diff in m.diffs.iter() {
+                        print!("{}", diff);
+                    }
+                    if m.hooks.is_empty() {
+                        println!("No rendered files would change.");
+                    } else {
+                        println!("Would run: {}", m.hooks.join(", "));
+                    }
+                    Ok(())
+                }
+                "NetErr" => {
+                    let m = reply
+                        .parse::<sup_proto::net::NetErr>()
+                        .map_err(SrvClientError::Decode)?;
+                    Err(SrvClientError::from(m))
+                }
+                _ => Err(SrvClientError::from(io::Error::from(
+                    io::ErrorKind::UnexpectedEof,
+                ))),
+            }
+                      })
+    })
+    .wait()?;
+    Ok(())
+}
+
 fn sub_svc_config(m: &ArgMatches<'_>) -> Result<()> {
     let ident = PackageIdent::from_str(m.value_of("PKG_IDENT").unwrap())?;
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
     let mut msg = sup_proto::ctl::SvcGetDefaultCfg::default();
     msg.ident = Some(ident.into());
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(msg).for_each(|reply| {
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        conn.call(msg).for_each(|reply| {
                           match reply.message_id() {
                 "ServiceCfg" => {
                     let m = reply
@@ -1014,8 +1082,8 @@ fn sub_svc_config(m: &ArgMatches<'_>) -> Result<()> {
                 ))),
             }
                       })
-                                                     })
-                                                     .wait()?;
+    })
+    .wait()?;
     Ok(())
 }
 
@@ -1023,15 +1091,16 @@ fn sub_svc_load(m: &ArgMatches<'_>) -> Result<()> {
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
     let mut msg = sup_proto::ctl::SvcLoad::default();
     update_svc_load_from_input(m, &mut msg)?;
     let ident: PackageIdent = m.value_of("PKG_IDENT").unwrap().parse()?;
     msg.ident = Some(ident.into());
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(msg)
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+                                                                              conn.call(msg)
                                                              .for_each(|m| handle_ctl_reply(&m))
-                                                     })
-                                                     .wait()?;
+                                                                          })
+                                                                          .wait()?;
     Ok(())
 }
 
@@ -1040,6 +1109,7 @@ fn sub_svc_unload(m: &ArgMatches<'_>, feature_flags: FeatureFlag) -> Result<()>
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
 
     let timeout_in_seconds = maybe_get_shutdown_timeout(m, feature_flags)?.map(Into::into);
     let signal = maybe_get_shutdown_signal(m, feature_flags)?.map(|s| s.to_string());
@@ -1047,11 +1117,11 @@ fn sub_svc_unload(m: &ArgMatches<'_>, feature_flags: FeatureFlag) -> Result<()>
     let msg = sup_proto::ctl::SvcUnload { ident: Some(ident.into()),
                                           signal,
                                           timeout_in_seconds };
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(msg)
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+                                                                              conn.call(msg)
                                                              .for_each(|m| handle_ctl_reply(&m))
-                                                     })
-                                                     .wait()?;
+                                                                          })
+                                                                          .wait()?;
     Ok(())
 }
 
@@ -1081,18 +1151,45 @@ fn maybe_get_shutdown_timeout(m: &ArgMatches<'_>,
     }
 }
 
+fn sub_svc_logs(m: &ArgMatches<'_>) -> Result<()> {
+    let ident = PackageIdent::from_str(m.value_of("PKG_IDENT").unwrap())?;
+    let cfg = config::load()?;
+    let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
+    let mut msg = sup_proto::ctl::SvcLogs::default();
+    msg.ident = Some(ident.into());
+    // Value will have already been validated by `cli::valid_numeric`
+    msg.lines = m.value_of("LINES").and_then(|n| n.parse().ok());
+    msg.follow = Some(m.is_present("FOLLOW"));
+    // Values will have already been validated by the CLI
+    msg.streams = m.values_of("STREAM")
+                   .into_iter()
+                   .flatten()
+                   .filter_map(|s| sup_proto::ctl::LogStream::from_str(s).ok())
+                   .map(|s| s as i32)
+                   .collect();
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+                                                                              conn.call(msg)
+                                                             .for_each(|m| handle_ctl_reply(&m))
+                                                                          })
+                                                                          .wait()?;
+    Ok(())
+}
+
 fn sub_svc_start(m: &ArgMatches<'_>) -> Result<()> {
     let ident = PackageIdent::from_str(m.value_of("PKG_IDENT").unwrap())?;
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
     let mut msg = sup_proto::ctl::SvcStart::default();
     msg.ident = Some(ident.into());
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(msg)
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+                                                                              conn.call(msg)
                                                              .for_each(|m| handle_ctl_reply(&m))
-                                                     })
-                                                     .wait()?;
+                                                                          })
+                                                                          .wait()?;
     Ok(())
 }
 
@@ -1100,39 +1197,40 @@ fn sub_svc_status(m: &ArgMatches<'_>) -> Result<()> {
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
     let mut msg = sup_proto::ctl::SvcStatus::default();
     if let Some(pkg) = m.value_of("PKG_IDENT") {
         msg.ident = Some(PackageIdent::from_str(pkg)?.into());
     }
 
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         let mut out = TabWriter::new(io::stdout());
-                                                         conn.call(msg)
-                .into_future()
-                .map_err(|(err, _)| err)
-                .and_then(move |(reply, rest)| {
-                    match reply {
-                        None => {
-                            return Err(SrvClientError::from(io::Error::from(
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        let mut out = TabWriter::new(io::stdout());
+        conn.call(msg)
+            .into_future()
+            .map_err(|(err, _)| err)
+            .and_then(move |(reply, rest)| {
+                match reply {
+                    None => {
+                        return Err(SrvClientError::from(io::Error::from(
                                 io::ErrorKind::UnexpectedEof,
                             )));
-                        }
-                        Some(m) => print_svc_status(&mut out, &m, true)?,
                     }
-                    Ok((out, rest))
-                })
-                .and_then(|(out, rest)| {
-                    rest.fold(out, move |mut out, reply| {
+                    Some(m) => print_svc_status(&mut out, &m, true)?,
+                }
+                Ok((out, rest))
+            })
+            .and_then(|(out, rest)| {
+                rest.fold(out, move |mut out, reply| {
                         print_svc_status(&mut out, &reply, false)?;
                         Ok::<_, SrvClientError>(out)
                     })
-                })
-                .and_then(|mut out| {
-                    out.flush()?;
-                    Ok(())
-                })
-                                                     })
-                                                     .wait()?;
+            })
+            .and_then(|mut out| {
+                out.flush()?;
+                Ok(())
+            })
+    })
+    .wait()?;
     Ok(())
 }
 
@@ -1141,6 +1239,7 @@ fn sub_svc_stop(m: &ArgMatches<'_>, feature_flags: FeatureFlag) -> Result<()> {
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
 
     let timeout_in_seconds = maybe_get_shutdown_timeout(m, feature_flags)?.map(Into::into);
     let signal = maybe_get_shutdown_signal(m, feature_flags)?.map(|s| s.to_string());
@@ -1149,11 +1248,11 @@ fn sub_svc_stop(m: &ArgMatches<'_>, feature_flags: FeatureFlag) -> Result<()> {
                                         timeout_in_seconds,
                                         signal };
 
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(msg)
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+                                                                              conn.call(msg)
                                                              .for_each(|m| handle_ctl_reply(&m))
-                                                     })
-                                                     .wait()?;
+                                                                          })
+                                                                          .wait()?;
     Ok(())
 }
 
@@ -1162,6 +1261,7 @@ fn sub_file_put(m: &ArgMatches<'_>) -> Result<()> {
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
     let mut ui = ui();
     let mut msg = sup_proto::ctl::SvcFilePut::default();
     let file = Path::new(m.value_of("FILE").unwrap());
@@ -1200,12 +1300,21 @@ fn sub_file_put(m: &ArgMatches<'_>) -> Result<()> {
         }
         _ => msg.content = Some(buf.to_vec()),
     }
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         ui.status(Status::Applying,
-                                                                   format!("via peer {}",
-                                                                           listen_ctl_addr))
-                                                           .unwrap();
-                                                         conn.call(msg).for_each(|reply| {
+    if let Some(name) = m.value_of("SIGNING_KEY") {
+        let pair = SigKeyPair::get_latest_pair_for(name, &cache, Some(&PairType::Secret))?;
+        ui.status(Status::Signing, format!("file as {}", pair.name_with_rev()))?;
+        let signed = service_file_signed_bytes(&service_group,
+                                               msg.filename.as_ref().unwrap(),
+                                               msg.version.unwrap(),
+                                               msg.is_encrypted.unwrap_or(false),
+                                               msg.content.as_ref().unwrap());
+        msg.signer = Some(pair.name_with_rev());
+        msg.signature = Some(pair.sign_detached(&signed)?);
+    }
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        ui.status(Status::Applying, format!("via peer {}", listen_ctl_addr))
+          .unwrap();
+        conn.call(msg).for_each(|reply| {
                           match reply.message_id() {
                 "NetOk" => Ok(()),
                 "NetErr" => {
@@ -1225,8 +1334,8 @@ fn sub_file_put(m: &ArgMatches<'_>) -> Result<()> {
                 ))),
             }
                       })
-                                                     })
-                                                     .wait()?;
+    })
+    .wait()?;
     ui.end("Uploaded file")?;
     Ok(())
 }
@@ -1235,10 +1344,11 @@ fn sub_sup_depart(m: &ArgMatches<'_>) -> Result<()> {
     let cfg = config::load()?;
     let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
     let mut ui = ui();
     let mut msg = sup_proto::ctl::SupDepart::default();
     msg.member_id = Some(m.value_of("MEMBER_ID").unwrap().to_string());
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
         ui.begin(format!("Permanently marking {} as departed",
                          msg.member_id
                             .as_ref()
@@ -1267,6 +1377,31 @@ fn sub_sup_depart(m: &ArgMatches<'_>) -> Result<()> {
     Ok(())
 }
 
+fn sub_sup_debug(m: &ArgMatches<'_>) -> Result<()> {
+    let cfg = config::load()?;
+    let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
+    let topic = sup_proto::ctl::DebugTopic::from_str(m.value_of("TOPIC").unwrap())?;
+    let mut msg = sup_proto::ctl::SupDebug::default();
+    msg.topic = Some(topic as i32);
+
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        let out = TabWriter::new(io::stdout());
+        conn.call(msg)
+            .fold((out, true), |(mut out, print_header), reply| {
+                print_sup_debug(&mut out, &reply, print_header)?;
+                Ok::<_, SrvClientError>((out, false))
+            })
+            .and_then(|(mut out, _)| {
+                out.flush()?;
+                Ok(())
+            })
+    })
+    .wait()?;
+    Ok(())
+}
+
 fn sub_sup_secret_generate() -> Result<()> {
     let mut ui = ui();
     let mut buf = String::new();
@@ -1289,6 +1424,15 @@ fn sub_ring_key_export(m: &ArgMatches<'_>) -> Result<()> {
     command::ring::key::export::start(ring, &cache_key_path)
 }
 
+fn sub_ring_endorse(ui: &mut UI, m: &ArgMatches<'_>) -> Result<()> {
+    let member = m.value_of("MEMBER_KEY").unwrap(); // Required via clap
+    let authority = m.value_of("AUTHORITY_KEY").unwrap(); // Required via clap
+    let cache_key_path = cache_key_path_from_matches(&m);
+    init();
+
+    command::ring::endorse::start(ui, member, authority, &cache_key_path)
+}
+
 fn sub_ring_key_generate(ui: &mut UI, m: &ArgMatches<'_>) -> Result<()> {
     let ring = m.value_of("RING").unwrap(); // Required via clap
     let cache_key_path = cache_key_path_from_matches(&m);
@@ -1297,6 +1441,50 @@ fn sub_ring_key_generate(ui: &mut UI, m: &ArgMatches<'_>) -> Result<()> {
     command::ring::key::generate::start(ui, ring, &cache_key_path)
 }
 
+fn sub_ring_key_rotate(m: &ArgMatches<'_>) -> Result<()> {
+    let ring = m.value_of("RING").unwrap(); // Required via clap
+    let cache_key_path = cache_key_path_from_matches(&m);
+    let cfg = config::load()?;
+    let listen_ctl_addr = listen_ctl_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(m.value_of("REMOTE_SUP"))?;
+    let mut ui = ui();
+    init();
+
+    ui.begin(format!("Generating a new revision of ring key {}", ring))?;
+    let pair = SymKey::generate_pair_for_ring(ring)?;
+    pair.to_pair_files(&cache_key_path)?;
+    let name_with_rev = pair.name_with_rev();
+    let mut msg = sup_proto::ctl::SupRingKeyRotate::default();
+    msg.key = Some(pair.to_secret_string()?);
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        ui.status(Status::Applying,
+                  format!("{} via peer {}", name_with_rev, listen_ctl_addr))
+          .unwrap();
+        conn.call(msg).for_each(|reply| {
+                          match reply.message_id() {
+                "NetOk" => Ok(()),
+                "NetErr" => {
+                    let m = reply
+                        .parse::<sup_proto::net::NetErr>()
+                        .map_err(SrvClientError::Decode)?;
+                    Err(SrvClientError::from(m))
+                }
+                _ => Err(SrvClientError::from(io::Error::from(
+                    io::ErrorKind::UnexpectedEof,
+                ))),
+            }
+                      })
+    })
+    .wait()?;
+    ui.end(format!("Distributing ring key {}. The ring moves to it once every member that \
+                    hasn't departed holds it, and retires the older revision once they all use \
+                    it; the ring_keys section of the Supervisor's /butterfly endpoint shows \
+                    the progress.",
+                   name_with_rev))?;
+    Ok(())
+}
+
 fn sub_ring_key_import(ui: &mut UI, m: &ArgMatches<'_>) -> Result<()> {
     let mut content = String::new();
     let cache_key_path = cache_key_path_from_matches(&m);
@@ -1421,6 +1609,42 @@ fn ctl_secret_key(config: &Config) -> Result<String> {
     }
 }
 
+/// Check if the HAB_CTL_TLS_CA_CERT env var is set. If so, connect to the Supervisor's Control
+/// Gateway over TLS, trusting that CA to sign its certificate. The certificate must be valid for
+/// the name in HAB_CTL_TLS_SERVER_NAME, or else for the host given with `--remote-sup`. A client
+/// certificate for mutual TLS is read from HAB_CTL_TLS_CERT and HAB_CTL_TLS_KEY if they are set.
+fn ctl_tls_config(remote_sup: Option<&str>) -> Result<Option<TlsConfig>> {
+    let ca_cert_path = match henv::var(CTL_TLS_CA_CERT_ENVVAR) {
+        Ok(v) => PathBuf::from(v),
+        Err(_) => return Ok(None),
+    };
+    let client_cert = match (henv::var(CTL_TLS_CERT_ENVVAR), henv::var(CTL_TLS_KEY_ENVVAR)) {
+        (Ok(cert), Ok(key)) => Some((PathBuf::from(cert), PathBuf::from(key))),
+        (Err(_), Err(_)) => None,
+        _ => {
+            return Err(Error::ArgumentError("HAB_CTL_TLS_CERT and \
+                                             HAB_CTL_TLS_KEY must be set \
+                                             together to use a client certificate"));
+        }
+    };
+    let server_name =
+        henv::var(CTL_TLS_SERVER_NAME_ENVVAR).unwrap_or_else(|_| ctl_host(remote_sup).to_string());
+    let client_cert = client_cert.as_ref()
+                                 .map(|(cert, key)| (cert.as_path(), key.as_path()));
+    Ok(Some(TlsConfig::new(&ca_cert_path,
+                           client_cert,
+                           &server_name)?))
+}
+
+/// The host part of a `--remote-sup` address, defaulting to the local machine.
+fn ctl_host(remote_sup: Option<&str>) -> &str {
+    match remote_sup {
+        Some(input) if input.find(':').is_some() => input.rsplitn(2, ':').last().unwrap_or(input),
+        Some(input) => input,
+        None => "localhost",
+    }
+}
+
 /// Check to see if an auth token exists and convert it to a string slice if it does. Unlike
 /// auth_token_param_or_env, it's ok for no auth token to be present here. This is useful for
 /// commands that can optionally take an auth token for operating on private packages.
@@ -1642,6 +1866,95 @@ fn print_svc_status<T>(out: &mut T,
              svc_elapsed,
              svc_pid,
              status.service_group,)?;
+    if let Some(ref rolled_back_from) = status.rolled_back_from {
+        writeln!(out,
+                 "{} was rolled back from {} after a failed update",
+                 status.service_group, rolled_back_from)?;
+    }
+    if let Some(ref pending) = status.pending_update {
+        let until = match pending.until {
+            Some(t) => {
+                NaiveDateTime::from_timestamp(t as i64, 0).format("%Y-%m-%d %H:%M UTC")
+                                                          .to_string()
+            }
+            None => "its update window opens".to_string(),
+        };
+        writeln!(out,
+                 "{} update to {} pending until {}",
+                 status.service_group, pending.ident, until)?;
+    }
+    if status.crash_looping.unwrap_or(false) {
+        writeln!(out,
+                 "{} is crash-looping ({} restarts within its restart window)",
+                 status.service_group,
+                 status.restarts.unwrap_or_default())?;
+    }
+    Ok(())
+}
+
+fn print_sup_debug<T>(out: &mut T,
+                      reply: &SrvMessage,
+                      print_header: bool)
+                      -> result::Result<(), SrvClientError>
+    where T: io::Write
+{
+    match reply.message_id() {
+        "RumorHeat" => {
+            let m = reply.parse::<sup_proto::ctl::RumorHeat>()
+                         .map_err(SrvClientError::Decode)?;
+            if print_header {
+                writeln!(out, "member_id\thot\ttotal")?;
+            }
+            writeln!(out, "{}\t{}\t{}", m.member_id, m.hot_rumors, m.total_rumors)?;
+        }
+        "PendingElection" => {
+            let m = reply.parse::<sup_proto::ctl::PendingElection>()
+                         .map_err(SrvClientError::Decode)?;
+            if print_header {
+                writeln!(out, "service_group\ttype\tterm\tstatus\tleading\tvotes")?;
+            }
+            writeln!(out,
+                     "{}\t{}\t{}\t{}\t{}\t{}",
+                     m.service_group,
+                     if m.update { "update" } else { "service" },
+                     m.term,
+                     m.status,
+                     m.member_id,
+                     m.votes)?;
+        }
+        "UpdaterStatus" => {
+            let m = reply.parse::<sup_proto::ctl::UpdaterStatus>()
+                         .map_err(SrvClientError::Decode)?;
+            if print_header {
+                writeln!(out, "service_group\tstate\theld\twatching")?;
+            }
+            writeln!(out,
+                     "{}\t{}\t{}\t{}",
+                     m.service_group,
+                     m.state,
+                     m.held_release.as_ref().map_or("<none>", String::as_str),
+                     m.watched_release.as_ref().map_or("<none>", String::as_str))?;
+        }
+        "WatcherStatus" => {
+            let m = reply.parse::<sup_proto::ctl::WatcherStatus>()
+                         .map_err(SrvClientError::Decode)?;
+            if print_header {
+                writeln!(out, "kind\tservice\tpath")?;
+            }
+            writeln!(out,
+                     "{}\t{}\t{}",
+                     m.kind,
+                     m.service.as_ref().map_or("<none>", String::as_str),
+                     m.path)?;
+        }
+        "NetOk" => writeln!(out, "Nothing to show.")?,
+        "NetErr" => {
+            let err = reply.parse::<sup_proto::net::NetErr>()
+                           .map_err(SrvClientError::Decode)?;
+            return Err(SrvClientError::from(err));
+        }
+        _ => warn!("Unexpected debug message, {:?}", reply),
+    }
     Ok(())
 }
 
@@ -1661,17 +1974,17 @@ fn supervisor_services() -> Result<Vec<PackageIdent>> {
 
     let cfg = config::load()?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls_config = ctl_tls_config(None)?;
     let listen_ctl_addr = ListenCtlAddr::default();
     let msg = sup_proto::ctl::SvcStatus::default();
 
     let mut out: Vec<PackageIdent> = vec![];
-    SrvClient::connect(&listen_ctl_addr, &secret_key).and_then(|conn| {
-                                                         conn.call(msg).for_each(|reply| {
+    SrvClient::connect(&listen_ctl_addr, &secret_key, tls_config.as_ref()).and_then(|conn| {
+        conn.call(msg).for_each(|reply| {
                           match reply.message_id() {
                               "ServiceStatus" => {
-                                  let m =
-                                      reply.parse::<sup_proto::types::ServiceStatus>()
-                                           .map_err(SrvClientError::Decode)?;
+                                  let m = reply.parse::<sup_proto::types::ServiceStatus>()
+                                               .map_err(SrvClientError::Decode)?;
                                   out.push(m.ident.into());
                                   Ok(())
                               }
@@ -1687,8 +2000,8 @@ fn supervisor_services() -> Result<Vec<PackageIdent>> {
                               }
                           }
                       })
-                                                     })
-                                                     .wait()?;
+    })
+    .wait()?;
     Ok(out)
 }
 
@@ -1764,6 +2077,28 @@ fn get_strategy_from_input(m: &ArgMatches<'_>) -> Option<UpdateStrategy> {
      .and_then(|f| UpdateStrategy::from_str(f).ok())
 }
 
+fn get_restart_policy_from_input(m: &ArgMatches<'_>) -> Option<RestartPolicy> {
+    // There won't be errors, because we validate with `valid_restart_policy`
+    m.value_of("RESTART_POLICY")
+     .and_then(|p| RestartPolicy::from_str(p).ok())
+}
+
+fn get_resource_limits_from_input(m: &ArgMatches<'_>) -> Option<sup_proto::types::ResourceLimits> {
+    // There won't be errors, because we validate with `valid_cpu_limit`,
+    // `valid_memory_limit` and `valid_pids_limit`
+    let limits = ResourceLimits { cpu_millis:   m.value_of("CPU_LIMIT")
+                                                 .and_then(|v| service::parse_cpu_limit(v).ok()),
+                                  memory_bytes: m.value_of("MEMORY_LIMIT")
+                                                 .and_then(|v| service::parse_memory_limit(v).ok()),
+                                  pids:         m.value_of("PIDS_LIMIT")
+                                                 .and_then(|v| service::parse_pids_limit(v).ok()), };
+    if limits.is_empty() {
+        None
+    } else {
+        Some(limits.into())
+    }
+}
+
 fn listen_ctl_addr_from_input(m: &ArgMatches<'_>) -> Result<ListenCtlAddr> {
     m.value_of("REMOTE_SUP")
      .map_or(Ok(ListenCtlAddr::default()), resolve_listen_ctl_addr)
@@ -1834,9 +2169,19 @@ fn update_svc_load_from_input(m: &ArgMatches<'_>, msg: &mut sup_proto::ctl::SvcL
     msg.group = get_group_from_input(m);
     msg.svc_encrypted_password = get_password_from_input(m)?;
     msg.health_check_interval = get_health_check_interval_from_input(m);
+    // Value will have already been validated by `cli::valid_update_window`
+    msg.update_window = m.value_of("UPDATE_WINDOW").map(ToString::to_string);
     msg.binding_mode = get_binding_mode_from_input(m).map(|v| v as i32);
     msg.topology = get_topology_from_input(m).map(|v| v as i32);
     msg.update_strategy = get_strategy_from_input(m).map(|v| v as i32);
+    msg.restart_policy = get_restart_policy_from_input(m).map(|v| v as i32);
+    // Values will have already been validated by `cli::valid_numeric`
+    msg.max_restarts = m.value_of("MAX_RESTARTS").and_then(|s| s.parse().ok());
+    msg.restart_window = m.value_of("RESTART_WINDOW").and_then(|s| s.parse().ok());
+    msg.max_restart_backoff = m.value_of("MAX_RESTART_BACKOFF")
+                               .and_then(|s| s.parse().ok());
+    msg.resource_limits = get_resource_limits_from_input(m);
+    msg.health_check = health_check_from_matches(m).map(Into::into);
     Ok(())
 }
 
@@ -1994,4 +2339,24 @@ mod test {
             assert_eq!(expected, actual);
         }
     }
+
+    mod ctl_host {
+        use super::*;
+
+        #[test]
+        fn port_is_removed() {
+            assert_eq!(ctl_host(Some("hab1.mycompany.com:9632")),
+                       "hab1.mycompany.com");
+        }
+
+        #[test]
+        fn host_without_port_is_kept() {
+            assert_eq!(ctl_host(Some("hab1.mycompany.com")), "hab1.mycompany.com");
+        }
+
+        #[test]
+        fn local_supervisor_is_localhost() {
+            assert_eq!(ctl_host(None), "localhost");
+        }
+    }
 }
@@ -1,10 +1,14 @@
-use std::io::{Read,
-              Write};
+use std::{io::{self,
+               Read,
+               Write},
+          net::{TcpStream,
+                ToSocketAddrs},
+          time::Duration};
 
 use httparse;
 use hyper::{self,
             method::Method,
-            net::{HttpConnector,
+            net::{HttpStream,
                   HttpsStream,
                   NetworkConnector,
                   SslClient},
@@ -12,20 +16,65 @@ use hyper::{self,
 
 use crate::proxy::ProxyInfo;
 
+/// A connector for plaintext connections which, unlike hyper's `HttpConnector`, can give up on
+/// connecting after a timeout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimeoutConnector {
+    connect_timeout: Option<Duration>,
+}
+
+impl TimeoutConnector {
+    /// Creates a new connector which waits at most `connect_timeout` for each address a host
+    /// resolves to, or as long as the operating system allows if there is no timeout.
+    pub fn new(connect_timeout: Option<Duration>) -> Self { TimeoutConnector { connect_timeout } }
+}
+
+impl NetworkConnector for TimeoutConnector {
+    type Stream = HttpStream;
+
+    fn connect(&self, host: &str, port: u16, scheme: &str) -> hyper::Result<HttpStream> {
+        if scheme != "http" {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid scheme for Http").into());
+        }
+        let stream = match self.connect_timeout {
+            Some(timeout) => connect_with_timeout(host, port, timeout)?,
+            None => TcpStream::connect((host, port))?,
+        };
+        Ok(HttpStream(stream))
+    }
+}
+
+fn connect_with_timeout(host: &str, port: u16, timeout: Duration) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput,
+                                   format!("{}:{} did not resolve to any addresses", host, port))
+                }))
+}
+
 /// A connector that uses an HTTP proxy server (pass-through for plaintext and tunneled for SSL
 /// sessions).
 pub struct ProxyHttpsConnector<S: SslClient> {
     proxy:           ProxyInfo,
-    proxy_connector: HttpConnector,
+    proxy_connector: TimeoutConnector,
     ssl_client:      S,
 }
 
 impl<S: SslClient> ProxyHttpsConnector<S> {
     /// Creates a new connection using the provided proxy server configuration and SSL
-    /// implementation.
-    pub fn new(proxy: ProxyInfo, ssl_client: S) -> hyper::Result<Self> {
+    /// implementation, connecting to the proxy server with `proxy_connector`.
+    pub fn new(proxy: ProxyInfo,
+               ssl_client: S,
+               proxy_connector: TimeoutConnector)
+               -> hyper::Result<Self> {
         Ok(ProxyHttpsConnector { proxy,
-                                 proxy_connector: HttpConnector,
+                                 proxy_connector,
                                  ssl_client })
     }
 }
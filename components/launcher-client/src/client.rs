@@ -1,7 +1,10 @@
 use crate::error::{Error,
                    Result};
-use habitat_common::types::UserInfo;
-use habitat_core::os::process::Pid;
+use habitat_common::{service_log::{LogConfig,
+                                   LogFormat},
+                     types::UserInfo};
+use habitat_core::{os::process::Pid,
+                   service::ResourceLimits};
 use habitat_launcher_protocol::{self as protocol,
                                 Error as ProtocolError};
 use ipc_channel::ipc::{IpcOneShotServer,
@@ -15,14 +18,18 @@ type Env = HashMap<String, String>;
 type IpcServer = IpcOneShotServer<Vec<u8>>;
 
 pub struct LauncherCli {
-    tx: IpcSender<Vec<u8>>,
-    rx: IpcReceiver<Vec<u8>>,
+    tx:              IpcSender<Vec<u8>>,
+    rx:              IpcReceiver<Vec<u8>>,
     // We persist the pipe identifier so we can delete the file on drop.
     // This is not necessary on Windows because named pipes are removed
     // upon releasing the last handle to the pipe. The ipc-channel crate
     // wraps the pipe in a WinHandle whose drop impl calls CloseHandle.
     #[cfg(not(windows))]
-    pipe: String,
+    pipe:            String,
+    /// Whether the Launcher answers `ProcessExit`.
+    process_exit:    bool,
+    /// Whether the Launcher enforces `ResourceLimits`.
+    resource_limits: bool,
 }
 
 #[cfg(not(windows))]
@@ -45,13 +52,20 @@ impl LauncherCli {
         let cmd = protocol::Register { pipe: pipe_to_sup.clone(), };
         Self::send(&tx, &cmd)?;
         let (rx, raw) = ipc_srv.accept().map_err(|_| Error::AcceptConn)?;
-        Self::read::<protocol::NetOk>(&raw)?;
+        let reply = Self::read::<protocol::NetOk>(&raw)?;
         Ok(LauncherCli { tx,
                          rx,
                          #[cfg(not(windows))]
-                         pipe: pipe_to_sup })
+                         pipe: pipe_to_sup,
+                         process_exit: reply.process_exit,
+                         resource_limits: reply.resource_limits })
     }
 
+    /// Whether `ResourceLimits` passed to `spawn` are enforced. Older
+    /// Launchers, and Launchers on platforms without cgroups, silently
+    /// ignore them.
+    pub fn enforces_resource_limits(&self) -> bool { self.resource_limits }
+
     /// Read a launcher protocol message from a byte array
     fn read<T>(bytes: &[u8]) -> Result<T>
         where T: protocol::LauncherMessage
@@ -120,6 +134,9 @@ impl LauncherCli {
     /// `username` and `groupname` are string names, while `uid` and
     /// `gid` are numeric IDs. Newer versions of the Launcher can
     /// accept either, but prefer numeric IDs.
+    ///
+    /// `limits` are only enforced when `enforces_resource_limits` says
+    /// so. Older Launchers ignore `log`, and only print the service's output.
     pub fn spawn(&self,
                  id: &str,
                  bin: &Path,
@@ -128,8 +145,30 @@ impl LauncherCli {
                             groupname,
                             gid, }: UserInfo,
                  password: Option<&str>,
-                 env: Env)
+                 env: Env,
+                 limits: ResourceLimits,
+                 log_dir: &Path,
+                 log: LogConfig)
                  -> Result<Pid> {
+        let limits = if limits.is_empty() {
+            None
+        } else {
+            Some(protocol::ResourceLimits { cpu_millis:   limits.cpu_millis,
+                                            memory_bytes: limits.memory_bytes,
+                                            pids:         limits.pids, })
+        };
+        let format = match log.format {
+            LogFormat::Plain => protocol::ServiceLogFormat::Plain,
+            LogFormat::Json => protocol::ServiceLogFormat::Json,
+        };
+        let log = protocol::ServiceLog { dir:          Some(log_dir.to_string_lossy().into_owned()),
+                                         format:       Some(format as i32),
+                                         max_bytes:    Some(log.max_bytes),
+                                         max_age_secs: Some(log.max_age
+                                                               .map_or(0, |age| age.as_secs())),
+                                         max_files:    Some(log.max_files),
+                                         compress:     Some(log.compress), };
+
         // On Windows, we only expect user to be Some.
         //
         // On Linux, we expect uid and gid to be Some, while
@@ -143,7 +182,9 @@ impl LauncherCli {
                                     svc_group_id: gid,
                                     svc_password: password.map(str::to_string),
                                     env,
-                                    id: id.to_string() };
+                                    id: id.to_string(),
+                                    limits,
+                                    log: Some(log) };
 
         Self::send(&self.tx, &msg)?;
         let reply = Self::recv::<protocol::SpawnOk>(&self.rx)?;
@@ -156,4 +197,15 @@ impl LauncherCli {
         let reply = Self::recv::<protocol::TerminateOk>(&self.rx)?;
         Ok(reply.exit_code)
     }
+
+    /// How a process that exited on its own did so, if the Launcher
+    /// still knows. Older Launchers never do.
+    pub fn process_exit(&self, pid: Pid) -> Result<Option<protocol::ProcessExitOk>> {
+        if !self.process_exit {
+            return Ok(None);
+        }
+        let msg = protocol::ProcessExit { pid: pid.into() };
+        Self::send(&self.tx, &msg)?;
+        Self::recv::<protocol::ProcessExitOk>(&self.rx).map(Some)
+    }
 }
@@ -27,6 +27,9 @@ pub trait LauncherMessage
 }
 
 pub use generated::{ErrCode,
+                    ResourceLimits,
+                    ServiceLog,
+                    ServiceLogFormat,
                     ShutdownMethod};
 
 // Now we're going to define our own set of structs to use internally, as well as conversion
@@ -66,18 +69,32 @@ impl fmt::Display for NetErr {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
-pub struct NetOk {}
+pub struct NetOk {
+    /// Whether the Launcher answers `ProcessExit`. Only set in reply
+    /// to `Register`.
+    pub process_exit:    bool,
+    /// Whether the Launcher enforces `ResourceLimits`. Only set in
+    /// reply to `Register`.
+    pub resource_limits: bool,
+}
 
 impl LauncherMessage for NetOk {
     type Generated = generated::NetOk;
 
     const MESSAGE_ID: &'static str = "NetOk";
 
-    fn from_proto(_proto: generated::NetOk) -> Result<Self> { Ok(NetOk {}) }
+    fn from_proto(proto: generated::NetOk) -> Result<Self> {
+        // Older Launchers don't say what they support
+        Ok(NetOk { process_exit:    proto.process_exit.unwrap_or(false),
+                   resource_limits: proto.resource_limits.unwrap_or(false), })
+    }
 }
 
 impl From<NetOk> for generated::NetOk {
-    fn from(_value: NetOk) -> Self { generated::NetOk {} }
+    fn from(value: NetOk) -> Self {
+        generated::NetOk { process_exit:    Some(value.process_exit),
+                           resource_limits: Some(value.resource_limits), }
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -132,6 +149,8 @@ pub struct Spawn {
     pub env:          HashMap<String, String>,
     pub svc_user_id:  Option<u32>,
     pub svc_group_id: Option<u32>,
+    pub limits:       Option<ResourceLimits>,
+    pub log:          Option<ServiceLog>,
 }
 
 impl LauncherMessage for Spawn {
@@ -147,7 +166,9 @@ impl LauncherMessage for Spawn {
                    svc_password: proto.svc_password,
                    env:          proto.env,
                    svc_user_id:  proto.svc_user_id,
-                   svc_group_id: proto.svc_group_id, })
+                   svc_group_id: proto.svc_group_id,
+                   limits:       proto.limits,
+                   log:          proto.log, })
     }
 }
 
@@ -160,7 +181,9 @@ impl From<Spawn> for generated::Spawn {
                            svc_password: value.svc_password,
                            env:          value.env,
                            svc_user_id:  value.svc_user_id,
-                           svc_group_id: value.svc_group_id, }
+                           svc_group_id: value.svc_group_id,
+                           limits:       value.limits,
+                           log:          value.log, }
     }
 }
 
@@ -206,6 +229,7 @@ impl From<Terminate> for generated::Terminate {
 pub struct TerminateOk {
     pub exit_code:       i32,
     pub shutdown_method: generated::ShutdownMethod,
+    pub oom_killed:      bool,
 }
 
 impl LauncherMessage for TerminateOk {
@@ -224,6 +248,8 @@ impl LauncherMessage for TerminateOk {
                     .ok_or(Error::ProtocolMismatch("shutdown_method"))?,
             )
             .ok_or(Error::ProtocolMismatch("shutdown_method"))?,
+            // Older Launchers don't know about OOM kills
+            oom_killed: proto.oom_killed.unwrap_or(false),
         })
     }
 }
@@ -231,7 +257,52 @@ impl LauncherMessage for TerminateOk {
 impl From<TerminateOk> for generated::TerminateOk {
     fn from(value: TerminateOk) -> Self {
         generated::TerminateOk { exit_code:       Some(value.exit_code),
-                                 shutdown_method: Some(value.shutdown_method as i32), }
+                                 shutdown_method: Some(value.shutdown_method as i32),
+                                 oom_killed:      Some(value.oom_killed), }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProcessExit {
+    pub pid: i64,
+}
+
+impl LauncherMessage for ProcessExit {
+    type Generated = generated::ProcessExit;
+
+    const MESSAGE_ID: &'static str = "ProcessExit";
+
+    fn from_proto(proto: generated::ProcessExit) -> Result<Self> {
+        Ok(ProcessExit { pid: proto.pid.ok_or(Error::ProtocolMismatch("pid"))?, })
+    }
+}
+
+impl From<ProcessExit> for generated::ProcessExit {
+    fn from(value: ProcessExit) -> Self { generated::ProcessExit { pid: Some(value.pid), } }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProcessExitOk {
+    pub exit_code:  i32,
+    pub oom_killed: bool,
+}
+
+impl LauncherMessage for ProcessExitOk {
+    type Generated = generated::ProcessExitOk;
+
+    const MESSAGE_ID: &'static str = "ProcessExitOk";
+
+    fn from_proto(proto: generated::ProcessExitOk) -> Result<Self> {
+        Ok(ProcessExitOk { exit_code:  proto.exit_code
+                                            .ok_or(Error::ProtocolMismatch("exit_code"))?,
+                           oom_killed: proto.oom_killed.unwrap_or(false), })
+    }
+}
+
+impl From<ProcessExitOk> for generated::ProcessExitOk {
+    fn from(value: ProcessExitOk) -> Self {
+        generated::ProcessExitOk { exit_code:  Some(value.exit_code),
+                                   oom_killed: Some(value.oom_killed), }
     }
 }
 
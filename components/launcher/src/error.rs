@@ -11,6 +11,7 @@ use crate::{SUP_CMD,
 #[derive(Debug)]
 pub enum Error {
     AcceptConn,
+    Cgroup(io::Error),
     Connect(io::Error),
     ExecWait(io::Error),
     GroupNotFound(String),
@@ -32,6 +33,7 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let msg = match *self {
             Error::AcceptConn => "Unable to accept connection from Supervisor".to_string(),
+            Error::Cgroup(ref e) => format!("Unable to apply resource limits, {}", e),
             Error::Connect(ref e) => {
                 format!("Unable to connect to Supervisor's comm channel, {}", e)
             }
@@ -60,6 +62,7 @@ impl error::Error for Error {
     fn description(&self) -> &str {
         match *self {
             Error::AcceptConn => "Unable to accept connection from Supervisor",
+            Error::Cgroup(_) => "Unable to apply resource limits",
             Error::Connect(_) => "Unable to connect to Supervisor's pipe",
             Error::GroupNotFound(_) => "No matching GID for group found",
             Error::ExecWait(_) => "OS Error while waiting on PID",
@@ -1,8 +1,10 @@
+mod process_exit;
 mod restart;
 mod spawn;
 mod terminate;
 
-pub use self::{restart::*,
+pub use self::{process_exit::*,
+               restart::*,
                spawn::*,
                terminate::*};
 
@@ -0,0 +1,27 @@
+use crate::protocol;
+
+use super::{HandleResult,
+            Handler};
+use crate::server::ServiceTable;
+
+pub struct ProcessExitHandler;
+impl Handler for ProcessExitHandler {
+    type Message = protocol::ProcessExit;
+    type Reply = protocol::ProcessExitOk;
+
+    fn handle(msg: Self::Message, services: &mut ServiceTable) -> HandleResult<Self::Reply> {
+        match services.take_exit(msg.pid as u32) {
+            Some(exit) => {
+                let mut reply = protocol::ProcessExitOk::default();
+                reply.exit_code = exit.code;
+                reply.oom_killed = exit.oom_killed;
+                Ok(reply)
+            }
+            None => {
+                let mut reply = protocol::NetErr::default();
+                reply.code = protocol::ErrCode::NoPid;
+                Err(reply)
+            }
+        }
+    }
+}
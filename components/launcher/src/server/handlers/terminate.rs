@@ -19,6 +19,7 @@ impl Handler for TerminateHandler {
                         let mut reply = protocol::TerminateOk::default();
                         reply.exit_code = status.code().unwrap_or(0);
                         reply.shutdown_method = shutdown_method;
+                        reply.oom_killed = service.oom_killed();
                         Ok(reply)
                     }
                     Err(_) => {
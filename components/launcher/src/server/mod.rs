@@ -40,7 +40,8 @@ use std::{collections::HashMap,
                  Condvar,
                  Mutex},
           thread,
-          time::Duration};
+          time::{Duration,
+                 Instant}};
 #[cfg(unix)]
 use std::{os::unix::process::ExitStatusExt,
           process::ExitStatus};
@@ -366,20 +367,47 @@ impl Server {
     }
 }
 
+/// How a service that exited on its own came to exit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServiceExit {
+    pub code:       i32,
+    /// Whether the service was killed for exceeding its memory limit.
+    pub oom_killed: bool,
+}
+
+/// How long the exit of a service is kept should the Supervisor never
+/// ask for it.
+const EXIT_RETENTION: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Debug, Default)]
-pub struct ServiceTable(HashMap<u32, Service>);
+pub struct ServiceTable {
+    services: HashMap<u32, Service>,
+    /// The exits of services that exited on their own, and when, kept
+    /// until the Supervisor asks for them or `EXIT_RETENTION` passes.
+    exits:    HashMap<u32, (Instant, ServiceExit)>,
+}
 
 impl ServiceTable {
-    pub fn get(&self, pid: u32) -> Option<&Service> { self.0.get(&pid) }
+    pub fn get(&self, pid: u32) -> Option<&Service> { self.services.get(&pid) }
 
-    pub fn get_mut(&mut self, pid: u32) -> Option<&mut Service> { self.0.get_mut(&pid) }
+    pub fn get_mut(&mut self, pid: u32) -> Option<&mut Service> { self.services.get_mut(&pid) }
 
-    pub fn insert(&mut self, service: Service) { self.0.insert(service.id(), service); }
+    pub fn insert(&mut self, service: Service) {
+        // The PID of a service that exited earlier may have been reused
+        self.exits.remove(&service.id());
+        self.services.insert(service.id(), service);
+    }
+
+    pub fn remove(&mut self, pid: u32) -> Option<Service> { self.services.remove(&pid) }
 
-    pub fn remove(&mut self, pid: u32) -> Option<Service> { self.0.remove(&pid) }
+    /// Take the exit of a service that has already exited and been
+    /// reaped, if it is known.
+    pub fn take_exit(&mut self, pid: u32) -> Option<ServiceExit> {
+        self.exits.remove(&pid).map(|(_, exit)| exit)
+    }
 
     fn kill_all(&mut self) {
-        for service in self.0.values_mut() {
+        for service in self.services.values_mut() {
             outputln!(preamble service.name(), "Stopping...");
             let shutdown_method = service.kill();
             outputln!(preamble service.name(), "Shutdown OK: {}", shutdown_method);
@@ -387,8 +415,11 @@ impl ServiceTable {
     }
 
     fn reap_services(&mut self) {
+        let now = Instant::now();
+        self.exits
+            .retain(|_, (exited_at, _)| now.duration_since(*exited_at) < EXIT_RETENTION);
         let mut dead: Vec<u32> = vec![];
-        for service in self.0.values_mut() {
+        for service in self.services.values_mut() {
             match service.try_wait() {
                 Ok(None) => (),
                 Ok(Some(code)) => {
@@ -396,6 +427,16 @@ impl ServiceTable {
                               service.name(),
                               service.id(),
                               code);
+                    let oom_killed = service.oom_killed();
+                    if oom_killed {
+                        outputln!(preamble service.name(),
+                                  "Killed for exceeding its memory limit");
+                    }
+                    // A service killed by a signal has no exit code
+                    self.exits.insert(service.id(),
+                                      (now,
+                                       ServiceExit { code: code.code().unwrap_or(-1),
+                                                     oom_killed }));
                     dead.push(service.id());
                 }
                 Err(err) => {
@@ -405,7 +446,7 @@ impl ServiceTable {
             }
         }
         for pid in dead {
-            self.0.remove(&pid);
+            self.services.remove(&pid);
         }
     }
 }
@@ -456,6 +497,7 @@ fn dispatch(tx: &Sender, bytes: &[u8], services: &mut ServiceTable) {
         }
     };
     let func = match msg.message_id() {
+        "ProcessExit" => handlers::ProcessExitHandler::run,
         "Restart" => handlers::RestartHandler::run,
         "Spawn" => handlers::SpawnHandler::run,
         "Terminate" => handlers::TerminateHandler::run,
@@ -484,7 +526,9 @@ fn setup_connection(server: IpcOneShotServer<Vec<u8>>) -> Result<(Receiver, Send
         let txn = protocol::NetTxn::from_bytes(&raw)?;
         let msg = txn.decode::<protocol::Register>()?;
         let tx = IpcSender::connect(msg.pipe).map_err(Error::Connect)?;
-        send(&tx, &protocol::NetOk::default())?;
+        send(&tx,
+             &protocol::NetOk { process_exit:    true,
+                                resource_limits: cfg!(target_os = "linux"), })?;
         {
             let (_, ref cvar) = *pair2;
             debug!("Connect thread finished; notifying waiting thread");
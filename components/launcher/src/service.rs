@@ -3,8 +3,12 @@ use crate::protocol;
 use core::os::process::windows_child::{ChildStderr,
                                        ChildStdout,
                                        ExitStatus};
-use habitat_common::output::{self,
-                             StructuredOutput};
+use habitat_common::{output::{self,
+                              StructuredOutput},
+                     service_log::{LogConfig,
+                                   LogFormat,
+                                   LogStream,
+                                   LogWriter}};
 #[cfg(unix)]
 use std::process::{ChildStderr,
                    ChildStdout,
@@ -14,7 +18,10 @@ use std::{fmt,
                BufRead,
                BufReader,
                Read},
-          thread};
+          sync::{Arc,
+                 Mutex},
+          thread,
+          time::Duration};
 
 pub use crate::sys::service::*;
 
@@ -29,16 +36,18 @@ impl Service {
                stdout: Option<ChildStdout>,
                stderr: Option<ChildStderr>)
                -> Self {
+        let log = spawn.log.as_ref().and_then(|log| open_log(&spawn.id, log));
         if let Some(stdout) = stdout {
             let id = spawn.id.to_string();
+            let log = log.clone();
             thread::Builder::new().name(format!("{}-out", spawn.id))
-                                  .spawn(move || pipe_stdout(stdout, &id))
+                                  .spawn(move || pipe_stdout(stdout, &id, log))
                                   .ok();
         }
         if let Some(stderr) = stderr {
             let id = spawn.id.to_string();
             thread::Builder::new().name(format!("{}-err", spawn.id))
-                                  .spawn(move || pipe_stderr(stderr, &id))
+                                  .spawn(move || pipe_stderr(stderr, &id, log))
                                   .ok();
         }
         Service { args: spawn,
@@ -55,6 +64,8 @@ impl Service {
 
     pub fn name(&self) -> &str { &self.args.id }
 
+    pub fn oom_killed(&self) -> bool { self.process.oom_killed() }
+
     pub fn take_args(self) -> protocol::Spawn { self.args }
 
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> { self.process.try_wait() }
@@ -68,14 +79,53 @@ impl fmt::Debug for Service {
     }
 }
 
+type SharedLog = Arc<Mutex<LogWriter>>;
+
+/// Open the log file the service's output is written to. A service
+/// whose output can't be logged is still started; its output is only
+/// printed.
+fn open_log(id: &str, log: &protocol::ServiceLog) -> Option<SharedLog> {
+    let dir = log.dir.as_ref()?;
+    let format = match log.format.and_then(protocol::ServiceLogFormat::from_i32) {
+        Some(protocol::ServiceLogFormat::Json) => LogFormat::Json,
+        _ => LogFormat::Plain,
+    };
+    let defaults = LogConfig::default();
+    let config = LogConfig { format,
+                             max_bytes: log.max_bytes.unwrap_or(defaults.max_bytes),
+                             max_age: log.max_age_secs
+                                         .filter(|secs| *secs > 0)
+                                         .map(Duration::from_secs),
+                             max_files: log.max_files.unwrap_or(defaults.max_files),
+                             compress: log.compress.unwrap_or(defaults.compress) };
+    match LogWriter::open(dir, id, config) {
+        Ok(writer) => Some(Arc::new(Mutex::new(writer))),
+        Err(err) => {
+            warn!("Unable to log the output of {} to {}, {}", id, dir, err);
+            None
+        }
+    }
+}
+
+fn log_line(log: &Option<SharedLog>, id: &str, stream: LogStream, content: &str) {
+    if let Some(ref log) = *log {
+        let mut writer = log.lock().expect("Service log lock poisoned");
+        if let Err(e) = writer.write_line(stream, content) {
+            eprintln!("logging output: '{}' of {} resulted in error: {}",
+                      content, id, e);
+        }
+    }
+}
+
 /// Consume output from a child process until EOF, then finish
-fn pipe_stdout<T>(out: T, id: &str)
+fn pipe_stdout<T>(out: T, id: &str, log: Option<SharedLog>)
     where T: Read
 {
     let mut reader = BufReader::new(out);
     let mut buffer = String::new();
     while reader.read_line(&mut buffer).unwrap() > 0 {
         let content = &buffer.trim_end_matches('\n');
+        log_line(&log, id, LogStream::Stdout, content);
         let so = StructuredOutput::succinct(&id, "O", output::get_format(), content);
         if let Err(e) = so.println() {
             println!("printing output: '{}' to stdout resulted in error: {}",
@@ -86,13 +136,14 @@ fn pipe_stdout<T>(out: T, id: &str)
 }
 
 /// Consume standard error from a child process until EOF, then finish
-fn pipe_stderr<T>(err: T, id: &str)
+fn pipe_stderr<T>(err: T, id: &str, log: Option<SharedLog>)
     where T: Read
 {
     let mut reader = BufReader::new(err);
     let mut buffer = String::new();
     while reader.read_line(&mut buffer).unwrap() > 0 {
         let content = &buffer.trim_end_matches('\n');
+        log_line(&log, id, LogStream::Stderr, content);
         let so = StructuredOutput::succinct(&id, "E", output::get_format(), content);
         if let Err(e) = so.eprintln() {
             eprintln!("printing output: '{}' to stderr resulted in error: {}",
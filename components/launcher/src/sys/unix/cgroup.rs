@@ -0,0 +1,166 @@
+//! Places services in their own cgroup v2 slice and applies their
+//! resource limits there.
+
+use crate::{core::os::cgroup::{self,
+                               CGROUP_ROOT,
+                               HABITAT_SLICE},
+            error::{Error,
+                    Result},
+            protocol::ResourceLimits};
+use libc;
+use std::{fs::{self,
+               File,
+               OpenOptions},
+          io::{self,
+               Write},
+          os::unix::io::AsRawFd,
+          path::{Path,
+                 PathBuf}};
+
+/// The controllers a service's slice needs.
+const CONTROLLERS: &[&str] = &["cpu", "memory", "pids"];
+/// The period over which a CPU limit is measured, in microseconds.
+const CPU_PERIOD_USEC: u64 = 100_000;
+
+#[derive(Debug)]
+pub struct Cgroup {
+    path:      PathBuf,
+    /// Writing a PID here moves that process into the cgroup.
+    procs:     File,
+    /// How many OOM kills the cgroup had seen when the service started.
+    oom_kills: u64,
+}
+
+impl Cgroup {
+    /// Create (or reuse) the slice for the service with the given id,
+    /// and apply its limits.
+    ///
+    /// A service that has no limits is run outside of any slice if
+    /// cgroups v2 can't be used, e.g. because the Launcher isn't
+    /// running as root; a service that does have limits fails to
+    /// start instead.
+    pub fn create(id: &str, limits: Option<&ResourceLimits>) -> Result<Option<Cgroup>> {
+        match Self::try_create(id, limits) {
+            Ok(cgroup) => Ok(Some(cgroup)),
+            Err(err) => {
+                if limits.map_or(false, |l| *l != ResourceLimits::default()) {
+                    Err(Error::Cgroup(err))
+                } else {
+                    debug!("Not placing {} in a cgroup, {}", id, err);
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    fn try_create(id: &str, limits: Option<&ResourceLimits>) -> io::Result<Cgroup> {
+        if !cgroup::is_v2() {
+            return Err(io::Error::new(io::ErrorKind::NotFound,
+                                      "the cgroup v2 hierarchy is not mounted"));
+        }
+        let root = Path::new(CGROUP_ROOT);
+        let slice = root.join(HABITAT_SLICE);
+        let path = cgroup::service_cgroup(id);
+        enable_controllers(root);
+        fs::create_dir_all(&slice)?;
+        enable_controllers(&slice);
+        fs::create_dir_all(&path)?;
+
+        // A reused cgroup may still carry limits from an earlier run,
+        // so every limit is written, even those that aren't set.
+        let default_limits = ResourceLimits::default();
+        let limits = limits.unwrap_or(&default_limits);
+        let cpu_max = match limits.cpu_millis {
+            Some(millis) => {
+                format!("{} {}",
+                        u64::from(millis) * CPU_PERIOD_USEC / 1000,
+                        CPU_PERIOD_USEC)
+            }
+            None => format!("max {}", CPU_PERIOD_USEC),
+        };
+        write_limit(&path, "cpu.max", &cpu_max, limits.cpu_millis.is_some())?;
+        write_limit(&path,
+                    "memory.max",
+                    &limit_value(limits.memory_bytes),
+                    limits.memory_bytes.is_some())?;
+        write_limit(&path,
+                    "pids.max",
+                    &limit_value(limits.pids),
+                    limits.pids.is_some())?;
+
+        let procs = OpenOptions::new().write(true)
+                                      .open(path.join("cgroup.procs"))?;
+        Ok(Cgroup { oom_kills: cgroup::oom_kills(&path),
+                    path,
+                    procs })
+    }
+
+    /// Returns a function that moves the process calling it into the
+    /// cgroup, for use between `fork` and `exec`.
+    ///
+    /// This only uses the already open `cgroup.procs` file, as nothing
+    /// else is safe to do in a forked child. Errors are ignored; the
+    /// Launcher moves the process itself once it has been spawned.
+    pub fn joiner(&self) -> impl FnMut() -> io::Result<()> + Send + Sync + 'static {
+        let fd = self.procs.as_raw_fd();
+        move || {
+            // Writing "0" moves the writing process
+            unsafe {
+                libc::write(fd, b"0".as_ptr() as *const libc::c_void, 1);
+            }
+            Ok(())
+        }
+    }
+
+    /// Move the given process into the cgroup.
+    pub fn add(&mut self, pid: u32) -> io::Result<()> {
+        self.procs.write_all(pid.to_string().as_bytes())
+    }
+
+    /// Whether a process in the cgroup has been killed for exceeding
+    /// the memory limit since the service started.
+    pub fn oom_killed(&self) -> bool { cgroup::oom_kills(&self.path) > self.oom_kills }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // This fails while processes that outlived the service are
+        // still in the cgroup; it is then reused when the service
+        // next starts.
+        if let Err(err) = fs::remove_dir(&self.path) {
+            debug!("Unable to remove cgroup {}, {}", self.path.display(), err);
+        }
+    }
+}
+
+/// Delegate the controllers services need to the children of the
+/// cgroup at `path`. Controllers the kernel doesn't offer are skipped.
+fn enable_controllers(path: &Path) {
+    for controller in CONTROLLERS {
+        if let Err(err) = fs::write(path.join("cgroup.subtree_control"),
+                                    format!("+{}", controller))
+        {
+            debug!("Unable to enable the {} controller for {}, {}",
+                   controller,
+                   path.display(),
+                   err);
+        }
+    }
+}
+
+/// Write a limit. Failing to reset a limit that isn't set is only
+/// logged, as the controller may simply not be available.
+fn write_limit(path: &Path, file: &str, value: &str, required: bool) -> io::Result<()> {
+    match fs::write(path.join(file), value) {
+        Err(err) if required => Err(err),
+        Err(err) => {
+            debug!("Unable to write {} to {}, {}", value, file, err);
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+fn limit_value(limit: Option<u64>) -> String {
+    limit.map_or_else(|| "max".to_string(), |l| l.to_string())
+}
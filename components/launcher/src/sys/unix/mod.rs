@@ -1 +1,3 @@
+#[cfg(target_os = "linux")]
+pub mod cgroup;
 pub mod service;
@@ -20,15 +20,22 @@ use crate::{error::{Error,
                     Result},
             service::Service};
 
-pub struct Process(Child);
+#[cfg(target_os = "linux")]
+use super::cgroup::Cgroup;
+
+pub struct Process {
+    child:  Child,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<Cgroup>,
+}
 
 impl Process {
-    pub fn id(&self) -> u32 { self.0.id() }
+    pub fn id(&self) -> u32 { self.child.id() }
 
     /// Attempt to gracefully terminate a process and then forcefully kill it after
     /// 8 seconds if it has not terminated.
     pub fn kill(&mut self) -> ShutdownMethod {
-        let mut pid_to_kill = self.0.id() as i32;
+        let mut pid_to_kill = self.child.id() as i32;
         // check the group of the process being killed
         // if it is the root process of the process group
         // we send our signals to the entire process group
@@ -64,9 +71,17 @@ impl Process {
         }
     }
 
-    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> { self.0.try_wait() }
+    /// Whether the process, or one of its children, was killed for
+    /// exceeding the service's memory limit.
+    #[cfg(target_os = "linux")]
+    pub fn oom_killed(&self) -> bool { self.cgroup.as_ref().map_or(false, Cgroup::oom_killed) }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn oom_killed(&self) -> bool { false }
 
-    pub fn wait(&mut self) -> io::Result<ExitStatus> { self.0.wait() }
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> { self.child.try_wait() }
+
+    pub fn wait(&mut self) -> io::Result<ExitStatus> { self.child.wait() }
 }
 
 pub fn run(msg: protocol::Spawn) -> Result<Service> {
@@ -91,6 +106,14 @@ pub fn run(msg: protocol::Spawn) -> Result<Service> {
     };
 
     cmd.before_exec(owned_pgid);
+    #[cfg(target_os = "linux")]
+    let mut cgroup = Cgroup::create(&msg.id, msg.limits.as_ref())?;
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(ref cgroup) = cgroup {
+            cmd.before_exec(cgroup.joiner());
+        }
+    }
     cmd.stdin(Stdio::null())
        .stdout(Stdio::piped())
        .stderr(Stdio::piped())
@@ -102,7 +125,22 @@ pub fn run(msg: protocol::Spawn) -> Result<Service> {
     let mut child = cmd.spawn().map_err(Error::Spawn)?;
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
-    let process = Process(child);
+    #[cfg(target_os = "linux")]
+    {
+        // On kernels that check the permissions of the writer rather
+        // than of whoever opened `cgroup.procs`, the child couldn't
+        // move itself once it had dropped privileges.
+        if let Some(ref mut cgroup) = cgroup {
+            if let Err(err) = cgroup.add(child.id()) {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(Error::Cgroup(err));
+            }
+        }
+    }
+    let process = Process { child,
+                            #[cfg(target_os = "linux")]
+                            cgroup };
     Ok(Service::new(msg, process, stdout, stderr))
 }
 
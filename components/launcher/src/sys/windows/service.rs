@@ -86,6 +86,10 @@ impl Process {
         }
     }
 
+    /// Resource limits aren't enforced on Windows, so nothing is ever
+    /// killed for exceeding them.
+    pub fn oom_killed(&self) -> bool { false }
+
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         unsafe {
             match synchapi::WaitForSingleObject(self.handle.raw(), 0) {
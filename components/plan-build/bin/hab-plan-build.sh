@@ -211,6 +211,34 @@
 #   [storage]="port host"
 # )
 #
+# ### pkg_svc_resource_limits
+# An associative array of the resources the service may use. On Linux, the Supervisor runs the
+# service in its own cgroup with these limits applied. `cpu` is a number of CPUs (`2`, `0.5` or
+# `500m`), `memory` is a number of bytes with an optional `K`, `M`, `G` or `T` suffix and `pids`
+# is the maximum number of processes. Any of them may be overridden when the service is loaded.
+# ```
+# pkg_svc_resource_limits=(
+#   [cpu]=0.5
+#   [memory]=512M
+#   [pids]=100
+# )
+# ```
+#
+# ### pkg_svc_health_check
+# An associative array describing a health check the Supervisor performs itself, for services
+# that don't need a `health-check` hook. `probe` is one of `http://HOST:PORT/PATH` (an HTTP GET),
+# `tcp://HOST:PORT` (a TCP connection) or `exec:COMMAND` (a command whose exit code is read as a
+# `health-check` hook's would be). HTTP probes may also give the `status` the response must have
+# (any 2xx status otherwise) and text the `body` must contain. `timeout` is how many seconds the
+# probe may take (10 by default). It may be overridden when the service is loaded.
+# ```
+# pkg_svc_health_check=(
+#   [probe]=http://localhost:8080/health
+#   [status]=200
+#   [timeout]=5
+# )
+# ```
+#
 # ### pkg_origin
 # A string to use for the origin. The origin is used to denote a particular upstream of a
 # package; when we resolve dependencies, we consider a version of a package to be equal
@@ -388,6 +416,8 @@ pkg_exposes=()
 declare -A pkg_exports
 declare -A pkg_binds
 declare -A pkg_binds_optional
+declare -A pkg_svc_resource_limits
+declare -A pkg_svc_health_check
 # The user to run the service as
 pkg_svc_user=hab
 # The group to run the service as
@@ -1918,6 +1948,8 @@ do_default_install() {
 # * `$pkg_prefix/EXPOSES` - An array of `pkg_exports` for which ports that this package exposes
 # * `$pkg_prefix/BINDS` - A list of services you connect to and keys that you expect to be exported
 # * `$pkg_prefix/BINDS_OPTIONAL` - Same as `BINDS` but not required for the service to start
+# * `$pkg_prefix/RESOURCE_LIMITS` - The CPU, memory and pids limits the service runs under
+# * `$pkg_prefix/HEALTH_CHECK` - The health check the Supervisor performs for the service
 # * `$pkg_prefix/FILES` - blake2b checksums of all files in the package
 # * `$pkg_prefix/LDFLAGS` - Any LDFLAGS for things that link against us
 # * `$pkg_prefix/LD_RUN_PATH` - The LD_RUN_PATH for things that link against us
@@ -1956,14 +1988,16 @@ _build_metadata() {
   if [[ -f "$PLAN_CONTEXT/hooks/run" || -n "${pkg_svc_run:-}" ]]; then
     _render_metadata_SVC_USER
     _render_metadata_SVC_GROUP
+    _render_metadata_RESOURCE_LIMITS
+    _render_metadata_HEALTH_CHECK
   fi
 
   return 0
 }
 
 # Copy the `./config` directory, relative to the Plan, to `$pkg_prefix/config`.
-# Do the same with `default.toml`. Delegates most of the implementation to the
-# `do_default_build_config()` function.
+# Do the same with `default.toml` and `config.schema.json`. Delegates most of
+# the implementation to the `do_default_build_config()` function.
 do_build_config() {
   do_default_build_config
   return $?
@@ -1981,6 +2015,9 @@ do_default_build_config() {
   if [[ -f "$PLAN_CONTEXT/default.toml" ]]; then
     cp "$PLAN_CONTEXT/default.toml" "$pkg_prefix"
   fi
+  if [[ -f "$PLAN_CONTEXT/config.schema.json" ]]; then
+    cp "$PLAN_CONTEXT/config.schema.json" "$pkg_prefix"
+  fi
   return 0
 }
 
@@ -202,6 +202,14 @@ _render_metadata_PKG_CONFIG_PATH() {
   fi
 }
 
+_render_metadata_HEALTH_CHECK() {
+    _render_associative_array_file "${pkg_prefix}" HEALTH_CHECK pkg_svc_health_check
+}
+
+_render_metadata_RESOURCE_LIMITS() {
+    _render_associative_array_file "${pkg_prefix}" RESOURCE_LIMITS pkg_svc_resource_limits
+}
+
 _render_metadata_RUNTIME_ENVIRONMENT(){
     debug "Rendering RUNTIME_ENVIRONMENT metadata file"
     _render_associative_array_file "${pkg_prefix}" RUNTIME_ENVIRONMENT __runtime_environment
@@ -6,7 +6,7 @@
 //! # RPC Call Example
 //!
 //! ```ignore
-//! let conn = SrvClient::connect(&listen_addr, secret_key).wait()?;
+//! let conn = SrvClient::connect(&listen_addr, secret_key, None).wait()?;
 //! let msg = protocols::ctl::ServiceGetDefaultCfg::new();
 //! conn.call(msg).for_each(|reply| {
 //!     match reply.message_id() {
@@ -33,15 +33,25 @@ use habitat_common as common;
 
 use std::{error,
           fmt,
-          io,
-          path::PathBuf};
+          fs::File,
+          io::{self,
+               BufReader},
+          path::{Path,
+                 PathBuf},
+          sync::Arc};
 
 use crate::protocol::{codec::*,
                       net::NetErr};
-use futures::{prelude::*,
+use futures::{future::{self,
+                       Either},
+              prelude::*,
               sink};
 use tokio::net::TcpStream;
 use tokio_codec::Framed;
+use tokio_rustls::{rustls::{internal::pemfile,
+                            ClientConfig},
+                   webpki::DNSNameRef,
+                   TlsConnector};
 
 use crate::common::types::ListenCtlAddr;
 
@@ -56,6 +66,12 @@ pub enum SrvClientError {
     CtlSecretNotFound(PathBuf),
     /// Decoding a message from the remote failed.
     Decode(prost::DecodeError),
+    /// A TLS certificate file could not be read or contained no certificates.
+    InvalidCertFile(PathBuf),
+    /// A TLS private key file could not be read or contained no RSA private key.
+    InvalidKeyFile(PathBuf),
+    /// The name to verify the remote server's TLS certificate against is not a valid DNS name.
+    InvalidServerName(String),
     /// An Os level IO error occurred.
     Io(io::Error),
     /// An RPC call to the remote was received but failed.
@@ -70,6 +86,9 @@ impl error::Error for SrvClientError {
             SrvClientError::ConnectionClosed => "Connection closed",
             SrvClientError::CtlSecretNotFound(_) => "Ctl secret key not found",
             SrvClientError::Decode(ref err) => err.description(),
+            SrvClientError::InvalidCertFile(_) => "Invalid TLS certificate file",
+            SrvClientError::InvalidKeyFile(_) => "Invalid TLS private key file",
+            SrvClientError::InvalidServerName(_) => "Invalid TLS server name",
             SrvClientError::Io(ref err) => err.description(),
             SrvClientError::NetErr(ref err) => err.description(),
             SrvClientError::ParseColor(ref err) => err.description(),
@@ -93,6 +112,19 @@ impl fmt::Display for SrvClientError {
                         path.display())
             }
             SrvClientError::Decode(ref err) => format!("{}", err),
+            SrvClientError::InvalidCertFile(ref path) => {
+                format!("Could not read any PEM-format certificates from {}",
+                        path.display())
+            }
+            SrvClientError::InvalidKeyFile(ref path) => {
+                format!("Could not read a PEM-format RSA private key from {}",
+                        path.display())
+            }
+            SrvClientError::InvalidServerName(ref name) => {
+                format!("Cannot verify the Supervisor's TLS certificate against '{}'; it must be \
+                         a DNS name. Set HAB_CTL_TLS_SERVER_NAME to the name in the certificate.",
+                        name)
+            }
             SrvClientError::Io(ref err) => {
                 format!("Unable to contact the Supervisor.\n\nIf the Supervisor you are \
                          contacting is local, this probably means it is not running. You can run \
@@ -124,6 +156,78 @@ impl From<termcolor::ParseColorError> for SrvClientError {
     fn from(err: termcolor::ParseColorError) -> Self { SrvClientError::ParseColor(err) }
 }
 
+/// TLS settings for connecting to a CtlGateway which only accepts TLS connections.
+#[derive(Clone)]
+pub struct TlsConfig {
+    config:      Arc<ClientConfig>,
+    server_name: String,
+}
+
+impl TlsConfig {
+    /// Trust the CA certificates in `ca_cert_path` to sign the server's certificate, which must be
+    /// valid for `server_name`. If `client_cert` is given as a certificate and private key path,
+    /// present that certificate to servers which require client authentication.
+    pub fn new(ca_cert_path: &Path,
+               client_cert: Option<(&Path, &Path)>,
+               server_name: &str)
+               -> Result<Self, SrvClientError> {
+        dns_name(server_name)?;
+        let mut config = ClientConfig::new();
+        let ca_file = &mut BufReader::new(File::open(ca_cert_path)?);
+        config.root_store
+              .add_pem_file(ca_file)
+              .and_then(|(added, _)| if added < 1 { Err(()) } else { Ok(()) })
+              .map_err(|_| SrvClientError::InvalidCertFile(ca_cert_path.to_path_buf()))?;
+        if let Some((cert_path, key_path)) = client_cert {
+            let cert_file = &mut BufReader::new(File::open(cert_path)?);
+            let key_file = &mut BufReader::new(File::open(key_path)?);
+            let cert_chain = pemfile::certs(cert_file).ok()
+                                                      .filter(|c| !c.is_empty())
+                                                      .ok_or_else(|| {
+                                                          SrvClientError::InvalidCertFile(
+                    cert_path.to_path_buf(),
+                )
+                                                      })?;
+            let key = pemfile::rsa_private_keys(key_file).ok()
+                                                         .and_then(|mut k| k.pop())
+                                                         .ok_or_else(|| {
+                                                             SrvClientError::InvalidKeyFile(
+                    key_path.to_path_buf(),
+                )
+                                                         })?;
+            config.set_single_client_cert(cert_chain, key);
+        }
+        Ok(TlsConfig { config:      Arc::new(config),
+                       server_name: server_name.to_string(), })
+    }
+
+    /// Wrap a connected socket in a TLS session with the server.
+    fn connect(&self,
+               socket: TcpStream)
+               -> impl Future<Item = Box<dyn SrvIo>, Error = SrvClientError> {
+        let connector = TlsConnector::from(self.config.clone());
+        let connect = dns_name(&self.server_name).map(|name| connector.connect(name, socket));
+        future::result(connect).and_then(|connect| connect.map_err(SrvClientError::from))
+                               .map(|stream| Box::new(stream) as Box<dyn SrvIo>)
+    }
+}
+
+/// Wrap the socket in a TLS session if a `TlsConfig` is given, or use it as-is otherwise.
+fn secure(socket: TcpStream,
+          tls_config: Option<TlsConfig>)
+          -> impl Future<Item = Box<dyn SrvIo>, Error = SrvClientError> {
+    match tls_config {
+        Some(c) => Either::A(c.connect(socket)),
+        None => Either::B(future::ok(Box::new(socket) as Box<dyn SrvIo>)),
+    }
+}
+
+fn dns_name(name: &str) -> Result<DNSNameRef<'_>, SrvClientError> {
+    DNSNameRef::try_from_ascii_str(name).map_err(|_| {
+                                            SrvClientError::InvalidServerName(name.to_string())
+                                        })
+}
+
 /// Client for connecting and communicating with a server listener which speaks SrvProtocol.
 ///
 /// See module doc for usage.
@@ -139,22 +243,27 @@ pub struct SrvClient {
 }
 
 impl SrvClient {
-    /// Connect to the given remote server and authenticate with the given secret_key.
+    /// Connect to the given remote server and authenticate with the given secret_key. The
+    /// connection is made over TLS if a `TlsConfig` is given.
     pub fn connect(addr: &ListenCtlAddr,
-                   secret_key: &str)
+                   secret_key: &str,
+                   tls_config: Option<&TlsConfig>)
                    -> Box<dyn Future<Item = SrvClient, Error = SrvClientError> + 'static> {
         let secret_key = secret_key.to_string();
-        let conn = TcpStream::connect(addr.as_ref()).map_err(SrvClientError::from)
-                                                    .and_then(move |socket| {
-                                                        let client = Self::new(socket, None);
-                                                        let mut request =
-                                                            protocol::ctl::Handshake::default();
-                                                        request.secret_key = Some(secret_key);
-                                                        client.call(request)
-                                                              .into_future()
-                                                              .map_err(|(err, _)| err)
-                                                              .and_then(move |(m, io)| {
-                                                                  m.map_or_else(
+        let tls_config = tls_config.cloned();
+        let conn =
+            TcpStream::connect(addr.as_ref()).map_err(SrvClientError::from)
+                                             .and_then(move |socket| secure(socket, tls_config))
+                                             .and_then(move |socket| {
+                                                 let client = Self::new(socket, None);
+                                                 let mut request =
+                                                     protocol::ctl::Handshake::default();
+                                                 request.secret_key = Some(secret_key);
+                                                 client.call(request)
+                                                       .into_future()
+                                                       .map_err(|(err, _)| err)
+                                                       .and_then(move |(m, io)| {
+                                                           m.map_or_else(
                             || Err(SrvClientError::ConnectionClosed),
                             move |m| {
                                 m.try_ok()
@@ -162,8 +271,8 @@ impl SrvClient {
                                     .and_then(|()| Ok(io.into_inner()))
                             },
                         )
-                                                              })
-                                                    });
+                                                       })
+                                             });
         Box::new(conn)
     }
 
@@ -174,7 +283,7 @@ impl SrvClient {
         Ok(buf)
     }
 
-    fn new(socket: TcpStream, current_txn: Option<SrvTxn>) -> Self {
+    fn new(socket: Box<dyn SrvIo>, current_txn: Option<SrvTxn>) -> Self {
         SrvClient { socket:      Framed::new(socket, SrvCodec::new()),
                     current_txn: current_txn.unwrap_or_default(), }
     }
@@ -1,4 +1,34 @@
+use crate::core::service::ServiceGroup;
+
 /// Maximum allowed size for a file to be uploaded to a service (in bytes).
 pub const MAX_FILE_PUT_SIZE_BYTES: usize = 64 * 1024;
 /// Maximum allowed size for a configuration to be applied to a service (in bytes).
 pub const MAX_SVC_CFG_SIZE: usize = 64 * 1024;
+
+/// The bytes a user key signs to author a configuration for a service group. The service group,
+/// version and encryption are signed along with the configuration itself, so a signature cannot
+/// be replayed for another service group or version.
+pub fn service_config_signed_bytes(service_group: &ServiceGroup,
+                                   version: u64,
+                                   is_encrypted: bool,
+                                   cfg: &[u8])
+                                   -> Vec<u8> {
+    let mut bytes = format!("SERVICE-CONFIG-1\n{}\n{}\n{}\n",
+                            service_group, version, is_encrypted).into_bytes();
+    bytes.extend_from_slice(cfg);
+    bytes
+}
+
+/// The bytes a user key signs to author a file for a service group. As with configuration, the
+/// service group, version, encryption and file name are signed along with the file's content.
+pub fn service_file_signed_bytes(service_group: &ServiceGroup,
+                                 filename: &str,
+                                 version: u64,
+                                 is_encrypted: bool,
+                                 content: &[u8])
+                                 -> Vec<u8> {
+    let mut bytes = format!("SERVICE-FILE-1\n{}\n{}\n{}\n{}\n",
+                            service_group, filename, version, is_encrypted).into_bytes();
+    bytes.extend_from_slice(content);
+    bytes
+}
@@ -54,7 +54,8 @@ use std::{fmt,
           io::{self,
                Cursor},
           str};
-use tokio::net::TcpStream;
+use tokio::io::{AsyncRead,
+                AsyncWrite};
 use tokio_codec::{Decoder,
                   Encoder,
                   Framed};
@@ -72,9 +73,15 @@ const RESPONSE_MASK: u32 = 0x1;
 const COMPLETE_OFFSET: u32 = 30;
 const COMPLETE_MASK: u32 = 0x1;
 
-/// A `TcpStream` framed with `SrvCodec`. This is the base socket connection that the CtlGateway
+/// A connection which the CtlGateway client and server can speak over. This is either a plain
+/// `TcpStream` or one wrapped in a TLS session.
+pub trait SrvIo: AsyncRead + AsyncWrite + Send {}
+
+impl<T> SrvIo for T where T: AsyncRead + AsyncWrite + Send {}
+
+/// A `SrvIo` framed with `SrvCodec`. This is the base socket connection that the CtlGateway
 /// client and server speak.
-pub type SrvStream = Framed<TcpStream, SrvCodec>;
+pub type SrvStream = Framed<Box<dyn SrvIo>, SrvCodec>;
 
 /// Sending half of `SrvStream`.
 pub type SrvSink = futures::stream::SplitSink<SrvStream>;
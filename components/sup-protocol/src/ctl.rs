@@ -6,8 +6,68 @@
 include!("generated/sup.ctl.rs");
 include!("generated/sup.ctl.impl.rs");
 
-use std::fmt;
+use crate::net::{self,
+                 ErrCode,
+                 NetErr};
+use std::{fmt,
+          str::FromStr};
 
 impl fmt::Display for ConsoleLine {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.line) }
 }
+
+impl LogStream {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+            LogStream::Hooks => "hooks",
+        }
+    }
+}
+
+impl FromStr for LogStream {
+    type Err = NetErr;
+
+    fn from_str(stream: &str) -> Result<Self, Self::Err> {
+        match stream {
+            "stdout" => Ok(LogStream::Stdout),
+            "stderr" => Ok(LogStream::Stderr),
+            "hooks" => Ok(LogStream::Hooks),
+            _ => Err(net::err(ErrCode::InvalidPayload, "Invalid log stream.")),
+        }
+    }
+}
+
+impl fmt::Display for LogStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.as_str()) }
+}
+
+impl DebugTopic {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DebugTopic::Heat => "heat",
+            DebugTopic::Elections => "elections",
+            DebugTopic::Updaters => "updaters",
+            DebugTopic::Watchers => "watchers",
+        }
+    }
+}
+
+impl FromStr for DebugTopic {
+    type Err = NetErr;
+
+    fn from_str(topic: &str) -> Result<Self, Self::Err> {
+        match topic {
+            "heat" => Ok(DebugTopic::Heat),
+            "elections" => Ok(DebugTopic::Elections),
+            "updaters" => Ok(DebugTopic::Updaters),
+            "watchers" => Ok(DebugTopic::Watchers),
+            _ => Err(net::err(ErrCode::InvalidPayload, "Invalid debug topic.")),
+        }
+    }
+}
+
+impl fmt::Display for DebugTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.as_str()) }
+}
@@ -12,6 +12,24 @@ impl message::MessageStatic for ServiceBindList {
 impl message::MessageStatic for SupDepart {
     const MESSAGE_ID: &'static str = "SupDepart";
 }
+impl message::MessageStatic for SupRingKeyRotate {
+    const MESSAGE_ID: &'static str = "SupRingKeyRotate";
+}
+impl message::MessageStatic for SupDebug {
+    const MESSAGE_ID: &'static str = "SupDebug";
+}
+impl message::MessageStatic for RumorHeat {
+    const MESSAGE_ID: &'static str = "RumorHeat";
+}
+impl message::MessageStatic for PendingElection {
+    const MESSAGE_ID: &'static str = "PendingElection";
+}
+impl message::MessageStatic for UpdaterStatus {
+    const MESSAGE_ID: &'static str = "UpdaterStatus";
+}
+impl message::MessageStatic for WatcherStatus {
+    const MESSAGE_ID: &'static str = "WatcherStatus";
+}
 impl message::MessageStatic for SvcFilePut {
     const MESSAGE_ID: &'static str = "SvcFilePut";
 }
@@ -21,6 +39,12 @@ impl message::MessageStatic for SvcGetDefaultCfg {
 impl message::MessageStatic for SvcValidateCfg {
     const MESSAGE_ID: &'static str = "SvcValidateCfg";
 }
+impl message::MessageStatic for SvcDryRunCfg {
+    const MESSAGE_ID: &'static str = "SvcDryRunCfg";
+}
+impl message::MessageStatic for SvcCfgDryRun {
+    const MESSAGE_ID: &'static str = "SvcCfgDryRun";
+}
 impl message::MessageStatic for SvcSetCfg {
     const MESSAGE_ID: &'static str = "SvcSetCfg";
 }
@@ -39,6 +63,9 @@ impl message::MessageStatic for SvcStop {
 impl message::MessageStatic for SvcStatus {
     const MESSAGE_ID: &'static str = "SvcStatus";
 }
+impl message::MessageStatic for SvcLogs {
+    const MESSAGE_ID: &'static str = "SvcLogs";
+}
 impl message::MessageStatic for ConsoleLine {
     const MESSAGE_ID: &'static str = "ConsoleLine";
 }
@@ -38,6 +38,88 @@ pub struct SupDepart {
     #[prost(string, optional, tag="1")]
     pub member_id: ::std::option::Option<std::string::String>,
 }
+/// Request to distribute a new revision of the ring key to the ring. Members move to it once
+/// every live member holds it, and then retire the revisions before it.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SupRingKeyRotate {
+    /// Contents of the new ring key file.
+    #[prost(string, optional, tag="1")]
+    pub key: ::std::option::Option<std::string::String>,
+}
+/// Request for a snapshot of one part of the Supervisor's internal state, for debugging. Only
+/// accepted from clients on the Supervisor's own host. Each item is replied with as the message
+/// listed for the requested topic.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SupDebug {
+    #[prost(enumeration="DebugTopic", optional, tag="1")]
+    pub topic: ::std::option::Option<i32>,
+}
+/// How many of the rumors the Supervisor is gossiping are still "hot" for a member, i.e., will
+/// be sent to it again.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RumorHeat {
+    #[prost(string, required, tag="1")]
+    pub member_id: std::string::String,
+    #[prost(uint32, required, tag="2")]
+    pub hot_rumors: u32,
+    #[prost(uint32, required, tag="3")]
+    pub total_rumors: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PendingElection {
+    #[prost(string, required, tag="1")]
+    pub service_group: std::string::String,
+    /// Whether this is an election for the service group's update leader.
+    #[prost(bool, required, tag="2")]
+    pub update: bool,
+    #[prost(uint64, required, tag="3")]
+    pub term: u64,
+    /// Either "running" or "no-quorum".
+    #[prost(string, required, tag="4")]
+    pub status: std::string::String,
+    /// The member currently leading the vote, and how many votes it has.
+    #[prost(string, required, tag="5")]
+    pub member_id: std::string::String,
+    #[prost(uint32, required, tag="6")]
+    pub votes: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UpdaterStatus {
+    #[prost(string, required, tag="1")]
+    pub service_group: std::string::String,
+    /// What the service's update worker is doing, e.g., "rolling: follower, waiting".
+    #[prost(string, required, tag="2")]
+    pub state: std::string::String,
+    /// A release held until the service's update window opens.
+    #[prost(string, optional, tag="3")]
+    pub held_release: ::std::option::Option<std::string::String>,
+    /// A recently-applied release still being watched for failure.
+    #[prost(string, optional, tag="4")]
+    pub watched_release: ::std::option::Option<std::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WatcherStatus {
+    /// What the watched path is for: "specs", "peers" or "user-config".
+    #[prost(string, required, tag="1")]
+    pub kind: std::string::String,
+    #[prost(string, required, tag="2")]
+    pub path: std::string::String,
+    /// The service whose configuration is watched, for "user-config" watchers.
+    #[prost(string, optional, tag="3")]
+    pub service: ::std::option::Option<std::string::String>,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -53,6 +135,12 @@ pub struct SvcFilePut {
     pub version: ::std::option::Option<u64>,
     #[prost(bool, optional, tag="5", default="false")]
     pub is_encrypted: ::std::option::Option<bool>,
+    /// Name of the user key `content` was signed with, if it is signed.
+    #[prost(string, optional, tag="6")]
+    pub signer: ::std::option::Option<std::string::String>,
+    /// Signature of `butterfly::service_file_signed_bytes` for this file.
+    #[prost(bytes, optional, tag="7")]
+    pub signature: ::std::option::Option<std::vec::Vec<u8>>,
 }
 /// Request for retrieving the default configuration for a given service.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -77,6 +165,34 @@ pub struct SvcValidateCfg {
     #[prost(bytes, optional, tag="3")]
     pub cfg: ::std::option::Option<std::vec::Vec<u8>>,
 }
+/// Request to preview applying a configuration to a running service, without gossiping it.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SvcDryRunCfg {
+    /// Service group of a running service to preview the configuration change against.
+    #[prost(message, optional, tag="1")]
+    pub service_group: ::std::option::Option<super::types::ServiceGroup>,
+    /// Structured and self-describing string format contained in the configuration string.
+    #[prost(enumeration="super::types::service_cfg::Format", optional, tag="2", default="Toml")]
+    pub format: ::std::option::Option<i32>,
+    /// Unencrypted configuration to preview.
+    #[prost(bytes, optional, tag="3")]
+    pub cfg: ::std::option::Option<std::vec::Vec<u8>>,
+}
+/// What applying a configuration to a running service would change.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SvcCfgDryRun {
+    /// A unified diff for each configuration file and hook whose rendered content would change.
+    #[prost(string, repeated, tag="1")]
+    pub diffs: ::std::vec::Vec<std::string::String>,
+    /// What the Supervisor would do to the service, in order: "reload" (or "restart", if the
+    /// service has no reload hook) and then "reconfigure".
+    #[prost(string, repeated, tag="2")]
+    pub hooks: ::std::vec::Vec<std::string::String>,
+}
 /// Request to set a running service's configuration to the given values.
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
@@ -96,6 +212,12 @@ pub struct SvcSetCfg {
     /// If the payload in `cfg` is encrypted with the remote Supervisor's Ring Key.
     #[prost(bool, optional, tag="4", default="false")]
     pub is_encrypted: ::std::option::Option<bool>,
+    /// Name of the user key `cfg` was signed with, if it is signed.
+    #[prost(string, optional, tag="5")]
+    pub signer: ::std::option::Option<std::string::String>,
+    /// Signature of `butterfly::service_config_signed_bytes` for this configuration.
+    #[prost(bytes, optional, tag="6")]
+    pub signature: ::std::option::Option<std::vec::Vec<u8>>,
 }
 /// Request to load a new service.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -148,6 +270,28 @@ pub struct SvcLoad {
     /// Health Check interval for the service
     #[prost(message, optional, tag="15")]
     pub health_check_interval: ::std::option::Option<super::types::HealthCheckInterval>,
+    /// Recurring window during which updates to the service may be applied.
+    #[prost(string, optional, tag="16")]
+    pub update_window: ::std::option::Option<std::string::String>,
+    /// When to restart the service's process after it exits.
+    #[prost(enumeration="super::types::RestartPolicy", optional, tag="17")]
+    pub restart_policy: ::std::option::Option<i32>,
+    /// How many restarts within the restart window before the service is
+    /// considered to be crash-looping.
+    #[prost(uint32, optional, tag="18")]
+    pub max_restarts: ::std::option::Option<u32>,
+    /// The restart window, in seconds.
+    #[prost(uint64, optional, tag="19")]
+    pub restart_window: ::std::option::Option<u64>,
+    /// The longest to wait between restarts, in seconds.
+    #[prost(uint64, optional, tag="20")]
+    pub max_restart_backoff: ::std::option::Option<u64>,
+    /// Overrides the resource limits declared by the service's package.
+    #[prost(message, optional, tag="21")]
+    pub resource_limits: ::std::option::Option<super::types::ResourceLimits>,
+    /// Overrides the health check declared by the service's package.
+    #[prost(message, optional, tag="22")]
+    pub health_check: ::std::option::Option<super::types::HealthCheck>,
 }
 /// Request to unload a loaded service.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -197,6 +341,24 @@ pub struct SvcStatus {
     #[prost(message, optional, tag="1")]
     pub ident: ::std::option::Option<super::types::PackageIdent>,
 }
+/// Request to retrieve the output of a service from its log file. Each line is replied with as a
+/// `ConsoleLine`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SvcLogs {
+    #[prost(message, optional, tag="1")]
+    pub ident: ::std::option::Option<super::types::PackageIdent>,
+    /// The number of most recent lines to reply with. Defaults to 10.
+    #[prost(uint32, optional, tag="2")]
+    pub lines: ::std::option::Option<u32>,
+    /// If set, keep replying with new lines as the service writes them until the client disconnects.
+    #[prost(bool, optional, tag="3")]
+    pub follow: ::std::option::Option<bool>,
+    /// Only reply with output from these streams. All streams are included when none are given.
+    #[prost(enumeration="LogStream", repeated, packed="false", tag="4")]
+    pub streams: ::std::vec::Vec<i32>,
+}
 /// A reply to various requests which contains a pre-formatted console line.
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
@@ -209,3 +371,28 @@ pub struct ConsoleLine {
     #[prost(bool, required, tag="3")]
     pub bold: bool,
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DebugTopic {
+    /// A `RumorHeat` for each member of the ring.
+    Heat = 0,
+    /// A `PendingElection` for each election which has not finished.
+    Elections = 1,
+    /// An `UpdaterStatus` for each service group the service updater is tracking.
+    Updaters = 2,
+    /// A `WatcherStatus` for each file or directory the Supervisor watches for changes.
+    Watchers = 3,
+}
+/// Where a line of a service's output came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogStream {
+    Stdout = 0,
+    Stderr = 1,
+    /// The output of the service's hooks, whichever stream they wrote it to.
+    Hooks = 2,
+}
@@ -86,6 +86,32 @@ pub struct ServiceStatus {
     pub service_group: ServiceGroup,
     #[prost(enumeration="DesiredState", optional, tag="5")]
     pub desired_state: ::std::option::Option<i32>,
+    /// Set when the Supervisor rolled the service back to its current
+    /// package after an update to this release failed.
+    #[prost(message, optional, tag="6")]
+    pub rolled_back_from: ::std::option::Option<PackageIdent>,
+    /// Set when an update has been found but is being held until the
+    /// service's update window opens.
+    #[prost(message, optional, tag="7")]
+    pub pending_update: ::std::option::Option<PendingUpdate>,
+    /// Set when the service's process keeps exiting shortly after being
+    /// restarted.
+    #[prost(bool, optional, tag="8")]
+    pub crash_looping: ::std::option::Option<bool>,
+    /// How many times the service's process has been restarted within
+    /// the current restart window.
+    #[prost(uint32, optional, tag="9")]
+    pub restarts: ::std::option::Option<u32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PendingUpdate {
+    #[prost(message, required, tag="1")]
+    pub ident: PackageIdent,
+    /// When the update window next opens, in seconds since the epoch.
+    #[prost(uint64, optional, tag="2")]
+    pub until: ::std::option::Option<u64>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 #[derive(Serialize, Deserialize)]
@@ -94,6 +120,39 @@ pub struct HealthCheckInterval {
     #[prost(uint64, required, tag="1")]
     pub seconds: u64,
 }
+/// Limits on the resources a service's processes may use. Only
+/// enforced on Linux.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ResourceLimits {
+    /// In thousandths of a CPU.
+    #[prost(uint32, optional, tag="1")]
+    pub cpu_millis: ::std::option::Option<u32>,
+    #[prost(uint64, optional, tag="2")]
+    pub memory_bytes: ::std::option::Option<u64>,
+    #[prost(uint64, optional, tag="3")]
+    pub pids: ::std::option::Option<u64>,
+}
+/// A health check the Supervisor performs itself, in place of a
+/// `health-check` hook.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HealthCheck {
+    /// One of `http://HOST:PORT/PATH`, `tcp://HOST:PORT` or `exec:COMMAND`.
+    #[prost(string, required, tag="1")]
+    pub probe: std::string::String,
+    /// For HTTP probes, the status the response must have.
+    #[prost(uint32, optional, tag="2")]
+    pub status: ::std::option::Option<u32>,
+    /// For HTTP probes, text the response body must contain.
+    #[prost(string, optional, tag="3")]
+    pub body: ::std::option::Option<std::string::String>,
+    /// How long the probe may take, in seconds.
+    #[prost(uint64, optional, tag="4")]
+    pub timeout: ::std::option::Option<u64>,
+}
 /// Encapsulate all possible sources we can install packages from.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -141,6 +200,9 @@ pub enum UpdateStrategy {
     None = 0,
     AtOnce = 1,
     Rolling = 2,
+    /// One elected member updates first and soaks the release before the
+    /// rest of the group follows.
+    Canary = 3,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -152,3 +214,14 @@ pub enum BindingMode {
     /// Service start-up is blocked until all binds are available
     Strict = 1,
 }
+/// When the Supervisor restarts a service whose process has exited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    Always = 0,
+    /// Only restart a process that exited unsuccessfully
+    OnFailure = 1,
+    Never = 2,
+}
@@ -204,6 +204,47 @@ impl From<core::service::HealthCheckInterval> for HealthCheckInterval {
     fn from(h: core::service::HealthCheckInterval) -> Self { Self { seconds: h.into() } }
 }
 
+impl From<core::service::ResourceLimits> for ResourceLimits {
+    fn from(limits: core::service::ResourceLimits) -> Self {
+        ResourceLimits { cpu_millis:   limits.cpu_millis,
+                         memory_bytes: limits.memory_bytes,
+                         pids:         limits.pids, }
+    }
+}
+
+impl Into<core::service::ResourceLimits> for ResourceLimits {
+    fn into(self) -> core::service::ResourceLimits {
+        core::service::ResourceLimits { cpu_millis:   self.cpu_millis,
+                                        memory_bytes: self.memory_bytes,
+                                        pids:         self.pids, }
+    }
+}
+
+impl From<core::service::HealthCheck> for HealthCheck {
+    fn from(check: core::service::HealthCheck) -> Self {
+        HealthCheck { probe:   check.probe.to_string(),
+                      status:  check.status.map(u32::from),
+                      body:    check.body,
+                      timeout: Some(check.timeout), }
+    }
+}
+
+impl HealthCheck {
+    /// The health check this describes, failing if its probe, status
+    /// or timeout is invalid.
+    pub fn parse(&self) -> core::Result<core::service::HealthCheck> {
+        let mut check = core::service::HealthCheck::new(self.probe.parse()?);
+        if let Some(status) = self.status {
+            check.status = Some(core::service::parse_health_check_status(&status.to_string())?);
+        }
+        check.body = self.body.clone();
+        if let Some(timeout) = self.timeout {
+            check.timeout = core::service::validate_health_check_timeout(timeout)?;
+        }
+        Ok(check)
+    }
+}
+
 impl From<package::PackageIdent> for PackageIdent {
     fn from(ident: package::PackageIdent) -> Self {
         Self { origin:  ident.origin,
@@ -317,6 +358,7 @@ impl UpdateStrategy {
             UpdateStrategy::None => "none",
             UpdateStrategy::AtOnce => "at-once",
             UpdateStrategy::Rolling => "rolling",
+            UpdateStrategy::Canary => "canary",
         }
     }
 }
@@ -329,6 +371,7 @@ impl FromStr for UpdateStrategy {
             "none" => Ok(UpdateStrategy::None),
             "at-once" => Ok(UpdateStrategy::AtOnce),
             "rolling" => Ok(UpdateStrategy::Rolling),
+            "canary" => Ok(UpdateStrategy::Canary),
             _ => Err(net::err(ErrCode::InvalidPayload, "Invalid update strategy.")),
         }
     }
@@ -338,6 +381,33 @@ impl fmt::Display for UpdateStrategy {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.as_str()) }
 }
 
+impl RestartPolicy {
+    fn as_str(&self) -> &str {
+        match *self {
+            RestartPolicy::Always => "always",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::Never => "never",
+        }
+    }
+}
+
+impl FromStr for RestartPolicy {
+    type Err = NetErr;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "always" => Ok(RestartPolicy::Always),
+            "on-failure" => Ok(RestartPolicy::OnFailure),
+            "never" => Ok(RestartPolicy::Never),
+            _ => Err(net::err(ErrCode::InvalidPayload, "Invalid restart policy.")),
+        }
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.as_str()) }
+}
+
 #[cfg(test)]
 mod test {
     use toml;
@@ -414,6 +484,14 @@ mod test {
         assert_eq!(strategy, UpdateStrategy::AtOnce);
     }
 
+    #[test]
+    fn update_strategy_canary_round_trips_through_str() {
+        let strategy = UpdateStrategy::from_str("canary").unwrap();
+
+        assert_eq!(strategy, UpdateStrategy::Canary);
+        assert_eq!("canary", strategy.to_string());
+    }
+
     #[test]
     fn update_strategy_from_str_invalid() {
         let strategy_str = "dope";
@@ -421,6 +499,23 @@ mod test {
         assert!(UpdateStrategy::from_str(strategy_str).is_err());
     }
 
+    #[test]
+    fn restart_policy_default() {
+        assert_eq!(RestartPolicy::default(), RestartPolicy::Always);
+    }
+
+    #[test]
+    fn restart_policy_round_trips_through_str() {
+        for policy in &[RestartPolicy::Always,
+                        RestartPolicy::OnFailure,
+                        RestartPolicy::Never]
+        {
+            assert_eq!(RestartPolicy::from_str(&policy.to_string()).unwrap(),
+                       *policy);
+        }
+        assert!(RestartPolicy::from_str("sometimes").is_err());
+    }
+
     #[test]
     fn update_strategy_to_string() {
         let strategy = UpdateStrategy::AtOnce;
@@ -50,6 +50,21 @@ fn raml2html_cmd(dst: PathBuf, src: PathBuf) -> io::Result<ExitStatus> {
 
 fn generate_event_protobufs() {
     let mut config = prost_build::Config::new();
+    // Events are also served as JSON by the HTTP gateway. Timestamps, durations and
+    // enumerations are serialized by functions in `event/types.rs`, where the generated code is
+    // included.
+    config.type_attribute(".", "#[derive(Serialize)]");
+    for (field, serializer) in &[("EventMetadata.occurred_at", "serialize_timestamp"),
+                                 ("HealthCheckEvent.execution", "serialize_duration"),
+                                 ("HealthCheckEvent.result", "serialize_health_check_result"),
+                                 ("HookExecutedEvent.execution", "serialize_duration"),
+                                 ("UpdateConfig.strategy", "serialize_update_strategy"),
+                                 ("AuditEvent.outcome", "serialize_audit_outcome"),
+                                 ("MemberHealthChangedEvent.health", "serialize_member_health")]
+    {
+        config.field_attribute(format!(".chef.habitat.supervisor.event.{}", field),
+                               format!("#[serde(serialize_with = \"{}\")]", serializer));
+    }
     config.compile_protos(&["protocols/event.proto"], &["protocols/"])
           .unwrap()
 }
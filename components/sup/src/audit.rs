@@ -0,0 +1,261 @@
+//! The Supervisor's audit log: a record of every change made to the Supervisor through the
+//! CtlGateway, and of every service configuration or file it applies from gossip.
+//!
+//! Records are appended as JSON lines to `audit.log` in the Supervisor's `audit` directory,
+//! which is rotated like the services' log files. They may also be published on the event
+//! stream.
+
+use crate::event;
+use habitat_common::service_log::{LogConfig,
+                                  LogFile,
+                                  LogFormat};
+use habitat_core::service::ServiceGroup;
+use serde_json;
+use std::{fmt,
+          io,
+          net::SocketAddr,
+          path::Path,
+          sync::{Arc,
+                 Mutex}};
+
+pub const AUDIT_LOG_FILE_NAME: &str = "audit.log";
+/// Recorded as the client of a request whose address could not be determined.
+const UNKNOWN_CLIENT: &str = "unknown";
+
+/// A handle to the audit log, shared by the gateways and services that record changes to the
+/// Supervisor.
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    file:    Arc<Mutex<LogFile>>,
+    /// Whether records are also published on the event stream.
+    publish: bool,
+}
+
+impl AuditLog {
+    /// Open the audit log in `dir` for appending, rotating it according to `config`.
+    pub fn open<P>(dir: P, config: LogConfig, publish: bool) -> io::Result<Self>
+        where P: AsRef<Path>
+    {
+        let config = LogConfig { format: LogFormat::Json,
+                                 ..config };
+        let file = LogFile::open(dir, AUDIT_LOG_FILE_NAME, config)?;
+        Ok(AuditLog { file: Arc::new(Mutex::new(file)),
+                      publish })
+    }
+
+    /// Append a record to the audit log.
+    pub fn record(&self, record: &AuditRecord) {
+        let result = self.file
+                         .lock()
+                         .expect("Audit log lock is poisoned")
+                         .write_entry(&record.to_json_line());
+        if let Err(e) = result {
+            error!("Unable to write audit record {:?}: {}", record, e);
+        }
+        if self.publish {
+            event::audit(record);
+        }
+    }
+}
+
+/// Where a change to the Supervisor came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// A client of the CtlGateway, such as `hab svc load`.
+    Ctl,
+    /// A rumor received from another Supervisor.
+    Gossip,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match *self {
+            Source::Ctl => "ctl",
+            Source::Gossip => "gossip",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Succeeded,
+    Failed,
+    /// The client's credential does not allow the request.
+    Denied,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditRecord {
+    pub timestamp:  String,
+    pub source:     Source,
+    /// The address of the CtlGateway client, or the ID of the member that sent the rumor.
+    pub client:     String,
+    /// The name of the credential the CtlGateway client authenticated with, or of the user key
+    /// the rumor was signed with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential: Option<String>,
+    /// The CtlGateway message, or the kind of rumor.
+    pub request:    String,
+    /// The service group the request acts on. Requests which name a service by its package
+    /// identifier record that instead, and departures record the departed member's ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target:     Option<String>,
+    pub outcome:    Outcome,
+    /// Why the request failed, or what the rumor changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail:     Option<String>,
+}
+
+impl AuditRecord {
+    /// A record of a request made through the CtlGateway, or through the HTTP gateway's write
+    /// endpoints. The client is recorded as `unknown` if its address could not be determined.
+    pub fn ctl(client: Option<SocketAddr>,
+               credential: &str,
+               request: &str,
+               target: Option<String>,
+               outcome: Outcome,
+               detail: Option<String>)
+               -> Self {
+        AuditRecord { timestamp: now(),
+                      source: Source::Ctl,
+                      client: client.map_or_else(|| UNKNOWN_CLIENT.to_string(),
+                                                 |addr| addr.to_string()),
+                      credential: Some(credential.to_string()),
+                      request: request.to_string(),
+                      target,
+                      outcome,
+                      detail }
+    }
+
+    /// A record of a rumor from `member_id`, signed by `author` if authors are verified, and
+    /// whether it could be applied to a service.
+    pub fn gossip(member_id: &str,
+                  author: Option<&str>,
+                  request: &str,
+                  service_group: &ServiceGroup,
+                  outcome: Outcome,
+                  detail: String)
+                  -> Self {
+        AuditRecord { timestamp: now(),
+                      source: Source::Gossip,
+                      client: member_id.to_string(),
+                      credential: author.map(str::to_string),
+                      request: request.to_string(),
+                      target: Some(service_group.to_string()),
+                      outcome,
+                      detail: Some(detail) }
+    }
+
+    fn to_json_line(&self) -> String {
+        // unwrap is safe, as an `AuditRecord` is always serializable
+        format!("{}\n", serde_json::to_string(self).unwrap())
+    }
+}
+
+fn now() -> String { time_crate::now_utc().rfc3339().to_string() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs,
+              str::FromStr};
+    use tempfile::TempDir;
+
+    #[test]
+    fn records_are_appended_to_the_audit_log() {
+        let dir = TempDir::new().unwrap();
+        let audit_log = AuditLog::open(dir.path(), LogConfig::default(), false).unwrap();
+        let record = |request: &str| {
+            AuditRecord::ctl(Some("10.0.0.1:4567".parse().unwrap()),
+                             "default",
+                             request,
+                             None,
+                             Outcome::Succeeded,
+                             None)
+        };
+        audit_log.record(&record("SvcStop"));
+        audit_log.clone().record(&record("SvcStart"));
+
+        let contents = fs::read_to_string(dir.path().join(AUDIT_LOG_FILE_NAME)).unwrap();
+        let requests: Vec<String> = contents.lines()
+                                            .map(|line| {
+                                                let json: serde_json::Value =
+                                                    serde_json::from_str(line).unwrap();
+                                                json["request"].as_str().unwrap().to_string()
+                                            })
+                                            .collect();
+        assert_eq!(requests, vec!["SvcStop", "SvcStart"]);
+    }
+
+    #[test]
+    fn ctl_records_include_the_credential() {
+        let record = AuditRecord::ctl(Some("10.0.0.1:4567".parse().unwrap()),
+                                      "oncall",
+                                      "SvcStop",
+                                      Some("core/redis".to_string()),
+                                      Outcome::Succeeded,
+                                      None);
+        let json: serde_json::Value = serde_json::from_str(&record.to_json_line()).unwrap();
+
+        assert_eq!(json["source"], "ctl");
+        assert_eq!(json["client"], "10.0.0.1:4567");
+        assert_eq!(json["credential"], "oncall");
+        assert_eq!(json["request"], "SvcStop");
+        assert_eq!(json["target"], "core/redis");
+        assert_eq!(json["outcome"], "succeeded");
+        assert!(json.get("detail").is_none());
+    }
+
+    #[test]
+    fn ctl_records_without_an_address_name_an_unknown_client() {
+        let record = AuditRecord::ctl(None, "http", "SvcStop", None, Outcome::Succeeded, None);
+        let json: serde_json::Value = serde_json::from_str(&record.to_json_line()).unwrap();
+
+        assert_eq!(json["client"], "unknown");
+    }
+
+    #[test]
+    fn gossip_records_name_the_sending_member() {
+        let service_group = ServiceGroup::from_str("redis.default").unwrap();
+        let record = AuditRecord::gossip("abc123",
+                                         None,
+                                         "ServiceConfig",
+                                         &service_group,
+                                         Outcome::Succeeded,
+                                         "incarnation 4".into());
+        let json: serde_json::Value = serde_json::from_str(&record.to_json_line()).unwrap();
+
+        assert_eq!(json["source"], "gossip");
+        assert_eq!(json["client"], "abc123");
+        assert!(json.get("credential").is_none());
+        assert_eq!(json["target"], "redis.default");
+        assert_eq!(json["detail"], "incarnation 4");
+
+        let record = AuditRecord::gossip("abc123",
+                                         Some("alice-20190101000000"),
+                                         "ServiceConfig",
+                                         &service_group,
+                                         Outcome::Failed,
+                                         "incarnation 5".into());
+        let json: serde_json::Value = serde_json::from_str(&record.to_json_line()).unwrap();
+        assert_eq!(json["credential"], "alice-20190101000000");
+        assert_eq!(json["outcome"], "failed");
+    }
+
+    #[test]
+    fn records_are_single_lines() {
+        let record = AuditRecord::ctl(Some("10.0.0.1:4567".parse().unwrap()),
+                                      "default",
+                                      "SvcLoad",
+                                      None,
+                                      Outcome::Failed,
+                                      Some("first line\nsecond line".to_string()));
+        let line = record.to_json_line();
+
+        assert_eq!(line.lines().count(), 1);
+        assert!(line.ends_with('\n'));
+    }
+}
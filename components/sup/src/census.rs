@@ -1,20 +1,30 @@
-use crate::error::{Error,
-                   SupError};
-use habitat_butterfly::{member::{Health,
+use crate::{error::{Error,
+                    SupError},
+            event};
+use habitat_butterfly::{error::{Error as ButterflyError,
+                                Result as ButterflyResult},
+                        member::{Health,
                                  Member,
                                  MemberList},
                         rumor::{election::{Election as ElectionRumor,
                                            ElectionStatus as ElectionStatusRumor,
                                            ElectionUpdate as ElectionUpdateRumor},
-                                service::{Service as ServiceRumor,
+                                service::{CanaryRelease,
+                                          CanaryStatus,
+                                          Service as ServiceRumor,
                                           SysInfo},
                                 service_config::ServiceConfig as ServiceConfigRumor,
                                 service_file::ServiceFile as ServiceFileRumor,
-                                RumorStore}};
+                                Author,
+                                RumorStore},
+                        server::AuthorCheck};
 use habitat_common::outputln;
 use habitat_core::{self,
+                   crypto::SigKeyPair,
                    package::PackageIdent,
                    service::ServiceGroup};
+use habitat_sup_protocol::butterfly::{service_config_signed_bytes,
+                                      service_file_signed_bytes};
 use serde::{ser::SerializeStruct,
             Serialize,
             Serializer};
@@ -43,6 +53,11 @@ pub struct CensusRing {
     last_membership_counter: usize,
     last_service_config_counter: usize,
     last_service_file_counter: usize,
+    /// The last known health of every member of the ring, so that
+    /// changes in it can be announced. A snapshot announces nothing,
+    /// and so has none.
+    #[serde(skip)]
+    member_health: Option<HashMap<MemberId, Health>>,
 }
 
 impl CensusRing {
@@ -61,12 +76,25 @@ impl CensusRing {
                      last_election_update_counter: 0,
                      last_membership_counter: 0,
                      last_service_config_counter: 0,
-                     last_service_file_counter: 0, }
+                     last_service_file_counter: 0,
+                     member_health: Some(HashMap::new()), }
+    }
+
+    /// A census of the ring as it stands right now, for looking at
+    /// rather than keeping up to date, e.g., to render a configuration
+    /// dry run. Unlike the `Manager`'s census, it publishes no events
+    /// when it is populated.
+    pub fn snapshot<I>(local_member_id: I) -> Self
+        where I: Into<MemberId>
+    {
+        CensusRing { member_health: None,
+                     ..Self::new(local_member_id) }
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn update_from_rumors(&mut self,
                               cache_key_path: &Path,
+                              author_trust: Option<&AuthorTrust>,
                               service_rumors: &RumorStore<ServiceRumor>,
                               election_rumors: &RumorStore<ElectionRumor>,
                               election_update_rumors: &RumorStore<ElectionUpdateRumor>,
@@ -87,8 +115,8 @@ impl CensusRing {
             self.populate_census(service_rumors, member_list);
             self.update_from_election_store(election_rumors);
             self.update_from_election_update_store(election_update_rumors);
-            self.update_from_service_config(cache_key_path, service_config_rumors);
-            self.update_from_service_files(cache_key_path, service_file_rumors);
+            self.update_from_service_config(cache_key_path, author_trust, service_config_rumors);
+            self.update_from_service_files(cache_key_path, author_trust, service_file_rumors);
 
             // Update our counters to reflect current state.
             self.last_membership_counter = member_list.get_update_counter();
@@ -137,9 +165,16 @@ impl CensusRing {
                           }
                       });
 
+        let census_groups = &mut self.census_groups;
+        let member_health = &mut self.member_health;
         member_list.with_members(|member| {
                        let health = member_list.health_of(&member).unwrap();
-                       for group in self.census_groups.values_mut() {
+                       if let Some(member_health) = member_health.as_mut() {
+                           if member_health.insert(member.id.clone(), health) != Some(health) {
+                               event::member_health_changed(&member.id, health);
+                           }
+                       }
+                       for group in census_groups.values_mut() {
                            if let Some(census_member) = group.find_member_mut(&member.id) {
                                census_member.update_from_member(&member);
                                census_member.update_from_health(health);
@@ -174,12 +209,14 @@ impl CensusRing {
 
     fn update_from_service_config(&mut self,
                                   cache_key_path: &Path,
+                                  author_trust: Option<&AuthorTrust>,
                                   service_config_rumors: &RumorStore<ServiceConfigRumor>) {
         service_config_rumors.with_keys(|(service_group, rumors)| {
             if let Ok(sg) = service_group_from_str(service_group) {
                 if let Some(service_config) = rumors.get("service_config") {
                     if let Some(census_group) = self.census_groups.get_mut(&sg) {
                         census_group.update_from_service_config_rumor(cache_key_path,
+                                                                      author_trust,
                                                                       service_config);
                     }
                 }
@@ -189,6 +226,7 @@ impl CensusRing {
 
     fn update_from_service_files(&mut self,
                                  cache_key_path: &Path,
+                                 author_trust: Option<&AuthorTrust>,
                                  service_file_rumors: &RumorStore<ServiceFileRumor>) {
         service_file_rumors.with_keys(|(service_group, rumors)| {
                                if let Ok(sg) = service_group_from_str(service_group) {
@@ -198,6 +236,7 @@ impl CensusRing {
                     .entry(sg.clone())
                     .or_insert_with(|| CensusGroup::new(sg, &local_member_id));
                                    census_group.update_from_service_file_rumors(cache_key_path,
+                                                                                author_trust,
                                                                                 rumors);
                                }
                            });
@@ -288,12 +327,87 @@ pub struct ServiceFile {
     pub filename:    String,
     pub incarnation: u64,
     pub body:        Vec<u8>,
+    /// The member whose rumor this file came from.
+    #[serde(skip_serializing)]
+    pub from_id:     MemberId,
+    /// The trusted user key this file was signed with. Only set when authors are verified, which
+    /// happens before the rumor is stored.
+    pub author:      Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ServiceConfig {
     pub incarnation: u64,
     pub value:       toml::value::Table,
+    /// The member whose rumor this configuration came from.
+    #[serde(skip_serializing)]
+    pub from_id:     MemberId,
+    /// The trusted user key this configuration was signed with. Only set when authors are
+    /// verified, which happens before the rumor is stored.
+    pub author:      Option<String>,
+}
+
+/// The user keys whose signed configuration and files are applied. Without one, configuration
+/// and files are applied whether or not they are signed.
+#[derive(Clone)]
+pub struct AuthorTrust(Vec<SigKeyPair>);
+
+impl AuthorTrust {
+    pub fn new(keys: Vec<SigKeyPair>) -> Self { AuthorTrust(keys) }
+
+    /// Check that `signed` was signed by `author` with a trusted user key, returning the name of
+    /// that key.
+    pub fn verify(&self, author: Option<&Author>, signed: &[u8]) -> Result<String, SupError> {
+        let author = author.ok_or_else(|| {
+                               sup_error!(Error::UntrustedAuthor("it is not signed".to_string()))
+                           })?;
+        let key = self.0
+                      .iter()
+                      .find(|key| key.name_with_rev() == author.signer)
+                      .ok_or_else(|| {
+                          sup_error!(Error::UntrustedAuthor(format!("{} is not trusted",
+                                                                    author.signer)))
+                      })?;
+        key.verify_detached(signed, &author.signature)
+           .map_err(|e| {
+               sup_error!(Error::UntrustedAuthor(format!("bad signature by {}: {}",
+                                                         author.signer, e)))
+           })?;
+        Ok(author.signer.clone())
+    }
+}
+
+impl AuthorCheck for AuthorTrust {
+    fn check_service_config(&self, service_config: &ServiceConfigRumor) -> ButterflyResult<()> {
+        let signed = service_config_signed_bytes(&service_config.service_group,
+                                                 service_config.incarnation,
+                                                 service_config.encrypted,
+                                                 &service_config.config);
+        self.verify(service_config.author.as_ref(), &signed)
+            .map(|_| ())
+            .map_err(|e| ButterflyError::UntrustedAuthor(e.to_string()))
+    }
+
+    fn check_service_file(&self, service_file: &ServiceFileRumor) -> ButterflyResult<()> {
+        let signed = service_file_signed_bytes(&service_file.service_group,
+                                               &service_file.filename,
+                                               service_file.incarnation,
+                                               service_file.encrypted,
+                                               &service_file.body);
+        self.verify(service_file.author.as_ref(), &signed)
+            .map(|_| ())
+            .map_err(|e| ButterflyError::UntrustedAuthor(e.to_string()))
+    }
+}
+
+impl fmt::Debug for AuthorTrust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.0
+                        .iter()
+                        .map(SigKeyPair::name_with_rev)
+                        .collect::<Vec<_>>();
+        write!(f, "AuthorTrust({})", names.join(", "))
+    }
 }
 
 #[derive(Debug)]
@@ -355,6 +469,18 @@ impl CensusGroup {
             .filter(|cm| cm.alive() || cm.suspect())
     }
 
+    /// Returns every release that a member of this group has run as a
+    /// canary and found to be unhealthy. Releases in this set must not
+    /// be rolled out to the rest of the group.
+    pub fn rejected_releases(&self) -> HashSet<PackageIdent> {
+        self.population
+            .values()
+            .filter_map(CensusMember::canary)
+            .filter(|canary| canary.status == CanaryStatus::Failed)
+            .filter_map(|canary| PackageIdent::from_str(&canary.pkg).ok())
+            .collect()
+    }
+
     pub fn changed_service_files(&self) -> Vec<&ServiceFile> {
         self.changed_service_files
             .iter()
@@ -446,16 +572,23 @@ impl CensusGroup {
 
     fn update_from_service_config_rumor(&mut self,
                                         cache_key_path: &Path,
+                                        author_trust: Option<&AuthorTrust>,
                                         service_config: &ServiceConfigRumor) {
+        if let Some(ref current) = self.service_config {
+            if service_config.incarnation <= current.incarnation {
+                return;
+            }
+        }
+        // The gossip layer only stores configuration from trusted authors
+        let author = author_trust.and(service_config.author.as_ref())
+                                 .map(|author| author.signer.clone());
         match service_config.config(cache_key_path) {
             Ok(config) => {
-                if self.service_config.is_none()
-                   || service_config.incarnation > self.service_config.as_ref().unwrap().incarnation
-                {
-                    self.service_config = Some(ServiceConfig { incarnation:
-                                                                   service_config.incarnation,
-                                                               value:       config, });
-                }
+                self.service_config =
+                    Some(ServiceConfig { incarnation: service_config.incarnation,
+                                         value: config,
+                                         from_id: service_config.from_id.clone(),
+                                         author });
             }
             Err(err) => warn!("{}", err),
         }
@@ -463,6 +596,7 @@ impl CensusGroup {
 
     fn update_from_service_file_rumors(&mut self,
                                        cache_key_path: &Path,
+                                       author_trust: Option<&AuthorTrust>,
                                        service_file_rumors: &HashMap<String, ServiceFileRumor>)
     {
         self.changed_service_files.clear();
@@ -473,12 +607,17 @@ impl CensusGroup {
                            .or_insert_with(ServiceFile::default);
 
             if service_file_rumor.incarnation > file.incarnation {
+                // The gossip layer only stores files from trusted authors
+                let author = author_trust.and(service_file_rumor.author.as_ref())
+                                         .map(|author| author.signer.clone());
                 match service_file_rumor.body(cache_key_path) {
                     Ok(body) => {
                         self.changed_service_files.push(filename.clone());
                         file.filename = filename.clone();
                         file.incarnation = service_file_rumor.incarnation;
                         file.body = body;
+                        file.from_id = service_file_rumor.from_id.clone();
+                        file.author = author;
                     }
                     Err(e) => {
                         warn!("Cannot decrypt service file for {} {} {}: {}",
@@ -571,6 +710,8 @@ pub struct CensusMember {
     suspect:   bool,
     confirmed: bool,
     departed:  bool,
+    ready:     bool,
+    canary:    Option<CanaryRelease>,
     // Maps must be represented last in a serializable struct for the current version of the toml
     // crate. Additionally, this deserialization method is required to correct any ordering issues
     // with the table being serialized - https://docs.rs/toml/0.4.0/toml/ser/fn.tables_last.html
@@ -595,6 +736,8 @@ impl CensusMember {
             Err(err) => warn!("Received a bad package ident from gossip data, err={}", err),
         };
         self.sys = rumor.sys.clone();
+        self.canary = rumor.canary.clone();
+        self.ready = rumor.ready;
         self.cfg = toml::from_slice(&rumor.cfg).unwrap_or_default();
     }
 
@@ -657,6 +800,15 @@ impl CensusMember {
     pub fn confirmed(&self) -> bool { self.confirmed }
 
     pub fn departed(&self) -> bool { self.departed }
+
+    /// Is this member's service ready to receive traffic? This is
+    /// reported by the member itself, independently of whether the
+    /// ring considers it alive.
+    pub fn ready(&self) -> bool { self.ready }
+
+    /// The outcome of the most recent canary update this member ran,
+    /// if any.
+    pub fn canary(&self) -> Option<&CanaryRelease> { self.canary.as_ref() }
 }
 
 /// This data structure just wraps the CensusMember and allows us to tweak the serialization logic.
@@ -670,7 +822,7 @@ impl<'a> Serialize for CensusMemberProxy<'a> {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
         where S: Serializer
     {
-        let mut strukt = serializer.serialize_struct("census_member", 24)?;
+        let mut strukt = serializer.serialize_struct("census_member", 25)?;
         strukt.serialize_field("member_id", &self.0.member_id)?;
         strukt.serialize_field("pkg", &self.0.pkg)?;
 
@@ -704,6 +856,7 @@ impl<'a> Serialize for CensusMemberProxy<'a> {
         strukt.serialize_field("suspect", &self.0.suspect)?;
         strukt.serialize_field("confirmed", &self.0.confirmed)?;
         strukt.serialize_field("departed", &self.0.departed)?;
+        strukt.serialize_field("ready", &self.0.ready)?;
         strukt.serialize_field("cfg", &self.0.cfg)?;
         strukt.end()
     }
@@ -727,13 +880,17 @@ mod tests {
                             rumor::{election::{self,
                                                Election as ElectionRumor,
                                                ElectionUpdate as ElectionUpdateRumor},
-                                    service::{Service as ServiceRumor,
+                                    service::{CanaryRelease,
+                                              CanaryStatus,
+                                              Service as ServiceRumor,
                                               SysInfo},
                                     service_config::ServiceConfig as ServiceConfigRumor,
                                     service_file::ServiceFile as ServiceFileRumor,
+                                    Author,
                                     RumorStore}};
     use habitat_common::cli::FS_ROOT;
-    use habitat_core::{fs::cache_key_path,
+    use habitat_core::{crypto::SigKeyPair,
+                       fs::cache_key_path,
                        package::ident::PackageIdent,
                        service::ServiceGroup};
     use serde_json;
@@ -757,6 +914,50 @@ mod tests {
         assert_eq!(members.next().unwrap().member_id, "member-b");
     }
 
+    #[test]
+    fn service_config_is_only_accepted_when_signed_by_a_trusted_author() {
+        let sg = ServiceGroup::new(None, "shield", "default", None).unwrap();
+        let cache_key_path = cache_key_path(Some(&*FS_ROOT));
+        let alice = SigKeyPair::generate_pair_for_origin("alice").unwrap();
+        let mallory = SigKeyPair::generate_pair_for_origin("mallory").unwrap();
+        let trust = AuthorTrust::new(vec![alice.clone()]);
+        let mut rumor = ServiceConfigRumor::new("member-b", sg.clone(), b"port = 80".to_vec());
+        rumor.incarnation = 1;
+        let signed = service_config_signed_bytes(&sg, 1, false, &rumor.config);
+
+        assert!(trust.check_service_config(&rumor).is_err());
+
+        rumor.author = Some(Author::new(mallory.name_with_rev(),
+                                        mallory.sign_detached(&signed).unwrap()));
+        assert!(trust.check_service_config(&rumor).is_err());
+
+        // A signature only covers the version it was made for
+        rumor.incarnation = 2;
+        rumor.author =
+            Some(Author::new(alice.name_with_rev(), alice.sign_detached(&signed).unwrap()));
+        assert!(trust.check_service_config(&rumor).is_err());
+
+        rumor.incarnation = 1;
+        trust.check_service_config(&rumor)
+             .expect("Trusted configuration was rejected");
+
+        let mut group = CensusGroup::new(sg, "member-a");
+        group.update_from_service_config_rumor(&cache_key_path, Some(&trust), &rumor);
+        let config = group.service_config
+                          .expect("Trusted configuration was not applied");
+        assert_eq!(config.incarnation, 1);
+        assert_eq!(config.author, Some(alice.name_with_rev()));
+    }
+
+    #[test]
+    fn update_from_rumors_reports_readiness() {
+        let (ring, _, sg_two) = test_census_ring();
+        let census_group = ring.census_group_for(&sg_two).unwrap();
+        let mut members = census_group.members();
+        assert!(members.next().unwrap().ready());
+        assert!(!members.next().unwrap().ready());
+    }
+
     #[test]
     fn census_ring_proxy_conforms_to_the_schema() {
         let (ring, ..) = test_census_ring();
@@ -791,11 +992,12 @@ mod tests {
                                             sg_two.clone(),
                                             sys_info.clone(),
                                             None);
-        let service_three = ServiceRumor::new("member-a".to_string(),
-                                              &pg_id,
-                                              sg_two.clone(),
-                                              sys_info.clone(),
-                                              None);
+        let mut service_three = ServiceRumor::new("member-a".to_string(),
+                                                  &pg_id,
+                                                  sg_two.clone(),
+                                                  sys_info.clone(),
+                                                  None);
+        service_three.ready = true;
 
         service_store.insert(service_one);
         service_store.insert(service_two);
@@ -825,6 +1027,7 @@ mod tests {
         let service_file_store: RumorStore<ServiceFileRumor> = RumorStore::default();
         let mut ring = CensusRing::new("member-b".to_string());
         ring.update_from_rumors(&cache_key_path(Some(&*FS_ROOT)),
+                                None,
                                 &service_store,
                                 &election_store,
                                 &election_update_store,
@@ -860,6 +1063,8 @@ mod tests {
                        suspect: health == Health::Suspect,
                        confirmed: health == Health::Confirmed,
                        departed: health == Health::Departed,
+                       ready: false,
+                       canary: None,
                        cfg: toml::value::Table::new(), }
     }
 
@@ -886,6 +1091,31 @@ mod tests {
         assert!(active_members.next().is_none());
     }
 
+    #[test]
+    fn rejected_releases_only_includes_failed_canaries() {
+        let bad: PackageIdent = "core/redis/4.0.14/20190319155852".parse().unwrap();
+        let good: PackageIdent = "core/redis/4.0.14/20190401000000".parse().unwrap();
+
+        let mut failed = test_census_member("failed-one", Health::Alive);
+        failed.canary = Some(CanaryRelease::new(&bad, CanaryStatus::Failed));
+        let mut passed = test_census_member("passed-one", Health::Alive);
+        passed.canary = Some(CanaryRelease::new(&good, CanaryStatus::Passed));
+        let untried = test_census_member("untried-one", Health::Alive);
+
+        let sg: ServiceGroup =
+            "test-service.default".parse()
+                                  .expect("This should be a valid service group");
+        let mut census_group = CensusGroup::new(sg, &"failed-one".to_string());
+        for member in vec![failed, passed, untried] {
+            census_group.population
+                        .insert(member.member_id.clone(), member);
+        }
+
+        let rejected = census_group.rejected_releases();
+        assert_eq!(rejected.len(), 1);
+        assert!(rejected.contains(&bad));
+    }
+
     fn assert_eq_member_ids(cm: Option<&CensusMember>, id: Option<&str>) {
         assert_eq!(cm.map(|cm| cm.member_id.as_str()), id);
     }
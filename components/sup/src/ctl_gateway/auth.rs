@@ -0,0 +1,317 @@
+//! Authorization of CtlGateway requests.
+//!
+//! Every client authenticates with a secret during its handshake. The secret in `CTL_SECRET` is
+//! always accepted with the `admin` role. Additional named secrets, each with its own role, may
+//! be listed in a `CTL_CREDENTIALS` file in the Supervisor's root:
+//!
+//! ```toml
+//! [[credential]]
+//! name = "monitoring"
+//! secret = "..."
+//! role = "read-only"
+//! ```
+//!
+//! Each request is then checked against the role of the credential its connection
+//! authenticated with before being dispatched to the Manager.
+
+use crate::error::{Error,
+                   Result};
+use habitat_core::crypto;
+use std::{fmt,
+          fs,
+          io,
+          iter,
+          net::{IpAddr,
+                SocketAddr},
+          path::Path,
+          result};
+use toml;
+
+static LOGKEY: &'static str = "AG";
+
+const CTL_CREDENTIALS_FILENAME: &str = "CTL_CREDENTIALS";
+
+/// Name given to the credential read from `CTL_SECRET`.
+pub const DEFAULT_CREDENTIAL_NAME: &str = "default";
+
+/// What a credential is allowed to do. Each role may do everything the roles before it may do.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    /// May read service status, configuration and logs.
+    ReadOnly,
+    /// May also start and stop services, and dry-run configuration, which shows it as it would
+    /// be rendered.
+    Operator,
+    /// May also load and unload services, apply configuration and files, depart members, and
+    /// rotate the ring key.
+    Admin,
+}
+
+impl Role {
+    /// The role needed to send a message with the given ID. Messages this function does not
+    /// know about require `Admin`.
+    pub fn required_for(message_id: &str) -> Role {
+        match message_id {
+            "SvcGetDefaultCfg" | "SvcValidateCfg" | "SvcStatus" | "SvcLogs" | "SupDebug" => {
+                Role::ReadOnly
+            }
+            // A dry run renders the configuration against gossiped values, which may be secret.
+            "SvcStart" | "SvcStop" | "SvcDryRunCfg" => Role::Operator,
+            _ => Role::Admin,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match *self {
+            Role::ReadOnly => "read-only",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// Whether a message may only be sent by clients connecting from the Supervisor's own host,
+/// whatever their credential allows.
+pub fn local_only(message_id: &str) -> bool { message_id == "SupDebug" }
+
+/// Whether a client connected from the Supervisor's own host. Clients of a dual-stack listener
+/// connecting over IPv4 appear with IPv4-mapped IPv6 addresses, such as `::ffff:127.0.0.1`.
+pub fn is_local(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback(),
+        IpAddr::V6(ip) => ip.is_loopback() || ip.to_ipv4().map_or(false, |ip| ip.is_loopback()),
+    }
+}
+
+/// A named secret a client may authenticate with.
+#[derive(Clone, Deserialize)]
+pub struct Credential {
+    pub name: String,
+    secret:   String,
+    pub role: Role,
+}
+
+// Hand-written so that secrets never end up in logs.
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credential")
+         .field("name", &self.name)
+         .field("role", &self.role)
+         .finish()
+    }
+}
+
+impl Credential {
+    pub fn new<N, S>(name: N, secret: S, role: Role) -> Self
+        where N: Into<String>,
+              S: Into<String>
+    {
+        Credential { name: name.into(),
+                     secret: secret.into(),
+                     role }
+    }
+
+    /// Whether this credential may send a message with the given ID.
+    pub fn allows(&self, message_id: &str) -> bool { self.role >= Role::required_for(message_id) }
+}
+
+#[derive(Default, Deserialize)]
+struct CredentialsFile {
+    #[serde(default)]
+    credential: Vec<Credential>,
+}
+
+/// All credentials a client may authenticate with.
+#[derive(Clone, Debug)]
+pub struct Credentials(Vec<Credential>);
+
+impl Credentials {
+    /// The `admin` credential for `secret_key`, followed by any listed in the `CTL_CREDENTIALS`
+    /// file in `sup_root`.
+    pub fn load<T>(sup_root: T, secret_key: String) -> Result<Self>
+        where T: AsRef<Path>
+    {
+        let path = sup_root.as_ref().join(CTL_CREDENTIALS_FILENAME);
+        let file =
+            match fs::read_to_string(&path) {
+                Ok(contents) => toml::from_str(&contents).map_err(|e| {
+                                    sup_error!(Error::CtlCredentialsParse(path.clone(), e))
+                                })?,
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => CredentialsFile::default(),
+                Err(e) => return Err(sup_error!(Error::CtlSecretIo(path, e))),
+            };
+        Self::new(secret_key, file.credential).map_err(|e| {
+                                                  sup_error!(Error::CtlCredentialsInvalid(path, e))
+                                              })
+    }
+
+    /// Every secret must be non-empty, and no two credentials may share one, as a client's
+    /// secret alone decides which credential, and so which role, it is given.
+    fn new(secret_key: String, credentials: Vec<Credential>) -> result::Result<Self, String> {
+        let default = Credential::new(DEFAULT_CREDENTIAL_NAME, secret_key, Role::Admin);
+        let mut all = Vec::with_capacity(credentials.len() + 1);
+        for credential in iter::once(default).chain(credentials) {
+            if credential.secret.is_empty() {
+                return Err(format!("credential '{}' has an empty secret", credential.name));
+            }
+            if let Some(other) = all.iter()
+                                    .find(|c| crypto::secure_eq(&c.secret, &credential.secret))
+            {
+                return Err(format!("credentials '{}' and '{}' have the same secret",
+                                   other.name, credential.name));
+            }
+            all.push(credential);
+        }
+        Ok(Credentials(all))
+    }
+
+    /// The credential with the given secret, if any. An empty secret never authenticates.
+    pub fn authenticate(&self, secret: &str) -> Option<&Credential> {
+        if secret.is_empty() {
+            return None;
+        }
+        self.0.iter().find(|c| crypto::secure_eq(&c.secret, secret))
+    }
+}
+
+/// Record a client which failed to authenticate.
+pub fn audit_failed_handshake(peer: SocketAddr) {
+    outputln!("Denied connection from {}: secret key mismatch", peer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CREDENTIALS: &str = r#"
+[[credential]]
+name = "monitoring"
+secret = "watching"
+role = "read-only"
+
+[[credential]]
+name = "oncall"
+secret = "paged"
+role = "operator"
+"#;
+
+    #[test]
+    fn roles_are_ordered_by_privilege() {
+        assert!(Role::ReadOnly < Role::Operator);
+        assert!(Role::Operator < Role::Admin);
+    }
+
+    #[test]
+    fn each_role_allows_only_its_messages() {
+        let read_only = Credential::new("r", "r", Role::ReadOnly);
+        let operator = Credential::new("o", "o", Role::Operator);
+        let admin = Credential::new("a", "a", Role::Admin);
+
+        assert!(read_only.allows("SvcStatus"));
+        assert!(!read_only.allows("SvcStop"));
+        assert!(!read_only.allows("SvcDryRunCfg"));
+        assert!(operator.allows("SvcDryRunCfg"));
+        assert!(operator.allows("SvcStop"));
+        assert!(!operator.allows("SvcLoad"));
+        assert!(admin.allows("SupDepart"));
+        assert!(!operator.allows("SomeNewMessage"));
+    }
+
+    #[test]
+    fn only_debug_requests_are_local_only() {
+        assert!(local_only("SupDebug"));
+        assert!(!local_only("SvcStatus"));
+        assert!(!local_only("SupDepart"));
+    }
+
+    #[test]
+    fn loopback_clients_are_local_whatever_their_address_family() {
+        assert!(is_local("127.0.0.1".parse().unwrap()));
+        assert!(is_local("::1".parse().unwrap()));
+        assert!(is_local("::ffff:127.0.0.1".parse().unwrap()));
+        assert!(!is_local("10.0.0.1".parse().unwrap()));
+        assert!(!is_local("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(!is_local("2001:db8::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn load_without_a_credentials_file_only_accepts_the_secret_key() {
+        let tmpdir = TempDir::new().unwrap();
+        let credentials = Credentials::load(tmpdir.path(), "sekrit".to_string()).unwrap();
+
+        let credential = credentials.authenticate("sekrit").unwrap();
+        assert_eq!(credential.name, DEFAULT_CREDENTIAL_NAME);
+        assert_eq!(credential.role, Role::Admin);
+        assert!(credentials.authenticate("other").is_none());
+    }
+
+    #[test]
+    fn load_reads_the_credentials_file() {
+        let tmpdir = TempDir::new().unwrap();
+        fs::write(tmpdir.path().join(CTL_CREDENTIALS_FILENAME), CREDENTIALS).unwrap();
+        let credentials = Credentials::load(tmpdir.path(), "sekrit".to_string()).unwrap();
+
+        let credential = credentials.authenticate("watching").unwrap();
+        assert_eq!(credential.name, "monitoring");
+        assert_eq!(credential.role, Role::ReadOnly);
+        assert_eq!(credentials.authenticate("paged").unwrap().role,
+                   Role::Operator);
+        assert_eq!(credentials.authenticate("sekrit").unwrap().role,
+                   Role::Admin);
+    }
+
+    #[test]
+    fn load_rejects_unknown_roles() {
+        let tmpdir = TempDir::new().unwrap();
+        fs::write(tmpdir.path().join(CTL_CREDENTIALS_FILENAME),
+                  CREDENTIALS.replace("operator", "root")).unwrap();
+
+        assert!(Credentials::load(tmpdir.path(), "sekrit".to_string()).is_err());
+    }
+
+    #[test]
+    fn load_rejects_empty_secrets() {
+        let tmpdir = TempDir::new().unwrap();
+        fs::write(tmpdir.path().join(CTL_CREDENTIALS_FILENAME),
+                  CREDENTIALS.replace("\"paged\"", "\"\"")).unwrap();
+
+        assert!(Credentials::load(tmpdir.path(), "sekrit".to_string()).is_err());
+    }
+
+    #[test]
+    fn load_rejects_duplicate_secrets() {
+        let tmpdir = TempDir::new().unwrap();
+        let path = tmpdir.path().join(CTL_CREDENTIALS_FILENAME);
+        fs::write(&path, CREDENTIALS.replace("paged", "watching")).unwrap();
+        assert!(Credentials::load(tmpdir.path(), "sekrit".to_string()).is_err());
+
+        // Nor may a credential reuse the secret key, which is always an admin's
+        fs::write(&path, CREDENTIALS.replace("paged", "sekrit")).unwrap();
+        assert!(Credentials::load(tmpdir.path(), "sekrit".to_string()).is_err());
+    }
+
+    #[test]
+    fn load_rejects_an_empty_secret_key() {
+        let tmpdir = TempDir::new().unwrap();
+
+        assert!(Credentials::load(tmpdir.path(), String::new()).is_err());
+    }
+
+    #[test]
+    fn empty_secrets_never_authenticate() {
+        let credentials = Credentials(vec![Credential::new("empty", "", Role::Admin)]);
+
+        assert!(credentials.authenticate("").is_none());
+    }
+
+    #[test]
+    fn credential_debug_hides_the_secret() {
+        let credential = Credential::new("monitoring", "watching", Role::ReadOnly);
+        assert!(!format!("{:?}", credential).contains("watching"));
+    }
+}
@@ -7,6 +7,7 @@
 //! protocol defined in [`protocol.codec`].
 
 pub mod acceptor;
+pub mod auth;
 pub mod handler;
 pub mod server;
 
@@ -98,20 +99,35 @@ impl CtlRequest {
         self.send_msg(msg, true);
     }
 
+    /// Like `reply_partial`, but returns false once the client has gone away, so that requests
+    /// which keep replying, such as following a service's log, know when to stop.
+    pub fn try_reply_partial<T>(&mut self, msg: T) -> bool
+        where T: Into<habitat_sup_protocol::codec::SrvMessage> + fmt::Debug
+    {
+        self.send_msg(msg, false)
+    }
+
+    /// Returns true once the client's connection has been closed. Unlike `try_reply_partial`, this
+    /// doesn't need anything to send, so requests that may go quiet for a long time can still
+    /// stop when the client leaves.
+    pub fn client_gone(&self) -> bool { self.tx.as_ref().map_or(true, |tx| tx.is_closed()) }
+
     /// Returns true if the request is transactional and false if not.
     pub fn transactional(&self) -> bool { self.transaction.is_some() && self.tx.is_some() }
 
-    fn send_msg<T>(&mut self, msg: T, complete: bool)
+    /// Returns false if the message could not be sent.
+    fn send_msg<T>(&mut self, msg: T, complete: bool) -> bool
         where T: Into<habitat_sup_protocol::codec::SrvMessage> + fmt::Debug
     {
         if !self.transactional() {
             warn!("Attempted to reply to a non-transactional message with {:?}",
                   msg);
-            return;
+            return false;
         }
         let mut wire: habitat_sup_protocol::codec::SrvMessage = msg.into();
         wire.reply_for(self.transaction.unwrap(), complete);
-        self.tx.as_ref().unwrap().start_send(wire).ok(); // ignore Err return
+        // An error means the client's connection has been closed.
+        self.tx.as_ref().unwrap().start_send(wire).is_ok()
     }
 }
 
@@ -8,19 +8,27 @@
 //! mpsc channel, [`CtlSender`], to [`CtlReceiver`]. A new mpsc pair is created for each
 //! transactional request where the sending half is given to a [`ctl_gateway.CtlRequest`].
 
-use super::{CtlRequest,
+use super::{auth::{self,
+                   Credential,
+                   Credentials,
+                   Role},
+            CtlRequest,
             REQ_TIMEOUT};
-use crate::manager::{action::ActionSender,
-                     commands,
-                     ManagerState};
+use crate::{audit::{AuditLog,
+                    AuditRecord,
+                    Outcome},
+            manager::{action::ActionSender,
+                      commands,
+                      ManagerState}};
 use futures::{future::{self,
                        Either},
               prelude::*,
               sync::mpsc,
               try_ready};
-use habitat_core::crypto;
+use habitat_core::crypto::keys::parse_key_str;
 use habitat_sup_protocol::{self as protocol,
                            codec::{SrvCodec,
+                                   SrvIo,
                                    SrvMessage,
                                    SrvStream,
                                    SrvTxn},
@@ -32,18 +40,22 @@ use prometheus::{HistogramTimer,
                  HistogramVec,
                  IntCounterVec};
 use prost;
+use rustls::ServerConfig;
 use std::{cell::RefCell,
           error,
           fmt,
           io,
           net::SocketAddr,
           rc::Rc,
+          sync::Arc,
           thread,
           time::Duration};
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener,
+                 TcpStream};
 use tokio_codec::Decoder;
 use tokio_core::{reactor,
                  try_nb};
+use tokio_rustls::TlsAcceptor;
 
 lazy_static! {
     static ref RPC_CALLS: IntCounterVec = register_int_counter_vec!("hab_sup_rpc_call_total",
@@ -137,6 +149,17 @@ impl CtlCommand {
                      req: CtlRequest::new(tx, txn), }
     }
 
+    /// Create the command for a transactional message from a client, whose replies are sent to
+    /// `tx`.
+    pub fn from_message(msg: &SrvMessage,
+                        tx: CtlSender)
+                        -> std::result::Result<Self, HandlerError> {
+        SrvHandler::command_from_message(msg, tx)
+    }
+
+    /// The service group, package or member a message acts on, for the audit log.
+    pub fn audit_target(msg: &SrvMessage) -> Option<String> { SrvHandler::audit_target(msg) }
+
     /// Run the contained closure with the given [`manager.ManagerState`].
     pub fn run(&mut self, state: &ManagerState, action_sender: ActionSender) -> NetResult<()> {
         (self.fun)(state, &mut self.req, action_sender)
@@ -147,20 +170,29 @@ impl CtlCommand {
 struct Client {
     handle: reactor::Handle,
     state:  Rc<RefCell<SrvState>>,
+    addr:   SocketAddr,
 }
 
 impl Client {
     /// Serve the client from the given framed socket stream.
     pub fn serve(self, socket: SrvStream) -> impl Future<Item = (), Error = HandlerError> {
         let mgr_sender = self.state.borrow().mgr_sender.clone();
+        let audit_log = self.state.borrow().audit_log.clone();
+        let addr = self.addr;
         self.handshake(socket)
-            .and_then(|socket| SrvHandler::new(socket, mgr_sender))
+            .and_then(move |(socket, credential)| {
+                SrvHandler::new(socket, mgr_sender, audit_log, credential, addr)
+            })
     }
 
     /// Initiate a handshake with the connected client before allowing future requests. A failed
-    /// handshake will close the connection.
-    fn handshake(&self, socket: SrvStream) -> impl Future<Item = SrvStream, Error = HandlerError> {
-        let secret_key = self.state.borrow().secret_key.to_string();
+    /// handshake will close the connection. A successful one resolves to the credential the
+    /// client authenticated with.
+    fn handshake(&self,
+                 socket: SrvStream)
+                 -> impl Future<Item = (SrvStream, Credential), Error = HandlerError> {
+        let credentials = self.state.borrow().credentials.clone();
+        let addr = self.addr;
         let handshake = socket.into_future()
                               .map_err(|(err, _)| HandlerError::from(err))
                               .and_then(move |(m, io)| {
@@ -184,8 +216,12 @@ impl Client {
                             match m.parse::<protocol::ctl::Handshake>() {
                                 Ok(decoded) => {
                                     trace!("Received handshake, {:?}", decoded);
-                                    let decoded_key = decoded.secret_key.unwrap_or_default();
-                                    Ok((m, crypto::secure_eq(decoded_key, secret_key), io))
+                                    let credential =
+                                        decoded.secret_key
+                                               .and_then(|key| {
+                                                   credentials.authenticate(&key).cloned()
+                                               });
+                                    Ok((m, credential, io))
                                 }
                                 Err(err) => {
                                     warn!("Handshake error, {:?}", err);
@@ -198,22 +234,23 @@ impl Client {
                     },
                 )
                               })
-                              .and_then(|(msg, success, socket)| {
-                                  let mut reply = if success {
+                              .and_then(move |(msg, credential, socket)| {
+                                  let mut reply = if credential.is_some() {
                                       SrvMessage::from(net::ok())
                                   } else {
+                                      auth::audit_failed_handshake(addr);
                                       SrvMessage::from(net::err(ErrCode::Unauthorized,
                                                                 "secret key mismatch"))
                                   };
                                   reply.reply_for(msg.transaction().unwrap(), true);
                                   socket.send(reply)
                                         .map_err(HandlerError::from)
-                                        .and_then(move |io| Ok((io, success)))
+                                        .and_then(move |io| Ok((io, credential)))
                               });
         handshake.select2(self.timeout(REQ_TIMEOUT)).then(|res| {
                                                         match res {
-                Ok(Either::A(((io, true), _to))) => future::ok(io),
-                Ok(Either::A(((_, false), _to))) => future::err(HandlerError::from(
+                Ok(Either::A(((io, Some(credential)), _to))) => future::ok((io, credential)),
+                Ok(Either::A(((_, None), _to))) => future::err(HandlerError::from(
                     io::Error::new(io::ErrorKind::ConnectionAborted, "handshake failed"),
                 )),
                 Ok(Either::B((_to, _hs))) => future::err(HandlerError::from(io::Error::new(
@@ -242,10 +279,23 @@ struct SrvHandler {
     ctl_receiver: CtlReceiver,
     ctl_sender:   CtlSender,
     timer:        Option<HistogramTimer>,
+    /// The credential the client authenticated with, which decides what it may request.
+    credential:   Credential,
+    audit_log:    AuditLog,
+    /// The client's address, for the audit log.
+    addr:         SocketAddr,
+    /// The message ID and target of a mutating request awaiting its reply, which is recorded
+    /// in the audit log once the reply is complete.
+    audited:      Option<(String, Option<String>)>,
 }
 
 impl SrvHandler {
-    fn new(io: SrvStream, mgr_sender: MgrSender) -> Self {
+    fn new(io: SrvStream,
+           mgr_sender: MgrSender,
+           audit_log: AuditLog,
+           credential: Credential,
+           addr: SocketAddr)
+           -> Self {
         let (ctl_sender, ctl_receiver) = mpsc::unbounded();
 
         SrvHandler { io,
@@ -253,7 +303,153 @@ impl SrvHandler {
                      mgr_sender,
                      ctl_receiver,
                      ctl_sender,
-                     timer: None }
+                     timer: None,
+                     credential,
+                     audit_log,
+                     addr,
+                     audited: None }
+    }
+
+    /// Check that the client's credential allows it to send the given message, and that
+    /// local-only messages come from the Supervisor's own host. A denied transactional message
+    /// is answered with an `Unauthorized` error.
+    fn authorize(&self, msg: &SrvMessage) -> bool {
+        let denial = if !self.credential.allows(msg.message_id()) {
+            Some(format!("Credential '{}' with role {} may not send {}",
+                         self.credential.name,
+                         self.credential.role,
+                         msg.message_id()))
+        } else if auth::local_only(msg.message_id()) && !auth::is_local(self.addr.ip()) {
+            Some(format!("{} may only be sent from the Supervisor's own host",
+                         msg.message_id()))
+        } else {
+            None
+        };
+        let allowed = denial.is_none();
+        // Denied and mutating requests are recorded in the audit log; the rest are only polls.
+        if allowed && !Self::is_audited(msg) {
+            debug!("Allowed {} request from {} with credential '{}' ({})",
+                   msg.message_id(),
+                   self.addr,
+                   self.credential.name,
+                   self.credential.role);
+        }
+        if let Some(denial) = denial {
+            self.record(msg.message_id(),
+                        Self::audit_target(msg),
+                        Outcome::Denied,
+                        None);
+            if let Some(txn) = msg.transaction() {
+                let mut reply = SrvMessage::from(net::err(ErrCode::Unauthorized, denial));
+                reply.reply_for(txn, true);
+                // The reply is picked up by the `Sending` state like any other.
+                self.ctl_sender
+                    .unbounded_send(reply)
+                    .expect("SrvHandler holds both ends of its reply channel");
+            }
+        }
+        allowed
+    }
+
+    /// Whether a message belongs in the audit log: every message that changes the Supervisor
+    /// does, as does a configuration dry run, which shows more than a read-only credential may.
+    fn is_audited(msg: &SrvMessage) -> bool {
+        Role::required_for(msg.message_id()) > Role::ReadOnly
+    }
+
+    /// The service group, package or member a message acts on, for the audit log.
+    fn audit_target(msg: &SrvMessage) -> Option<String> {
+        match msg.message_id() {
+            "SvcFilePut" => {
+                msg.parse::<protocol::ctl::SvcFilePut>()
+                   .ok()
+                   .and_then(|m| m.service_group)
+                   .map(|sg| sg.to_string())
+            }
+            "SvcSetCfg" => {
+                msg.parse::<protocol::ctl::SvcSetCfg>()
+                   .ok()
+                   .and_then(|m| m.service_group)
+                   .map(|sg| sg.to_string())
+            }
+            "SvcDryRunCfg" => {
+                msg.parse::<protocol::ctl::SvcDryRunCfg>()
+                   .ok()
+                   .and_then(|m| m.service_group)
+                   .map(|sg| sg.to_string())
+            }
+            "SvcLoad" => {
+                msg.parse::<protocol::ctl::SvcLoad>()
+                   .ok()
+                   .and_then(|m| m.ident)
+                   .map(|ident| ident.to_string())
+            }
+            "SvcUnload" => {
+                msg.parse::<protocol::ctl::SvcUnload>()
+                   .ok()
+                   .and_then(|m| m.ident)
+                   .map(|ident| ident.to_string())
+            }
+            "SvcStart" => {
+                msg.parse::<protocol::ctl::SvcStart>()
+                   .ok()
+                   .and_then(|m| m.ident)
+                   .map(|ident| ident.to_string())
+            }
+            "SvcStop" => {
+                msg.parse::<protocol::ctl::SvcStop>()
+                   .ok()
+                   .and_then(|m| m.ident)
+                   .map(|ident| ident.to_string())
+            }
+            "SupDepart" => {
+                msg.parse::<protocol::ctl::SupDepart>()
+                   .ok()
+                   .and_then(|m| m.member_id)
+            }
+            "SupRingKeyRotate" => {
+                msg.parse::<protocol::ctl::SupRingKeyRotate>()
+                   .ok()
+                   .and_then(|m| m.key)
+                   .and_then(|key| parse_key_str(&key).ok())
+                   .map(|(_, name_with_rev, _)| name_with_rev)
+            }
+            _ => None,
+        }
+    }
+
+    /// Record the outcome of the pending mutating request, judged by its final reply.
+    fn audit_reply(&mut self, reply: &SrvMessage) {
+        if let Some((message_id, target)) = self.audited.take() {
+            let (outcome, detail) = match reply.try_ok() {
+                Ok(()) => (Outcome::Succeeded, None),
+                Err(err) => (Outcome::Failed, Some(err.msg)),
+            };
+            self.record(&message_id, target, outcome, detail);
+        }
+    }
+
+    /// Record a mutating request that was rejected before it reached the Manager.
+    fn audit_rejection(&self, msg: &SrvMessage, detail: String) {
+        if Self::is_audited(msg) {
+            self.record(msg.message_id(),
+                        Self::audit_target(msg),
+                        Outcome::Failed,
+                        Some(detail));
+        }
+    }
+
+    fn record(&self,
+              message_id: &str,
+              target: Option<String>,
+              outcome: Outcome,
+              detail: Option<String>) {
+        self.audit_log.record(&AuditRecord::ctl(Some(self.addr),
+                                                &self.credential.name,
+                                                message_id,
+                                                target,
+                                                outcome,
+                                                detail));
     }
 
     fn command_from_message(msg: &SrvMessage,
@@ -296,6 +492,15 @@ impl SrvHandler {
                                        commands::service_cfg_validate(state, req, m.clone())
                                    }))
             }
+            "SvcDryRunCfg" => {
+                let m = msg.parse::<protocol::ctl::SvcDryRunCfg>()
+                           .map_err(HandlerError::from)?;
+                Ok(CtlCommand::new(ctl_sender,
+                                   msg.transaction(),
+                                   move |state, req, _action_sender| {
+                                       commands::service_cfg_dry_run(state, req, m.clone())
+                                   }))
+            }
             "SvcLoad" => {
                 let m = msg.parse::<protocol::ctl::SvcLoad>()
                            .map_err(HandlerError::from)?;
@@ -344,6 +549,15 @@ impl SrvHandler {
                                        commands::service_status(state, req, m.clone())
                                    }))
             }
+            "SvcLogs" => {
+                let m = msg.parse::<protocol::ctl::SvcLogs>()
+                           .map_err(HandlerError::from)?;
+                Ok(CtlCommand::new(ctl_sender,
+                                   msg.transaction(),
+                                   move |state, req, _action_sender| {
+                                       commands::service_logs(state, req, m.clone())
+                                   }))
+            }
             "SupDepart" => {
                 let m = msg.parse::<protocol::ctl::SupDepart>()
                            .map_err(HandlerError::from)?;
@@ -353,6 +567,24 @@ impl SrvHandler {
                                        commands::supervisor_depart(state, req, m.clone())
                                    }))
             }
+            "SupRingKeyRotate" => {
+                let m = msg.parse::<protocol::ctl::SupRingKeyRotate>()
+                           .map_err(HandlerError::from)?;
+                Ok(CtlCommand::new(ctl_sender,
+                                   msg.transaction(),
+                                   move |state, req, _action_sender| {
+                                       commands::ring_key_rotate(state, req, m.clone())
+                                   }))
+            }
+            "SupDebug" => {
+                let m = msg.parse::<protocol::ctl::SupDebug>()
+                           .map_err(HandlerError::from)?;
+                Ok(CtlCommand::new(ctl_sender,
+                                   msg.transaction(),
+                                   move |state, req, _action_sender| {
+                                       commands::supervisor_debug(state, req, m.clone())
+                                   }))
+            }
             _ => {
                 warn!("Unhandled message, {}", msg.message_id());
                 Err(HandlerError::from(io::Error::from(io::ErrorKind::InvalidData)))
@@ -385,20 +617,37 @@ impl Future for SrvHandler {
                             self.start_timer(&msg.message_id());
                             trace!("OnMessage, {}", msg.message_id());
 
-                            let cmd =
-                                match Self::command_from_message(&msg, self.ctl_sender.clone()) {
-                                    Ok(cmd) => cmd,
-                                    Err(_) => {
-                                        break;
-                                    }
-                                };
+                            if !self.authorize(&msg) {
+                                self.state = SrvHandlerState::Sending;
+                                continue;
+                            }
+
+                            let cmd = match Self::command_from_message(&msg,
+                                                                       self.ctl_sender.clone())
+                            {
+                                Ok(cmd) => cmd,
+                                Err(err) => {
+                                    self.audit_rejection(&msg,
+                                                         format!("Unable to decode request, {}",
+                                                                 err));
+                                    break;
+                                }
+                            };
 
                             match self.mgr_sender.start_send(cmd) {
                                 Ok(AsyncSink::Ready) => {
+                                    if Self::is_audited(&msg) {
+                                        self.audited = Some((msg.message_id().to_string(),
+                                                             Self::audit_target(&msg)));
+                                    }
                                     self.state = SrvHandlerState::Sending;
                                     continue;
                                 }
-                                Ok(AsyncSink::NotReady(_)) => return Ok(Async::NotReady),
+                                Ok(AsyncSink::NotReady(_)) => {
+                                    let detail = "The Supervisor is not ready to accept requests";
+                                    self.audit_rejection(&msg, detail.to_string());
+                                    return Ok(Async::NotReady);
+                                }
                                 Err(err) => {
                                     // An error here means that the
                                     // receiving end of this channel went
@@ -420,6 +669,7 @@ impl Future for SrvHandler {
                         Ok(Async::Ready(Some(msg))) => {
                             trace!("MgrSender -> SrvHandler, {:?}", msg);
                             if msg.is_complete() {
+                                self.audit_reply(&msg);
                                 self.state = SrvHandlerState::Sent;
                             }
                             try_nb!(self.io.start_send(msg));
@@ -455,21 +705,30 @@ enum SrvHandlerState {
 }
 
 struct SrvState {
-    secret_key: String,
-    mgr_sender: MgrSender,
+    credentials: Credentials,
+    mgr_sender:  MgrSender,
+    audit_log:   AuditLog,
 }
 
 /// Start a new thread which will run the CtlGateway server.
 ///
-/// New connections will be authenticated using `secret_key`. Messages from the main thread
-/// will be sent over the channel `mgr_sender`.
-pub fn run(listen_addr: SocketAddr, secret_key: String, mgr_sender: MgrSender) {
+/// New connections will be authenticated using `credentials`, which also decide what each
+/// connection may request. Messages from the main thread will be sent over the channel
+/// `mgr_sender`, and the changes they make are recorded in `audit_log`. If a `tls_config` is
+/// given, connections must first complete a TLS handshake using it.
+pub fn run(listen_addr: SocketAddr,
+           credentials: Credentials,
+           mgr_sender: MgrSender,
+           audit_log: AuditLog,
+           tls_config: Option<ServerConfig>) {
     let tb = thread::Builder::new().name("ctl-gateway".to_string());
     tb.spawn(move || {
           let mut core = reactor::Core::new().unwrap();
           let handle = core.handle();
-          let state = SrvState { secret_key,
-                                 mgr_sender };
+          let acceptor = tls_config.map(|c| TlsAcceptor::from(Arc::new(c)));
+          let state = SrvState { credentials,
+                                 mgr_sender,
+                                 audit_log };
           let state = Rc::new(RefCell::new(state));
           let server =
               TcpListener::bind(&listen_addr).expect("Could not bind ctl gateway listen address!")
@@ -478,10 +737,14 @@ pub fn run(listen_addr: SocketAddr, secret_key: String, mgr_sender: MgrSender) {
                                                  let addr =
                                                      tcp_stream.peer_addr().expect("Couldn't get \
                                                                                     peer address!");
-                                                 let io = SrvCodec::new().framed(tcp_stream);
                                                  let client = Client { handle: handle.clone(),
-                                                                       state:  state.clone(), };
-                                                 (client.serve(io), addr)
+                                                                       state: state.clone(),
+                                                                       addr };
+                                                 let conn = secure(tcp_stream, acceptor.as_ref())
+                                                     .and_then(move |io| {
+                                                         client.serve(SrvCodec::new().framed(io))
+                                                     });
+                                                 (conn, addr)
                                              })
                                              .for_each(|(client, addr)| {
                                                  handle.spawn(client.then(move |res| {
@@ -497,3 +760,18 @@ pub fn run(listen_addr: SocketAddr, secret_key: String, mgr_sender: MgrSender) {
       })
       .expect("ctl-gateway thread start failure");
 }
+
+/// Complete a TLS handshake on a newly accepted connection if the gateway was started with a TLS
+/// configuration, or use it as-is otherwise.
+fn secure(tcp_stream: TcpStream,
+          acceptor: Option<&TlsAcceptor>)
+          -> impl Future<Item = Box<dyn SrvIo>, Error = HandlerError> {
+    match acceptor {
+        Some(acceptor) => {
+            Either::A(acceptor.accept(tcp_stream)
+                              .map(|stream| Box::new(stream) as Box<dyn SrvIo>)
+                              .map_err(HandlerError::from))
+        }
+        None => Either::B(future::ok(Box::new(tcp_stream) as Box<dyn SrvIo>)),
+    }
+}
@@ -105,6 +105,8 @@ pub enum Error {
     TestBootFail,
     ButterflyError(habitat_butterfly::error::Error),
     CtlSecretIo(PathBuf, io::Error),
+    CtlCredentialsParse(PathBuf, toml::de::Error),
+    CtlCredentialsInvalid(PathBuf, String),
     APIClient(habitat_api_client::Error),
     EnvJoinPathsError(env::JoinPathsError),
     ExecCommandNotFound(String),
@@ -135,6 +137,7 @@ pub enum Error {
     NoSuchBind(String),
     NotifyCreateError(notify::Error),
     NotifyError(notify::Error),
+    NoTrustedKeys(String),
     NulError(ffi::NulError),
     OneshotCanceled(oneshot::Canceled),
     PackageNotFound(package::PackageIdent),
@@ -163,6 +166,7 @@ pub enum Error {
     TomlEncode(toml::ser::Error),
     TryRecvError(mpsc::TryRecvError),
     UnpackFailed,
+    UntrustedAuthor(String),
     UserNotFound(String),
 }
 
@@ -212,6 +216,14 @@ impl fmt::Display for SupError {
                         path.display(),
                         err)
             }
+            Error::CtlCredentialsParse(ref path, ref err) => {
+                format!("Unable to parse ctl credentials file {}, {}",
+                        path.display(),
+                        err)
+            }
+            Error::CtlCredentialsInvalid(ref path, ref err) => {
+                format!("Invalid ctl credentials in {}, {}", path.display(), err)
+            }
             Error::ExecCommandNotFound(ref c) => {
                 format!("`{}' was not found on the filesystem or in PATH", c)
             }
@@ -252,6 +264,9 @@ impl fmt::Display for SupError {
             Error::NoSuchBind(ref b) => format!("No such bind: {}", b),
             Error::NotifyCreateError(ref e) => format!("Notify create error: {}", e),
             Error::NotifyError(ref e) => format!("Notify error: {}", e),
+            Error::NoTrustedKeys(ref name) => {
+                format!("No public keys named {} found to trust", name)
+            }
             Error::NulError(ref e) => e.to_string(),
             Error::OneshotCanceled(ref e) => e.to_string(),
             Error::PackageNotFound(ref pkg) => {
@@ -313,6 +328,9 @@ impl fmt::Display for SupError {
             Error::TomlEncode(ref e) => format!("Failed to encode TOML: {}", e),
             Error::TryRecvError(ref err) => err.to_string(),
             Error::UnpackFailed => "Failed to unpack a package".to_string(),
+            Error::UntrustedAuthor(ref reason) => {
+                format!("Not signed by a trusted user key, {}", reason)
+            }
             Error::UserNotFound(ref e) => format!("No UID for user '{}' could be found", e),
         };
 
@@ -363,6 +381,8 @@ impl error::Error for SupError {
             Error::TestBootFail => "Simulated boot failure",
             Error::ButterflyError(ref err) => err.description(),
             Error::CtlSecretIo(..) => "IoError while reading ctl secret",
+            Error::CtlCredentialsParse(..) => "Ctl credentials file could not be parsed",
+            Error::CtlCredentialsInvalid(..) => "Ctl credentials are empty or not unique",
             Error::ExecCommandNotFound(_) => "Exec command was not found on filesystem or in PATH",
             Error::EventError(_) => "Eventing error",
             Error::GroupNotFound(_) => "No matching GID for group found",
@@ -398,6 +418,7 @@ impl error::Error for SupError {
             Error::NoSuchBind(_) => "No such bind found for this service",
             Error::NotifyCreateError(_) => "Notify create error",
             Error::NotifyError(_) => "Notify error",
+            Error::NoTrustedKeys(_) => "No public keys found to trust",
             Error::NulError(_) => {
                 "An attempt was made to build a CString with a null byte inside it"
             }
@@ -432,6 +453,7 @@ impl error::Error for SupError {
             Error::TomlEncode(_) => "Failed to encode toml!",
             Error::TryRecvError(_) => "A channel failed to receive a response",
             Error::UnpackFailed => "Failed to unpack a package",
+            Error::UntrustedAuthor(_) => "Not signed by a trusted user key",
             Error::UserNotFound(_) => "No matching UID for user found",
         }
     }
@@ -9,11 +9,20 @@
 //!
 //! All events are published under the "habitat" subject.
 //!
+//! Events can also be sent, as JSON, to other sinks (see
+//! `init_sinks`): a file, an HTTP webhook or a Unix domain socket.
+//!
+//! Independently of any stream, events are also delivered as JSON to
+//! local subscribers (see `subscribe`), such as the HTTP gateway's
+//! `/events` endpoint. The `init_core` function must be called before
+//! events can be delivered to either.
+//!
 //! [1]:https://github.com/nats-io/nats-streaming-server
 
 mod error;
 // ratsio_stream is the default, but setting it as a default in Cargo.toml
 // makes it trickier to use nitox instead.
+mod sink;
 #[cfg(feature = "nitox_stream")]
 #[path = "event/nitox.rs"]
 mod stream_impl;
@@ -22,27 +31,51 @@ mod stream_impl;
 mod stream_impl;
 mod types;
 
+pub use self::sink::DEFAULT_BUFFER_SIZE;
 pub(crate) use self::types::ServiceMetadata;
-use self::types::{EventMessage,
-                  EventMetadata,
-                  HealthCheckEvent,
-                  ServiceStartedEvent,
-                  ServiceStoppedEvent};
-use crate::manager::{service::{HealthCheckResult,
-                               Service},
-                     sys::Sys};
+use self::{sink::{BufferedSink,
+                  SinkEvent},
+           types::{AuditEvent,
+                   ElectionFinishedEvent,
+                   ElectionStartedEvent,
+                   EventMessage,
+                   EventMetadata,
+                   HealthCheckEvent,
+                   HookExecutedEvent,
+                   MemberHealthChangedEvent,
+                   ServiceConfigUpdatedEvent,
+                   ServiceCrashLoopingEvent,
+                   ServiceFileUpdatedEvent,
+                   ServiceStartedEvent,
+                   ServiceStoppedEvent,
+                   ServiceUpdateAppliedEvent,
+                   ServiceUpdateDetectedEvent,
+                   ServiceUpdateRolledBackEvent,
+                   SupervisorUpdatedEvent}};
+use crate::{audit::AuditRecord,
+            manager::{service::{HealthCheckResult,
+                                Service},
+                      sys::Sys}};
 use clap::ArgMatches;
 pub use error::{Error,
                 Result};
-use futures::sync::mpsc::UnboundedSender;
+use futures::sync::mpsc::{self,
+                          Receiver,
+                          Sender,
+                          UnboundedSender};
+use habitat_butterfly::member::Health;
 use habitat_common::types::{AutomateAuthToken,
+                            EventSinkConfig,
                             EventStreamMetadata};
-use habitat_core::env::Config as EnvConfig;
+use habitat_core::{env::Config as EnvConfig,
+                   package::PackageIdent};
 use state::Container;
 use std::{net::SocketAddr,
           num::ParseIntError,
           str::FromStr,
-          sync::Once,
+          sync::{Arc,
+                 Mutex,
+                 Once},
           time::Duration};
 
 static INIT: Once = Once::new();
@@ -50,17 +83,27 @@ lazy_static! {
     // TODO (CM): When const fn support lands in stable, we can ditch
     // this lazy_static call.
 
-    /// Reference to the event stream.
-    static ref EVENT_STREAM: Container = Container::new();
+    /// Every sink events are sent to, including the event stream.
+    static ref SINKS: Mutex<Vec<BufferedSink>> = Mutex::new(Vec::new());
     /// Core information that is shared between all events.
     static ref EVENT_CORE: Container = Container::new();
+    /// Local subscribers to events, such as clients of the HTTP
+    /// gateway's `/events` endpoint.
+    static ref SUBSCRIBERS: Mutex<Vec<Sender<LocalEvent>>> = Mutex::new(Vec::new());
 }
 
+/// How many events a local subscriber may fall behind by before it is
+/// disconnected.
+const SUBSCRIBER_BUFFER_SIZE: usize = 1024;
+
+/// Stashes the core event information that will be a part of all
+/// events in a global static reference for access later. Until this
+/// is called, no events are sent anywhere.
+pub fn init_core(event_core: EventCore) { EVENT_CORE.set(event_core); }
+
 /// Starts a new thread for sending events to a NATS Streaming
-/// server. Stashes the handle to the stream, as well as the core
-/// event information that will be a part of all events, in a global
-/// static reference for access later.
-pub fn init_stream(config: EventStreamConfig, event_core: EventCore) -> Result<()> {
+/// server, and adds the stream to the sinks all events are sent to.
+pub fn init_stream(config: EventStreamConfig, buffer_size: usize) -> Result<()> {
     // call_once can't return a Result (or anything), so we'll fake it
     // by hanging onto any error we might receive.
     let mut return_value: Result<()> = Ok(());
@@ -69,8 +112,7 @@ pub fn init_stream(config: EventStreamConfig, event_core: EventCore) -> Result<(
             let conn_info = EventConnectionInfo::new(config.token, config.url);
             match stream_impl::init_stream(conn_info) {
                 Ok(event_stream) => {
-                    EVENT_STREAM.set(event_stream);
-                    EVENT_CORE.set(event_core);
+                    return_value = add_sink("nats", Box::new(event_stream), buffer_size);
                 }
                 Err(e) => return_value = Err(e),
             }
@@ -79,6 +121,23 @@ pub fn init_stream(config: EventStreamConfig, event_core: EventCore) -> Result<(
     return_value
 }
 
+/// Starts a thread for each of the given sinks, holding up to
+/// `buffer_size` events for each while it is unavailable.
+pub fn init_sinks(configs: &[EventSinkConfig], buffer_size: usize) -> Result<()> {
+    for config in configs {
+        add_sink(&config.to_string(), sink::from_config(config)?, buffer_size)?;
+    }
+    Ok(())
+}
+
+fn add_sink(name: &str, sink: Box<dyn sink::EventSink>, buffer_size: usize) -> Result<()> {
+    let sink = BufferedSink::spawn(name, sink, buffer_size)?;
+    SINKS.lock()
+         .expect("Event sinks lock is poisoned")
+         .push(sink);
+    Ok(())
+}
+
 /// Captures all event stream-related configuration options that would
 /// be passed in by a user
 #[derive(Clone, Debug)]
@@ -89,6 +148,7 @@ pub struct EventStreamConfig {
     meta:        EventStreamMetadata,
     token:       AutomateAuthToken,
     url:         String,
+    audit:       bool,
 }
 
 impl<'a> From<&'a ArgMatches<'a>> for EventStreamConfig {
@@ -104,10 +164,17 @@ impl<'a> From<&'a ArgMatches<'a>> for EventStreamConfig {
                             token:       AutomateAuthToken::from(m),
                             url:         m.value_of("EVENT_STREAM_URL")
                                           .map(str::to_string)
-                                          .expect("Required option for EventStream feature"), }
+                                          .expect("Required option for EventStream feature"),
+                            audit:       m.is_present("EVENT_STREAM_AUDIT"), }
     }
 }
 
+impl EventStreamConfig {
+    /// Whether records written to the Supervisor's audit log should
+    /// also be sent to the event stream.
+    pub fn audit(&self) -> bool { self.audit }
+}
+
 /// All the information needed to establish a connection to a NATS
 /// Streaming server.
 pub struct EventConnectionInfo {
@@ -150,20 +217,22 @@ pub struct EventCore {
 }
 
 impl EventCore {
-    pub fn new(config: &EventStreamConfig, sys: &Sys, fqdn: String) -> Self {
+    /// Without an event stream `config`, events carry no environment,
+    /// application, site or metadata.
+    pub fn new(config: Option<&EventStreamConfig>, sys: &Sys, fqdn: String) -> Self {
         EventCore { supervisor_id: sys.member_id.clone(),
                     ip_address: sys.gossip_listen(),
                     fqdn,
-                    environment: config.environment.clone(),
-                    application: config.application.clone(),
-                    site: config.site.clone(),
-                    meta: config.meta.clone() }
+                    environment: config.map(|c| c.environment.clone()).unwrap_or_default(),
+                    application: config.map(|c| c.application.clone()).unwrap_or_default(),
+                    site: config.and_then(|c| c.site.clone()),
+                    meta: config.map(|c| c.meta.clone()).unwrap_or_default() }
     }
 }
 
 /// Send an event for the start of a Service.
 pub fn service_started(service: &Service) {
-    if stream_initialized() {
+    if should_publish() {
         publish(ServiceStartedEvent { service_metadata: Some(service.to_service_metadata()),
                                       event_metadata:   None, });
     }
@@ -171,12 +240,156 @@ pub fn service_started(service: &Service) {
 
 /// Send an event for the stop of a Service.
 pub fn service_stopped(service: &Service) {
-    if stream_initialized() {
+    if should_publish() {
         publish(ServiceStoppedEvent { service_metadata: Some(service.to_service_metadata()),
                                       event_metadata:   None, });
     }
 }
 
+/// Send an event for a new release of a Service's package, found by
+/// the Service's updater.
+pub fn service_update_detected(service: &Service, update_to: &PackageIdent) {
+    if should_publish() {
+        publish(ServiceUpdateDetectedEvent { service_metadata:
+                                                 Some(service.to_service_metadata()),
+                                             event_metadata:       None,
+                                             update_package_ident: update_to.to_string(), });
+    }
+}
+
+/// Send an event for a Service that has been restarted on a new
+/// release of its package.
+pub fn service_update_applied(service: &Service, previous: &PackageIdent) {
+    if should_publish() {
+        publish(ServiceUpdateAppliedEvent { service_metadata:
+                                                Some(service.to_service_metadata()),
+                                            event_metadata:         None,
+                                            previous_package_ident: previous.to_string(), });
+    }
+}
+
+/// Send an event for a Service whose update failed, and which is
+/// being returned to the release it ran before the update.
+pub fn service_update_rolled_back(service: &Service, rollback_to: &PackageIdent, reason: &str) {
+    if should_publish() {
+        publish(ServiceUpdateRolledBackEvent { service_metadata:
+                                                   Some(service.to_service_metadata()),
+                                               event_metadata:         None,
+                                               rollback_package_ident: rollback_to.to_string(),
+                                               reason:                 reason.to_string(), });
+    }
+}
+
+/// Send an event for a Service that keeps exiting soon after being
+/// restarted.
+pub fn service_crash_looping(service: &Service, restarts: u32) {
+    if should_publish() {
+        publish(ServiceCrashLoopingEvent { service_metadata:
+                                               Some(service.to_service_metadata()),
+                                           event_metadata: None,
+                                           restarts });
+    }
+}
+
+/// Send an event for a record written to the Supervisor's audit
+/// log.
+pub fn audit(record: &AuditRecord) {
+    if should_publish() {
+        let outcome: types::AuditOutcome = record.outcome.into();
+        publish(AuditEvent { event_metadata: None,
+                             source:         record.source.to_string(),
+                             client:         record.client.clone(),
+                             credential:     record.credential.clone().unwrap_or_default(),
+                             request:        record.request.clone(),
+                             target:         record.target.clone().unwrap_or_default(),
+                             outcome:        i32::from(outcome),
+                             detail:         record.detail.clone().unwrap_or_default(), });
+    }
+}
+
+/// Send an event for the run of one of a Service's lifecycle hooks.
+/// `code` is the hook's exit code, or `None` if the hook could not be
+/// run, or was terminated by a signal.
+pub fn hook_executed(metadata: ServiceMetadata,
+                     hook: &str,
+                     code: Option<i32>,
+                     execution: Duration) {
+    if should_publish() {
+        publish(HookExecutedEvent { service_metadata: Some(metadata),
+                                    event_metadata:   None,
+                                    hook:             hook.to_string(),
+                                    exit_code:        code,
+                                    execution:        Some(execution.into()), });
+    }
+}
+
+/// Send an event for the start of an election in a Service's group.
+pub fn election_started(service: &Service) {
+    if should_publish() {
+        publish(ElectionStartedEvent { service_metadata: Some(service.to_service_metadata()),
+                                       event_metadata:   None, });
+    }
+}
+
+/// Send an event for the end of an election in a Service's group.
+/// `is_leader` is whether this Supervisor is the new leader.
+pub fn election_finished(service: &Service, leader_id: &str, is_leader: bool) {
+    if should_publish() {
+        publish(ElectionFinishedEvent { service_metadata: Some(service.to_service_metadata()),
+                                        event_metadata:   None,
+                                        leader_id:        leader_id.to_string(),
+                                        won:              is_leader, });
+    }
+}
+
+/// Send an event for a Service that has applied a new configuration
+/// gossiped to its group, signed by `author` if authors are verified.
+pub fn service_config_updated(service: &Service, incarnation: u64, author: Option<&str>) {
+    if should_publish() {
+        publish(ServiceConfigUpdatedEvent { service_metadata:
+                                                Some(service.to_service_metadata()),
+                                            event_metadata: None,
+                                            incarnation,
+                                            author: author.unwrap_or_default().to_string() });
+    }
+}
+
+/// Send an event for a Service that has written a new file gossiped
+/// to its group, signed by `author` if authors are verified.
+pub fn service_file_updated(service: &Service,
+                            filename: &str,
+                            incarnation: u64,
+                            author: Option<&str>) {
+    if should_publish() {
+        publish(ServiceFileUpdatedEvent { service_metadata: Some(service.to_service_metadata()),
+                                          event_metadata: None,
+                                          filename: filename.to_string(),
+                                          incarnation,
+                                          author: author.unwrap_or_default().to_string() });
+    }
+}
+
+/// Send an event for a member of the Supervisor ring whose health has
+/// changed.
+pub fn member_health_changed(member_id: &str, health: Health) {
+    if should_publish() {
+        let health: types::MemberHealth = health.into();
+        publish(MemberHealthChangedEvent { event_metadata: None,
+                                           member_id:      member_id.to_string(),
+                                           health:         i32::from(health), });
+    }
+}
+
+/// Send an event for the Supervisor restarting into a new release of
+/// its own package.
+pub fn supervisor_updated(previous: &PackageIdent, updated_to: &PackageIdent) {
+    if should_publish() {
+        publish(SupervisorUpdatedEvent { event_metadata:         None,
+                                         previous_package_ident: previous.to_string(),
+                                         package_ident:          updated_to.to_string(), });
+    }
+}
+
 // Takes metadata directly, rather than a `&Service` like other event
 // functions, because of how the asynchronous health checking
 // currently works. Revisit when async/await + Pin is all stabilized.
@@ -185,7 +398,7 @@ pub fn service_stopped(service: &Service) {
 pub fn health_check(metadata: ServiceMetadata,
                     check_result: HealthCheckResult,
                     execution: Option<Duration>) {
-    if stream_initialized() {
+    if should_publish() {
         let check_result: types::HealthCheckResult = check_result.into();
         publish(HealthCheckEvent { service_metadata: Some(metadata),
                                    event_metadata:   None,
@@ -196,18 +409,47 @@ pub fn health_check(metadata: ServiceMetadata,
 
 ////////////////////////////////////////////////////////////////////////
 
+/// An event delivered to a local subscriber.
+#[derive(Clone, Debug)]
+pub struct LocalEvent {
+    /// The kind of event, e.g., `service-started`.
+    pub name: &'static str,
+    pub json: String,
+}
+
+/// Receive every event published from now on. Dropping the receiver
+/// ends the subscription, as does falling more than
+/// `SUBSCRIBER_BUFFER_SIZE` events behind, in which case the receiver
+/// ends after the events it was sent.
+pub fn subscribe() -> Receiver<LocalEvent> {
+    let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+    SUBSCRIBERS.lock()
+               .expect("Event subscribers lock is poisoned")
+               .push(tx);
+    rx
+}
+
 /// Internal helper function to know whether or not to go to the trouble of
 /// creating event structures. If the event stream hasn't been
-/// initialized, then we shouldn't need to do anything.
-fn stream_initialized() -> bool { EVENT_STREAM.try_get::<EventStream>().is_some() }
+/// initialized, there are no other sinks, and nobody has subscribed
+/// locally, then we shouldn't need to do anything.
+fn should_publish() -> bool {
+    EVENT_CORE.try_get::<EventCore>().is_some()
+    && (!SINKS.lock()
+              .expect("Event sinks lock is poisoned")
+              .is_empty()
+        || !SUBSCRIBERS.lock()
+                       .expect("Event subscribers lock is poisoned")
+                       .is_empty())
+}
 
 /// Publish an event. This is the main interface that client code will
 /// use.
 ///
-/// If `init_stream` has not been called already, this function will
-/// be a no-op.
+/// If `init_core` has not been called already, this function will be
+/// a no-op.
 fn publish(mut event: impl EventMessage) {
-    if let Some(e) = EVENT_STREAM.try_get::<EventStream>() {
+    if let Some(core) = EVENT_CORE.try_get::<EventCore>() {
         // TODO (CM): Yeah... this is looking pretty gross. The
         // intention is to be able to timestamp the events right as
         // they go out.
@@ -221,9 +463,56 @@ fn publish(mut event: impl EventMessage) {
         // The ugliness is at least contained, though.
         event.event_metadata(EventMetadata { occurred_at:
                                                  Some(std::time::SystemTime::now().into()),
-                                             ..EVENT_CORE.get::<EventCore>().to_event_metadata() });
+                                             ..core.to_event_metadata() });
+
+        send_to_sinks(&event);
+        send_to_subscribers(&event);
+    }
+}
+
+fn send_to_sinks(event: &impl EventMessage) {
+    let sinks = SINKS.lock().expect("Event sinks lock is poisoned");
+    if sinks.is_empty() {
+        return;
+    }
+    let sink_event = Arc::new(SinkEvent { name:     event.event_name(),
+                                          protobuf: event.to_bytes(),
+                                          json:     event.to_json(), });
+    for sink in sinks.iter() {
+        sink.send(Arc::clone(&sink_event));
+    }
+}
+
+/// Deliver an event to every local subscriber.
+fn send_to_subscribers(event: &impl EventMessage) {
+    let mut subscribers = SUBSCRIBERS.lock()
+                                     .expect("Event subscribers lock is poisoned");
+    if subscribers.is_empty() {
+        return;
+    }
+    let local_event = LocalEvent { name: event.event_name(),
+                                   json: event.to_json(), };
+    deliver(&mut subscribers, &local_event);
+}
 
-        e.send(event.to_bytes());
+/// Deliver an event to each subscriber, forgetting those that have
+/// gone away. Those that have fallen too far behind are forgotten as
+/// well, rather than buffering events for them without limit, which
+/// disconnects them once they have caught up with what they were sent.
+fn deliver(subscribers: &mut Vec<Sender<LocalEvent>>, event: &LocalEvent) {
+    let mut i = 0;
+    while i < subscribers.len() {
+        match subscribers[i].try_send(event.clone()) {
+            Ok(()) => i += 1,
+            Err(e) => {
+                if e.is_full() {
+                    warn!("Disconnecting an event subscriber which fell more than {} events \
+                           behind",
+                          SUBSCRIBER_BUFFER_SIZE);
+                }
+                subscribers.swap_remove(i);
+            }
+        }
     }
 }
 
@@ -267,3 +556,55 @@ impl EnvConfig for EventThreadStartupWait {
 impl Into<Duration> for EventThreadStartupWait {
     fn into(self) -> Duration { Duration::from_secs(self.secs) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{Future,
+                  Stream};
+
+    fn local_event() -> LocalEvent {
+        LocalEvent { name: "service-started",
+                     json: "{}".to_string(), }
+    }
+
+    #[test]
+    fn subscribers_keep_receiving_events_while_they_keep_up() {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+        let mut subscribers = vec![tx];
+
+        deliver(&mut subscribers, &local_event());
+
+        assert_eq!(subscribers.len(), 1);
+        let (received, _rx) = rx.into_future().wait().ok().unwrap();
+        assert_eq!(received.unwrap().name, "service-started");
+    }
+
+    #[test]
+    fn subscribers_which_went_away_are_forgotten() {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+        let mut subscribers = vec![tx];
+        drop(rx);
+
+        deliver(&mut subscribers, &local_event());
+
+        assert!(subscribers.is_empty());
+    }
+
+    #[test]
+    fn lagging_subscribers_are_disconnected_after_the_events_they_were_sent() {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+        let mut subscribers = vec![tx];
+
+        let mut attempts = 0;
+        while !subscribers.is_empty() {
+            deliver(&mut subscribers, &local_event());
+            attempts += 1;
+            assert!(attempts <= SUBSCRIBER_BUFFER_SIZE + 2,
+                    "A lagging subscriber was never disconnected");
+        }
+
+        // Every delivery but the last one succeeded.
+        assert_eq!(rx.wait().count(), attempts - 1);
+    }
+}
@@ -1,5 +1,7 @@
 //! Event subsystem-specific error handling
 
+use habitat_common::types::EventSinkConfig;
+use habitat_http_client;
 use std::{error,
           fmt,
           io,
@@ -12,6 +14,8 @@ pub type Result<T> = result::Result<T, Error>;
 pub enum Error {
     ConnectEventServerError(mpsc::RecvTimeoutError),
     SpawnEventThreadError(io::Error),
+    UnsupportedEventSink(EventSinkConfig),
+    WebhookClientError(habitat_http_client::Error),
 }
 
 // TODO (CM): I would have like to have derived Fail on our Error
@@ -29,6 +33,10 @@ impl fmt::Display for Error {
                 "Could not establish streaming connection to NATS server".fmt(f)
             }
             Error::SpawnEventThreadError(_) => "Could not spawn eventing thread".fmt(f),
+            Error::UnsupportedEventSink(ref sink) => {
+                write!(f, "Event sink {} is not supported on this platform", sink)
+            }
+            Error::WebhookClientError(_) => "Could not create HTTP client for webhook".fmt(f),
         }
     }
 }
@@ -38,6 +46,8 @@ impl error::Error for Error {
         match self {
             Error::ConnectEventServerError(ref e) => Some(e),
             Error::SpawnEventThreadError(ref e) => Some(e),
+            Error::UnsupportedEventSink(_) => None,
+            Error::WebhookClientError(ref e) => Some(e),
         }
     }
 }
@@ -0,0 +1,478 @@
+//! Destinations for the Supervisor's events.
+//!
+//! Every sink runs on its own thread, behind a bounded in-memory
+//! buffer. Should a sink be unavailable (a webhook that's down, a
+//! socket nobody is listening on), events are held in the buffer and
+//! delivery is retried with exponential backoff. Once the buffer is
+//! full, the oldest events are dropped to make room for new ones.
+//! Should the sink's thread itself fall behind (a webhook that's slow
+//! to respond), new events are dropped rather than queued for it
+//! without limit.
+
+use super::{Error,
+            EventStream,
+            Result};
+use crate::{PRODUCT,
+            VERSION};
+use habitat_common::types::EventSinkConfig;
+use habitat_http_client::ApiClient;
+use hyper::{header::ContentType,
+            Url};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::{collections::VecDeque,
+          fs::{File,
+               OpenOptions},
+          io::{self,
+               Write},
+          path::PathBuf,
+          sync::{atomic::{AtomicUsize,
+                          Ordering},
+                 mpsc::{self,
+                        RecvTimeoutError,
+                        TrySendError},
+                 Arc},
+          thread,
+          time::{Duration,
+                 Instant}};
+
+/// The first delay before retrying a sink that has failed.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+/// The longest delay between retries of a sink that keeps failing.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// How long to wait for a webhook to accept a connection.
+const WEBHOOK_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait for a webhook to accept an event or respond to it.
+const WEBHOOK_SOCKET_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many events each sink holds while it is unavailable, unless
+/// configured otherwise.
+pub const DEFAULT_BUFFER_SIZE: usize = 1000;
+
+/// An event, ready to be delivered to any sink.
+#[derive(Debug)]
+pub struct SinkEvent {
+    /// The kind of event, e.g., `service-started`.
+    pub name:     &'static str,
+    /// The event, encoded as a protobuf message.
+    pub protobuf: Vec<u8>,
+    /// The event as JSON, which never contains newlines.
+    pub json:     String,
+}
+
+/// A destination for events.
+pub trait EventSink: Send {
+    /// Deliver a single event. An error means the sink is
+    /// unavailable; the same event will be given again once it has
+    /// been retried.
+    fn send(&mut self, event: &SinkEvent) -> io::Result<()>;
+}
+
+/// Create the sink described by `config`.
+pub fn from_config(config: &EventSinkConfig) -> Result<Box<dyn EventSink>> {
+    match *config {
+        EventSinkConfig::File(ref path) => Ok(Box::new(FileSink::new(path.clone()))),
+        EventSinkConfig::Webhook(ref url) => Ok(Box::new(WebhookSink::new(url.clone())?)),
+        EventSinkConfig::UnixSocket(ref path) => unix_socket_sink(path.clone()),
+    }
+}
+
+#[cfg(unix)]
+fn unix_socket_sink(path: PathBuf) -> Result<Box<dyn EventSink>> {
+    Ok(Box::new(UnixSocketSink::new(path)))
+}
+
+#[cfg(not(unix))]
+fn unix_socket_sink(path: PathBuf) -> Result<Box<dyn EventSink>> {
+    Err(Error::UnsupportedEventSink(EventSinkConfig::UnixSocket(path)))
+}
+
+/// A lightweight handle for a sink running on its own thread.
+pub struct BufferedSink {
+    name:    String,
+    tx:      mpsc::SyncSender<Arc<SinkEvent>>,
+    /// How many events have been dropped since the sink's thread last
+    /// kept up.
+    dropped: AtomicUsize,
+}
+
+impl BufferedSink {
+    /// Start delivering events to `sink` on a new thread, holding up
+    /// to `capacity` of them while it is unavailable, and as many
+    /// again while its thread is busy.
+    pub fn spawn(name: &str, sink: Box<dyn EventSink>, capacity: usize) -> Result<Self> {
+        let (tx, rx) = mpsc::sync_channel(capacity.max(1));
+        let mut buffer = SinkBuffer::new(name.to_string(), sink, capacity);
+        thread::Builder::new().name("event-sink".to_string())
+                              .spawn(move || buffer.run(&rx))
+                              .map_err(Error::SpawnEventThreadError)?;
+        Ok(BufferedSink { name: name.to_string(),
+                          tx,
+                          dropped: AtomicUsize::new(0) })
+    }
+
+    /// Queues an event to be sent out, dropping it if the sink's
+    /// thread has fallen too far behind.
+    pub fn send(&self, event: Arc<SinkEvent>) {
+        match self.tx.try_send(event) {
+            Ok(()) => {
+                let dropped = self.dropped.swap(0, Ordering::Relaxed);
+                if dropped > 0 {
+                    info!("Event sink {} has caught up; {} events were dropped",
+                          self.name, dropped);
+                }
+            }
+            Err(TrySendError::Full(_)) => {
+                if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+                    warn!("Event sink {} is falling behind; dropping new events",
+                          self.name);
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                error!("Failed to queue event: event sink thread has exited");
+            }
+        }
+    }
+}
+
+/// Holds the events that have not yet been delivered to a sink.
+struct SinkBuffer {
+    name:     String,
+    sink:     Box<dyn EventSink>,
+    events:   VecDeque<Arc<SinkEvent>>,
+    capacity: usize,
+    /// How many events have been dropped since the sink last
+    /// succeeded.
+    dropped:  usize,
+    backoff:  Backoff,
+    /// When the sink may next be retried, if it has failed.
+    retry_at: Option<Instant>,
+}
+
+impl SinkBuffer {
+    fn new(name: String, sink: Box<dyn EventSink>, capacity: usize) -> Self {
+        SinkBuffer { name,
+                     sink,
+                     events: VecDeque::new(),
+                     capacity: capacity.max(1),
+                     dropped: 0,
+                     backoff: Backoff::default(),
+                     retry_at: None }
+    }
+
+    fn run(&mut self, rx: &mpsc::Receiver<Arc<SinkEvent>>) {
+        loop {
+            let received = match self.retry_at {
+                Some(retry_at) => {
+                    let now = Instant::now();
+                    let wait = if retry_at > now {
+                        retry_at - now
+                    } else {
+                        Duration::from_secs(0)
+                    };
+                    rx.recv_timeout(wait)
+                }
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(event) => self.push(event),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    // The Supervisor is shutting down; make one last
+                    // attempt to deliver what we have.
+                    self.flush(Instant::now());
+                    return;
+                }
+            }
+            self.flush(Instant::now());
+        }
+    }
+
+    fn push(&mut self, event: Arc<SinkEvent>) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            if self.dropped == 0 {
+                warn!("Event sink {} is full; dropping its oldest events",
+                      self.name);
+            }
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+
+    /// Deliver buffered events, oldest first, until the sink fails or
+    /// the buffer is empty. Does nothing if the sink failed and it is
+    /// not yet time to retry it.
+    fn flush(&mut self, now: Instant) {
+        if self.retry_at.map_or(false, |retry_at| now < retry_at) {
+            return;
+        }
+        while let Some(event) = self.events.front().cloned() {
+            if let Err(e) = self.sink.send(&event) {
+                let delay = self.backoff.next_delay();
+                if self.retry_at.is_none() {
+                    warn!("Event sink {} is unavailable, retrying in {:?}: {}",
+                          self.name, delay, e);
+                }
+                self.retry_at = Some(now + delay);
+                return;
+            }
+            self.events.pop_front();
+            if self.retry_at.take().is_some() {
+                info!("Event sink {} is available again; {} events were dropped",
+                      self.name, self.dropped);
+            }
+            self.backoff.reset();
+            self.dropped = 0;
+        }
+    }
+}
+
+/// Exponential backoff between retries of a failing sink.
+#[derive(Debug)]
+struct Backoff {
+    next: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self { Backoff { next: MIN_BACKOFF } }
+}
+
+impl Backoff {
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(MAX_BACKOFF);
+        delay
+    }
+
+    fn reset(&mut self) { self.next = MIN_BACKOFF; }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+impl EventSink for EventStream {
+    fn send(&mut self, event: &SinkEvent) -> io::Result<()> {
+        // The NATS client takes care of reconnecting itself.
+        EventStream::send(self, event.protobuf.clone());
+        Ok(())
+    }
+}
+
+/// Appends each event as a line of JSON to a file.
+struct FileSink {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl FileSink {
+    fn new(path: PathBuf) -> Self { FileSink { path, file: None } }
+}
+
+impl EventSink for FileSink {
+    fn send(&mut self, event: &SinkEvent) -> io::Result<()> {
+        if self.file.is_none() {
+            self.file = Some(OpenOptions::new().create(true)
+                                               .append(true)
+                                               .open(&self.path)?);
+        }
+        let result = write_line(self.file.as_mut().unwrap(), event);
+        if result.is_err() {
+            // Reopen the file on the next attempt.
+            self.file = None;
+        }
+        result
+    }
+}
+
+/// `POST`s each event as JSON to a URL. Any response but a success
+/// counts as a failure, as does a webhook that takes too long to
+/// accept a connection or to respond.
+struct WebhookSink {
+    url:    Url,
+    client: ApiClient,
+}
+
+impl WebhookSink {
+    fn new(url: Url) -> Result<Self> {
+        let client =
+            ApiClient::with_timeouts(url.clone(),
+                                     PRODUCT,
+                                     VERSION,
+                                     None,
+                                     Some(WEBHOOK_CONNECT_TIMEOUT),
+                                     WEBHOOK_SOCKET_TIMEOUT).map_err(Error::WebhookClientError)?;
+        Ok(WebhookSink { url, client })
+    }
+}
+
+impl EventSink for WebhookSink {
+    fn send(&mut self, event: &SinkEvent) -> io::Result<()> {
+        let response = self.client
+                           .post("")
+                           .header(ContentType::json())
+                           .body(event.json.as_str())
+                           .send()
+                           .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        if response.status.is_success() {
+            Ok(())
+        } else {
+            Err(io::Error::new(io::ErrorKind::Other,
+                               format!("{} responded {}",
+                                       self.url, response.status)))
+        }
+    }
+}
+
+/// Writes each event as a line of JSON to a Unix domain socket,
+/// reconnecting to it whenever a write fails.
+#[cfg(unix)]
+struct UnixSocketSink {
+    path:   PathBuf,
+    stream: Option<UnixStream>,
+}
+
+#[cfg(unix)]
+impl UnixSocketSink {
+    fn new(path: PathBuf) -> Self { UnixSocketSink { path, stream: None } }
+}
+
+#[cfg(unix)]
+impl EventSink for UnixSocketSink {
+    fn send(&mut self, event: &SinkEvent) -> io::Result<()> {
+        if self.stream.is_none() {
+            self.stream = Some(UnixStream::connect(&self.path)?);
+        }
+        let result = write_line(self.stream.as_mut().unwrap(), event);
+        if result.is_err() {
+            self.stream = None;
+        }
+        result
+    }
+}
+
+fn write_line<W: Write>(writer: &mut W, event: &SinkEvent) -> io::Result<()> {
+    writer.write_all(format!("{}\n", event.json).as_bytes())?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs,
+              sync::Mutex};
+    use tempfile::TempDir;
+
+    /// A sink which fails while `down` is set, recording the names of
+    /// the events it delivers.
+    #[derive(Clone, Default)]
+    struct TestSink {
+        down:      Arc<Mutex<bool>>,
+        delivered: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl EventSink for TestSink {
+        fn send(&mut self, event: &SinkEvent) -> io::Result<()> {
+            if *self.down.lock().unwrap() {
+                Err(io::Error::new(io::ErrorKind::ConnectionRefused, "down"))
+            } else {
+                self.delivered.lock().unwrap().push(event.name);
+                Ok(())
+            }
+        }
+    }
+
+    fn event(name: &'static str) -> Arc<SinkEvent> {
+        Arc::new(SinkEvent { name,
+                             protobuf: vec![],
+                             json: format!(r#"{{"name":"{}"}}"#, name) })
+    }
+
+    #[test]
+    fn events_are_held_until_the_sink_is_available() {
+        let sink = TestSink::default();
+        *sink.down.lock().unwrap() = true;
+        let mut buffer = SinkBuffer::new("test".to_string(), Box::new(sink.clone()), 10);
+        let start = Instant::now();
+
+        buffer.push(event("service-started"));
+        buffer.flush(start);
+        buffer.push(event("service-stopped"));
+        buffer.flush(start);
+        assert!(sink.delivered.lock().unwrap().is_empty());
+        assert_eq!(buffer.events.len(), 2);
+
+        *sink.down.lock().unwrap() = false;
+        // Not yet time to retry
+        buffer.flush(start);
+        assert!(sink.delivered.lock().unwrap().is_empty());
+
+        buffer.flush(start + MIN_BACKOFF);
+        assert_eq!(*sink.delivered.lock().unwrap(),
+                   vec!["service-started", "service-stopped"]);
+        assert!(buffer.events.is_empty());
+        assert!(buffer.retry_at.is_none());
+    }
+
+    #[test]
+    fn oldest_events_are_dropped_when_the_buffer_is_full() {
+        let sink = TestSink::default();
+        *sink.down.lock().unwrap() = true;
+        let mut buffer = SinkBuffer::new("test".to_string(), Box::new(sink.clone()), 2);
+
+        buffer.push(event("first"));
+        buffer.push(event("second"));
+        buffer.push(event("third"));
+        *sink.down.lock().unwrap() = false;
+        buffer.flush(Instant::now());
+
+        assert_eq!(*sink.delivered.lock().unwrap(), vec!["second", "third"]);
+        assert_eq!(buffer.dropped, 0);
+    }
+
+    #[test]
+    fn new_events_are_dropped_while_the_sink_thread_falls_behind() {
+        let (tx, rx) = mpsc::sync_channel(2);
+        let sink = BufferedSink { name: "test".to_string(),
+                                  tx,
+                                  dropped: AtomicUsize::new(0) };
+
+        sink.send(event("first"));
+        sink.send(event("second"));
+        sink.send(event("third"));
+        assert_eq!(sink.dropped.load(Ordering::Relaxed), 1);
+
+        let received: Vec<_> = rx.try_iter().map(|e| e.name).collect();
+        assert_eq!(received, vec!["first", "second"]);
+
+        sink.send(event("fourth"));
+        assert_eq!(sink.dropped.load(Ordering::Relaxed), 0);
+        assert_eq!(rx.try_recv().unwrap().name, "fourth");
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_a_maximum() {
+        let mut backoff = Backoff::default();
+        assert_eq!(backoff.next_delay(), MIN_BACKOFF);
+        assert_eq!(backoff.next_delay(), MIN_BACKOFF * 2);
+        for _ in 0..10 {
+            backoff.next_delay();
+        }
+        assert_eq!(backoff.next_delay(), MAX_BACKOFF);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), MIN_BACKOFF);
+    }
+
+    #[test]
+    fn file_sink_appends_json_lines() {
+        let dir = TempDir::new().expect("Could not create tempdir");
+        let path = dir.path().join("events.log");
+        let mut sink = FileSink::new(path.clone());
+
+        sink.send(&event("service-started")).unwrap();
+        sink.send(&event("service-stopped")).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(),
+                   "{\"name\":\"service-started\"}\n{\"name\":\"service-stopped\"}\n");
+    }
+}
@@ -2,10 +2,17 @@
 //! Supervisor.
 
 use super::EventCore;
-use crate::manager::service::{HealthCheckResult as DomainHealthCheckResult,
-                              Service,
-                              UpdateStrategy as DomainUpdateStrategy};
+use crate::{audit::Outcome,
+            manager::service::{HealthCheckResult as DomainHealthCheckResult,
+                               Service,
+                               UpdateStrategy as DomainUpdateStrategy}};
+use habitat_butterfly::member::Health;
 use prost::Message;
+use prost_types::{self,
+                  Timestamp};
+use serde::{Serialize,
+            Serializer};
+use serde_json;
 
 include!(concat!(env!("OUT_DIR"), "/chef.habitat.supervisor.event.rs"));
 
@@ -23,6 +30,27 @@ impl Into<HealthCheckResult> for DomainHealthCheckResult {
     }
 }
 
+impl Into<AuditOutcome> for Outcome {
+    fn into(self) -> AuditOutcome {
+        match self {
+            Outcome::Succeeded => AuditOutcome::Succeeded,
+            Outcome::Failed => AuditOutcome::Failed,
+            Outcome::Denied => AuditOutcome::Denied,
+        }
+    }
+}
+
+impl Into<MemberHealth> for Health {
+    fn into(self) -> MemberHealth {
+        match self {
+            Health::Alive => MemberHealth::Alive,
+            Health::Suspect => MemberHealth::Suspect,
+            Health::Confirmed => MemberHealth::Confirmed,
+            Health::Departed => MemberHealth::Departed,
+        }
+    }
+}
+
 impl Service {
     /// Create a protobuf metadata struct for Service-related event
     /// messages.
@@ -50,6 +78,7 @@ impl Service {
             }
             DomainUpdateStrategy::AtOnce => UpdateStrategy::AtOnce,
             DomainUpdateStrategy::Rolling => UpdateStrategy::Rolling,
+            DomainUpdateStrategy::Canary => UpdateStrategy::Canary,
         };
 
         Some(UpdateConfig { strategy: strategy.into(),
@@ -72,12 +101,16 @@ impl EventCore {
     }
 }
 
-pub trait EventMessage: Message + Sized {
+pub trait EventMessage: Message + Serialize + Sized {
     /// All messages will have some top-level metadata about the
     /// Supervisor they come from. This function allows us to set it
     /// generically when we send the message out.
     fn event_metadata(&mut self, event_metadata: EventMetadata);
 
+    /// The name local subscribers see the event under, e.g.,
+    /// `service-started`.
+    fn event_name(&self) -> &'static str;
+
     /// Convert a message to bytes for sending to NATS.
     fn to_bytes(&self) -> Vec<u8> {
         let mut buf = bytes::BytesMut::with_capacity(self.encoded_len());
@@ -88,25 +121,240 @@ pub trait EventMessage: Message + Sized {
             .expect("UNEXPECTED PROST ERROR: encoded_len() was not long enough!");
         buf.to_vec()
     }
+
+    /// Convert a message to JSON for local subscribers.
+    fn to_json(&self) -> String {
+        // unwrap is safe, as the serializers for every field are
+        // infallible
+        serde_json::to_string(self).unwrap()
+    }
 }
 
-// TODO (CM): these are repetitive, and will only get more so as we
-// add events. Consider implementing via macro instead.
+macro_rules! impl_event_message {
+    ($($event:ident => $name:expr),* $(,)*) => {
+        $(
+            impl EventMessage for $event {
+                fn event_metadata(&mut self, event_metadata: EventMetadata) {
+                    self.event_metadata = Some(event_metadata);
+                }
 
-impl EventMessage for ServiceStartedEvent {
-    fn event_metadata(&mut self, event_metadata: EventMetadata) {
-        self.event_metadata = Some(event_metadata);
+                fn event_name(&self) -> &'static str { $name }
+            }
+        )*
+    };
+}
+
+impl_event_message! {
+    ServiceStartedEvent => "service-started",
+    ServiceStoppedEvent => "service-stopped",
+    HealthCheckEvent => "health-check",
+    ServiceCrashLoopingEvent => "service-crash-looping",
+    ServiceUpdateDetectedEvent => "service-update-detected",
+    ServiceUpdateAppliedEvent => "service-update-applied",
+    ServiceUpdateRolledBackEvent => "service-update-rolled-back",
+    HookExecutedEvent => "hook-executed",
+    ElectionStartedEvent => "election-started",
+    ElectionFinishedEvent => "election-finished",
+    ServiceConfigUpdatedEvent => "service-config-updated",
+    ServiceFileUpdatedEvent => "service-file-updated",
+    MemberHealthChangedEvent => "member-health-changed",
+    SupervisorUpdatedEvent => "supervisor-updated",
+    AuditEvent => "audit",
+}
+
+////////////////////////////////////////////////////////////////////////
+// JSON serializers for fields of the generated types, named in
+// build.rs.
+
+fn serialize_timestamp<S>(timestamp: &Option<Timestamp>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    match timestamp {
+        Some(t) => {
+            let tm = time_crate::at_utc(time_crate::Timespec::new(t.seconds, t.nanos));
+            serializer.serialize_str(&tm.rfc3339().to_string())
+        }
+        None => serializer.serialize_none(),
     }
 }
 
-impl EventMessage for ServiceStoppedEvent {
-    fn event_metadata(&mut self, event_metadata: EventMetadata) {
-        self.event_metadata = Some(event_metadata);
+/// Durations are given in (fractional) seconds.
+fn serialize_duration<S>(duration: &Option<prost_types::Duration>,
+                         serializer: S)
+                         -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    match duration {
+        Some(d) => serializer.serialize_f64(d.seconds as f64 + f64::from(d.nanos) / 1e9),
+        None => serializer.serialize_none(),
     }
 }
 
-impl EventMessage for HealthCheckEvent {
-    fn event_metadata(&mut self, event_metadata: EventMetadata) {
-        self.event_metadata = Some(event_metadata);
+fn serialize_health_check_result<S>(value: &i32, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let name = HealthCheckResult::from_i32(*value).map(|result| {
+                                                      match result {
+                                                          HealthCheckResult::Ok => "ok",
+                                                          HealthCheckResult::Warning => "warning",
+                                                          HealthCheckResult::Critical => "critical",
+                                                          HealthCheckResult::Unknown => "unknown",
+                                                      }
+                                                  });
+    serialize_enum(*value, name, serializer)
+}
+
+fn serialize_update_strategy<S>(value: &i32, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let name = UpdateStrategy::from_i32(*value).map(|strategy| {
+                                                   match strategy {
+                                                       UpdateStrategy::AtOnce => "at-once",
+                                                       UpdateStrategy::Rolling => "rolling",
+                                                       UpdateStrategy::Canary => "canary",
+                                                   }
+                                               });
+    serialize_enum(*value, name, serializer)
+}
+
+fn serialize_audit_outcome<S>(value: &i32, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let name = AuditOutcome::from_i32(*value).map(|outcome| {
+                                                 match outcome {
+                                                     AuditOutcome::Succeeded => "succeeded",
+                                                     AuditOutcome::Failed => "failed",
+                                                     AuditOutcome::Denied => "denied",
+                                                 }
+                                             });
+    serialize_enum(*value, name, serializer)
+}
+
+fn serialize_member_health<S>(value: &i32, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let name = MemberHealth::from_i32(*value).map(|health| {
+                                                 match health {
+                                                     MemberHealth::Alive => "alive",
+                                                     MemberHealth::Suspect => "suspect",
+                                                     MemberHealth::Confirmed => "confirmed",
+                                                     MemberHealth::Departed => "departed",
+                                                 }
+                                             });
+    serialize_enum(*value, name, serializer)
+}
+
+/// Enumerations are given by name, falling back to their number
+/// should a value be unknown.
+fn serialize_enum<S>(value: i32, name: Option<&str>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    match name {
+        Some(name) => serializer.serialize_str(name),
+        None => serializer.serialize_i32(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json,
+                     Value};
+    use std::time::Duration;
+
+    fn to_value(event: &impl EventMessage) -> Value {
+        serde_json::from_str(&event.to_json()).expect("events serialize to valid JSON")
+    }
+
+    #[test]
+    fn hook_executed_events_give_the_exit_code_and_duration() {
+        let event = HookExecutedEvent { service_metadata: None,
+                                        event_metadata:   None,
+                                        hook:             "init".to_string(),
+                                        exit_code:        Some(1),
+                                        execution:        Some(Duration::from_millis(1500).into()), };
+
+        assert_eq!(event.event_name(), "hook-executed");
+        let value = to_value(&event);
+        assert_eq!(value["hook"], json!("init"));
+        assert_eq!(value["exit_code"], json!(1));
+        assert_eq!(value["execution"], json!(1.5));
+    }
+
+    #[test]
+    fn hook_executed_events_without_an_exit_code_give_null() {
+        let event = HookExecutedEvent { service_metadata: None,
+                                        event_metadata:   None,
+                                        hook:             "run".to_string(),
+                                        exit_code:        None,
+                                        execution:        None, };
+
+        assert_eq!(to_value(&event)["exit_code"], Value::Null);
+    }
+
+    #[test]
+    fn election_events_are_named_and_give_the_leader() {
+        let started = ElectionStartedEvent { service_metadata: None,
+                                             event_metadata:   None, };
+        assert_eq!(started.event_name(), "election-started");
+
+        let finished = ElectionFinishedEvent { service_metadata: None,
+                                               event_metadata:   None,
+                                               leader_id:        "abc123".to_string(),
+                                               won:              true, };
+        assert_eq!(finished.event_name(), "election-finished");
+        let value = to_value(&finished);
+        assert_eq!(value["leader_id"], json!("abc123"));
+        assert_eq!(value["won"], json!(true));
+    }
+
+    #[test]
+    fn member_health_changed_events_give_the_health_by_name() {
+        for (health, name) in &[(Health::Alive, "alive"),
+                                (Health::Suspect, "suspect"),
+                                (Health::Confirmed, "confirmed"),
+                                (Health::Departed, "departed")]
+        {
+            let health: MemberHealth = (*health).into();
+            let event = MemberHealthChangedEvent { event_metadata: None,
+                                                   member_id:      "abc123".to_string(),
+                                                   health:         i32::from(health), };
+
+            assert_eq!(event.event_name(), "member-health-changed");
+            let value = to_value(&event);
+            assert_eq!(value["member_id"], json!("abc123"));
+            assert_eq!(value["health"], json!(name));
+        }
+    }
+
+    #[test]
+    fn unknown_enumeration_values_are_given_by_number() {
+        let event = MemberHealthChangedEvent { event_metadata: None,
+                                               member_id:      "abc123".to_string(),
+                                               health:         42, };
+
+        assert_eq!(to_value(&event)["health"], json!(42));
+    }
+
+    #[test]
+    fn config_and_file_updates_give_their_author() {
+        let config = ServiceConfigUpdatedEvent { service_metadata: None,
+                                                 event_metadata:   None,
+                                                 incarnation:      7,
+                                                 author:           "ops".to_string(), };
+        assert_eq!(config.event_name(), "service-config-updated");
+        let value = to_value(&config);
+        assert_eq!(value["incarnation"], json!(7));
+        assert_eq!(value["author"], json!("ops"));
+
+        let file = ServiceFileUpdatedEvent { service_metadata: None,
+                                             event_metadata:   None,
+                                             filename:         "app.pem".to_string(),
+                                             incarnation:      3,
+                                             author:           String::new(), };
+        assert_eq!(file.event_name(), "service-file-updated");
+        let value = to_value(&file);
+        assert_eq!(value["filename"], json!("app.pem"));
+        assert_eq!(value["author"], json!(""));
     }
 }
@@ -1,10 +1,20 @@
-use crate::{error::{Result,
+use crate::{audit::{AuditLog,
+                    AuditRecord,
+                    Outcome},
+            ctl_gateway::server::{CtlCommand,
+                                  CtlReceiver,
+                                  MgrSender},
+            error::{Result,
                     SupError},
+            event::{self,
+                    LocalEvent},
             manager::{self,
                       service::{HealthCheckHook,
-                                HealthCheckResult}}};
+                                HealthCheckResult,
+                                ReadyHook}}};
 use actix;
-use actix_web::{http::{self,
+use actix_web::{error::ErrorInternalServerError,
+                http::{self,
                        StatusCode},
                 middleware::{Finished,
                              Middleware,
@@ -12,11 +22,21 @@ use actix_web::{http::{self,
                 pred::Predicate,
                 server,
                 App,
+                AsyncResponder,
                 FromRequest,
+                FutureResponse,
+                HttpMessage,
                 HttpRequest,
                 HttpResponse,
                 Path,
                 Request};
+use bytes::Bytes;
+use futures::{future,
+              sync::mpsc,
+              Async,
+              Future,
+              Poll,
+              Stream};
 use habitat_common::{cli::{LISTEN_HTTP_ADDRESS_ENVVAR,
                            LISTEN_HTTP_DEFAULT_IP,
                            LISTEN_HTTP_DEFAULT_PORT},
@@ -25,7 +45,21 @@ use habitat_common::{cli::{LISTEN_HTTP_ADDRESS_ENVVAR,
 use habitat_core::{crypto,
                    env as henv,
                    env::Config as EnvConfig,
-                   service::ServiceGroup};
+                   package::PackageIdent,
+                   service::{parse_cpu_limit,
+                             parse_memory_limit,
+                             parse_pids_limit,
+                             ApplicationEnvironment,
+                             ResourceLimits,
+                             ServiceGroup,
+                             UpdateWindow}};
+use habitat_sup_protocol::{self as protocol,
+                           codec::{SrvMessage,
+                                   SrvTxn},
+                           net::{self,
+                                 ErrCode,
+                                 NetErr,
+                                 NetResult}};
 use prometheus::{self,
                  CounterVec,
                  Encoder,
@@ -40,6 +74,7 @@ use std::{cell::Cell,
           fs::File,
           io::{self,
                Read},
+          mem,
           net::{IpAddr,
                 SocketAddr,
                 SocketAddrV4,
@@ -54,6 +89,7 @@ use std::{cell::Cell,
                  Mutex,
                  RwLock},
           thread};
+use toml;
 
 const APIDOCS: &str = include_str!(concat!(env!("OUT_DIR"), "/api.html"));
 pub const HTTP_THREADS_ENVVAR: &str = "HAB_SUP_HTTP_THREADS";
@@ -62,6 +98,9 @@ pub const HTTP_THREAD_COUNT: usize = 2;
 /// Default listening port for the HTTPGateway listener.
 pub const DEFAULT_PORT: u16 = 9631;
 
+/// Name recorded in the audit log for requests made through the HTTP gateway's write endpoints.
+const AUDIT_CREDENTIAL_NAME: &str = "http-gateway";
+
 lazy_static! {
     static ref HTTP_GATEWAY_REQUESTS: CounterVec =
         register_counter_vec!("hab_sup_http_gateway_requests_total",
@@ -149,6 +188,178 @@ struct HealthCheckBody {
     stderr: String,
 }
 
+#[derive(Default, Serialize)]
+struct ReadyBody {
+    ready:  bool,
+    stdout: String,
+    stderr: String,
+}
+
+/// Body of a request to load a service. Its fields mirror those of `SvcLoad`, with enumerations
+/// and identifiers given as they would be on the command line. Unknown fields are refused,
+/// rather than silently ignored.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoadBody {
+    ident:                   String,
+    /// As `APPLICATION.ENVIRONMENT`.
+    application_environment: Option<String>,
+    group:                   Option<String>,
+    bldr_url:                Option<String>,
+    bldr_channel:            Option<String>,
+    #[serde(default)]
+    binds:                   Vec<String>,
+    binding_mode:            Option<String>,
+    topology:                Option<String>,
+    update_strategy:         Option<String>,
+    update_window:           Option<String>,
+    restart_policy:          Option<String>,
+    max_restarts:            Option<u32>,
+    restart_window:          Option<u64>,
+    max_restart_backoff:     Option<u64>,
+    health_check_interval:   Option<u64>,
+    resource_limits:         Option<ResourceLimitsBody>,
+    health_check:            Option<protocol::types::HealthCheck>,
+    /// Refused, as it names a path on the Supervisor's own host.
+    config_from:             Option<String>,
+    /// Refused, as only `hab` on the Supervisor's own host can encrypt the password.
+    svc_encrypted_password:  Option<String>,
+    #[serde(default)]
+    force:                   bool,
+}
+
+/// Resource limits for a service, given as they would be to `--cpu-limit`, `--memory-limit` and
+/// `--pids-limit`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ResourceLimitsBody {
+    cpu:    Option<String>,
+    memory: Option<String>,
+    pids:   Option<String>,
+}
+
+impl ResourceLimitsBody {
+    fn into_msg(self) -> habitat_core::Result<protocol::types::ResourceLimits> {
+        let mut limits = ResourceLimits::default();
+        if let Some(ref cpu) = self.cpu {
+            limits.cpu_millis = Some(parse_cpu_limit(cpu)?);
+        }
+        if let Some(ref memory) = self.memory {
+            limits.memory_bytes = Some(parse_memory_limit(memory)?);
+        }
+        if let Some(ref pids) = self.pids {
+            limits.pids = Some(parse_pids_limit(pids)?);
+        }
+        Ok(limits.into())
+    }
+}
+
+fn invalid_payload(err: habitat_core::Error) -> NetErr {
+    net::err(ErrCode::InvalidPayload, err.to_string())
+}
+
+impl LoadBody {
+    fn into_msg(self) -> NetResult<protocol::ctl::SvcLoad> {
+        let ident = PackageIdent::from_str(&self.ident).map_err(|e| {
+                                                           net::err(ErrCode::InvalidPayload,
+                                                                    e.to_string())
+                                                       })?;
+        if self.config_from.is_some() {
+            return Err(net::err(ErrCode::InvalidPayload,
+                                "config_from may only be set with `hab svc load` \
+                                 on the Supervisor's host"));
+        }
+        if self.svc_encrypted_password.is_some() {
+            return Err(net::err(ErrCode::InvalidPayload,
+                                "svc_encrypted_password may only be set with \
+                                 `hab svc load` on the Supervisor's host"));
+        }
+        let mut msg = protocol::ctl::SvcLoad::default();
+        msg.ident = Some(ident.into());
+        if let Some(ref app_env) = self.application_environment {
+            let app_env = ApplicationEnvironment::from_str(app_env).map_err(invalid_payload)?;
+            msg.application_environment = Some(app_env.into());
+        }
+        msg.group = self.group;
+        msg.bldr_url = self.bldr_url;
+        msg.bldr_channel = self.bldr_channel;
+        if !self.binds.is_empty() {
+            let mut list = protocol::ctl::ServiceBindList::default();
+            for bind in &self.binds {
+                list.binds
+                    .push(protocol::types::ServiceBind::from_str(bind)?);
+            }
+            msg.binds = Some(list);
+        }
+        if let Some(ref mode) = self.binding_mode {
+            msg.binding_mode = Some(protocol::types::BindingMode::from_str(mode)? as i32);
+        }
+        if let Some(ref topology) = self.topology {
+            msg.topology = Some(protocol::types::Topology::from_str(topology)? as i32);
+        }
+        if let Some(ref strategy) = self.update_strategy {
+            msg.update_strategy = Some(protocol::types::UpdateStrategy::from_str(strategy)? as i32);
+        }
+        if let Some(ref window) = self.update_window {
+            UpdateWindow::from_str(window).map_err(invalid_payload)?;
+        }
+        msg.update_window = self.update_window;
+        if let Some(ref policy) = self.restart_policy {
+            msg.restart_policy = Some(protocol::types::RestartPolicy::from_str(policy)? as i32);
+        }
+        msg.max_restarts = self.max_restarts;
+        msg.restart_window = self.restart_window;
+        msg.max_restart_backoff = self.max_restart_backoff;
+        msg.health_check_interval =
+            self.health_check_interval
+                .map(|seconds| protocol::types::HealthCheckInterval { seconds });
+        if let Some(limits) = self.resource_limits {
+            msg.resource_limits = Some(limits.into_msg().map_err(invalid_payload)?);
+        }
+        if let Some(check) = self.health_check {
+            check.parse().map_err(invalid_payload)?;
+            msg.health_check = Some(check);
+        }
+        if self.force {
+            msg.force = Some(true);
+        }
+        Ok(msg)
+    }
+}
+
+/// Body of a request to apply configuration to a service group, mirroring `SvcSetCfg`. The
+/// configuration is given as a JSON object, and gossiped as the equivalent TOML.
+#[derive(Debug, Deserialize)]
+struct ApplyConfigBody {
+    version: u64,
+    cfg:     toml::value::Table,
+}
+
+impl ApplyConfigBody {
+    fn into_msg(self, service_group: ServiceGroup) -> NetResult<protocol::ctl::SvcSetCfg> {
+        let cfg =
+            toml::to_string(&toml::Value::Table(self.cfg)).map_err(|e| {
+                                                              net::err(ErrCode::InvalidPayload,
+                                                                       e.to_string())
+                                                          })?;
+        Ok(protocol::ctl::SvcSetCfg { service_group: Some(service_group.into()),
+                                      cfg:           Some(cfg.into_bytes()),
+                                      version:       Some(self.version),
+                                      is_encrypted:  Some(false),
+                                      signer:        None,
+                                      signature:     None, })
+    }
+}
+
+/// Response to a request made through one of the write endpoints.
+#[derive(Default, Serialize)]
+struct CommandBody {
+    /// Lines the Supervisor output while handling the request.
+    output: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error:  Option<String>,
+}
+
 impl Into<StatusCode> for HealthCheckResult {
     fn into(self) -> StatusCode {
         match self {
@@ -164,19 +375,26 @@ struct AppState {
     authentication_token: Option<String>,
     timer:                Cell<Option<HistogramTimer>>,
     feature_flags:        FeatureFlag,
+    /// Dispatches requests from the write endpoints to the Manager, as the CtlGateway does.
+    mgr_sender:           MgrSender,
+    audit_log:            AuditLog,
 }
 
 impl AppState {
     fn new(gs: Arc<RwLock<manager::GatewayState>>,
            authentication_token: GatewayAuthenticationToken,
-           feature_flags: FeatureFlag)
+           feature_flags: FeatureFlag,
+           mgr_sender: MgrSender,
+           audit_log: AuditLog)
            -> Self {
         AppState { gateway_state: gs,
                    // We'll unwrap to the inner type, since the
                    // GatewayAuthenticationToken type has done its job by this point.
                    authentication_token: authentication_token.0,
                    timer: Cell::new(None),
-                   feature_flags }
+                   feature_flags,
+                   mgr_sender,
+                   audit_log }
     }
 }
 
@@ -266,6 +484,8 @@ impl Server {
                gateway_state: Arc<RwLock<manager::GatewayState>>,
                authentication_token: GatewayAuthenticationToken,
                feature_flags: FeatureFlag,
+               mgr_sender: MgrSender,
+               audit_log: AuditLog,
                control: Arc<(Mutex<ServerStartup>, Condvar)>) {
         thread::spawn(move || {
             let &(ref lock, ref cvar) = &*control;
@@ -283,7 +503,9 @@ impl Server {
             let mut server = server::new(move || {
                                  let app_state = AppState::new(gateway_state.clone(),
                                                                authentication_token.clone(),
-                                                               feature_flags);
+                                                               feature_flags,
+                                                               mgr_sender.clone(),
+                                                               audit_log.clone());
                                  App::with_state(app_state).middleware(Authentication)
                                                            .middleware(Metrics)
                                                            .configure(routes)
@@ -335,27 +557,53 @@ impl Predicate<AppState> for RedactHTTP {
 
 fn routes(app: App<AppState>) -> App<AppState> {
     app.resource("/", |r| r.get().f(doc))
-       .resource("/services", |r| r.get().f(services))
+       .resource("/services", |r| {
+           r.get().f(services);
+           r.post().f(load);
+       })
        .resource("/services/{svc}/{group}", |r| {
-           r.get().f(service_without_org)
+           r.get().f(service_without_org);
+           r.delete().f(unload_without_org);
        })
        .resource("/services/{svc}/{group}/config", |r| {
-           r.get().f(config_without_org)
+           r.get().f(config_without_org);
+           r.post().f(apply_config_without_org);
        })
        .resource("/services/{svc}/{group}/health", |r| {
            r.get().f(health_without_org)
        })
+       .resource("/services/{svc}/{group}/ready", |r| {
+           r.get().f(ready_without_org)
+       })
+       .resource("/services/{svc}/{group}/start", |r| {
+           r.post().f(start_without_org)
+       })
+       .resource("/services/{svc}/{group}/stop", |r| {
+           r.post().f(stop_without_org)
+       })
        .resource("/services/{svc}/{group}/{org}", |r| {
-           r.get().f(service_with_org)
+           r.get().f(service_with_org);
+           r.delete().f(unload_with_org);
        })
        .resource("/services/{svc}/{group}/{org}/config", |r| {
-           r.get().f(config_with_org)
+           r.get().f(config_with_org);
+           r.post().f(apply_config_with_org);
        })
        .resource("/services/{svc}/{group}/{org}/health", |r| {
            r.get().f(health_with_org)
        })
+       .resource("/services/{svc}/{group}/{org}/ready", |r| {
+           r.get().f(ready_with_org)
+       })
+       .resource("/services/{svc}/{group}/{org}/start", |r| {
+           r.post().f(start_with_org)
+       })
+       .resource("/services/{svc}/{group}/{org}/stop", |r| {
+           r.post().f(stop_with_org)
+       })
        .resource("/butterfly", |r| r.get().filter(RedactHTTP).f(butterfly))
        .resource("/census", |r| r.get().filter(RedactHTTP).f(census))
+       .resource("/events", |r| r.get().filter(RedactHTTP).f(events))
        .resource("/metrics", |r| r.get().f(metrics))
 }
 
@@ -383,6 +631,24 @@ fn census(req: &HttpRequest<AppState>) -> HttpResponse {
     json_response(data.to_string())
 }
 
+/// Stream the Supervisor's events to the client as server-sent events
+/// until it disconnects.
+fn events(_req: &HttpRequest<AppState>) -> HttpResponse {
+    let stream =
+        event::subscribe().map(|event| event_frame(&event))
+                          .map_err(|()| ErrorInternalServerError("Event subscription closed"));
+    HttpResponse::Ok().content_type("text/event-stream")
+                      .header(http::header::CACHE_CONTROL, "no-cache")
+                      .streaming(stream)
+}
+
+/// A server-sent event named for the kind of event, whose data is the
+/// event as JSON. The JSON never contains newlines, so it fits on a
+/// single `data` line.
+fn event_frame(event: &LocalEvent) -> Bytes {
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event.name, event.json))
+}
+
 fn services(req: &HttpRequest<AppState>) -> HttpResponse {
     let data = &req.state()
                    .gateway_state
@@ -477,6 +743,62 @@ fn health(req: &HttpRequest<AppState>,
     }
 }
 
+fn ready_with_org(req: &HttpRequest<AppState>) -> HttpResponse {
+    let (svc, group, org) = Path::<(String, String, String)>::extract(&req).unwrap()
+                                                                           .into_inner();
+    ready(req, svc, group, Some(&org))
+}
+
+fn ready_without_org(req: &HttpRequest<AppState>) -> HttpResponse {
+    let (svc, group) = Path::<(String, String)>::extract(&req).unwrap()
+                                                              .into_inner();
+    ready(req, svc, group, None)
+}
+
+fn ready(req: &HttpRequest<AppState>,
+         svc: String,
+         group: String,
+         org: Option<&str>)
+         -> HttpResponse {
+    let service_group = match ServiceGroup::new(None, svc, group, org) {
+        Ok(sg) => sg,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let gateway_state = &req.state()
+                            .gateway_state
+                            .read()
+                            .expect("GatewayState lock is poisoned");
+
+    match gateway_state.readiness_data.get(&service_group) {
+        Some(&ready) => {
+            let mut body = ReadyBody::default();
+            let stdout_path = hooks::stdout_log_path::<ReadyHook>(&service_group);
+            let stderr_path = hooks::stderr_log_path::<ReadyHook>(&service_group);
+            let http_status = if ready {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+
+            body.ready = ready;
+            if let Ok(mut file) = File::open(&stdout_path) {
+                let _ = file.read_to_string(&mut body.stdout);
+            }
+            if let Ok(mut file) = File::open(&stderr_path) {
+                let _ = file.read_to_string(&mut body.stderr);
+            }
+
+            HttpResponse::build(http_status).json(&body)
+        }
+        None => {
+            debug!("Didn't find any readiness data for service group {:?}",
+                   &service_group);
+            HttpResponse::NotFound().finish()
+        }
+    }
+}
+
 fn service_with_org(req: &HttpRequest<AppState>) -> HttpResponse {
     let (svc, group, org) = Path::<(String, String, String)>::extract(&req).unwrap()
                                                                            .into_inner();
@@ -534,8 +856,292 @@ fn metrics(_req: &HttpRequest<AppState>) -> HttpResponse {
 fn doc(_req: &HttpRequest<AppState>) -> HttpResponse {
     HttpResponse::Ok().content_type("text/html").body(APIDOCS)
 }
+
+fn load(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let request = req.clone();
+    req.json()
+       .from_err()
+       .and_then(move |body: LoadBody| {
+           match body.into_msg() {
+               Ok(msg) => dispatch(&request, msg),
+               Err(err) => error_response(err),
+           }
+       })
+       .responder()
+}
+
+fn unload_with_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group, org) = Path::<(String, String, String)>::extract(&req).unwrap()
+                                                                           .into_inner();
+    unload(req, svc, group, Some(&org))
+}
+
+fn unload_without_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group) = Path::<(String, String)>::extract(&req).unwrap()
+                                                              .into_inner();
+    unload(req, svc, group, None)
+}
+
+fn unload(req: &HttpRequest<AppState>,
+          svc: String,
+          group: String,
+          org: Option<&str>)
+          -> FutureResponse<HttpResponse> {
+    match service_ident(req, svc, group, org) {
+        Ok(ident) => {
+            dispatch(req,
+                     protocol::ctl::SvcUnload { ident: Some(ident),
+                                                ..Default::default() })
+        }
+        Err(err) => error_response(err),
+    }
+}
+
+fn start_with_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group, org) = Path::<(String, String, String)>::extract(&req).unwrap()
+                                                                           .into_inner();
+    start(req, svc, group, Some(&org))
+}
+
+fn start_without_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group) = Path::<(String, String)>::extract(&req).unwrap()
+                                                              .into_inner();
+    start(req, svc, group, None)
+}
+
+fn start(req: &HttpRequest<AppState>,
+         svc: String,
+         group: String,
+         org: Option<&str>)
+         -> FutureResponse<HttpResponse> {
+    match service_ident(req, svc, group, org) {
+        Ok(ident) => dispatch(req, protocol::ctl::SvcStart { ident: Some(ident) }),
+        Err(err) => error_response(err),
+    }
+}
+
+fn stop_with_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group, org) = Path::<(String, String, String)>::extract(&req).unwrap()
+                                                                           .into_inner();
+    stop(req, svc, group, Some(&org))
+}
+
+fn stop_without_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group) = Path::<(String, String)>::extract(&req).unwrap()
+                                                              .into_inner();
+    stop(req, svc, group, None)
+}
+
+fn stop(req: &HttpRequest<AppState>,
+        svc: String,
+        group: String,
+        org: Option<&str>)
+        -> FutureResponse<HttpResponse> {
+    match service_ident(req, svc, group, org) {
+        Ok(ident) => {
+            dispatch(req,
+                     protocol::ctl::SvcStop { ident: Some(ident),
+                                              ..Default::default() })
+        }
+        Err(err) => error_response(err),
+    }
+}
+
+fn apply_config_with_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group, org) = Path::<(String, String, String)>::extract(&req).unwrap()
+                                                                           .into_inner();
+    apply_config(req, svc, group, Some(&org))
+}
+
+fn apply_config_without_org(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let (svc, group) = Path::<(String, String)>::extract(&req).unwrap()
+                                                              .into_inner();
+    apply_config(req, svc, group, None)
+}
+
+fn apply_config(req: &HttpRequest<AppState>,
+                svc: String,
+                group: String,
+                org: Option<&str>)
+                -> FutureResponse<HttpResponse> {
+    let service_group = match ServiceGroup::new(None, svc, group, org) {
+        Ok(sg) => sg,
+        Err(e) => return error_response(net::err(ErrCode::InvalidPayload, e.to_string())),
+    };
+    if req.state()
+          .gateway_state
+          .read()
+          .expect("GatewayState lock is poisoned")
+          .config_requires_author
+    {
+        return error_response(net::err(ErrCode::Unauthorized,
+                                       "This Supervisor only applies configuration signed by a \
+                                        trusted user key, which the HTTP gateway cannot sign; \
+                                        apply it with `hab config apply --sign` instead"));
+    }
+    let request = req.clone();
+    req.json()
+       .from_err()
+       .and_then(move |body: ApplyConfigBody| {
+           match body.into_msg(service_group) {
+               Ok(msg) => dispatch(&request, msg),
+               Err(err) => error_response(err),
+           }
+       })
+       .responder()
+}
 // End route handlers
 
+/// Send a request to the Manager just as the CtlGateway would for one of its clients, and
+/// respond with its outcome once the Manager has finished with it. Requests are refused unless
+/// the gateway requires an authentication token.
+fn dispatch<T>(req: &HttpRequest<AppState>, msg: T) -> FutureResponse<HttpResponse>
+    where T: Into<SrvMessage>
+{
+    if req.state().authentication_token.is_none() {
+        return error_response(net::err(ErrCode::Unauthorized,
+                                       format!("Changes through the HTTP gateway require {} \
+                                                to be set",
+                                               GatewayAuthenticationToken::ENVVAR)));
+    }
+
+    let mut msg: SrvMessage = msg.into();
+    // Replies come back over this request's own channel, so any transaction will do.
+    msg.set_transaction(SrvTxn::from(1));
+    let (ctl_sender, ctl_receiver) = mpsc::unbounded();
+    let cmd = match CtlCommand::from_message(&msg, ctl_sender) {
+        Ok(cmd) => cmd,
+        Err(e) => return error_response(net::err(ErrCode::Internal, e.to_string())),
+    };
+    if req.state().mgr_sender.unbounded_send(cmd).is_err() {
+        return error_response(net::err(ErrCode::Internal, "Supervisor is shutting down"));
+    }
+
+    let audit_log = req.state().audit_log.clone();
+    let peer_addr = req.peer_addr();
+    let message_id = msg.message_id().to_string();
+    let target = CtlCommand::audit_target(&msg);
+    CtlReply::new(ctl_receiver).map(move |(result, output)| {
+                                   let (outcome, detail) = match result {
+                                       Ok(()) => (Outcome::Succeeded, None),
+                                       Err(ref err) => (Outcome::Failed, Some(err.msg.clone())),
+                                   };
+                                   audit_log.record(&AuditRecord::ctl(peer_addr,
+                                                                      AUDIT_CREDENTIAL_NAME,
+                                                                      &message_id,
+                                                                      target,
+                                                                      outcome,
+                                                                      detail));
+                                   command_response(result, output)
+                               })
+                               .responder()
+}
+
+fn command_response(result: NetResult<()>, output: Vec<String>) -> HttpResponse {
+    match result {
+        Ok(()) => {
+            HttpResponse::Ok().json(CommandBody { output,
+                                                  error: None })
+        }
+        Err(err) => {
+            HttpResponse::build(error_status(&err)).json(CommandBody { output,
+                                                                       error: Some(err.msg) })
+        }
+    }
+}
+
+fn error_response(err: NetErr) -> FutureResponse<HttpResponse> {
+    Box::new(future::ok(command_response(Err(err), Vec::new())))
+}
+
+fn error_status(err: &NetErr) -> StatusCode {
+    match ErrCode::from_i32(err.code) {
+        Some(ErrCode::NotFound) => StatusCode::NOT_FOUND,
+        Some(ErrCode::Conflict) => StatusCode::CONFLICT,
+        Some(ErrCode::Unauthorized) => StatusCode::FORBIDDEN,
+        Some(ErrCode::BadPayload) | Some(ErrCode::InvalidPayload) | Some(ErrCode::UpdateClient) => {
+            StatusCode::BAD_REQUEST
+        }
+        Some(ErrCode::NotSupported) => StatusCode::NOT_IMPLEMENTED,
+        Some(ErrCode::EntityTooLarge) => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// The identifier the service in the given service group was loaded with, which the
+/// CtlGateway messages use to name it.
+fn service_ident(req: &HttpRequest<AppState>,
+                 svc: String,
+                 group: String,
+                 org: Option<&str>)
+                 -> NetResult<protocol::types::PackageIdent> {
+    let service_group =
+        ServiceGroup::new(None, svc, group, org).map_err(|e| {
+                                                    net::err(ErrCode::InvalidPayload, e.to_string())
+                                                })?;
+    let data = &req.state()
+                   .gateway_state
+                   .read()
+                   .expect("GatewayState lock is poisoned")
+                   .services_data;
+    let service = service_from_services(&service_group, &data);
+    let ident = service.as_ref()
+                       .and_then(|s| s["spec_identifier"].as_str())
+                       .and_then(|i| PackageIdent::from_str(i).ok());
+    match ident {
+        Some(ident) => Ok(ident.into()),
+        None => {
+            Err(net::err(ErrCode::NotFound,
+                         format!("Service group {} is not loaded",
+                                 service_group)))
+        }
+    }
+}
+
+/// Resolves to the result of a request dispatched to the Manager, along with the console output
+/// sent while handling it, once the Manager sends its final reply.
+struct CtlReply {
+    rx:     CtlReceiver,
+    output: Vec<String>,
+}
+
+impl CtlReply {
+    fn new(rx: CtlReceiver) -> Self {
+        CtlReply { rx,
+                   output: Vec::new() }
+    }
+}
+
+impl Future for CtlReply {
+    type Error = actix_web::Error;
+    type Item = (NetResult<()>, Vec<String>);
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            match self.rx.poll() {
+                Ok(Async::Ready(Some(msg))) => {
+                    if msg.message_id() == "ConsoleLine" {
+                        if let Ok(line) = msg.parse::<protocol::ctl::ConsoleLine>() {
+                            self.output.push(line.line.trim_end().to_string());
+                        }
+                    }
+                    if msg.is_complete() {
+                        let output = mem::replace(&mut self.output, Vec::new());
+                        return Ok(Async::Ready((msg.try_ok(), output)));
+                    }
+                }
+                Ok(Async::Ready(None)) | Err(()) => {
+                    let output = mem::replace(&mut self.output, Vec::new());
+                    let err = net::err(ErrCode::Internal,
+                                       "Supervisor stopped handling the request before replying");
+                    return Ok(Async::Ready((Err(err), output)));
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+            }
+        }
+    }
+}
+
 fn service_from_services(service_group: &ServiceGroup, services_json: &str) -> Option<Json> {
     match serde_json::from_str(services_json) {
         Ok(Json::Array(services)) => {
@@ -672,4 +1278,108 @@ mod tests {
         assert!(!failure.is_valid(),
                 "Expected schema validation to fail, but it succeeded");
     }
+
+    #[test]
+    fn load_body_converts_to_svc_load() {
+        let body: super::LoadBody = serde_json::from_str(r#"{"ident": "core/redis",
+                                                            "group": "prod",
+                                                            "binds": ["db:postgresql.default"],
+                                                            "topology": "leader",
+                                                            "health_check_interval": 10}"#).unwrap();
+        let msg = body.into_msg().unwrap();
+
+        assert_eq!(msg.ident.unwrap().to_string(), "core/redis");
+        assert_eq!(msg.group, Some("prod".to_string()));
+        assert_eq!(msg.binds.unwrap().binds.len(), 1);
+        assert_eq!(msg.topology,
+                   Some(habitat_sup_protocol::types::Topology::Leader as i32));
+        assert_eq!(msg.health_check_interval.unwrap().seconds, 10);
+        assert_eq!(msg.force, None);
+    }
+
+    #[test]
+    fn load_body_rejects_invalid_values() {
+        let body: super::LoadBody =
+            serde_json::from_str(r#"{"ident": "core/redis", "topology": "ring"}"#).unwrap();
+        let err = body.into_msg().unwrap_err();
+
+        assert_eq!(super::error_status(&err),
+                   actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn load_body_converts_service_settings() {
+        let body: super::LoadBody =
+            serde_json::from_str(r#"{"ident": "core/redis",
+                                     "application_environment": "myapp.prod",
+                                     "update_window": "0 2 * * sat,sun 4h",
+                                     "max_restart_backoff": 60,
+                                     "resource_limits": {"cpu": "0.5", "memory": "512M"},
+                                     "health_check": {"probe": "tcp://localhost:6379",
+                                                      "timeout": 5}}"#).unwrap();
+        let msg = body.into_msg().unwrap();
+
+        let app_env = msg.application_environment.unwrap();
+        assert_eq!(app_env.application, "myapp");
+        assert_eq!(app_env.environment, "prod");
+        assert_eq!(msg.update_window, Some("0 2 * * sat,sun 4h".to_string()));
+        assert_eq!(msg.max_restart_backoff, Some(60));
+        let limits = msg.resource_limits.unwrap();
+        assert_eq!(limits.cpu_millis, Some(500));
+        assert_eq!(limits.memory_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(limits.pids, None);
+        let check = msg.health_check.unwrap();
+        assert_eq!(check.probe, "tcp://localhost:6379");
+        assert_eq!(check.timeout, Some(5));
+    }
+
+    #[test]
+    fn load_body_rejects_invalid_service_settings() {
+        for json in &[r#"{"ident": "core/redis", "application_environment": "myapp"}"#,
+                      r#"{"ident": "core/redis", "update_window": "sometime"}"#,
+                      r#"{"ident": "core/redis", "resource_limits": {"cpu": "lots"}}"#,
+                      r#"{"ident": "core/redis", "health_check": {"probe": "ping"}}"#]
+        {
+            let body: super::LoadBody = serde_json::from_str(json).unwrap();
+            let err = body.into_msg().unwrap_err();
+            assert_eq!(super::error_status(&err),
+                       actix_web::http::StatusCode::BAD_REQUEST,
+                       "{}",
+                       json);
+        }
+    }
+
+    #[test]
+    fn load_body_refuses_host_only_and_unknown_fields() {
+        for json in &[r#"{"ident": "core/redis", "config_from": "/src/redis"}"#,
+                      r#"{"ident": "core/redis", "svc_encrypted_password": "sekret"}"#]
+        {
+            let body: super::LoadBody = serde_json::from_str(json).unwrap();
+            assert!(body.into_msg().is_err(), "{}", json);
+        }
+        assert!(serde_json::from_str::<super::LoadBody>(r#"{"ident": "core/redis",
+                                                             "update_windw": "0 2 * * sat 4h"}"#).is_err());
+    }
+
+    #[test]
+    fn apply_config_body_converts_json_to_toml() {
+        let body: super::ApplyConfigBody =
+            serde_json::from_str(r#"{"version": 3, "cfg": {"port": 6380, "tls": {"enabled": true}}}"#).unwrap();
+        let service_group = "redis.default".parse().unwrap();
+        let msg = body.into_msg(service_group).unwrap();
+        let cfg: toml::value::Table = toml::from_slice(&msg.cfg.unwrap()).unwrap();
+
+        assert_eq!(msg.version, Some(3));
+        assert_eq!(cfg["port"].as_integer(), Some(6380));
+        assert_eq!(cfg["tls"]["enabled"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn events_are_framed_as_server_sent_events() {
+        let event = crate::event::LocalEvent { name: "service-started",
+                                               json: r#"{"service_metadata":{}}"#.to_string(), };
+
+        assert_eq!(&super::event_frame(&event)[..],
+                   &b"event: service-started\ndata: {\"service_metadata\":{}}\n\n"[..]);
+    }
 }
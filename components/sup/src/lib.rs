@@ -66,6 +66,7 @@ macro_rules! sup_error {
 #[cfg(test)]
 #[macro_use]
 pub mod cli_test_helpers;
+pub mod audit;
 pub mod census;
 pub mod cli;
 pub mod command;
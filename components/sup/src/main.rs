@@ -16,32 +16,43 @@ extern crate tempfile;
 extern crate time;
 extern crate url;
 
-use crate::sup::{cli::cli,
+use crate::sup::{census::AuthorTrust,
+                 cli::cli,
                  command,
                  error::{Error,
                          Result,
                          SupError},
-                 event::EventStreamConfig,
+                 event::{self,
+                         EventStreamConfig},
                  manager::{Manager,
                            ManagerConfig,
                            TLSConfig,
                            PROC_LOCK_FILE},
                  util};
 use clap::ArgMatches;
+use habitat_butterfly::trust::{MemberKey,
+                               MemberTrust};
 use habitat_common::{cli::{cache_key_path_from_matches,
+                           health_check_from_matches,
                            GOSSIP_DEFAULT_PORT},
                      command::package::install::InstallSource,
                      output::{self,
                               OutputFormat,
                               OutputVerbosity},
                      outputln,
+                     service_log::LogConfig,
+                     types::EventSinkConfig,
                      ui::{NONINTERACTIVE_ENVVAR,
                           UI},
                      FeatureFlag};
 #[cfg(windows)]
 use habitat_core::crypto::dpapi::encrypt;
 use habitat_core::{crypto::{self,
+                            keys::PairType,
+                            SigKeyPair,
                             SymKey},
+                   service::{self,
+                             ResourceLimits},
                    url::{bldr_url_from_env,
                          default_bldr_url},
                    ChannelIdent};
@@ -50,6 +61,7 @@ use habitat_launcher_client::{LauncherCli,
 use habitat_sup_protocol::{ctl::ServiceBindList,
                            types::{ApplicationEnvironment,
                                    BindingMode,
+                                   RestartPolicy,
                                    ServiceBind,
                                    Topology,
                                    UpdateStrategy}};
@@ -63,7 +75,8 @@ use std::{env,
                  PathBuf},
           process,
           str::{self,
-                FromStr}};
+                FromStr},
+          time::Duration};
 #[cfg(test)]
 use tempfile::TempDir;
 
@@ -228,10 +241,16 @@ fn mgrcfg_from_sup_run_matches(m: &ArgMatches,
         cache_key_path,
         update_url: bldr_url(m),
         update_channel: channel(m),
+        // Value will have already been validated by `cli::valid_update_window`
+        update_window: m.value_of("AUTO_UPDATE_WINDOW")
+                        .and_then(|w| w.parse().ok()),
         http_disable: m.is_present("HTTP_DISABLE"),
         organization: m.value_of("ORGANIZATION").map(str::to_string),
         gossip_permanent: m.is_present("PERMANENT_PEER"),
         ring_key: get_ring_key(m, &cache_key_path_from_matches(m))?,
+        member_key: get_member_key(m, &cache_key_path_from_matches(m))?,
+        member_trust: get_member_trust(m, &cache_key_path_from_matches(m))?,
+        author_trust: get_author_trust(m, &cache_key_path_from_matches(m))?,
         gossip_peers: get_peers(m)?,
         watch_peer_file: m.value_of("PEER_WATCH_FILE").map(str::to_string),
         // TODO: Refactor this to remove the duplication
@@ -286,8 +305,26 @@ fn mgrcfg_from_sup_run_matches(m: &ArgMatches,
                 ca_cert_path,
             }
         }),
+        ctl_tls_config: m.value_of("CTL_KEY_FILE").map(|kf| {
+            let cert_path = m
+                .value_of("CTL_CERT_FILE")
+                .map(PathBuf::from)
+                .expect("CTL_CERT_FILE should always have a value if CTL_KEY_FILE has a value.");
+            let ca_cert_path = m.value_of("CTL_CA_CERT_FILE").map(PathBuf::from);
+            TLSConfig {
+                key_path: PathBuf::from(kf),
+                cert_path,
+                ca_cert_path,
+            }
+        }),
         feature_flags,
         event_stream_config,
+        event_sinks: EventSinkConfig::from_matches(m),
+        event_sink_buffer_size: m
+            .value_of("EVENT_SINK_BUFFER_SIZE")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(event::DEFAULT_BUFFER_SIZE),
+        service_log: get_service_log_config(m),
     };
 
     Ok(cfg)
@@ -329,8 +366,16 @@ fn get_peers(matches: &ArgMatches) -> Result<Vec<SocketAddr>> {
 fn get_ring_key(m: &ArgMatches, cache_key_path: &Path) -> Result<Option<SymKey>> {
     match m.value_of("RING") {
         Some(val) => {
-            let key = SymKey::get_latest_pair_for(&val, cache_key_path)?;
-            Ok(Some(key))
+            // Start with the oldest revision in the cache, which the rest of the ring still
+            // accepts unless it has been retired. Newer revisions are moved to once every member
+            // accepts them.
+            match SymKey::get_pairs_for(&val, cache_key_path)?.pop() {
+                Some(key) => Ok(Some(key)),
+                None => {
+                    let msg = format!("No revisions found for {} sym key", val);
+                    Err(sup_error!(Error::HabitatCore(habitat_core::Error::CryptoError(msg))))
+                }
+            }
         }
         None => {
             match m.value_of("RING_KEY") {
@@ -344,6 +389,51 @@ fn get_ring_key(m: &ArgMatches, cache_key_path: &Path) -> Result<Option<SymKey>>
     }
 }
 
+fn get_member_key(m: &ArgMatches, cache_key_path: &Path) -> Result<Option<MemberKey>> {
+    match m.value_of("MEMBER_KEY") {
+        Some(val) => {
+            let pair =
+                SigKeyPair::get_latest_pair_for(val, cache_key_path, Some(&PairType::Secret))?;
+            // Only needed when peers trust an authority rather than an allow-list
+            let endorsement = pair.get_endorsement(cache_key_path).ok();
+            Ok(Some(MemberKey::new(pair, endorsement)))
+        }
+        None => Ok(None),
+    }
+}
+
+fn get_member_trust(m: &ArgMatches, cache_key_path: &Path) -> Result<Option<MemberTrust>> {
+    let (names, trust): (_, fn(Vec<SigKeyPair>) -> MemberTrust) =
+        match (m.values_of("MEMBER_TRUST"), m.values_of("MEMBER_CA")) {
+            (Some(names), _) => (names, MemberTrust::AllowList),
+            (None, Some(names)) => (names, MemberTrust::Endorsed),
+            (None, None) => return Ok(None),
+        };
+    Ok(Some(trust(get_trusted_keys(names, cache_key_path)?)))
+}
+
+fn get_author_trust(m: &ArgMatches, cache_key_path: &Path) -> Result<Option<AuthorTrust>> {
+    match m.values_of("AUTHOR_TRUST") {
+        Some(names) => Ok(Some(AuthorTrust::new(get_trusted_keys(names, cache_key_path)?))),
+        None => Ok(None),
+    }
+}
+
+/// Every revision of the public keys with these names.
+fn get_trusted_keys<'a, I>(names: I, cache_key_path: &Path) -> Result<Vec<SigKeyPair>>
+    where I: IntoIterator<Item = &'a str>
+{
+    let mut keys = Vec::new();
+    for name in names {
+        let pairs = SigKeyPair::get_pairs_for(name, cache_key_path, Some(&PairType::Public))?;
+        if pairs.is_empty() {
+            return Err(sup_error!(Error::NoTrustedKeys(name.to_string())));
+        }
+        keys.extend(pairs);
+    }
+    Ok(keys)
+}
+
 /// Resolve a Builder URL. Taken from CLI args, the environment, or
 /// (failing those) a default value.
 fn bldr_url(m: &ArgMatches) -> String {
@@ -398,6 +488,45 @@ fn get_strategy_from_input(m: &ArgMatches) -> Option<UpdateStrategy> {
      .and_then(|f| UpdateStrategy::from_str(f).ok())
 }
 
+fn get_restart_policy_from_input(m: &ArgMatches) -> Option<RestartPolicy> {
+    m.value_of("RESTART_POLICY")
+     .and_then(|p| RestartPolicy::from_str(p).ok())
+}
+
+fn get_resource_limits_from_input(m: &ArgMatches)
+                                  -> Option<habitat_sup_protocol::types::ResourceLimits> {
+    let limits = ResourceLimits { cpu_millis:   m.value_of("CPU_LIMIT")
+                                                 .and_then(|v| service::parse_cpu_limit(v).ok()),
+                                  memory_bytes: m.value_of("MEMORY_LIMIT")
+                                                 .and_then(|v| service::parse_memory_limit(v).ok()),
+                                  pids:         m.value_of("PIDS_LIMIT")
+                                                 .and_then(|v| service::parse_pids_limit(v).ok()), };
+    if limits.is_empty() {
+        None
+    } else {
+        Some(limits.into())
+    }
+}
+
+fn get_service_log_config(m: &ArgMatches) -> LogConfig {
+    let defaults = LogConfig::default();
+    // Values will have already been validated by the CLI
+    LogConfig { format:    m.value_of("SERVICE_LOG_FORMAT")
+                            .and_then(|f| f.parse().ok())
+                            .unwrap_or(defaults.format),
+                max_bytes: m.value_of("SERVICE_LOG_MAX_SIZE")
+                            .and_then(|s| service::parse_memory_limit(s).ok())
+                            .unwrap_or(defaults.max_bytes),
+                max_age:   m.value_of("SERVICE_LOG_MAX_AGE")
+                            .and_then(|a| a.parse().ok())
+                            .filter(|secs| *secs > 0)
+                            .map(Duration::from_secs),
+                max_files: m.value_of("SERVICE_LOG_MAX_FILES")
+                            .and_then(|n| n.parse().ok())
+                            .unwrap_or(defaults.max_files),
+                compress:  !m.is_present("SERVICE_LOG_NO_COMPRESS"), }
+}
+
 fn get_binds_from_input(m: &ArgMatches) -> Result<Option<ServiceBindList>> {
     match m.values_of("BIND") {
         Some(bind_strs) => {
@@ -488,6 +617,14 @@ fn update_svc_load_from_input(m: &ArgMatches,
     msg.binding_mode = get_binding_mode_from_input(m).map(|v| v as i32);
     msg.topology = get_topology_from_input(m).map(|v| v as i32);
     msg.update_strategy = get_strategy_from_input(m).map(|v| v as i32);
+    msg.update_window = m.value_of("UPDATE_WINDOW").map(ToString::to_string);
+    msg.restart_policy = get_restart_policy_from_input(m).map(|v| v as i32);
+    msg.max_restarts = m.value_of("MAX_RESTARTS").and_then(|s| s.parse().ok());
+    msg.restart_window = m.value_of("RESTART_WINDOW").and_then(|s| s.parse().ok());
+    msg.max_restart_backoff = m.value_of("MAX_RESTART_BACKOFF")
+                               .and_then(|s| s.parse().ok());
+    msg.resource_limits = get_resource_limits_from_input(m);
+    msg.health_check = health_check_from_matches(m).map(Into::into);
     Ok(())
 }
 
@@ -534,6 +671,38 @@ mod test {
             assert_eq!(config.auto_update, false);
         }
 
+        #[test]
+        fn auto_update_window_should_be_set() {
+            let cmd_vec = vec!["hab-sup",
+                               "run",
+                               "--auto-update",
+                               "--auto-update-window",
+                               "0 2 * * sat 4h"];
+            let config = config_from_cmd_vec(cmd_vec);
+            assert_eq!(config.update_window,
+                       Some("0 2 * * sat 4h".parse().unwrap()));
+
+            let config = config_from_cmd_str("hab-sup run --auto-update");
+            assert_eq!(config.update_window, None);
+        }
+
+        #[test]
+        fn service_log_should_be_set() {
+            let config = config_from_cmd_str("hab-sup run --service-log-format json \
+                                              --service-log-max-size 1M --service-log-max-age \
+                                              3600 --service-log-max-files 2 \
+                                              --service-log-no-compress");
+            assert_eq!(config.service_log,
+                       LogConfig { format:    "json".parse().unwrap(),
+                                   max_bytes: 1024 * 1024,
+                                   max_age:   Some(Duration::from_secs(3600)),
+                                   max_files: 2,
+                                   compress:  false, });
+
+            let config = config_from_cmd_str("hab-sup run --service-log-max-age 0");
+            assert_eq!(config.service_log, LogConfig::default());
+        }
+
         #[test]
         fn update_url_should_be_set() {
             let config = config_from_cmd_str("hab-sup run -u http://fake.example.url");
@@ -712,5 +881,48 @@ RCFaO84j41GmrzWddxMdsXpGdn3iuIy7Mw3xYrjPLsE="#,
                        "foobar-20160504220722");
         }
 
+        #[test]
+        fn member_trust_is_set_properly_by_name() {
+            let key_cache = TempDir::new().expect("Could not create tempdir");
+            let lock = lock_var();
+            lock.set(key_cache.path());
+
+            let pair =
+                SigKeyPair::generate_pair_for_origin("web-1").expect("Could not generate key pair");
+            pair.to_pair_files(key_cache.path())
+                .expect("Could not write key pair");
+            let config = config_from_cmd_str("hab-sup run --member-key web-1 --member-trust web-1");
+
+            assert_eq!(config.member_key
+                             .expect("No member key on manager config")
+                             .name_with_rev(),
+                       pair.name_with_rev());
+            match config.member_trust {
+                Some(MemberTrust::AllowList(keys)) => {
+                    assert_eq!(keys.len(), 1);
+                    assert_eq!(keys[0].name_with_rev(), pair.name_with_rev());
+                }
+                other => panic!("Expected an allow-list, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn author_trust_is_set_properly_by_name() {
+            let key_cache = TempDir::new().expect("Could not create tempdir");
+            let lock = lock_var();
+            lock.set(key_cache.path());
+
+            let pair =
+                SigKeyPair::generate_pair_for_origin("alice-signing").expect("Could not generate \
+                                                                              key pair");
+            pair.to_pair_files(key_cache.path())
+                .expect("Could not write key pair");
+            let config = config_from_cmd_str("hab-sup run --author-trust alice-signing");
+
+            assert_eq!(format!("{:?}",
+                               config.author_trust
+                                     .expect("No author trust on manager config")),
+                       format!("AuthorTrust({})", pair.name_with_rev()));
+        }
     }
 }
@@ -1,32 +1,50 @@
 //! All the code for responding to Supervisor commands
 
-use crate::{ctl_gateway::CtlRequest,
+use crate::{census::CensusRing,
+            ctl_gateway::CtlRequest,
             error::Error,
             manager::{action::{ActionSender,
                                SupervisorAction},
                       service::{spec::{IntoServiceSpec,
                                        ServiceSpec},
                                 DesiredState,
+                                PendingUpdate,
                                 ProcessState},
                       ManagerState},
             util};
-use habitat_butterfly as butterfly;
+use habitat_butterfly::{self as butterfly,
+                        rumor::{election::{Election,
+                                           ElectionStatus},
+                                Author}};
 use habitat_common::{command::package::install::InstallSource,
                      outputln,
+                     service_log::{self,
+                                   LogFollower},
                      templating::package::Pkg,
                      ui::UIWriter};
-use habitat_core::{package::{Identifiable,
+use habitat_core::{crypto::{keys::{parse_key_str,
+                                   parse_name_with_rev},
+                            SymKey},
+                   fs::svc_logs_path,
+                   package::{Identifiable,
                              PackageIdent,
                              PackageTarget},
                    service::ServiceGroup,
                    ChannelIdent};
 use habitat_sup_protocol::{self as protocol,
+                           codec::SrvMessage,
+                           ctl::{DebugTopic,
+                                 LogStream},
                            net::{self,
                                  ErrCode,
                                  NetResult}};
 use serde_json;
 use std::{fmt,
-          result};
+          path::{Path,
+                 PathBuf},
+          result,
+          thread,
+          time::Duration};
 use time::{self,
            Duration as TimeDuration,
            Timespec};
@@ -34,6 +52,9 @@ use toml;
 
 static LOGKEY: &'static str = "CMD";
 
+/// How often a followed service log is checked for new lines.
+const LOG_FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
 pub fn service_cfg(mgr: &ManagerState,
                    req: &mut CtlRequest,
                    opts: protocol::ctl::SvcGetDefaultCfg)
@@ -60,60 +81,54 @@ pub fn service_cfg(mgr: &ManagerState,
     Err(net::err(ErrCode::NotFound, format!("Service not loaded, {}", ident)))
 }
 
-pub fn service_cfg_validate(_mgr: &ManagerState,
+pub fn service_cfg_validate(mgr: &ManagerState,
                             req: &mut CtlRequest,
                             opts: protocol::ctl::SvcValidateCfg)
                             -> NetResult<()> {
     let cfg = opts.cfg.ok_or_else(err_update_client)?;
-    let format = opts.format
-                     .and_then(protocol::types::service_cfg::Format::from_i32)
-                     .unwrap_or_default();
-    if cfg.len() > protocol::butterfly::MAX_SVC_CFG_SIZE {
-        return Err(net::err(ErrCode::EntityTooLarge, "Configuration too large."));
+    let new_cfg = decode_cfg(&cfg, opts.format)?;
+    if let Some(service_group) = opts.service_group {
+        validate_cfg_schema(mgr, &service_group.into(), new_cfg)?;
     }
-    if format != protocol::types::service_cfg::Format::Toml {
-        return Err(net::err(ErrCode::NotSupported,
-                            format!("Configuration format {} not available.",
-                                    format)));
-    }
-    let _new_cfg: toml::value::Table = toml::from_slice(&cfg).map_err(|e| {
-                                                                 net::err(
-            ErrCode::BadPayload,
-            format!("Unable to decode configuration as {}, {}", format, e),
-        )
-                                                             })?;
     req.reply_complete(net::ok());
     Ok(())
-    // JW TODO: Hold off on validation until we can validate services which aren't currently
-    // loaded in the Supervisor but are known through rumor propagation.
-    // let service_group: ServiceGroup = opts.service_group.into();
-    // for service in mgr.services.read().unwrap().iter() {
-    //     if service.service_group != service_group {
-    //         continue;
-    //     }
-    //     if let Some(interface) = service.cfg.interface() {
-    //         match Cfg::validate(interface, &new_cfg) {
-    //             None => req.reply_complete(net::ok()),
-    //             Some(errors) => {
-    //                 for error in errors {
-    //                     req.reply_partial(net::err(ErrCode::InvalidPayload, error));
-    //                 }
-    //                 req.reply_complete(net::ok());
-    //             }
-    //         }
-    //         return Ok(());
-    //     } else {
-    //         // No interface, this service can't be configured.
-    //         return Err(net::err(
-    //             ErrCode::NotFound,
-    //             "Service has no configurable attributes.",
-    //         ));
-    //     }
-    // }
-    // Err(net::err(
-    //     ErrCode::NotFound,
-    //     format!("Service not loaded, {}", service_group),
-    // ))
+}
+
+pub fn service_cfg_dry_run(mgr: &ManagerState,
+                           req: &mut CtlRequest,
+                           opts: protocol::ctl::SvcDryRunCfg)
+                           -> NetResult<()> {
+    let cfg = opts.cfg.ok_or_else(err_update_client)?;
+    let service_group: ServiceGroup = opts.service_group.ok_or_else(err_update_client)?.into();
+    let new_cfg = decode_cfg(&cfg, opts.format)?;
+    // The census is rebuilt from the gossip layer, exactly as the `Manager` builds its own, so
+    // that the dry-run renders against the same data a real change would. Being a snapshot, it
+    // announces nothing the `Manager`'s census has not.
+    let mut census_ring = CensusRing::snapshot(mgr.butterfly.member_id());
+    census_ring.update_from_rumors(&mgr.cfg.cache_key_path,
+                                   mgr.cfg.author_trust.as_ref(),
+                                   &mgr.butterfly.service_store,
+                                   &mgr.butterfly.election_store,
+                                   &mgr.butterfly.update_store,
+                                   &mgr.butterfly.member_list,
+                                   &mgr.butterfly.service_config_store,
+                                   &mgr.butterfly.service_file_store);
+    let services = mgr.services.read().expect("Services lock is poisoned");
+    let service = services.values()
+                          .find(|s| s.service_group == service_group)
+                          .ok_or_else(|| {
+                              net::err(ErrCode::NotFound,
+                                       format!("Service not loaded, {}", service_group))
+                          })?;
+    if census_ring.census_group_for(&service_group).is_none() {
+        return Err(net::err(ErrCode::NotFound,
+                            format!("Service group {} not yet gossiped",
+                                    service_group)));
+    }
+    let dry_run = service.dry_run_cfg(&census_ring, new_cfg)
+                         .map_err(|e| net::err(ErrCode::InvalidPayload, e.to_string()))?;
+    req.reply_complete(dry_run);
+    Ok(())
 }
 
 pub fn service_cfg_set(mgr: &ManagerState,
@@ -127,20 +142,30 @@ pub fn service_cfg_set(mgr: &ManagerState,
     if cfg.len() > protocol::butterfly::MAX_SVC_CFG_SIZE {
         return Err(net::err(ErrCode::EntityTooLarge, "Configuration too large."));
     }
+    // Encrypted configuration can only be read by the members it was encrypted for.
+    if !is_encrypted {
+        validate_cfg_schema(mgr, &service_group, decode_cfg(&cfg, None)?)?;
+    }
+    let author = Author::from_parts(opts.signer, opts.signature);
+    if let Some(ref author_trust) = mgr.cfg.author_trust {
+        let signed = protocol::butterfly::service_config_signed_bytes(&service_group,
+                                                                      version,
+                                                                      is_encrypted,
+                                                                      &cfg);
+        author_trust.verify(author.as_ref(), &signed)
+                    .map_err(|e| net::err(ErrCode::Unauthorized, e.to_string()))?;
+    }
     outputln!("Setting new configuration version {} for {}",
               version,
               service_group,);
-    let mut client =
-        match butterfly::client::Client::new(&mgr.cfg.gossip_listen.local_addr().to_string(),
-                                             mgr.cfg.ring_key.clone())
-        {
-            Ok(client) => client,
-            Err(err) => {
-                outputln!("Failed to connect to own gossip server, {}", err);
-                return Err(net::err(ErrCode::Internal, err.to_string()));
-            }
-        };
-    client.send_service_config(service_group, version, &cfg, is_encrypted)
+    let mut client = match gossip_client(mgr, mgr.butterfly.ring_key()) {
+        Ok(client) => client,
+        Err(err) => {
+            outputln!("Failed to connect to own gossip server, {}", err);
+            return Err(net::err(ErrCode::Internal, err.to_string()));
+        }
+    };
+    client.send_service_config(service_group, version, &cfg, is_encrypted, author)
           .map_err(|e| net::err(ErrCode::Internal, e.to_string()))
           .map(|_| {
               req.reply_complete(net::ok());
@@ -159,21 +184,33 @@ pub fn service_file_put(mgr: &ManagerState,
     if content.len() > protocol::butterfly::MAX_FILE_PUT_SIZE_BYTES {
         return Err(net::err(ErrCode::EntityTooLarge, "File content too large."));
     }
+    let author = Author::from_parts(opts.signer, opts.signature);
+    if let Some(ref author_trust) = mgr.cfg.author_trust {
+        let signed = protocol::butterfly::service_file_signed_bytes(&service_group,
+                                                                    &filename,
+                                                                    version,
+                                                                    is_encrypted,
+                                                                    &content);
+        author_trust.verify(author.as_ref(), &signed)
+                    .map_err(|e| net::err(ErrCode::Unauthorized, e.to_string()))?;
+    }
     outputln!("Receiving new version {} of file {} for {}",
               version,
               filename,
               service_group,);
-    let mut client =
-        match butterfly::client::Client::new(&mgr.cfg.gossip_listen.local_addr().to_string(),
-                                             mgr.cfg.ring_key.clone())
-        {
-            Ok(client) => client,
-            Err(err) => {
-                outputln!("Failed to connect to own gossip server, {}", err);
-                return Err(net::err(ErrCode::Internal, err.to_string()));
-            }
-        };
-    client.send_service_file(service_group, filename, version, &content, is_encrypted)
+    let mut client = match gossip_client(mgr, mgr.butterfly.ring_key()) {
+        Ok(client) => client,
+        Err(err) => {
+            outputln!("Failed to connect to own gossip server, {}", err);
+            return Err(net::err(ErrCode::Internal, err.to_string()));
+        }
+    };
+    client.send_service_file(service_group,
+                             filename,
+                             version,
+                             &content,
+                             is_encrypted,
+                             author)
           .map_err(|e| net::err(ErrCode::Internal, e.to_string()))
           .map(|_| {
               req.reply_complete(net::ok());
@@ -197,7 +234,7 @@ pub fn service_load(mgr: &ManagerState,
     match mgr.cfg.spec_for_ident(source.as_ref()) {
         None => {
             let mut spec = ServiceSpec::default();
-            opts.into_spec(&mut spec);
+            opts.into_spec(&mut spec)?;
 
             // We don't have any record of this thing; let's set it up!
             //
@@ -225,7 +262,7 @@ pub fn service_load(mgr: &ManagerState,
                                             ident)));
             }
 
-            opts.into_spec(&mut spec);
+            opts.into_spec(&mut spec)?;
 
             // Only install if we don't have something
             // locally; otherwise you could potentially
@@ -327,16 +364,13 @@ pub fn supervisor_depart(mgr: &ManagerState,
                          opts: protocol::ctl::SupDepart)
                          -> NetResult<()> {
     let member_id = opts.member_id.ok_or_else(err_update_client)?;
-    let mut client =
-        match butterfly::client::Client::new(&mgr.cfg.gossip_listen.local_addr().to_string(),
-                                             mgr.cfg.ring_key.clone())
-        {
-            Ok(client) => client,
-            Err(err) => {
-                outputln!("Failed to connect to own gossip server, {}", err);
-                return Err(net::err(ErrCode::Internal, err.to_string()));
-            }
-        };
+    let mut client = match gossip_client(mgr, mgr.butterfly.ring_key()) {
+        Ok(client) => client,
+        Err(err) => {
+            outputln!("Failed to connect to own gossip server, {}", err);
+            return Err(net::err(ErrCode::Internal, err.to_string()));
+        }
+    };
     outputln!("Attempting to depart member: {}", member_id);
     match client.send_departure(&member_id) {
         Ok(()) => {
@@ -347,6 +381,42 @@ pub fn supervisor_depart(mgr: &ManagerState,
     }
 }
 
+pub fn ring_key_rotate(mgr: &ManagerState,
+                       req: &mut CtlRequest,
+                       opts: protocol::ctl::SupRingKeyRotate)
+                       -> NetResult<()> {
+    let key = opts.key.ok_or_else(err_update_client)?;
+    let current = mgr.butterfly.ring_key().ok_or_else(|| {
+                                               net::err(ErrCode::InvalidPayload,
+                                                        "This Supervisor's ring is not encrypted")
+                                           })?;
+    let invalid = |e: habitat_core::Error| net::err(ErrCode::InvalidPayload, e.to_string());
+    let (_, name_with_rev, _) = parse_key_str(&key).map_err(invalid)?;
+    let (name, rev) = parse_name_with_rev(&name_with_rev).map_err(invalid)?;
+    if name != current.name || rev <= current.rev {
+        let msg = format!("{} is not a newer revision of ring key {}",
+                          name_with_rev,
+                          current.name_with_rev());
+        return Err(net::err(ErrCode::InvalidPayload, msg));
+    }
+    let internal = |e: habitat_core::Error| net::err(ErrCode::Internal, e.to_string());
+    let (ring_key, _) =
+        SymKey::write_file_from_str(&key, &mgr.cfg.cache_key_path).map_err(internal)?;
+    let mut client = match gossip_client(mgr, Some(current)) {
+        Ok(client) => client,
+        Err(err) => {
+            outputln!("Failed to connect to own gossip server, {}", err);
+            return Err(net::err(ErrCode::Internal, err.to_string()));
+        }
+    };
+    outputln!("Distributing ring key {}", name_with_rev);
+    client.send_ring_key(&ring_key)
+          .map_err(|e| net::err(ErrCode::Internal, e.to_string()))
+          .map(|_| {
+              req.reply_complete(net::ok());
+          })
+}
+
 pub fn service_status(mgr: &ManagerState,
                       req: &mut CtlRequest,
                       opts: protocol::ctl::SvcStatus)
@@ -387,16 +457,322 @@ pub fn service_status(mgr: &ManagerState,
     Ok(())
 }
 
+pub fn service_logs(mgr: &ManagerState,
+                    req: &mut CtlRequest,
+                    opts: protocol::ctl::SvcLogs)
+                    -> NetResult<()> {
+    let ident: PackageIdent = opts.ident.ok_or_else(err_update_client)?.into();
+    let log_dir =
+        mgr.services
+           .read()
+           .expect("Services lock is poisoned")
+           .values()
+           .find(|service| service.pkg.ident.satisfies(&ident))
+           .map(|service| svc_logs_path(&service.pkg.name))
+           .ok_or_else(|| net::err(ErrCode::NotFound, format!("Service not loaded, {}", ident)))?;
+    let streams: Vec<LogStream> = opts.streams
+                                      .iter()
+                                      .filter_map(|s| LogStream::from_i32(*s))
+                                      .collect();
+    let sources = LogSource::for_streams(&log_dir, &streams);
+
+    // Start following before reading the most recent lines, so that
+    // nothing written in between is missed.
+    let followers: Vec<(LogFollower, LogSource)> = if opts.follow.unwrap_or(false) {
+        sources.iter()
+               .map(|source| (LogFollower::new(&source.path), source.clone()))
+               .collect()
+    } else {
+        Vec::new()
+    };
+    let count = opts.lines.unwrap_or(10) as usize;
+    for source in &sources {
+        let lines =
+            service_log::tail_matching(&source.path, count, |l| source.includes(l)).map_err(|e| {
+                net::err(ErrCode::Io,
+                         format!("Unable to read {}, {}", source.path.display(), e))
+            })?;
+        for line in lines {
+            req.reply_partial(console_line(line));
+        }
+    }
+
+    if followers.is_empty() {
+        req.reply_complete(net::ok());
+    } else {
+        // Lines keep being sent from another thread, as the Manager
+        // can't wait on the client.
+        let req = req.clone();
+        let name = format!("follow-{}", ident);
+        thread::Builder::new().name(name)
+                              .spawn(move || follow_logs(followers, req, &ident))
+                              .map_err(|e| {
+                                  net::err(ErrCode::Internal,
+                                           format!("Unable to follow the log, {}", e))
+                              })?;
+    }
+    Ok(())
+}
+
+pub fn supervisor_debug(mgr: &ManagerState,
+                        req: &mut CtlRequest,
+                        opts: protocol::ctl::SupDebug)
+                        -> NetResult<()> {
+    let topic = opts.topic
+                    .and_then(DebugTopic::from_i32)
+                    .ok_or_else(err_update_client)?;
+    match topic {
+        DebugTopic::Heat => reply_with_all(req, rumor_heat(&mgr.butterfly)),
+        DebugTopic::Elections => reply_with_all(req, pending_elections(&mgr.butterfly)),
+        DebugTopic::Updaters => {
+            let statuses = mgr.updater
+                              .lock()
+                              .expect("Updater lock is poisoned")
+                              .statuses();
+            reply_with_all(req, statuses)
+        }
+        DebugTopic::Watchers => reply_with_all(req, watcher_statuses(mgr)),
+    }
+    Ok(())
+}
+
 ////////////////////////////////////////////////////////////////////////
 // Private helper functions
+
+/// A client for this Supervisor's own gossip server, encrypting with `ring_key`.
+fn gossip_client(mgr: &ManagerState,
+                 ring_key: Option<SymKey>)
+                 -> butterfly::error::Result<butterfly::client::Client> {
+    butterfly::client::Client::with_transport(&mgr.cfg.gossip_listen.local_addr().to_string(),
+                                              mgr.butterfly.gossip_transport(),
+                                              ring_key,
+                                              mgr.cfg.member_key.clone())
+}
+
+/// Decode a configuration sent by a client, in the given format.
+fn decode_cfg(cfg: &[u8], format: Option<i32>) -> NetResult<toml::value::Table> {
+    let format = format.and_then(protocol::types::service_cfg::Format::from_i32)
+                       .unwrap_or_default();
+    if cfg.len() > protocol::butterfly::MAX_SVC_CFG_SIZE {
+        return Err(net::err(ErrCode::EntityTooLarge, "Configuration too large."));
+    }
+    if format != protocol::types::service_cfg::Format::Toml {
+        return Err(net::err(ErrCode::NotSupported,
+                            format!("Configuration format {} not available.",
+                                    format)));
+    }
+    toml::from_slice(cfg).map_err(|e| {
+                             net::err(ErrCode::BadPayload,
+                                      format!("Unable to decode configuration as {}, {}",
+                                              format, e))
+                         })
+}
+
+/// Check a configuration about to be gossiped to a service group
+/// against the schema its package ships, if any. Only a member of the
+/// group loaded on this Supervisor can be checked here, since members
+/// elsewhere may be running other releases; each member checks it
+/// against its own schema as it is applied.
+fn validate_cfg_schema(mgr: &ManagerState,
+                       service_group: &ServiceGroup,
+                       gossip: toml::value::Table)
+                       -> NetResult<()> {
+    let services = mgr.services.read().expect("Services lock is poisoned");
+    let service = services.values()
+                          .find(|s| &s.service_group == service_group);
+    match service {
+        Some(service) => {
+            let mut cfg = service.cfg.clone();
+            cfg.gossip = Some(gossip);
+            cfg.validate_schema()
+               .map_err(|e| net::err(ErrCode::InvalidPayload, e.to_string()))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Reply with each message in turn, or with `NetOk` if there are
+/// none.
+fn reply_with_all<T>(req: &mut CtlRequest, replies: Vec<T>)
+    where T: Into<SrvMessage> + fmt::Debug
+{
+    let mut replies = replies.into_iter().peekable();
+    if replies.peek().is_none() {
+        req.reply_complete(net::ok());
+    }
+    while let Some(reply) = replies.next() {
+        if replies.peek().is_some() {
+            req.reply_partial(reply);
+        } else {
+            req.reply_complete(reply);
+        }
+    }
+}
+
+fn rumor_heat(server: &butterfly::Server) -> Vec<protocol::ctl::RumorHeat> {
+    let total_rumors = server.rumor_count() as u32;
+    let mut heat = Vec::new();
+    for (member_id, hot_rumors) in server.hot_rumor_counts() {
+        heat.push(protocol::ctl::RumorHeat { member_id,
+                                             hot_rumors: hot_rumors as u32,
+                                             total_rumors });
+    }
+    heat.sort_by(|a, b| a.member_id.cmp(&b.member_id));
+    heat
+}
+
+fn pending_elections(server: &butterfly::Server) -> Vec<protocol::ctl::PendingElection> {
+    let mut elections = Vec::new();
+    server.election_store
+          .with_keys(|(_, rumors)| {
+              elections.extend(rumors.values().filter_map(|e| pending_election(e, false)));
+          });
+    server.update_store
+          .with_keys(|(_, rumors)| {
+              elections.extend(rumors.values().filter_map(|e| pending_election(e, true)));
+          });
+    elections.sort_by(|a, b| a.service_group.cmp(&b.service_group));
+    elections
+}
+
+fn pending_election(election: &Election, update: bool) -> Option<protocol::ctl::PendingElection> {
+    let status = match election.status {
+        ElectionStatus::Running => "running",
+        ElectionStatus::NoQuorum => "no-quorum",
+        ElectionStatus::Finished => return None,
+    };
+    Some(protocol::ctl::PendingElection { service_group: election.service_group.clone(),
+                                          update,
+                                          term: election.term,
+                                          status: status.to_string(),
+                                          member_id: election.member_id.clone(),
+                                          votes: election.votes.len() as u32 })
+}
+
+fn watcher_statuses(mgr: &ManagerState) -> Vec<protocol::ctl::WatcherStatus> {
+    let mut watchers = vec![protocol::ctl::WatcherStatus { kind:    "specs".to_string(),
+                                                           path:    mgr.cfg
+                                                                       .sup_root()
+                                                                       .join("specs")
+                                                                       .display()
+                                                                       .to_string(),
+                                                           service: None, }];
+    if let Some(ref path) = mgr.cfg.watch_peer_file {
+        watchers.push(protocol::ctl::WatcherStatus { kind:    "peers".to_string(),
+                                                     path:    path.clone(),
+                                                     service: None, });
+    }
+    let user_config_paths = mgr.user_config_watcher
+                               .read()
+                               .expect("user-config-watcher lock is poisoned")
+                               .watched_paths();
+    for (service, path) in user_config_paths {
+        watchers.push(protocol::ctl::WatcherStatus { kind:    "user-config".to_string(),
+                                                     path:    path.display().to_string(),
+                                                     service: Some(service), });
+    }
+    watchers
+}
+
+/// A log file to reply with lines from.
+#[derive(Clone)]
+struct LogSource {
+    path:    PathBuf,
+    /// The service output streams whose lines are included, or `None`
+    /// to include every line.
+    streams: Option<Vec<service_log::LogStream>>,
+}
+
+impl LogSource {
+    /// The log files to read for the requested streams, in the order
+    /// their recent lines are replied with. Every stream is included
+    /// when none are requested.
+    fn for_streams(log_dir: &Path, requested: &[LogStream]) -> Vec<LogSource> {
+        let wanted = |stream| requested.is_empty() || requested.contains(&stream);
+        let mut sources = Vec::new();
+        if wanted(LogStream::Hooks) {
+            sources.push(LogSource { path:    log_dir.join(service_log::HOOK_LOG_FILE_NAME),
+                                     streams: None, });
+        }
+        let streams = match (wanted(LogStream::Stdout), wanted(LogStream::Stderr)) {
+            (true, true) => Some(None),
+            (true, false) => Some(Some(vec![service_log::LogStream::Stdout])),
+            (false, true) => Some(Some(vec![service_log::LogStream::Stderr])),
+            (false, false) => None,
+        };
+        if let Some(streams) = streams {
+            sources.push(LogSource { path: log_dir.join(service_log::LOG_FILE_NAME),
+                                     streams });
+        }
+        sources
+    }
+
+    fn includes(&self, line: &str) -> bool {
+        match self.streams {
+            Some(ref streams) => {
+                service_log::line_stream(line).map_or(false, |stream| streams.contains(&stream))
+            }
+            None => true,
+        }
+    }
+}
+
+/// Reply with each line written to a service's logs until the client
+/// disconnects. Nothing completes the reply while the logs can still
+/// be read; following only ends when the client goes away, which is
+/// checked even while the service is quiet.
+fn follow_logs(mut followers: Vec<(LogFollower, LogSource)>,
+               mut req: CtlRequest,
+               ident: &PackageIdent) {
+    loop {
+        if req.client_gone() {
+            debug!("Stopped following the logs of {}, the client went away",
+                   ident);
+            return;
+        }
+        for (follower, source) in followers.iter_mut() {
+            match follower.read_lines() {
+                Ok(lines) => {
+                    for line in lines.into_iter().filter(|l| source.includes(l)) {
+                        if !req.try_reply_partial(console_line(line)) {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    debug!("Stopped following the logs of {}, {}", ident, e);
+                    req.reply_complete(net::ok());
+                    return;
+                }
+            }
+        }
+        thread::sleep(LOG_FOLLOW_INTERVAL);
+    }
+}
+
+fn console_line(mut line: String) -> protocol::ctl::ConsoleLine {
+    line.push('\n');
+    protocol::ctl::ConsoleLine { line,
+                                 color: None,
+                                 bold: false }
+}
+
 fn err_update_client() -> net::NetErr { net::err(ErrCode::UpdateClient, "client out of date") }
 
 #[derive(Deserialize)]
 struct ServiceStatus {
-    pkg:           Pkg,
-    process:       ProcessStatus,
-    service_group: ServiceGroup,
-    desired_state: DesiredState,
+    pkg:              Pkg,
+    process:          ProcessStatus,
+    service_group:    ServiceGroup,
+    desired_state:    DesiredState,
+    #[serde(default, deserialize_with = "deserialize_ident")]
+    rolled_back_from: Option<PackageIdent>,
+    #[serde(default)]
+    pending_update:   Option<PendingUpdate>,
+    #[serde(default)]
+    crash_looping:    bool,
+    #[serde(default)]
+    restarts:         u32,
 }
 
 impl fmt::Display for ServiceStatus {
@@ -414,6 +790,10 @@ impl From<ServiceStatus> for protocol::types::ServiceStatus {
         proto.process = Some(other.process.into());
         proto.service_group = other.service_group.into();
         proto.desired_state = Some(other.desired_state.into());
+        proto.rolled_back_from = other.rolled_back_from.map(Into::into);
+        proto.pending_update = other.pending_update.map(Into::into);
+        proto.crash_looping = Some(other.crash_looping);
+        proto.restarts = Some(other.restarts);
         proto
     }
 }
@@ -475,6 +855,14 @@ fn deserialize_time<'de, D>(d: D) -> result::Result<TimeDuration, D::Error>
     d.deserialize_u64(FromTimespec)
 }
 
+fn deserialize_ident<'de, D>(d: D) -> result::Result<Option<PackageIdent>, D::Error>
+    where D: serde::Deserializer<'de>
+{
+    let ident: Option<String> = serde::Deserialize::deserialize(d)?;
+    ident.map(|ident| ident.parse().map_err(serde::de::Error::custom))
+         .transpose()
+}
+
 /// Helper function to ensure that all errors in sending are handled identically.
 fn send_action(action: SupervisorAction, sender: &ActionSender) -> NetResult<()> {
     if sender.send(action).is_err() {
@@ -485,3 +873,83 @@ fn send_action(action: SupervisorAction, sender: &ActionSender) -> NetResult<()>
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{manager::{service_updater::ServiceUpdater,
+                          user_config_watcher::UserConfigWatcher,
+                          GatewayState,
+                          ManagerConfig},
+                test_helpers::butterfly_server};
+    use std::{collections::HashMap,
+              sync::{Arc,
+                     Mutex,
+                     RwLock}};
+
+    fn manager_state(cfg: ManagerConfig) -> ManagerState {
+        let butterfly = butterfly_server();
+        ManagerState { cfg,
+                       services: Arc::new(RwLock::new(HashMap::new())),
+                       gateway_state: Arc::new(RwLock::new(GatewayState::default())),
+                       updater: Arc::new(Mutex::new(ServiceUpdater::new(butterfly.clone()))),
+                       butterfly,
+                       user_config_watcher: Arc::new(RwLock::new(UserConfigWatcher::new())) }
+    }
+
+    #[test]
+    fn pending_elections_report_their_status_and_votes() {
+        let mut election = Election::new("member-a", "redis.default", 3, 0, true);
+        election.votes.push("member-b".to_string());
+
+        let pending = pending_election(&election, true).expect("a pending election");
+        assert_eq!(pending.service_group, "redis.default");
+        assert!(pending.update);
+        assert_eq!(pending.term, 3);
+        assert_eq!(pending.status, "running");
+        assert_eq!(pending.member_id, "member-a");
+        assert_eq!(pending.votes, 2);
+
+        let election = Election::new("member-a", "redis.default", 3, 0, false);
+        let pending = pending_election(&election, false).expect("a pending election");
+        assert!(!pending.update);
+        assert_eq!(pending.status, "no-quorum");
+    }
+
+    #[test]
+    fn finished_elections_are_not_pending() {
+        let mut election = Election::new("member-a", "redis.default", 3, 0, true);
+        election.finish();
+
+        assert!(pending_election(&election, false).is_none());
+    }
+
+    #[test]
+    fn watcher_statuses_list_the_specs_and_peers_watchers() {
+        let cfg = ManagerConfig { custom_state_path: Some(PathBuf::from("/tmp/watchers")),
+                                  watch_peer_file: Some("/tmp/peers".to_string()),
+                                  ..ManagerConfig::default() };
+        let mgr = manager_state(cfg);
+
+        let watchers = watcher_statuses(&mgr);
+
+        assert_eq!(watchers.len(), 2);
+        assert_eq!(watchers[0].kind, "specs");
+        assert_eq!(watchers[0].path,
+                   mgr.cfg.sup_root().join("specs").display().to_string());
+        assert_eq!(watchers[0].service, None);
+        assert_eq!(watchers[1].kind, "peers");
+        assert_eq!(watchers[1].path, "/tmp/peers");
+        assert_eq!(watchers[1].service, None);
+    }
+
+    #[test]
+    fn without_a_peer_file_only_specs_are_watched() {
+        let mgr = manager_state(ManagerConfig::default());
+
+        let watchers = watcher_statuses(&mgr);
+
+        assert_eq!(watchers.len(), 1);
+        assert_eq!(watchers[0].kind, "specs");
+    }
+}
@@ -30,7 +30,9 @@ use self::{action::{ShutdownSpec,
            spec_watcher::SpecWatcher,
            sys::Sys,
            user_config_watcher::UserConfigWatcher};
-use crate::{census::{CensusRing,
+use crate::{audit::AuditLog,
+            census::{AuthorTrust,
+                     CensusRing,
                      CensusRingProxy},
             config::GossipListenAddr,
             ctl_gateway::{self,
@@ -53,13 +55,19 @@ use habitat_butterfly::{member::Member,
                         server::{timing::Timing,
                                  ServerProxy,
                                  Suitability},
-                        trace::Trace};
+                        trace::Trace,
+                        transport::TcpTransport,
+                        trust::{MemberKey,
+                                MemberTrust}};
 use habitat_common::{outputln,
-                     types::ListenCtlAddr,
+                     service_log::LogConfig,
+                     types::{EventSinkConfig,
+                             ListenCtlAddr},
                      FeatureFlag};
 #[cfg(unix)]
 use habitat_core::os::signals::SignalEvent;
-use habitat_core::{crypto::SymKey,
+use habitat_core::{crypto::{keys::parse_name_with_rev,
+                            SymKey},
                    env::{self,
                          Config},
                    fs::FS_ROOT_PATH,
@@ -70,7 +78,8 @@ use habitat_core::{crypto::SymKey,
                    package::{Identifiable,
                              PackageIdent,
                              PackageInstall},
-                   service::ServiceGroup,
+                   service::{ServiceGroup,
+                             UpdateWindow},
                    util::ToI64,
                    ChannelIdent};
 use habitat_launcher_client::{LauncherCli,
@@ -184,6 +193,7 @@ pub struct FsCfg {
 
     data_path:      PathBuf,
     specs_path:     PathBuf,
+    audit_path:     PathBuf,
     member_id_file: PathBuf,
     proc_lock_file: PathBuf,
 }
@@ -195,6 +205,7 @@ impl FsCfg {
         let sup_root = sup_root.into();
         FsCfg { specs_path: sup_root.join("specs"),
                 data_path: sup_root.join("data"),
+                audit_path: sup_root.join("audit"),
                 member_id_file: sup_root.join(MEMBER_ID_FILE),
                 proc_lock_file: sup_root.join(PROC_LOCK_FILE),
                 sup_root }
@@ -203,23 +214,39 @@ impl FsCfg {
 
 #[derive(Clone, Debug)]
 pub struct ManagerConfig {
-    pub auto_update:         bool,
-    pub custom_state_path:   Option<PathBuf>,
-    pub cache_key_path:      PathBuf,
-    pub update_url:          String,
-    pub update_channel:      ChannelIdent,
-    pub gossip_listen:       GossipListenAddr,
-    pub ctl_listen:          ListenCtlAddr,
-    pub http_listen:         http_gateway::ListenAddr,
-    pub http_disable:        bool,
-    pub gossip_peers:        Vec<SocketAddr>,
-    pub gossip_permanent:    bool,
-    pub ring_key:            Option<SymKey>,
-    pub organization:        Option<String>,
-    pub watch_peer_file:     Option<String>,
-    pub tls_config:          Option<TLSConfig>,
-    pub feature_flags:       FeatureFlag,
-    pub event_stream_config: Option<EventStreamConfig>,
+    pub auto_update:            bool,
+    pub custom_state_path:      Option<PathBuf>,
+    pub cache_key_path:         PathBuf,
+    pub update_url:             String,
+    pub update_channel:         ChannelIdent,
+    pub update_window:          Option<UpdateWindow>,
+    pub gossip_listen:          GossipListenAddr,
+    pub ctl_listen:             ListenCtlAddr,
+    pub http_listen:            http_gateway::ListenAddr,
+    pub http_disable:           bool,
+    pub gossip_peers:           Vec<SocketAddr>,
+    pub gossip_permanent:       bool,
+    pub ring_key:               Option<SymKey>,
+    /// The key this Supervisor signs its gossip with.
+    pub member_key:             Option<MemberKey>,
+    /// Whose signed gossip this Supervisor accepts. Without it, gossip is not verified.
+    pub member_trust:           Option<MemberTrust>,
+    /// Whose signed configuration and files this Supervisor applies. Without it, they are
+    /// applied whether or not they are signed.
+    pub author_trust:           Option<AuthorTrust>,
+    pub organization:           Option<String>,
+    pub watch_peer_file:        Option<String>,
+    pub tls_config:             Option<TLSConfig>,
+    /// TLS settings for the CtlGateway. Without them, it accepts plain TCP connections.
+    pub ctl_tls_config:         Option<TLSConfig>,
+    pub feature_flags:          FeatureFlag,
+    pub event_stream_config:    Option<EventStreamConfig>,
+    /// Where events are sent, besides any event stream.
+    pub event_sinks:            Vec<EventSinkConfig>,
+    /// How many events each sink holds while it is unavailable.
+    pub event_sink_buffer_size: usize,
+    /// How the output of services is logged to disk.
+    pub service_log:            LogConfig,
 }
 
 #[derive(Clone, Debug)]
@@ -324,6 +351,11 @@ pub struct ManagerState {
     pub cfg: ManagerConfig,
     pub services: Arc<RwLock<HashMap<PackageIdent, Service>>>,
     pub gateway_state: Arc<RwLock<GatewayState>>,
+    /// Shared with the `Manager`, so that `hab sup debug` can report
+    /// their internal state.
+    pub butterfly: habitat_butterfly::Server,
+    pub updater: Arc<Mutex<ServiceUpdater>>,
+    pub user_config_watcher: Arc<RwLock<UserConfigWatcher>>,
 }
 
 /// All the data that is ultimately served from the Supervisor's HTTP
@@ -339,6 +371,12 @@ pub struct GatewayState {
     /// Data returned by /services/<SERVICE_NAME>/<GROUP_NAME>/health
     /// endpoint
     pub health_check_data: HashMap<ServiceGroup, HealthCheckResult>,
+    /// Data returned by /services/<SERVICE_NAME>/<GROUP_NAME>/ready
+    /// endpoint
+    pub readiness_data: HashMap<ServiceGroup, bool>,
+    /// Whether configuration must be signed by a trusted user key
+    /// before it is applied
+    pub config_requires_author: bool,
 }
 
 pub struct Manager {
@@ -362,10 +400,14 @@ pub struct Manager {
     user_config_watcher: Arc<RwLock<UserConfigWatcher>>,
     spec_dir:            SpecDir,
     organization:        Option<String>,
+    service_log:         LogConfig,
+    audit_log:           AuditLog,
     self_updater:        Option<SelfUpdater>,
     service_states:      HashMap<PackageIdent, Timespec>,
     sys:                 Arc<Sys>,
     http_disable:        bool,
+    /// The ring key store's update counter when its rumors were last applied.
+    ring_key_counter:    usize,
 
     /// Collects the identifiers of all services that are currently
     /// doing something asynchronously (like shutting down, or running
@@ -427,7 +469,10 @@ impl Manager {
         let cfg_static = cfg.clone();
         let self_updater = if cfg.auto_update {
             if current.fully_qualified() {
-                Some(SelfUpdater::new(current, cfg.update_url, cfg.update_channel))
+                Some(SelfUpdater::new(current,
+                                      cfg.update_url,
+                                      cfg.update_channel,
+                                      cfg.update_window))
             } else {
                 warn!("Supervisor version not fully qualified, unable to start self-updater");
                 None
@@ -442,15 +487,51 @@ impl Manager {
         let member = Self::load_member(&mut sys, &fs_cfg)?;
         let services = Arc::new(RwLock::new(HashMap::new()));
 
-        let server = habitat_butterfly::Server::new(sys.gossip_listen(),
-                                                    sys.gossip_listen(),
-                                                    member,
-                                                    Trace::default(),
-                                                    cfg.ring_key,
-                                                    None,
-                                                    Some(&fs_cfg.data_path),
-                                                    Box::new(SuitabilityLookup(services.clone())))?;
+        let mut server =
+            habitat_butterfly::Server::new(sys.gossip_listen(),
+                                           sys.gossip_listen(),
+                                           member,
+                                           Trace::default(),
+                                           cfg.ring_key,
+                                           None,
+                                           Some(&fs_cfg.data_path),
+                                           Box::new(SuitabilityLookup(services.clone())))?;
         outputln!("Supervisor Member-ID {}", sys.member_id);
+        if let Some(member_key) = cfg.member_key {
+            outputln!("Signing gossip with member key {}",
+                      member_key.name_with_rev());
+            server.set_member_key(member_key);
+        }
+        if let Some(member_trust) = cfg.member_trust {
+            outputln!("Only accepting gossip from trusted member keys, {:?}",
+                      member_trust);
+            server.set_member_trust(member_trust);
+        }
+        if cfg.feature_flags.contains(FeatureFlag::TCP_GOSSIP) {
+            outputln!("Carrying gossip over native TCP, and ZeroMQ for members that need it");
+            server.set_gossip_transport(Box::new(TcpTransport::default()));
+        }
+        if let Some(ring_key) = server.ring_key() {
+            // Newer revisions in the key cache are accepted too, in case a ring key rotation was
+            // underway when this Supervisor stopped. Those retired before it stopped are dropped
+            // once the gossip server starts.
+            match SymKey::get_pairs_for(&ring_key.name, &cfg.cache_key_path) {
+                Ok(revisions) => {
+                    for revision in revisions {
+                        server.add_ring_key(revision);
+                    }
+                }
+                Err(err) => {
+                    warn!("Unable to read revisions of ring key {}, {}",
+                          ring_key.name, err)
+                }
+            }
+        }
+        if let Some(ref author_trust) = cfg.author_trust {
+            outputln!("Only applying configuration and files signed by trusted user keys, {:?}",
+                      author_trust);
+            server.set_author_check(Box::new(author_trust.clone()));
+        }
         for peer_addr in &cfg.gossip_peers {
             let mut peer = Member::default();
             peer.address = format!("{}", peer_addr.ip());
@@ -470,6 +551,24 @@ impl Manager {
 
         let spec_watcher = SpecWatcher::run(&spec_dir)?;
 
+        let publish_audit = cfg.feature_flags.contains(FeatureFlag::EVENT_STREAM)
+                            && cfg.event_stream_config
+                                  .as_ref()
+                                  .map_or(false, EventStreamConfig::audit);
+        // Collect the FQDN of the running machine
+        let fqdn = habitat_core::os::net::fqdn().unwrap_or_else(|| sys.hostname.clone());
+        outputln!("Event FQDN {}", fqdn);
+
+        // Events are always available to subscribers of the HTTP
+        // gateway's `/events` endpoint, whether or not they are also
+        // sent to an event stream.
+        let es_config = if cfg.feature_flags.contains(FeatureFlag::EVENT_STREAM) {
+            cfg.event_stream_config
+        } else {
+            None
+        };
+        event::init_core(EventCore::new(es_config.as_ref(), &sys, fqdn));
+
         if cfg.feature_flags.contains(FeatureFlag::EVENT_STREAM) {
             // Putting configuration of the stream behind a feature
             // flag for now. If the flag isn't set, just don't
@@ -479,37 +578,46 @@ impl Manager {
             // TODO: Determine what the actual connection parameters
             // should be, and process them at some point before here.
             let es_config =
-                cfg.event_stream_config
-                   .expect("Config should be present if the EventStream feature is enabled");
-
-            // Collect the FQDN of the running machine
-            let fqdn = habitat_core::os::net::fqdn().unwrap_or_else(|| sys.hostname.clone());
-            outputln!("Event FQDN {}", fqdn);
+                es_config.expect("Config should be present if the EventStream feature is enabled");
 
-            let ec = EventCore::new(&es_config, &sys, fqdn);
             // unwrap won't fail here; if there were an issue, from_env()
             // would have already propagated an error up the stack.
-            event::init_stream(es_config, ec)?;
+            event::init_stream(es_config, cfg.event_sink_buffer_size)?;
         }
+        event::init_sinks(&cfg.event_sinks, cfg.event_sink_buffer_size)?;
 
+        let audit_log = AuditLog::open(&fs_cfg.audit_path, cfg.service_log, publish_audit)?;
+
+        let updater = Arc::new(Mutex::new(ServiceUpdater::new(server.clone())));
+        let user_config_watcher = Arc::new(RwLock::new(UserConfigWatcher::new()));
+        let gateway_state = GatewayState { config_requires_author: cfg_static.author_trust
+                                                                             .is_some(),
+                                           ..Default::default() };
         Ok(Manager { state: Arc::new(ManagerState { cfg: cfg_static,
                                                     services,
                                                     gateway_state:
-                                                        Arc::new(RwLock::new(GatewayState::default())) }),
+                                                        Arc::new(RwLock::new(gateway_state)),
+                                                    butterfly: server.clone(),
+                                                    updater: Arc::clone(&updater),
+                                                    user_config_watcher:
+                                                        Arc::clone(&user_config_watcher) }),
                      self_updater,
-                     updater: Arc::new(Mutex::new(ServiceUpdater::new(server.clone()))),
+                     updater,
                      census_ring: CensusRing::new(sys.member_id.clone()),
                      butterfly: server,
                      launcher,
                      peer_watcher,
                      spec_watcher,
-                     user_config_watcher: Arc::new(RwLock::new(UserConfigWatcher::new())),
+                     user_config_watcher,
                      spec_dir,
                      fs_cfg: Arc::new(fs_cfg),
                      organization: cfg.organization,
+                     service_log: cfg.service_log,
+                     audit_log,
                      service_states: HashMap::new(),
                      sys: Arc::new(sys),
                      http_disable: cfg.http_disable,
+                     ring_key_counter: 0,
                      busy_services: Arc::new(Mutex::new(HashSet::new())),
                      services_need_reconciliation: ReconciliationFlag::new(false),
                      feature_flags: cfg.feature_flags })
@@ -595,16 +703,27 @@ impl Manager {
     }
 
     fn add_service(&mut self, spec: &ServiceSpec) {
+        // A service that was rolled back from a failed update stays on
+        // the release it was rolled back to.
+        let rollback = self.updater
+                           .lock()
+                           .expect("Updater lock poisoned")
+                           .rollback(&spec.ident)
+                           .cloned();
+
         // JW TODO: This clone sucks, but our data structures are a bit messy here. What we really
         // want is the service to hold the spec and, on failure, return an error with the spec
         // back to us. Since we consume and deconstruct the spec in `Service::new()` which
         // `Service::load()` eventually delegates to we just can't have that. We should clean
         // this up in the future.
-        let service = match Service::load(self.sys.clone(),
-                                          spec.clone(),
-                                          self.fs_cfg.clone(),
-                                          self.organization.as_ref().map(|org| &**org),
-                                          self.state.gateway_state.clone())
+        let mut service = match Service::load(self.sys.clone(),
+                                              spec.clone(),
+                                              rollback.as_ref().map(|r| &r.to),
+                                              self.fs_cfg.clone(),
+                                              self.organization.as_ref().map(|org| &**org),
+                                              self.service_log,
+                                              self.audit_log.clone(),
+                                              self.state.gateway_state.clone())
         {
             Ok(service) => {
                 outputln!("Starting {} ({})", &spec.ident, service.pkg.ident);
@@ -615,6 +734,7 @@ impl Manager {
                 return;
             }
         };
+        service.rolled_back_from = rollback.map(|r| r.from);
 
         if let Ok(package) =
             PackageInstall::load(&service.pkg.ident, Some(Path::new(&*FS_ROOT_PATH)))
@@ -721,8 +841,18 @@ impl Manager {
         let http_listen_addr = self.sys.http_listen();
         let ctl_listen_addr = self.sys.ctl_listen();
         let ctl_secret_key = ctl_gateway::readgen_secret_key(&self.fs_cfg.sup_root)?;
+        let ctl_credentials =
+            ctl_gateway::auth::Credentials::load(&self.fs_cfg.sup_root, ctl_secret_key)?;
+        let ctl_tls_server_config = match &self.state.cfg.ctl_tls_config {
+            Some(c) => Some(tls_config(c)?),
+            None => None,
+        };
         outputln!("Starting ctl-gateway on {}", &ctl_listen_addr);
-        ctl_gateway::server::run(ctl_listen_addr, ctl_secret_key, mgr_sender);
+        ctl_gateway::server::run(ctl_listen_addr,
+                                 ctl_credentials,
+                                 mgr_sender.clone(),
+                                 self.audit_log.clone(),
+                                 ctl_tls_server_config);
         debug!("ctl-gateway started");
 
         if self.http_disable {
@@ -754,6 +884,8 @@ impl Manager {
                                       self.state.gateway_state.clone(),
                                       http_gateway::GatewayAuthenticationToken::configured_value(),
                                       self.feature_flags,
+                                      mgr_sender,
+                                      self.audit_log.clone(),
                                       pair.clone());
 
             let &(ref lock, ref cvar) = &*pair;
@@ -939,6 +1071,7 @@ impl Manager {
             }
 
             self.update_peers_from_watch_file()?;
+            self.update_ring_keys_from_rumors();
             self.update_running_services_from_user_config_watcher();
 
             for f in self.stop_services_with_updates() {
@@ -948,6 +1081,7 @@ impl Manager {
             self.restart_elections(self.feature_flags);
             self.census_ring
                 .update_from_rumors(&self.state.cfg.cache_key_path,
+                                    self.state.cfg.author_trust.as_ref(),
                                     &self.butterfly.service_store,
                                     &self.butterfly.election_store,
                                     &self.butterfly.update_store,
@@ -976,6 +1110,7 @@ impl Manager {
                 if service.tick(&self.census_ring, &self.launcher, &runtime.executor()) {
                     self.gossip_latest_service_rumor(&service);
                 }
+                service.update_metrics();
             }
 
             // This is really only needed until everything is running
@@ -1073,6 +1208,22 @@ impl Manager {
                                                       })
                                                       .collect();
 
+        // Services that are not restarting may still have a new canary
+        // verdict for the rest of their group.
+        let idents_to_regossip: Vec<_> =
+            state_services.iter()
+                          .filter(|(current_ident, service)| {
+                              !idents_to_restart.contains(current_ident)
+                              && updater.take_regossip(&service.service_group)
+                          })
+                          .map(|(current_ident, _)| current_ident.clone())
+                          .collect();
+        // Gossiping a service rumor takes the updater lock.
+        drop(updater);
+        for current_ident in idents_to_regossip {
+            self.gossip_latest_service_rumor(&state_services[&current_ident]);
+        }
+
         let mut services_to_restart = Vec::with_capacity(idents_to_restart.len());
         for current_ident in idents_to_restart {
             // unwrap is safe because we've to the write lock, and we
@@ -1115,7 +1266,12 @@ impl Manager {
             1
         };
 
-        self.butterfly.insert_service(service.to_rumor(incarnation));
+        let mut rumor = service.to_rumor(incarnation);
+        rumor.canary = self.updater
+                           .lock()
+                           .expect("Updater lock poisoned")
+                           .canary_release(&service.service_group);
+        self.butterfly.insert_service(rumor);
     }
 
     fn check_for_departure(&self) -> bool { self.butterfly.is_departed() }
@@ -1123,6 +1279,8 @@ impl Manager {
     fn check_for_changed_services(&mut self) -> bool {
         let mut service_states = HashMap::new();
         let mut active_services = Vec::new();
+        let mut pending_updates_changed = false;
+        let updater = self.updater.lock().expect("Updater lock poisoned");
         for service in self.state
                            .services
                            .write()
@@ -1131,7 +1289,15 @@ impl Manager {
         {
             service_states.insert(service.spec_ident.clone(), service.last_state_change());
             active_services.push(service.spec_ident.clone());
+            // Updates held until an update window opens show up in
+            // `hab svc status`.
+            let pending_update = updater.pending_update(service);
+            if service.pending_update != pending_update {
+                service.pending_update = pending_update;
+                pending_updates_changed = true;
+            }
         }
+        drop(updater);
 
         for loaded in self.spec_dir
                           .specs()
@@ -1145,7 +1311,7 @@ impl Manager {
             self.service_states = service_states.clone();
             true
         } else {
-            false
+            pending_updates_changed
         }
     }
 
@@ -1203,8 +1369,11 @@ impl Manager {
                 .flat_map(|spec| {
                     Service::load(self.sys.clone(),
                                   spec.clone(),
+                                  None,
                                   self.fs_cfg.clone(),
                                   self.organization.as_ref().map(|org| &**org),
+                                  self.service_log,
+                                  self.audit_log.clone(),
                                   self.state.gateway_state.clone()).into_iter()
                 })
                 .collect();
@@ -1529,6 +1698,40 @@ impl Manager {
         }
     }
 
+    /// Keep each newer revision of the ring key gossiped to the ring in the key cache, so it
+    /// survives a restart, and start decrypting with it.
+    fn update_ring_keys_from_rumors(&mut self) {
+        let update_counter = self.butterfly.ring_key_store.get_update_counter();
+        if update_counter == self.ring_key_counter {
+            return;
+        }
+        self.ring_key_counter = update_counter;
+        let current = match self.butterfly.ring_key() {
+            Some(current) => current,
+            None => return,
+        };
+        let mut rumors = Vec::new();
+        self.butterfly
+            .ring_key_store
+            .with_rumors("ring_key", |rumor| rumors.push(rumor.clone()));
+        for rumor in rumors {
+            match parse_name_with_rev(&rumor.name_with_rev) {
+                Ok((ref name, ref rev)) if *name == current.name && *rev > current.rev => {}
+                _ => continue,
+            }
+            match SymKey::write_file_from_str(&rumor.secret, &self.state.cfg.cache_key_path) {
+                Ok((ring_key, _)) => {
+                    if self.butterfly.add_ring_key(ring_key) {
+                        outputln!("Received ring key {}; it will be used once every member \
+                                   holds it",
+                                  rumor.name_with_rev);
+                    }
+                }
+                Err(err) => warn!("Unable to keep ring key {}, {}", rumor.name_with_rev, err),
+            }
+        }
+    }
+
     fn update_running_services_from_user_config_watcher(&mut self) {
         let mut services = self.state
                                .services
@@ -1800,23 +2003,31 @@ mod test {
     // code, so only implement it under test configuration.
     impl Default for ManagerConfig {
         fn default() -> Self {
-            ManagerConfig { auto_update:         false,
-                            custom_state_path:   None,
-                            cache_key_path:      cache_key_path(Some(&*FS_ROOT)),
-                            update_url:          "".to_string(),
-                            update_channel:      ChannelIdent::default(),
-                            gossip_listen:       GossipListenAddr::default(),
-                            ctl_listen:          ListenCtlAddr::default(),
-                            http_listen:         http_gateway::ListenAddr::default(),
-                            http_disable:        false,
-                            gossip_peers:        vec![],
-                            gossip_permanent:    false,
-                            ring_key:            None,
-                            organization:        None,
-                            watch_peer_file:     None,
-                            tls_config:          None,
-                            feature_flags:       FeatureFlag::empty(),
-                            event_stream_config: None, }
+            ManagerConfig { auto_update:            false,
+                            custom_state_path:      None,
+                            cache_key_path:         cache_key_path(Some(&*FS_ROOT)),
+                            update_url:             "".to_string(),
+                            update_channel:         ChannelIdent::default(),
+                            update_window:          None,
+                            gossip_listen:          GossipListenAddr::default(),
+                            ctl_listen:             ListenCtlAddr::default(),
+                            http_listen:            http_gateway::ListenAddr::default(),
+                            http_disable:           false,
+                            gossip_peers:           vec![],
+                            gossip_permanent:       false,
+                            ring_key:               None,
+                            member_key:             None,
+                            member_trust:           None,
+                            author_trust:           None,
+                            organization:           None,
+                            watch_peer_file:        None,
+                            tls_config:             None,
+                            ctl_tls_config:         None,
+                            feature_flags:          FeatureFlag::empty(),
+                            event_stream_config:    None,
+                            event_sinks:            Vec::new(),
+                            event_sink_buffer_size: event::DEFAULT_BUFFER_SIZE,
+                            service_log:            LogConfig::default(), }
         }
     }
 
@@ -1879,6 +2090,131 @@ mod test {
 
     }
 
+    mod tls {
+        use super::*;
+        use rustls::{ClientConfig,
+                     ClientSession,
+                     ServerSession,
+                     Session,
+                     TLSError};
+        use std::io::{Read,
+                      Write};
+        use tokio_rustls::webpki::DNSNameRef;
+
+        fn fixture(name: &str) -> PathBuf {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
+                                                     .join("fixtures")
+                                                     .join("tls")
+                                                     .join(name)
+        }
+
+        fn server_session(require_client_cert: bool) -> ServerSession {
+            let config = TLSConfig { cert_path:    fixture("server.crt"),
+                                     key_path:     fixture("server.key"),
+                                     ca_cert_path: if require_client_cert {
+                                         Some(fixture("ca.crt"))
+                                     } else {
+                                         None
+                                     }, };
+            ServerSession::new(&Arc::new(tls_config(&config).expect("Couldn't load the server \
+                                                                     TLS config")))
+        }
+
+        /// A session with a client which trusts the test CA, and presents the named certificate
+        /// if one is given.
+        fn client_session(cert: Option<&str>) -> ClientSession {
+            let mut config = ClientConfig::new();
+            let ca_file = &mut BufReader::new(File::open(fixture("ca.crt")).unwrap());
+            config.root_store.add_pem_file(ca_file).unwrap();
+            if let Some(name) = cert {
+                let cert_file =
+                    &mut BufReader::new(File::open(fixture(&format!("{}.crt", name))).unwrap());
+                let key_file =
+                    &mut BufReader::new(File::open(fixture(&format!("{}.key", name))).unwrap());
+                config.set_single_client_cert(pemfile::certs(cert_file).unwrap(),
+                                              pemfile::rsa_private_keys(key_file).unwrap()
+                                                                                 .remove(0));
+            }
+            let name = DNSNameRef::try_from_ascii_str("localhost").unwrap();
+            ClientSession::new(&Arc::new(config), name)
+        }
+
+        /// Move everything `from` has to send over to `to`, returning how many bytes that was.
+        fn transfer(from: &mut dyn Session, to: &mut dyn Session) -> usize {
+            let mut buf = Vec::new();
+            while from.wants_write() {
+                from.write_tls(&mut buf).unwrap();
+            }
+            let mut pending = &buf[..];
+            while !pending.is_empty() {
+                to.read_tls(&mut pending).unwrap();
+            }
+            buf.len()
+        }
+
+        fn handshake(client: &mut ClientSession,
+                     server: &mut ServerSession)
+                     -> result::Result<(), TLSError> {
+            while client.is_handshaking() || server.is_handshaking() {
+                let sent = transfer(client, server);
+                server.process_new_packets()?;
+                let received = transfer(server, client);
+                client.process_new_packets()?;
+                if sent == 0 && received == 0 {
+                    panic!("The TLS handshake stalled");
+                }
+            }
+            Ok(())
+        }
+
+        fn assert_round_trip(client: &mut ClientSession, server: &mut ServerSession) {
+            client.write_all(b"ping").unwrap();
+            transfer(client, server);
+            server.process_new_packets().unwrap();
+            let mut buf = [0; 4];
+            server.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf, b"ping");
+
+            server.write_all(b"pong").unwrap();
+            transfer(server, client);
+            client.process_new_packets().unwrap();
+            client.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf, b"pong");
+        }
+
+        #[test]
+        fn clients_trusting_the_server_complete_a_handshake() {
+            let mut client = client_session(None);
+            let mut server = server_session(false);
+            handshake(&mut client, &mut server).expect("TLS handshake failed");
+            assert_round_trip(&mut client, &mut server);
+        }
+
+        #[test]
+        fn clients_with_a_trusted_certificate_complete_a_mutual_handshake() {
+            let mut client = client_session(Some("client"));
+            let mut server = server_session(true);
+            handshake(&mut client, &mut server).expect("TLS handshake failed");
+            assert!(server.get_peer_certificates()
+                          .map_or(false, |c| !c.is_empty()));
+            assert_round_trip(&mut client, &mut server);
+        }
+
+        #[test]
+        fn clients_without_a_certificate_are_rejected() {
+            let mut client = client_session(None);
+            let mut server = server_session(true);
+            assert!(handshake(&mut client, &mut server).is_err());
+        }
+
+        #[test]
+        fn clients_with_an_untrusted_certificate_are_rejected() {
+            let mut client = client_session(Some("other-client"));
+            let mut server = server_session(true);
+            assert!(handshake(&mut client, &mut server).is_err());
+        }
+    }
+
     mod specs_to_operations {
         //! Testing out the reconciliation of on-disk spec files with
         //! what is currently running.
@@ -9,11 +9,14 @@ use std::{sync::mpsc::{sync_channel,
           time::Duration};
 
 use crate::{env,
+            event,
             util};
 use habitat_common::{command::package::install::InstallSource,
+                     outputln,
                      ui::UI};
 use habitat_core::{package::{PackageIdent,
                              PackageInstall},
+                   service::UpdateWindow,
                    ChannelIdent};
 use time::{Duration as TimeDuration,
            SteadyTime};
@@ -27,18 +30,30 @@ pub struct SelfUpdater {
     current:        PackageIdent,
     update_url:     String,
     update_channel: ChannelIdent,
+    update_window:  Option<UpdateWindow>,
+    /// A newer Supervisor that was found while the update window was
+    /// closed.
+    held:           Option<PackageInstall>,
+    held_announced: bool,
 }
 
 // TODO (CM): Want to use the Periodic trait here, but can't due to
 // how things are currently structured (The service updater had a worker)
 
 impl SelfUpdater {
-    pub fn new(current: PackageIdent, update_url: String, update_channel: ChannelIdent) -> Self {
+    pub fn new(current: PackageIdent,
+               update_url: String,
+               update_channel: ChannelIdent,
+               update_window: Option<UpdateWindow>)
+               -> Self {
         let rx = Self::init(current.clone(), update_url.clone(), update_channel.clone());
         SelfUpdater { rx,
                       current,
                       update_url,
-                      update_channel }
+                      update_channel,
+                      update_window,
+                      held:           None,
+                      held_announced: false, }
     }
 
     /// Spawn a new Supervisor updater thread.
@@ -95,8 +110,14 @@ impl SelfUpdater {
     }
 
     pub fn updated(&mut self) -> Option<PackageInstall> {
+        if self.held.is_some() {
+            return self.release_held();
+        }
         match self.rx.try_recv() {
-            Ok(package) => Some(package),
+            Ok(package) => {
+                self.held = Some(package);
+                self.release_held()
+            }
             Err(TryRecvError::Empty) => None,
             Err(TryRecvError::Disconnected) => {
                 debug!("Self updater has died, restarting...");
@@ -107,6 +128,34 @@ impl SelfUpdater {
             }
         }
     }
+
+    /// Hand back the held Supervisor package if the update window is
+    /// open (or there isn't one), otherwise keep holding on to it.
+    fn release_held(&mut self) -> Option<PackageInstall> {
+        match self.update_window {
+            Some(ref window) if !window.is_open() => {
+                if let Some(ref package) = self.held {
+                    if !self.held_announced {
+                        outputln!("Holding update to {} until the update window ({}) opens at {}",
+                                  package.ident(),
+                                  window,
+                                  window.next_opening()
+                                        .map(|t| time::at_utc(t).rfc3339().to_string())
+                                        .unwrap_or_else(|| "never".to_string()));
+                        self.held_announced = true;
+                    }
+                }
+                None
+            }
+            _ => {
+                let package = self.held.take();
+                if let Some(ref package) = package {
+                    event::supervisor_updated(&self.current, package.ident());
+                }
+                package
+            }
+        }
+    }
 }
 
 fn update_frequency() -> i64 {
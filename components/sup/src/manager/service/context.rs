@@ -411,6 +411,7 @@ struct SvcMember<'a> {
     suspect: Cow<'a, bool>,
     confirmed: Cow<'a, bool>,
     departed: Cow<'a, bool>,
+    ready: Cow<'a, bool>,
     cfg: Cow<'a, toml::value::Table>,
 }
 
@@ -444,6 +445,7 @@ impl<'a> SvcMember<'a> {
                     suspect:   Cow::Owned(c.suspect()),
                     confirmed: Cow::Owned(c.confirmed()),
                     departed:  Cow::Owned(c.departed()),
+                    ready:     Cow::Owned(c.ready()),
 
                     cfg: Cow::Borrowed(&c.cfg), }
     }
@@ -509,6 +511,7 @@ impl<'a> Serialize for SvcMember<'a> {
         map.serialize_entry("suspect", &self.suspect)?;
         map.serialize_entry("confirmed", &self.confirmed)?;
         map.serialize_entry("departed", &self.departed)?;
+        map.serialize_entry("ready", &self.ready)?;
 
         map.serialize_entry("cfg", &self.cfg)?;
 
@@ -646,6 +649,7 @@ two = 2
                     suspect: Cow::Owned(false),
                     confirmed: Cow::Owned(false),
                     departed: Cow::Owned(false),
+                    ready: Cow::Owned(true),
                     cfg: Cow::Owned(toml::value::Table::new()), }
     }
 
@@ -1,7 +1,9 @@
 use crate::manager::{event::{self,
                              ServiceMetadata as ServiceEventMetadata},
-                     service::{hook_runner,
-                               hooks::HealthCheckHook,
+                     service::{hook_runner::HookRunner,
+                               hooks::{HealthCheckHook,
+                                       ReadyHook},
+                               probe::ProbeRunner,
                                supervisor::Supervisor},
                      GatewayState};
 use futures::{future::{self,
@@ -10,8 +12,10 @@ use futures::{future::{self,
                        Future,
                        Loop},
               IntoFuture};
-use habitat_common::templating::package::Pkg;
-use habitat_core::service::{HealthCheckInterval,
+use habitat_common::{service_log::HookLog,
+                     templating::package::Pkg};
+use habitat_core::service::{HealthCheck,
+                            HealthCheckInterval,
                             ServiceGroup};
 use std::{fmt,
           ops::Deref,
@@ -21,7 +25,7 @@ use std::{fmt,
           time::{Duration,
                  Instant}};
 
-/// The possible results of running a health check hook.
+/// The possible results of running a health check hook or probe.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub enum HealthCheckResult {
     Ok,
@@ -59,11 +63,26 @@ impl fmt::Display for HealthCheckResult {
     }
 }
 
+impl HealthCheckResult {
+    /// Whether a service with this result should be considered ready
+    /// when it has no ready hook of its own.
+    pub fn is_passing(self) -> bool {
+        match self {
+            HealthCheckResult::Ok | HealthCheckResult::Warning => true,
+            HealthCheckResult::Critical | HealthCheckResult::Unknown => false,
+        }
+    }
+}
+
 /// All state needed for checking the health of a service over time.
 #[derive(Clone)]
 pub struct State {
+    /// A health check for the Supervisor to perform itself. If
+    /// present, it is used instead of any health check hook.
+    check:                  Option<HealthCheck>,
     // All hooks currently need these
     hook:                   Option<Arc<HealthCheckHook>>,
+    ready_hook:             Option<Arc<ReadyHook>>,
     service_group:          ServiceGroup,
     package:                Pkg,
     svc_encrypted_password: Option<String>,
@@ -72,7 +91,7 @@ pub struct State {
 
     /// A reference to the process supervisor for the service. This is
     /// used to create a "proxy health check" for services that do not
-    /// provide their own health check hook.
+    /// provide their own health check hook or probe.
     supervisor: Arc<Mutex<Supervisor>>,
 
     /// The configured interval at which to run health checks for this
@@ -84,15 +103,26 @@ pub struct State {
     /// store the result of the health check here.
     service_health_result: Arc<Mutex<HealthCheckResult>>,
 
+    /// A reference to whether the service is currently ready. The
+    /// ready hook is run after every health check that isn't
+    /// critical; without one, the service is ready whenever its
+    /// health check passes.
+    service_ready: Arc<Mutex<bool>>,
+
     /// A reference to the Supervisor's gateway state. We also store
-    /// the status in here for making it available via the HTTP
-    /// gateway.
+    /// the status and readiness in here for making them available via
+    /// the HTTP gateway.
     gateway_state: Arc<RwLock<GatewayState>>,
+
+    /// How the output of the health check and ready hooks is logged.
+    hook_log: HookLog,
 }
 
 impl State {
     #[allow(clippy::too_many_arguments)]
-    pub fn new(hook: Option<Arc<HealthCheckHook>>,
+    pub fn new(check: Option<HealthCheck>,
+               hook: Option<Arc<HealthCheckHook>>,
+               ready_hook: Option<Arc<ReadyHook>>,
                service_group: ServiceGroup,
                package: Pkg,
                svc_encrypted_password: Option<String>,
@@ -100,9 +130,13 @@ impl State {
                supervisor: Arc<Mutex<Supervisor>>,
                nominal_interval: HealthCheckInterval,
                service_health_result: Arc<Mutex<HealthCheckResult>>,
-               gateway_state: Arc<RwLock<GatewayState>>)
+               service_ready: Arc<Mutex<bool>>,
+               gateway_state: Arc<RwLock<GatewayState>>,
+               hook_log: HookLog)
                -> Self {
-        State { hook,
+        State { check,
+                hook,
+                ready_hook,
                 service_group,
                 package,
                 svc_encrypted_password,
@@ -110,14 +144,19 @@ impl State {
                 supervisor,
                 nominal_interval,
                 service_health_result,
-                gateway_state }
+                service_ready,
+                gateway_state,
+                hook_log }
     }
 
-    /// Creates a future that runs the health check and then waits for
-    /// a suitable interval. Multiple such iterations will then be
+    /// Creates a future that runs the health check, followed by the
+    /// ready hook if there is one, and then waits for a suitable
+    /// interval. Multiple such iterations will then be
     /// chained together for an unending stream of health checks.
     fn single_iteration(self) -> impl Future<Item = (), Error = ()> {
-        let State { hook,
+        let State { check,
+                    hook,
+                    ready_hook,
                     service_group,
                     package,
                     svc_encrypted_password,
@@ -125,19 +164,37 @@ impl State {
                     supervisor,
                     nominal_interval,
                     service_health_result,
-                    gateway_state, } = self;
+                    service_ready,
+                    gateway_state,
+                    hook_log, } = self;
 
         // Use an Arc to avoid having to have full clones everywhere. :/
         let service_group = Arc::new(service_group);
         let service_group_ref = Arc::clone(&service_group);
 
-        if let Some(hook) = hook {
-            let hr = hook_runner::HookRunner::new(hook,
-                                                  service_group.deref().clone(),
-                                                  package,
-                                                  svc_encrypted_password);
-            Either::A(hr.into_future()
+        let ready_runner = ready_hook.map(|hook| {
+                                         HookRunner::new(hook,
+                                                         service_group.deref().clone(),
+                                                         package.clone(),
+                                                         svc_encrypted_password.clone(),
+                                                         hook_log.clone())
+                                     });
+
+        if let Some(check) = check {
+            let pr = ProbeRunner::new(check,
+                                      service_group.deref().clone(),
+                                      package,
+                                      svc_encrypted_password);
+            Either::A(pr.into_future()
                         .map(|(result, duration)| (result, Some(duration))))
+        } else if let Some(hook) = hook {
+            let hr = HookRunner::new(hook,
+                                     service_group.deref().clone(),
+                                     package,
+                                     svc_encrypted_password,
+                                     hook_log);
+            Either::B(Either::A(hr.into_future()
+                                  .map(|(result, duration)| (result, Some(duration)))))
         } else {
             let status = match supervisor.lock()
                                          .expect("couldn't unlock supervisor")
@@ -147,9 +204,9 @@ impl State {
                 (false, _) => HealthCheckResult::Critical,
             };
             // no hook means no execution time!
-            Either::B(lazy(move || Ok((status, None::<Duration>))))
+            Either::B(Either::B(lazy(move || Ok((status, None::<Duration>)))))
         }.map_err(move |e| {
-             error!("Error running health check hook for {}: {:?}",
+             error!("Error running health check for {}: {:?}",
                     service_group_ref, e)
          })
          .and_then(move |(check_result, duration)| {
@@ -164,6 +221,37 @@ impl State {
                           .health_check_data
                           .insert(service_group.deref().clone(), check_result);
 
+             let readiness = match ready_runner {
+                 // A service failing its health check is never ready,
+                 // whatever its ready hook would say.
+                 Some(_) if check_result == HealthCheckResult::Critical => {
+                     Either::B(future::ok(false))
+                 }
+                 Some(runner) => {
+                     let service_group = Arc::clone(&service_group);
+                     Either::A(runner.into_future()
+                                     .map(|(ready, _)| ready)
+                                     .or_else(move |e| {
+                                         error!("Error running ready hook for {}: {:?}",
+                                                service_group, e);
+                                         Ok(false)
+                                     }))
+                 }
+                 None => Either::B(future::ok(check_result.is_passing())),
+             };
+             let service_group = Arc::clone(&service_group);
+             readiness.map(move |ready| {
+                          debug!("Caching readiness = '{}' for '{}'", ready, service_group);
+                          *service_ready.lock()
+                                        .expect("Could not unlock service_ready") = ready;
+                          gateway_state.write()
+                                       .expect("GatewayState lock is poisoned")
+                                       .readiness_data
+                                       .insert(service_group.deref().clone(), ready);
+                          (service_group, check_result)
+                      })
+         })
+         .and_then(move |(service_group, check_result)| {
              let interval = if check_result == HealthCheckResult::Ok {
                  // routine health check
                  nominal_interval
@@ -8,13 +8,16 @@
 //! `std::process::Command`, we could consider it. In the meantime,
 //! this seems to do the trick.
 
-use super::{hook_timer,
+use super::{metrics,
             spawned_future::SpawnedFuture,
             Pkg};
-use crate::error::SupError;
+use crate::{error::SupError,
+            event::{self,
+                    ServiceMetadata}};
 use futures::{sync::oneshot,
               IntoFuture};
-use habitat_common::templating::hooks::Hook;
+use habitat_common::{service_log::HookLog,
+                     templating::hooks::Hook};
 use habitat_core::service::ServiceGroup;
 use std::{sync::Arc,
           thread,
@@ -22,10 +25,13 @@ use std::{sync::Arc,
                  Instant}};
 
 pub struct HookRunner<H: Hook + Sync> {
-    hook:          Arc<H>,
-    service_group: ServiceGroup,
-    pkg:           Pkg,
-    passwd:        Option<String>,
+    hook:           Arc<H>,
+    service_group:  ServiceGroup,
+    pkg:            Pkg,
+    passwd:         Option<String>,
+    hook_log:       HookLog,
+    /// If set, a hook-executed event is sent for the run of the hook.
+    event_metadata: Option<ServiceMetadata>,
 }
 
 impl<H> HookRunner<H> where H: Hook + Sync
@@ -33,12 +39,22 @@ impl<H> HookRunner<H> where H: Hook + Sync
     pub fn new(hook: Arc<H>,
                service_group: ServiceGroup,
                pkg: Pkg,
-               passwd: Option<String>)
+               passwd: Option<String>,
+               hook_log: HookLog)
                -> HookRunner<H> {
         HookRunner { hook,
                      service_group,
                      pkg,
-                     passwd }
+                     passwd,
+                     hook_log,
+                     event_metadata: None }
+    }
+
+    /// Send a hook-executed event, describing the service with
+    /// `metadata`, once the hook has run.
+    pub fn with_event(mut self, metadata: ServiceMetadata) -> Self {
+        self.event_metadata = Some(metadata);
+        self
     }
 }
 impl<H: Hook + Sync + 'static> IntoFuture for HookRunner<H> {
@@ -61,12 +77,21 @@ impl<H: Hook + Sync + 'static> IntoFuture for HookRunner<H> {
                                       // _timer is for Prometheus metrics, but we also want
                                       // the runtime for other purposes. Unfortunately,
                                       // we're not able to use the same timer for both :(
-                                      let _timer = hook_timer(H::file_name());
+                                      let _timer =
+                                          metrics::hook_timer(&self.service_group, H::file_name());
                                       let start = Instant::now();
-                                      let exit_value = self.hook.run(&self.service_group,
-                                                                     &self.pkg,
-                                                                     self.passwd.as_ref());
+                                      let (exit_value, exit_code) =
+                                          self.hook.run_with_exit_code(&self.service_group,
+                                                                       &self.pkg,
+                                                                       self.passwd.as_ref(),
+                                                                       &self.hook_log);
                                       let run_time = start.elapsed();
+                                      if let Some(metadata) = self.event_metadata {
+                                          event::hook_executed(metadata,
+                                                               H::file_name(),
+                                                               exit_code,
+                                                               run_time);
+                                      }
                                       tx.send((exit_value, run_time))
                                         .expect("Couldn't send oneshot signal from HookRunner: \
                                                  receiver went away");
@@ -97,6 +97,47 @@ impl Hook for HealthCheckHook {
     fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
 }
 
+/// Reports whether a service should receive traffic, as opposed to
+/// whether it is running at all, which is the health check's job. A
+/// service is ready while its ready hook exits 0 and its health check
+/// isn't critical.
+#[derive(Debug, Serialize)]
+pub struct ReadyHook {
+    render_pair:     RenderPair,
+    stdout_log_path: PathBuf,
+    stderr_log_path: PathBuf,
+}
+
+impl Hook for ReadyHook {
+    type ExitValue = bool;
+
+    fn file_name() -> &'static str { "ready" }
+
+    fn new(package_name: &str, pair: RenderPair) -> Self {
+        ReadyHook { render_pair:     pair,
+                    stdout_log_path: hooks::stdout_log_path::<Self>(package_name),
+                    stderr_log_path: hooks::stderr_log_path::<Self>(package_name), }
+    }
+
+    fn handle_exit<'a>(&self, pkg: &Pkg, _: &'a HookOutput, status: ExitStatus) -> Self::ExitValue {
+        match status.code() {
+            Some(code) => code == 0,
+            None => {
+                Self::output_termination_message(&pkg.name, status);
+                false
+            }
+        }
+    }
+
+    fn path(&self) -> &Path { &self.render_pair.path }
+
+    fn renderer(&self) -> &TemplateRenderer { &self.render_pair.renderer }
+
+    fn stdout_log_path(&self) -> &Path { &self.stdout_log_path }
+
+    fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
+}
+
 #[derive(Debug, Serialize)]
 pub struct InitHook {
     render_pair:     RenderPair,
@@ -431,6 +472,7 @@ pub struct HookTable {
     pub run:          Option<RunHook>,
     pub post_run:     Option<PostRunHook>,
     pub post_stop:    Option<Arc<PostStopHook>>,
+    pub ready:        Option<Arc<ReadyHook>>,
 }
 
 impl HookTable {
@@ -453,6 +495,7 @@ impl HookTable {
                 table.post_run = PostRunHook::load(package_name, &hooks_path, &templates);
                 table.post_stop =
                     PostStopHook::load(package_name, &hooks_path, &templates).map(Arc::new);
+                table.ready = ReadyHook::load(package_name, &hooks_path, &templates).map(Arc::new);
             }
         }
         debug!("{}, Hooks loaded, destination={}, templates={}",
@@ -498,9 +541,52 @@ impl HookTable {
         if let Some(ref hook) = self.post_stop {
             changed |= self.compile_one(hook.as_ref(), service_group, ctx);
         }
+        if let Some(ref hook) = self.ready {
+            changed |= self.compile_one(hook.as_ref(), service_group, ctx);
+        }
         changed
     }
 
+    /// Render all loaded hooks from the table without writing them.
+    ///
+    /// Returns the path each hook compiles to, and its content.
+    pub fn render<T>(&self, ctx: &T) -> habitat_common::Result<Vec<(PathBuf, String)>>
+        where T: Serialize
+    {
+        let mut rendered = Vec::new();
+        if let Some(ref hook) = self.file_updated {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.health_check {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.init {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.reload {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.reconfigure {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.suitability {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.run {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.post_run {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.post_stop {
+            rendered.push(hook.render(ctx)?);
+        }
+        if let Some(ref hook) = self.ready {
+            rendered.push(hook.render(ctx)?);
+        }
+        Ok(rendered)
+    }
+
     fn compile_one<H, T>(&self, hook: &H, service_group: &str, ctx: &T) -> bool
         where H: Hook,
               T: Serialize
@@ -569,7 +655,8 @@ mod tests {
                       ReloadHook
                       RunHook
                       SuitabilityHook
-                      PostStopHook);
+                      PostStopHook
+                      ReadyHook);
 
     fn hook_templates_path() -> PathBuf {
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
@@ -658,6 +745,7 @@ mod tests {
 
         let mut ring = CensusRing::new("member-a");
         ring.update_from_rumors(&cache_key_path(Some(&*FS_ROOT)),
+                                None,
                                 &service_store,
                                 &election_store,
                                 &election_update_store,
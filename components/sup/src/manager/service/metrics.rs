@@ -0,0 +1,242 @@
+//! Per-service metrics, exposed on the HTTP gateway's `/metrics`
+//! endpoint and labelled by service group.
+//!
+//! Memory and CPU figures are read from `/proc` for the process the
+//! Supervisor tracks, so they are only available on Linux, and do not
+//! include any other processes the service may have started.
+
+use super::{health::HealthCheckResult,
+            ProcessState};
+use habitat_core::{os::process::Pid,
+                   service::ServiceGroup};
+use prometheus::{GaugeVec,
+                 HistogramTimer,
+                 HistogramVec,
+                 IntCounterVec,
+                 IntGaugeVec};
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::{collections::{HashMap,
+                        HashSet},
+          sync::Mutex};
+use time::Timespec;
+
+lazy_static! {
+    static ref HOOK_DURATION: HistogramVec =
+        register_histogram_vec!("hab_sup_hook_duration_seconds",
+                                "The time it takes for a hook to run",
+                                &["hook"]).unwrap();
+    static ref SERVICE_HOOK_DURATION: HistogramVec =
+        register_histogram_vec!("hab_sup_service_hook_duration_seconds",
+                                "The time it takes for one of the service's hooks to run",
+                                &["hook", "service_group"]).unwrap();
+    static ref RESIDENT_MEMORY: IntGaugeVec =
+        register_int_gauge_vec!("hab_sup_service_resident_memory_bytes",
+                                "Resident memory of the service's process. Linux only",
+                                &["service_group"]).unwrap();
+    static ref CPU_TIME: GaugeVec =
+        register_gauge_vec!("hab_sup_service_cpu_seconds",
+                            "CPU time used by the service's process and the children it has \
+                             reaped. Linux only",
+                            &["service_group"]).unwrap();
+    static ref RESTARTS: IntCounterVec =
+        register_int_counter_vec!("hab_sup_service_restarts_total",
+                                  "How many times the service's process has been restarted \
+                                   after exiting",
+                                  &["service_group"]).unwrap();
+    static ref UPTIME: IntGaugeVec =
+        register_int_gauge_vec!("hab_sup_service_uptime_seconds",
+                                "How long the service's process has been up, or 0 while it is \
+                                 down",
+                                &["service_group"]).unwrap();
+    static ref HEALTH_CHECK: IntGaugeVec =
+        register_int_gauge_vec!("hab_sup_service_health_check_status",
+                                "The result of the service's last health check: 0 is OK, 1 \
+                                 WARNING, 2 CRITICAL and 3 UNKNOWN",
+                                &["service_group"]).unwrap();
+    /// The hooks timed for each service group, so that `remove` can
+    /// find their series; hook names aren't a fixed set.
+    static ref TIMED_HOOKS: Mutex<HashMap<String, HashSet<String>>> = Mutex::default();
+}
+
+/// Times a run of a hook for both the overall and the per-service hook
+/// durations.
+pub struct HookTimer(HistogramTimer, HistogramTimer);
+
+impl HookTimer {
+    pub fn observe_duration(self) {
+        self.0.observe_duration();
+        self.1.observe_duration();
+    }
+}
+
+// This returns a HookTimer that we can use to track how long hooks take to execute. Note that
+// times will get tracked automatically when the HookTimer goes out of scope.
+pub fn hook_timer(service_group: &ServiceGroup, hook: &str) -> HookTimer {
+    let label = service_group.to_string();
+    TIMED_HOOKS.lock()
+               .expect("TIMED_HOOKS lock poisoned")
+               .entry(label.clone())
+               .or_default()
+               .insert(hook.to_string());
+    HookTimer(HOOK_DURATION.with_label_values(&[hook]).start_timer(),
+              SERVICE_HOOK_DURATION.with_label_values(&[hook, &label])
+                                   .start_timer())
+}
+
+pub fn restarted(service_group: &ServiceGroup) {
+    RESTARTS.with_label_values(&[&service_group.to_string()])
+            .inc();
+}
+
+/// Refresh the gauges of a service from the state of its process.
+pub fn update(service_group: &ServiceGroup,
+              pid: Option<Pid>,
+              state: ProcessState,
+              state_entered: Timespec,
+              health_check: HealthCheckResult) {
+    let label = service_group.to_string();
+    let uptime = match state {
+        ProcessState::Up => (time::get_time() - state_entered).num_seconds().max(0),
+        ProcessState::Down => 0,
+    };
+    UPTIME.with_label_values(&[&label]).set(uptime);
+    HEALTH_CHECK.with_label_values(&[&label])
+                .set(health_check_status(health_check));
+    // Make sure the counter is exported before the first restart.
+    RESTARTS.with_label_values(&[&label]).inc_by(0);
+
+    let stats = pid.and_then(ProcessStats::read).unwrap_or_default();
+    RESIDENT_MEMORY.with_label_values(&[&label])
+                   .set(stats.resident_bytes as i64);
+    CPU_TIME.with_label_values(&[&label]).set(stats.cpu_seconds);
+}
+
+/// Stop exporting the metrics of a service that is no longer running.
+pub fn remove(service_group: &ServiceGroup) {
+    let label = service_group.to_string();
+    let labels = [label.as_str()];
+    // Errors only mean the service never had the metric.
+    RESIDENT_MEMORY.remove_label_values(&labels).ok();
+    CPU_TIME.remove_label_values(&labels).ok();
+    RESTARTS.remove_label_values(&labels).ok();
+    UPTIME.remove_label_values(&labels).ok();
+    HEALTH_CHECK.remove_label_values(&labels).ok();
+    let hooks = TIMED_HOOKS.lock()
+                           .expect("TIMED_HOOKS lock poisoned")
+                           .remove(&label)
+                           .unwrap_or_default();
+    for hook in hooks {
+        SERVICE_HOOK_DURATION.remove_label_values(&[&hook, &label])
+                             .ok();
+    }
+}
+
+/// Health checks report the same values as the exit codes of the
+/// `health-check` hook.
+fn health_check_status(result: HealthCheckResult) -> i64 {
+    match result {
+        HealthCheckResult::Ok => 0,
+        HealthCheckResult::Warning => 1,
+        HealthCheckResult::Critical => 2,
+        HealthCheckResult::Unknown => 3,
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ProcessStats {
+    resident_bytes: u64,
+    cpu_seconds:    f64,
+}
+
+impl ProcessStats {
+    #[cfg(target_os = "linux")]
+    fn read(pid: Pid) -> Option<ProcessStats> {
+        let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks_per_second <= 0 {
+            return None;
+        }
+        Some(ProcessStats { resident_bytes: resident_bytes(&status)?,
+                            cpu_seconds:    cpu_ticks(&stat)? as f64 / ticks_per_second as f64, })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read(_pid: Pid) -> Option<ProcessStats> { None }
+}
+
+/// The `VmRSS` line of `/proc/<pid>/status`, which is given in kB.
+#[cfg(target_os = "linux")]
+fn resident_bytes(status: &str) -> Option<u64> {
+    status.lines()
+          .find(|line| line.starts_with("VmRSS:"))
+          .and_then(|line| line.split_whitespace().nth(1))
+          .and_then(|kb| kb.parse::<u64>().ok())
+          .map(|kb| kb * 1024)
+}
+
+/// The sum of `utime`, `stime`, `cutime` and `cstime` from
+/// `/proc/<pid>/stat`, in clock ticks.
+#[cfg(target_os = "linux")]
+fn cpu_ticks(stat: &str) -> Option<u64> {
+    // The command name is in parentheses and may itself contain
+    // spaces, so fields are counted from the closing parenthesis, which
+    // is followed by the third field.
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    fields.get(11..15)?
+          .iter()
+          .map(|f| f.parse::<u64>().ok())
+          .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removing_a_service_removes_its_hook_durations() {
+        let service_group = ServiceGroup::new(None, "metrics-remove", "test", None).unwrap();
+        let label = service_group.to_string();
+        hook_timer(&service_group, "init").observe_duration();
+        hook_timer(&service_group, "run").observe_duration();
+        assert!(SERVICE_HOOK_DURATION.remove_label_values(&["init", &label])
+                                     .is_ok());
+        hook_timer(&service_group, "init").observe_duration();
+
+        remove(&service_group);
+        assert!(SERVICE_HOOK_DURATION.remove_label_values(&["init", &label])
+                                     .is_err());
+        assert!(SERVICE_HOOK_DURATION.remove_label_values(&["run", &label])
+                                     .is_err());
+        // The overall durations are shared by every service, so they stay
+        assert!(HOOK_DURATION.remove_label_values(&["init"]).is_ok());
+        assert!(!TIMED_HOOKS.lock().unwrap().contains_key(&label));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn resident_bytes_is_read_from_status() {
+        let status =
+            "Name:\tredis-server\nVmPeak:\t   60000 kB\nVmRSS:\t    9800 kB\nThreads:\t4\n";
+        assert_eq!(resident_bytes(status), Some(9800 * 1024));
+        assert_eq!(resident_bytes("Name:\tkthreadd\n"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn cpu_ticks_are_read_from_stat() {
+        let stat = "4242 (redis server) S 1 4242 4242 0 -1 4194560 1000 0 0 0 120 30 7 3 20 0 4 0 \
+                    1234 61440000 2450 18446744073709551615";
+        assert_eq!(cpu_ticks(stat), Some(160));
+        assert_eq!(cpu_ticks("4242 (redis"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn our_own_stats_can_be_read() {
+        let stats = ProcessStats::read(std::process::id() as Pid).expect("Couldn't read the \
+                                                                          stats of this process");
+        assert!(stats.resident_bytes > 0);
+    }
+}
@@ -12,6 +12,9 @@ mod context;
 mod health;
 mod hook_runner;
 mod hooks;
+mod metrics;
+mod probe;
+mod restart;
 mod spawned_future;
 pub mod spec;
 mod supervisor;
@@ -19,19 +22,29 @@ mod terminator;
 
 use self::{context::RenderContext,
            hooks::HookTable,
-           supervisor::Supervisor};
+           restart::{ExitOutcome,
+                     RestartConfig,
+                     RestartTracker},
+           supervisor::{ExitReason,
+                        ProcessExit,
+                        Supervisor}};
 pub use self::{health::HealthCheckResult,
-               hooks::HealthCheckHook,
+               hooks::{HealthCheckHook,
+                       ReadyHook},
                spec::{DesiredState,
                       IntoServiceSpec,
                       ServiceSpec}};
-use crate::{census::{CensusGroup,
+use crate::{audit::{AuditLog,
+                    AuditRecord,
+                    Outcome},
+            census::{CensusGroup,
                      CensusRing,
                      ElectionStatus,
                      ServiceFile},
             error::{Error,
                     Result,
                     SupError},
+            event,
             manager::{action::ShutdownSpec,
                       FsCfg,
                       GatewayState,
@@ -47,6 +60,8 @@ pub use habitat_common::templating::{config::{Cfg,
                                                Pkg,
                                                PkgProxy}};
 use habitat_common::{outputln,
+                     service_log::{HookLog,
+                                   LogConfig},
                      templating::{config::CfgRenderer,
                                   hooks::Hook}};
 use habitat_core::{crypto::hash,
@@ -57,20 +72,29 @@ use habitat_core::{crypto::hash,
                    package::{metadata::Bind,
                              PackageIdent,
                              PackageInstall},
-                   service::{HealthCheckInterval,
+                   service::{HealthCheck,
+                             HealthCheckInterval,
+                             ResourceLimits,
                              ServiceBind,
-                             ServiceGroup},
+                             ServiceGroup,
+                             UpdateWindow},
+                   util::{deserialize_using_from_str,
+                          serialize_using_to_string},
                    ChannelIdent};
+#[cfg(target_os = "linux")]
+use habitat_core::os::cgroup::{self,
+                               ResourceUsage};
 use habitat_launcher_client::LauncherCli;
-use habitat_sup_protocol::types::BindingMode;
+use habitat_sup_protocol::{self as protocol,
+                           types::BindingMode};
 pub use habitat_sup_protocol::types::{ProcessState,
+                                      RestartPolicy,
                                       Topology,
                                       UpdateStrategy};
-use prometheus::{HistogramTimer,
-                 HistogramVec};
 use serde::{ser::SerializeStruct,
             Serialize,
             Serializer};
+use similar::TextDiff;
 use std::{self,
           collections::HashSet,
           fmt,
@@ -81,7 +105,9 @@ use std::{self,
           result,
           sync::{Arc,
                  Mutex,
-                 RwLock}};
+                 RwLock},
+          time::{Duration,
+                 Instant}};
 use time::Timespec;
 use tokio::runtime::TaskExecutor;
 
@@ -90,13 +116,6 @@ static LOGKEY: &'static str = "SR";
 #[cfg(not(windows))]
 pub const GOSSIP_FILE_PERMISSIONS: u32 = 0o640;
 
-lazy_static! {
-    static ref HOOK_DURATION: HistogramVec =
-        register_histogram_vec!("hab_sup_hook_duration_seconds",
-                                "The time it takes for a hook to run",
-                                &["hook"]).unwrap();
-}
-
 /// When evaluating whether a particular service group can satisfy a
 /// bind of the Service, there are several states it can be
 /// in. Depending on which point in the lifecycle of the Service we
@@ -119,6 +138,24 @@ enum BindStatus<'a> {
     Unknown(SupError),
 }
 
+/// An update that was found for a service while its update window was
+/// closed, and that will be applied once the window opens.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PendingUpdate {
+    #[serde(deserialize_with = "deserialize_using_from_str",
+            serialize_with = "serialize_using_to_string")]
+    pub ident: PackageIdent,
+    /// When the update window next opens, in seconds since the epoch.
+    pub until: Option<u64>,
+}
+
+impl From<PendingUpdate> for protocol::types::PendingUpdate {
+    fn from(other: PendingUpdate) -> Self {
+        protocol::types::PendingUpdate { ident: other.ident.into(),
+                                         until: other.until, }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct Service {
     pub service_group:       ServiceGroup,
@@ -129,11 +166,17 @@ pub struct Service {
     pub spec_ident:          PackageIdent,
     pub topology:            Topology,
     pub update_strategy:     UpdateStrategy,
+    pub update_window:       Option<UpdateWindow>,
     pub cfg:                 Cfg,
     pub pkg:                 Pkg,
     pub sys:                 Arc<Sys>,
     pub initialized:         bool,
     pub user_config_updated: bool,
+    /// The release this service was rolled back from, if it is
+    /// running an earlier release because an update failed.
+    pub rolled_back_from:    Option<PackageIdent>,
+    /// An update that is being held until `update_window` opens.
+    pub pending_update:      Option<PendingUpdate>,
 
     #[serde(skip_serializing)]
     config_renderer: CfgRenderer,
@@ -148,7 +191,18 @@ pub struct Service {
     // hook, we need to wrap some Arc<Mutex<_>> protection around it
     // :(
     health_check_result: Arc<Mutex<HealthCheckResult>>,
+    /// Whether the service should receive traffic, as decided by its
+    /// ready hook or, lacking one, its health check. Updated by the
+    /// same asynchronous future as `health_check_result`.
+    ready: Arc<Mutex<bool>>,
+    /// The readiness last gossiped to the rest of the ring.
+    #[serde(skip_serializing)]
+    gossiped_ready: bool,
     last_election_status: ElectionStatus,
+    /// Whether an election has started and not yet finished. An
+    /// election can lose quorum and regain it without starting over.
+    #[serde(skip_serializing)]
+    election_underway: bool,
     needs_reload: bool,
     needs_reconfiguration: bool,
     /// The mapping of bind name to a service group, specified by the
@@ -183,6 +237,25 @@ pub struct Service {
     supervisor: Arc<Mutex<Supervisor>>,
     svc_encrypted_password: Option<String>,
     health_check_interval: HealthCheckInterval,
+    /// The limits given in the service's spec, which take precedence
+    /// over those declared by its package.
+    resource_limits: ResourceLimits,
+    #[serde(skip_serializing)]
+    pkg_resource_limits: ResourceLimits,
+    /// The health check given in the service's spec, which takes
+    /// precedence over the one declared by its package.
+    health_check_config: Option<HealthCheck>,
+    #[serde(skip_serializing)]
+    pkg_health_check_config: Option<HealthCheck>,
+    /// How the service's output is logged to disk.
+    #[serde(skip_serializing)]
+    service_log: LogConfig,
+    /// Where the output of the service's hooks is logged.
+    #[serde(skip_serializing)]
+    hook_log: HookLog,
+    /// Where configuration and files applied from gossip are recorded.
+    #[serde(skip_serializing)]
+    audit_log: AuditLog,
 
     #[serde(skip_serializing)]
     /// Whether a service's default configuration changed on a package
@@ -196,6 +269,14 @@ pub struct Service {
     /// can stop that future.
     #[serde(skip_serializing)]
     health_check_handle: Option<sup_futures::FutureHandle>,
+    /// How many times the service's process has been found dead
+    /// since this service was loaded.
+    #[serde(skip_serializing)]
+    unexpected_exits: u32,
+    /// Applies the service's restart policy whenever its process
+    /// exits.
+    #[serde(skip_serializing)]
+    restart: RestartTracker,
 }
 
 impl Service {
@@ -204,10 +285,14 @@ impl Service {
            spec: ServiceSpec,
            manager_fs_cfg: Arc<FsCfg>,
            organization: Option<&str>,
+           service_log: LogConfig,
+           audit_log: AuditLog,
            gateway_state: Arc<RwLock<GatewayState>>)
            -> Result<Service> {
         spec.validate(&package)?;
         let all_pkg_binds = package.all_binds()?;
+        let pkg_resource_limits = package.resource_limits()?;
+        let pkg_health_check_config = package.health_check()?;
         let pkg = Pkg::from_install(&package)?;
         let spec_file = manager_fs_cfg.specs_path.join(spec.file_name());
         let service_group = ServiceGroup::new(spec.application_environment.as_ref(),
@@ -216,6 +301,11 @@ impl Service {
                                               organization)?;
         let config_root = Self::config_root(&pkg, spec.config_from.as_ref());
         let hooks_root = Self::hooks_root(&pkg, spec.config_from.as_ref());
+        let restart_config =
+            RestartConfig { policy:       spec.restart_policy,
+                            max_restarts: spec.max_restarts,
+                            window:       Duration::from_secs(spec.restart_window),
+                            max_backoff:  Duration::from_secs(spec.max_restart_backoff), };
         Ok(Service { sys,
                      cfg: Cfg::new(&pkg, spec.config_from.as_ref())?,
                      config_renderer: CfgRenderer::new(&config_root)?,
@@ -223,14 +313,19 @@ impl Service {
                      channel: spec.channel,
                      desired_state: spec.desired_state,
                      health_check_result: Default::default(),
+                     ready: Default::default(),
+                     gossiped_ready: false,
                      hooks: HookTable::load(&pkg.name,
                                             &hooks_root,
                                             svc_hooks_path(&service_group.service())),
                      initialized: false,
                      last_election_status: ElectionStatus::None,
+                     election_underway: false,
                      needs_reload: false,
                      needs_reconfiguration: false,
                      user_config_updated: false,
+                     rolled_back_from: None,
+                     pending_update: None,
                      manager_fs_cfg,
                      supervisor: Arc::new(Mutex::new(Supervisor::new(&service_group))),
                      pkg,
@@ -243,12 +338,22 @@ impl Service {
                      spec_file,
                      topology: spec.topology,
                      update_strategy: spec.update_strategy,
+                     update_window: spec.update_window,
                      config_from: spec.config_from,
                      svc_encrypted_password: spec.svc_encrypted_password,
                      health_check_interval: spec.health_check_interval,
+                     resource_limits: spec.resource_limits,
+                     pkg_resource_limits,
+                     health_check_config: spec.health_check,
+                     pkg_health_check_config,
+                     service_log,
+                     hook_log: HookLog::new(service_log),
+                     audit_log,
                      defaults_updated: false,
                      gateway_state,
-                     health_check_handle: None })
+                     health_check_handle: None,
+                     unexpected_exits: 0,
+                     restart: RestartTracker::new(restart_config) })
     }
 
     /// Returns the config root given the package and optional config-from path.
@@ -265,20 +370,28 @@ impl Service {
                    .join("hooks")
     }
 
+    /// Load the service described by `spec`. When `pinned` is given,
+    /// that release is loaded instead of the latest installed package
+    /// satisfying the spec's identifier (e.g. after a rollback).
     pub fn load(sys: Arc<Sys>,
                 spec: ServiceSpec,
+                pinned: Option<&PackageIdent>,
                 manager_fs_cfg: Arc<FsCfg>,
                 organization: Option<&str>,
+                service_log: LogConfig,
+                audit_log: AuditLog,
                 gateway_state: Arc<RwLock<GatewayState>>)
                 -> Result<Service> {
         // The package for a spec should already be installed.
         let fs_root_path = Path::new(&*FS_ROOT_PATH);
-        let package = PackageInstall::load(&spec.ident, Some(fs_root_path))?;
+        let package = PackageInstall::load(pinned.unwrap_or(&spec.ident), Some(fs_root_path))?;
         Ok(Self::new(sys,
                      &package,
                      spec,
                      manager_fs_cfg,
                      organization,
+                     service_log,
+                     audit_log,
                      gateway_state)?)
     }
 
@@ -296,7 +409,9 @@ impl Service {
                                .start(&self.pkg,
                                       &self.service_group,
                                       launcher,
-                                      self.svc_encrypted_password.as_ref().map(String::as_str))
+                                      self.svc_encrypted_password.as_ref().map(String::as_str),
+                                      self.resource_limits(),
+                                      self.service_log)
                                .err()
         {
             outputln!(preamble self.service_group, "Service start failed: {}", err);
@@ -311,7 +426,9 @@ impl Service {
     /// Create the state necessary for managing a repeatedly-running
     /// health check hook.
     fn health_state(&self) -> health::State {
-        health::State::new(self.hooks.health_check.clone(),
+        health::State::new(self.health_check_config().cloned(),
+                           self.hooks.health_check.clone(),
+                           self.hooks.ready.clone(),
                            self.service_group.clone(),
                            self.pkg.clone(),
                            self.svc_encrypted_password.clone(),
@@ -319,7 +436,9 @@ impl Service {
                            Arc::clone(&self.supervisor),
                            self.health_check_interval,
                            Arc::clone(&self.health_check_result),
-                           Arc::clone(&self.gateway_state))
+                           Arc::clone(&self.ready),
+                           Arc::clone(&self.gateway_state),
+                           self.hook_log.clone())
     }
 
     /// Initiate an endless future that performs periodic health
@@ -377,10 +496,10 @@ impl Service {
                     .expect("Couldn't lock supervisor")
                     .stop(shutdown_spec)
                     .and_then(move |_| {
-                        gs.write()
-                          .expect("GatewayState lock is poisoned")
-                          .health_check_data
-                          .remove(&service_group);
+                        let mut gs = gs.write().expect("GatewayState lock is poisoned");
+                        gs.health_check_data.remove(&service_group);
+                        gs.readiness_data.remove(&service_group);
+                        metrics::remove(&service_group);
                         Ok(())
                     });
 
@@ -399,7 +518,7 @@ impl Service {
 
     /// Runs the reconfigure hook if present, otherwise restarts the service.
     fn reload(&mut self, launcher: &LauncherCli) {
-        let _timer = hook_timer("reload");
+        let _timer = metrics::hook_timer(&self.service_group, "reload");
         self.needs_reload = false;
         if self.process_down() || self.hooks.reload.is_none() {
             if let Some(err) =
@@ -409,19 +528,67 @@ impl Service {
                     .restart(&self.pkg,
                              &self.service_group,
                              launcher,
-                             self.svc_encrypted_password.as_ref().map(String::as_ref))
+                             self.svc_encrypted_password.as_ref().map(String::as_ref),
+                             self.resource_limits(),
+                             self.service_log)
                     .err()
             {
                 outputln!(preamble self.service_group, "Service restart failed: {}", err);
             }
         } else {
             let hook = self.hooks.reload.as_ref().unwrap();
-            hook.run(&self.service_group,
-                     &self.pkg,
-                     self.svc_encrypted_password.as_ref());
+            self.run_hook(hook.as_ref());
         }
     }
 
+    /// The result of the most recent health check for this service.
+    pub fn health_check_result(&self) -> HealthCheckResult {
+        *self.health_check_result
+             .lock()
+             .expect("Couldn't lock health check result")
+    }
+
+    /// Whether the service is ready to receive traffic.
+    pub fn ready(&self) -> bool { *self.ready.lock().expect("Couldn't lock readiness") }
+
+    /// How many times the service's process has died and been
+    /// restarted since it was loaded.
+    pub fn unexpected_exits(&self) -> u32 { self.unexpected_exits }
+
+    pub fn restart_policy(&self) -> RestartPolicy { self.restart.config().policy }
+
+    /// Whether the service's process keeps exiting shortly after being
+    /// restarted.
+    pub fn crash_looping(&self) -> bool { self.restart.crash_looping() }
+
+    /// How many times the service's process has been restarted within
+    /// the current restart window.
+    pub fn restarts(&self) -> u32 { self.restart.restarts() }
+
+    /// What the service's processes are currently using, read from the
+    /// cgroup the Launcher placed them in.
+    #[cfg(target_os = "linux")]
+    pub fn resource_usage(&self) -> Option<ResourceUsage> {
+        ResourceUsage::read(&cgroup::service_cgroup(&self.service_group.to_string()))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn resource_usage(&self) -> Option<()> { None }
+
+    /// Refresh the service's metrics on the HTTP gateway's `/metrics`
+    /// endpoint.
+    pub fn update_metrics(&self) {
+        let (pid, state, state_entered) = {
+            let supervisor = self.supervisor.lock().expect("Couldn't lock supervisor");
+            (supervisor.pid(), supervisor.state, supervisor.state_entered)
+        };
+        metrics::update(&self.service_group,
+                        pid,
+                        state,
+                        state_entered,
+                        self.health_check_result());
+    }
+
     pub fn last_state_change(&self) -> Timespec {
         self.supervisor
             .lock()
@@ -431,7 +598,8 @@ impl Service {
 
     /// Performs updates and executes hooks.
     ///
-    /// Returns `true` if the service was updated.
+    /// Returns `true` if the service was updated or its readiness
+    /// changed, i.e. if it needs to be gossiped again.
     pub fn tick(&mut self,
                 census_ring: &CensusRing,
                 launcher: &LauncherCli,
@@ -477,6 +645,7 @@ impl Service {
                         if self.last_election_status != census_group.election_status {
                             outputln!(preamble self.service_group,
                                       "Waiting to execute hooks; election hasn't started");
+                            self.election_started(census_group.election_status);
                             self.last_election_status = census_group.election_status;
                         }
                     }
@@ -484,6 +653,9 @@ impl Service {
                         if self.last_election_status != census_group.election_status {
                             outputln!(preamble self.service_group,
                                       "Waiting to execute hooks; election in progress.");
+                            if self.election_started(census_group.election_status) {
+                                event::election_started(self);
+                            }
                             self.last_election_status = census_group.election_status;
                         }
                     }
@@ -492,7 +664,7 @@ impl Service {
                             outputln!(preamble self.service_group,
                                       "Waiting to execute hooks; election in progress, \
                                       and we have no quorum.");
-
+                            self.election_started(census_group.election_status);
                             self.last_election_status = census_group.election_status
                         }
                     }
@@ -504,6 +676,10 @@ impl Service {
                             outputln!(preamble self.service_group,
                                       "Executing hooks; {} is the leader",
                                       leader_id.to_string());
+                            let leader_id = leader_id.to_string();
+                            let won = leader_id == self.sys.member_id;
+                            self.election_started(census_group.election_status);
+                            event::election_finished(self, &leader_id, won);
                             self.last_election_status = census_group.election_status;
                         }
                         self.execute_hooks(launcher, executor)
@@ -525,7 +701,13 @@ impl Service {
             self.restart_health_checks(executor);
         }
 
-        svc_updated
+        // Peers use our readiness to decide whether to send us
+        // traffic, so they need to hear about it as soon as it changes.
+        let ready = self.ready();
+        let ready_changed = ready != self.gossiped_ready;
+        self.gossiped_ready = ready;
+
+        svc_updated || ready_changed
     }
 
     pub fn to_spec(&self) -> ServiceSpec {
@@ -538,6 +720,12 @@ impl Service {
         spec.channel = self.channel.clone();
         spec.topology = self.topology;
         spec.update_strategy = self.update_strategy;
+        spec.update_window = self.update_window.clone();
+        let restart_config = self.restart.config();
+        spec.restart_policy = restart_config.policy;
+        spec.max_restarts = restart_config.max_restarts;
+        spec.restart_window = restart_config.window.as_secs();
+        spec.max_restart_backoff = restart_config.max_backoff.as_secs();
         spec.binds = self.binds.clone();
         spec.binding_mode = self.binding_mode;
         spec.config_from = self.config_from.clone();
@@ -545,9 +733,26 @@ impl Service {
             spec.svc_encrypted_password = Some(password.clone())
         }
         spec.health_check_interval = self.health_check_interval;
+        spec.resource_limits = self.resource_limits;
+        spec.health_check = self.health_check_config.clone();
         spec
     }
 
+    /// The limits the service's process runs under: those given in its
+    /// spec, falling back to those declared by its package.
+    pub fn resource_limits(&self) -> ResourceLimits {
+        self.resource_limits.or(self.pkg_resource_limits)
+    }
+
+    /// The health check the Supervisor performs itself, in place of
+    /// any `health-check` hook: the one given in the service's spec,
+    /// falling back to the one declared by its package.
+    pub fn health_check_config(&self) -> Option<&HealthCheck> {
+        self.health_check_config
+            .as_ref()
+            .or_else(|| self.pkg_health_check_config.as_ref())
+    }
+
     /// Iterate through all the service binds, marking any that are
     /// unsatisfied in `self.unsatisfied_binds`.
     ///
@@ -557,6 +762,25 @@ impl Service {
     /// Thereafter, if binds become unsatisfied during the running of
     /// the service, those binds will be removed from the rendering
     /// context, allowing services to take appropriate action.
+    /// Tracks the service group's election status, returning whether
+    /// it means an election has just started. An election only starts
+    /// once it is running with quorum; losing quorum and regaining it
+    /// during the same election does not start another one.
+    fn election_started(&mut self, status: ElectionStatus) -> bool {
+        match status {
+            ElectionStatus::ElectionInProgress => {
+                let started = !self.election_underway;
+                self.election_underway = true;
+                started
+            }
+            ElectionStatus::ElectionNoQuorum => false,
+            ElectionStatus::None | ElectionStatus::ElectionFinished => {
+                self.election_underway = false;
+                false
+            }
+        }
+    }
+
     fn validate_binds(&mut self, census_ring: &CensusRing) {
         for bind in self.binds.iter() {
             let mut bind_is_unsatisfied = true;
@@ -711,9 +935,39 @@ impl Service {
                 if config.incarnation <= self.cfg.gossip_incarnation {
                     return false;
                 }
-                self.cfg
-                    .set_gossip(config.incarnation, config.value.clone());
-                true
+                let author = config.author.as_ref().map(String::as_str);
+                // Members elsewhere may run releases with other schemas, so gossiped
+                // configuration is checked against this member's as it is applied.
+                let applied = self.cfg
+                                  .set_gossip(config.incarnation, config.value.clone());
+                match applied {
+                    Ok(()) => {
+                        self.audit_log.record(&AuditRecord::gossip(&config.from_id,
+                                                                   author,
+                                                                   "ServiceConfig",
+                                                                   &self.service_group,
+                                                                   Outcome::Succeeded,
+                                                                   format!("incarnation {}",
+                                                                           config.incarnation)));
+                        event::service_config_updated(self, config.incarnation, author);
+                        true
+                    }
+                    Err(err) => {
+                        outputln!(preamble self.service_group,
+                                  "Ignoring configuration version {}: {}",
+                                  config.incarnation,
+                                  err);
+                        self.audit_log.record(&AuditRecord::gossip(&config.from_id,
+                                                                   author,
+                                                                   "ServiceConfig",
+                                                                   &self.service_group,
+                                                                   Outcome::Failed,
+                                                                   format!("incarnation {}, {}",
+                                                                           config.incarnation,
+                                                                           err)));
+                        false
+                    }
+                }
             }
             None => false,
         }
@@ -764,6 +1018,61 @@ impl Service {
         cfg_changed
     }
 
+    /// Renders the service's configuration files and hooks as they would be if `gossip` were
+    /// applied to its service group, without writing anything to disk.
+    ///
+    /// Returns a diff of each file that would change, and what the service would do about it.
+    pub fn dry_run_cfg(&self,
+                       census_ring: &CensusRing,
+                       gossip: toml::value::Table)
+                       -> Result<protocol::ctl::SvcCfgDryRun> {
+        let mut cfg = self.cfg.clone();
+        cfg.set_gossip(self.cfg.gossip_incarnation, gossip)?;
+        let ctx = RenderContext::new(&self.service_group,
+                                     &self.sys,
+                                     &self.pkg,
+                                     &cfg,
+                                     census_ring,
+                                     self.binds
+                                         .iter()
+                                         .filter(|b| !self.unsatisfied_binds.contains(b)));
+        let rendered_cfg = self.config_renderer
+                               .render(&self.pkg.svc_config_path, &ctx)?;
+        let rendered_hooks = self.hooks.render(&ctx)?;
+        let mut diffs = Vec::new();
+
+        let mut reconfigure = false;
+        for (path, content) in rendered_cfg {
+            if let Some(diff) = rendered_diff(&path, &content) {
+                diffs.push(diff);
+                reconfigure = true;
+            }
+        }
+        let mut reload = false;
+        for (path, content) in rendered_hooks {
+            if let Some(diff) = rendered_diff(&path, &content) {
+                diffs.push(diff);
+                reload = true;
+            }
+        }
+
+        // This mirrors `execute_hooks`: any change reloads the service, and a configuration
+        // change also reconfigures it.
+        let mut would_run = Vec::new();
+        if reload || reconfigure {
+            if self.hooks.reload.is_some() {
+                would_run.push(hooks::ReloadHook::file_name().to_string());
+            } else {
+                would_run.push("restart".to_string());
+            }
+        }
+        if reconfigure && self.hooks.reconfigure.is_some() {
+            would_run.push(hooks::ReconfigureHook::file_name().to_string());
+        }
+        Ok(protocol::ctl::SvcCfgDryRun { diffs,
+                                         hooks: would_run })
+    }
+
     pub fn to_rumor(&self, incarnation: u64) -> ServiceRumor {
         let exported = match self.cfg.to_exported(&self.pkg) {
             Ok(exported) => Some(exported),
@@ -780,12 +1089,13 @@ impl Service {
                                           self.sys.as_sys_info().clone(),
                                           exported);
         rumor.incarnation = incarnation;
+        rumor.ready = self.ready();
         rumor
     }
 
     /// Run initialization hook if present.
     fn initialize(&mut self) {
-        let timer = hook_timer("initialize");
+        let timer = metrics::hook_timer(&self.service_group, "initialize");
 
         if self.initialized {
             timer.observe_duration();
@@ -795,34 +1105,43 @@ impl Service {
         outputln!(preamble self.service_group, "Initializing");
         self.initialized = true;
         if let Some(ref hook) = self.hooks.init {
-            self.initialized = hook.run(&self.service_group,
-                                        &self.pkg,
-                                        self.svc_encrypted_password.as_ref())
+            self.initialized = self.run_hook(hook.as_ref())
         }
     }
 
     /// Run reconfigure hook if present.
     fn reconfigure(&mut self) {
-        let _timer = hook_timer("reconfigure");
+        let _timer = metrics::hook_timer(&self.service_group, "reconfigure");
 
         self.needs_reconfiguration = false;
         if let Some(ref hook) = self.hooks.reconfigure {
-            hook.run(&self.service_group,
-                     &self.pkg,
-                     self.svc_encrypted_password.as_ref());
+            self.run_hook(hook.as_ref());
         }
     }
 
     fn post_run(&mut self) {
-        let _timer = hook_timer("post-run");
+        let _timer = metrics::hook_timer(&self.service_group, "post-run");
 
         if let Some(ref hook) = self.hooks.post_run {
-            hook.run(&self.service_group,
-                     &self.pkg,
-                     self.svc_encrypted_password.as_ref());
+            self.run_hook(hook.as_ref());
         }
     }
 
+    /// Run one of the service's lifecycle hooks, sending an event
+    /// recording its exit code and how long it took.
+    fn run_hook<H: Hook>(&self, hook: &H) -> H::ExitValue {
+        let started = Instant::now();
+        let (exit_value, exit_code) = hook.run_with_exit_code(&self.service_group,
+                                                              &self.pkg,
+                                                              self.svc_encrypted_password.as_ref(),
+                                                              &self.hook_log);
+        event::hook_executed(self.to_service_metadata(),
+                             H::file_name(),
+                             exit_code,
+                             started.elapsed());
+        exit_value
+    }
+
     // This hook method looks different from all the others because
     // it's the only one that runs async right now.
     fn post_stop(&self) -> Option<hook_runner::HookRunner<hooks::PostStopHook>> {
@@ -831,22 +1150,23 @@ impl Service {
                                                                       self.service_group.clone(),
                                                                       self.pkg.clone(),
                                                                       self.svc_encrypted_password
-                                                                          .clone())
+                                                                          .clone(),
+                                                                      self.hook_log.clone())
+                                         .with_event(self.to_service_metadata())
                                      })
     }
 
     pub fn suitability(&self) -> Option<u64> {
-        let _timer = hook_timer("suitability");
+        let _timer = metrics::hook_timer(&self.service_group, "suitability");
 
         if !self.initialized {
             return None;
         }
 
-        self.hooks.suitability.as_ref().and_then(|hook| {
-                                           hook.run(&self.service_group,
-                                                    &self.pkg,
-                                                    self.svc_encrypted_password.as_ref())
-                                       })
+        self.hooks
+            .suitability
+            .as_ref()
+            .and_then(|hook| self.run_hook(hook.as_ref()))
     }
 
     /// Helper for compiling configuration templates into configuration files.
@@ -937,10 +1257,28 @@ impl Service {
                 self.post_run();
             }
         } else {
-            self.check_process();
+            let now = Instant::now();
+            if self.check_process() {
+                if self.restart.process_running(now) {
+                    outputln!(preamble self.service_group,
+                              "Process stayed up for {}s; no longer crash-looping",
+                              self.restart.config().window.as_secs());
+                }
+            } else if !self.restart.exit_handled() {
+                self.unexpected_exits += 1;
+                self.process_exited(launcher, now);
+            }
+            // A process that exited stays down until its restart
+            // policy allows it to be started again.
+            if self.process_down() && !self.restart.restart_due(now) {
+                return;
+            }
             // NOTE: if you need reconfiguration and you DON'T have a
             // reload script, you're going to restart anyway.
             if self.needs_reload || self.process_down() || self.needs_reconfiguration {
+                if self.restart.restarted(now) {
+                    metrics::restarted(&self.service_group);
+                }
                 self.reload(launcher);
                 if self.needs_reconfiguration {
                     // NOTE this only runs the hook if it's defined
@@ -950,15 +1288,56 @@ impl Service {
         }
     }
 
+    /// Apply the restart policy to a process that has just been found
+    /// dead.
+    fn process_exited(&mut self, launcher: &LauncherCli, now: Instant) {
+        let exit = self.supervisor
+                       .lock()
+                       .expect("Couldn't lock supervisor")
+                       .process_exit(launcher);
+        let exit_code = exit.map(|e| e.code);
+        let exited = match exit {
+            Some(ProcessExit { reason: ExitReason::OomKilled,
+                               .. }) => {
+                "Process was killed for exceeding its memory limit".to_string()
+            }
+            Some(ProcessExit { code, .. }) => format!("Process exited with code {}", code),
+            None => "Process exited".to_string(),
+        };
+        match self.restart.process_exited(exit_code, now) {
+            ExitOutcome::Restart { crash_looping: true,
+                                   delay, } => {
+                outputln!(preamble self.service_group,
+                          "{}; restarted {} times within {}s, crash-looping. Restarting every \
+                           {}s until it stays up.",
+                          exited,
+                          self.restart.restarts(),
+                          self.restart.config().window.as_secs(),
+                          delay.as_secs());
+                event::service_crash_looping(self, self.restart.restarts());
+            }
+            ExitOutcome::Restart { delay, .. } => {
+                outputln!(preamble self.service_group,
+                          "{}; restarting in {}s",
+                          exited,
+                          delay.as_secs());
+            }
+            ExitOutcome::StayDown => {
+                outputln!(preamble self.service_group,
+                          "{}; not restarting, as the restart policy is {}",
+                          exited,
+                          self.restart.config().policy);
+            }
+        }
+    }
+
     /// Run file-updated hook if present.
     fn file_updated(&self) -> bool {
-        let _timer = hook_timer("file-updated");
+        let _timer = metrics::hook_timer(&self.service_group, "file-updated");
 
         if self.initialized {
             if let Some(ref hook) = self.hooks.file_updated {
-                return hook.run(&self.service_group,
-                                &self.pkg,
-                                self.svc_encrypted_password.as_ref());
+                return self.run_hook(hook.as_ref());
             }
         }
 
@@ -980,6 +1359,20 @@ impl Service {
             if self.cache_service_file(&service_file) {
                 outputln!(preamble self.service_group, "Service file updated, {}",
                     service_file.filename);
+                let author = service_file.author.as_ref().map(String::as_str);
+                self.audit_log
+                    .record(&AuditRecord::gossip(&service_file.from_id,
+                                                 author,
+                                                 "ServiceFile",
+                                                 &self.service_group,
+                                                 Outcome::Succeeded,
+                                                 format!("{} incarnation {}",
+                                                         service_file.filename,
+                                                         service_file.incarnation)));
+                event::service_file_updated(self,
+                                            &service_file.filename,
+                                            service_file.incarnation,
+                                            author);
                 updated = true;
             }
         }
@@ -1079,12 +1472,6 @@ impl fmt::Display for Service {
     }
 }
 
-// This returns a HistogramTimer that we can use to track how long hooks take to execute. Note that
-// times will get tracked automatically when the HistogramTimer goes out of scope.
-fn hook_timer(name: &str) -> HistogramTimer {
-    HOOK_DURATION.with_label_values(&[name]).start_timer()
-}
-
 /// This enum represents whether or not we want to render config information when we serialize this
 /// service via the ServiceProxy struct below. Choosing ConfigRendering::Full will render the
 /// config, and choosing ConfigRendering::Redacted will not render it. This matches up to the
@@ -1121,9 +1508,9 @@ impl<'a> Serialize for ServiceProxy<'a> {
         where S: Serializer
     {
         let num_fields: usize = if self.config_rendering == ConfigRendering::Full {
-            27
+            37
         } else {
-            26
+            36
         };
 
         let s = &self.service;
@@ -1139,8 +1526,10 @@ impl<'a> Serialize for ServiceProxy<'a> {
 
         strukt.serialize_field("channel", &s.channel)?;
         strukt.serialize_field("config_from", &s.config_from)?;
+        strukt.serialize_field("crash_looping", &s.crash_looping())?;
         strukt.serialize_field("desired_state", &s.desired_state)?;
         strukt.serialize_field("health_check", &s.health_check_result)?;
+        strukt.serialize_field("health_check_config", &s.health_check_config())?;
         strukt.serialize_field("hooks", &s.hooks)?;
         strukt.serialize_field("initialized", &s.initialized)?;
         strukt.serialize_field("last_election_status", &s.last_election_status)?;
@@ -1148,6 +1537,8 @@ impl<'a> Serialize for ServiceProxy<'a> {
         strukt.serialize_field("needs_reconfiguration", &s.needs_reconfiguration)?;
         strukt.serialize_field("needs_reload", &s.needs_reload)?;
 
+        strukt.serialize_field("pending_update", &s.pending_update)?;
+
         let pkg_proxy = PkgProxy::new(&s.pkg);
         strukt.serialize_field("pkg", &pkg_proxy)?;
 
@@ -1156,6 +1547,13 @@ impl<'a> Serialize for ServiceProxy<'a> {
                                 .lock()
                                 .expect("Couldn't lock supervisor")
                                 .deref())?;
+        strukt.serialize_field("ready", &s.ready())?;
+        strukt.serialize_field("resource_limits", &s.resource_limits())?;
+        strukt.serialize_field("resource_usage", &s.resource_usage())?;
+        strukt.serialize_field("restart_policy", &s.restart_policy())?;
+        strukt.serialize_field("restarts", &s.restarts())?;
+        strukt.serialize_field("rolled_back_from",
+                               &s.rolled_back_from.as_ref().map(ToString::to_string))?;
         strukt.serialize_field("service_group", &s.service_group)?;
         strukt.serialize_field("spec_file", &s.spec_file)?;
         strukt.serialize_field("spec_ident", &s.spec_ident)?;
@@ -1165,11 +1563,25 @@ impl<'a> Serialize for ServiceProxy<'a> {
         strukt.serialize_field("sys", &s.sys)?;
         strukt.serialize_field("topology", &s.topology)?;
         strukt.serialize_field("update_strategy", &s.update_strategy)?;
+        strukt.serialize_field("update_window", &s.update_window)?;
         strukt.serialize_field("user_config_updated", &s.user_config_updated)?;
         strukt.end()
     }
 }
 
+/// A unified diff from the current content of `path` to `content`, or `None` if rendering
+/// `content` would leave the file as it is.
+fn rendered_diff(path: &Path, content: &str) -> Option<String> {
+    let current = fs::read_to_string(path).unwrap_or_default();
+    if current == content {
+        return None;
+    }
+    let path = path.display().to_string();
+    Some(TextDiff::from_lines(current.as_str(), content).unified_diff()
+                                                        .header(&path, &path)
+                                                        .to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1179,6 +1591,7 @@ mod tests {
     use habitat_common::types::ListenCtlAddr;
     use serde_json;
     use std::str::FromStr;
+    use tempfile::TempDir;
 
     fn initialize_test_service() -> Service {
         let listen_ctl_addr =
@@ -1208,11 +1621,18 @@ mod tests {
                                                                              spec, but it didn't");
         let asys = Arc::new(sys);
         let fscfg = FsCfg::new("/tmp");
+        let audit_log = AuditLog::open(&fscfg.audit_path, LogConfig::default(), false).unwrap();
         let afs = Arc::new(fscfg);
 
         let gs = Arc::new(RwLock::new(GatewayState::default()));
-        Service::new(asys, &install, spec, afs, Some("haha"), gs).expect("I wanted a service to \
-                                                                          load, but it didn't")
+        Service::new(asys,
+                     &install,
+                     spec,
+                     afs,
+                     Some("haha"),
+                     LogConfig::default(),
+                     audit_log,
+                     gs).expect("I wanted a service to load, but it didn't")
     }
 
     #[test]
@@ -1237,4 +1657,32 @@ mod tests {
                                                                    JSON but failed");
         assert_valid(&json_without_config, "http_gateway_services_schema.json");
     }
+
+    #[test]
+    fn elections_start_once_they_are_running_with_quorum() {
+        let mut service = initialize_test_service();
+
+        assert!(!service.election_started(ElectionStatus::None));
+        assert!(!service.election_started(ElectionStatus::ElectionNoQuorum));
+        assert!(service.election_started(ElectionStatus::ElectionInProgress));
+        assert!(!service.election_started(ElectionStatus::ElectionNoQuorum));
+        assert!(!service.election_started(ElectionStatus::ElectionInProgress));
+        assert!(!service.election_started(ElectionStatus::ElectionFinished));
+        assert!(service.election_started(ElectionStatus::ElectionInProgress));
+    }
+
+    #[test]
+    fn rendered_diff_only_reports_changed_content() {
+        let tmp = TempDir::new().expect("create temp dir");
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "port = 80\n").expect("write config");
+
+        assert!(rendered_diff(&path, "port = 80\n").is_none());
+        let diff = rendered_diff(&path, "port = 8080\n").expect("a diff");
+        assert!(diff.contains("-port = 80\n+port = 8080\n"));
+
+        let new_file = tmp.path().join("new.toml");
+        let diff = rendered_diff(&new_file, "port = 80\n").expect("a diff");
+        assert!(diff.contains("+port = 80\n"));
+    }
 }
@@ -0,0 +1,376 @@
+//! Runs a service's declarative health check on a separate thread,
+//! and wraps the whole execution in a future, just as `HookRunner`
+//! does for a `health-check` hook.
+//!
+//! HTTP and TCP probes are made directly by the Supervisor; `exec`
+//! probes run their command with the service's environment and as its
+//! user, as a hook would be. Every probe is abandoned once the check's
+//! timeout has passed, and reported as critical.
+
+use super::{health::HealthCheckResult,
+            metrics,
+            spawned_future::SpawnedFuture,
+            Pkg};
+use crate::error::SupError;
+use futures::{sync::oneshot,
+              IntoFuture};
+use habitat_common::outputln;
+use habitat_core::service::{HealthCheck,
+                            HealthCheckProbe,
+                            ServiceGroup};
+use std::{io::{self,
+               Read,
+               Write},
+          net::{TcpStream,
+                ToSocketAddrs},
+          thread,
+          time::{Duration,
+                 Instant}};
+
+static LOGKEY: &'static str = "HP";
+
+/// How often to check whether an `exec` probe's command has exited.
+const EXEC_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How much of an HTTP response is read; enough for any status line
+/// and the start of the body.
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+pub struct ProbeRunner {
+    check:         HealthCheck,
+    service_group: ServiceGroup,
+    pkg:           Pkg,
+    passwd:        Option<String>,
+}
+
+impl ProbeRunner {
+    pub fn new(check: HealthCheck,
+               service_group: ServiceGroup,
+               pkg: Pkg,
+               passwd: Option<String>)
+               -> ProbeRunner {
+        ProbeRunner { check,
+                      service_group,
+                      pkg,
+                      passwd }
+    }
+
+    fn run(&self, deadline: Instant) -> HealthCheckResult {
+        let result = match self.check.probe {
+            HealthCheckProbe::Http { ref host,
+                                     port,
+                                     ref path, } => {
+                http_get(host, port, path, deadline).map(|(status, body)| {
+                                                        check_response(&self.check, status, &body)
+                                                    })
+            }
+            HealthCheckProbe::Tcp { ref host, port } => {
+                connect(host, port, deadline).map(|_| Ok(HealthCheckResult::Ok))
+            }
+            HealthCheckProbe::Exec { ref command } => {
+                exec(command,
+                     &self.pkg,
+                     self.passwd.as_ref().map(String::as_str),
+                     deadline).map(Ok)
+            }
+        };
+        match result {
+            Ok(Ok(result)) => result,
+            Ok(Err(reason)) => {
+                outputln!(preamble self.service_group,
+                          "Health check {} failed: {}", self.check.probe, reason);
+                HealthCheckResult::Critical
+            }
+            Err(err) => {
+                let reason = match err.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+                        format!("timed out after {}s", self.check.timeout)
+                    }
+                    _ => err.to_string(),
+                };
+                outputln!(preamble self.service_group,
+                          "Health check {} failed: {}", self.check.probe, reason);
+                HealthCheckResult::Critical
+            }
+        }
+    }
+}
+
+impl IntoFuture for ProbeRunner {
+    type Error = SupError;
+    type Future = SpawnedFuture<Self::Item>;
+    type Item = (HealthCheckResult, Duration);
+
+    fn into_future(self) -> Self::Future {
+        let (tx, rx) = oneshot::channel();
+
+        let handle_result =
+            thread::Builder::new().name(format!("health-probe-{}", self.service_group))
+                                  .spawn(move || {
+                                      let _timer =
+                                          metrics::hook_timer(&self.service_group, "health-check");
+                                      let start = Instant::now();
+                                      let result = self.run(start + self.check.timeout());
+                                      let run_time = start.elapsed();
+                                      tx.send((result, run_time))
+                                        .expect("Couldn't send oneshot signal from ProbeRunner: \
+                                                 receiver went away");
+                                  });
+
+        match handle_result {
+            Ok(_handle) => rx.into(),
+            Err(io_err) => io_err.into(),
+        }
+    }
+}
+
+/// The time left before `deadline`, or a timeout error if it has
+/// passed.
+fn remaining(deadline: Instant) -> io::Result<Duration> {
+    let now = Instant::now();
+    if now < deadline {
+        Ok(deadline - now)
+    } else {
+        Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+    }
+}
+
+fn connect(host: &str, port: u16, deadline: Instant) -> io::Result<TcpStream> {
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound,
+                                      format!("{} did not resolve to any address", host));
+    for addr in format!("{}:{}", host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, remaining(deadline)?) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+/// Make an HTTP/1.0 GET request, so that the response is neither
+/// chunked nor kept alive, returning its status and (the start of) its
+/// body.
+fn http_get(host: &str, port: u16, path: &str, deadline: Instant) -> io::Result<(u16, String)> {
+    let mut stream = connect(host, port, deadline)?;
+    stream.set_write_timeout(Some(remaining(deadline)?))?;
+    write!(stream,
+           "GET {} HTTP/1.0\r\nHost: {}:{}\r\nUser-Agent: habitat-supervisor\r\nConnection: \
+            close\r\n\r\n",
+           path, host, port)?;
+
+    let mut response = Vec::new();
+    let mut buf = [0; 4096];
+    while response.len() < MAX_RESPONSE_BYTES {
+        stream.set_read_timeout(Some(remaining(deadline)?))?;
+        match stream.read(&mut buf)? {
+            0 => break,
+            n => response.extend_from_slice(&buf[..n]),
+        }
+    }
+    parse_response(&response).ok_or_else(|| {
+                                 io::Error::new(io::ErrorKind::InvalidData,
+                                                "malformed HTTP response")
+                             })
+}
+
+fn parse_response(response: &[u8]) -> Option<(u16, String)> {
+    let response = String::from_utf8_lossy(response);
+    let status = response.lines()
+                         .next()?
+                         .split_whitespace()
+                         .nth(1)?
+                         .parse()
+                         .ok()?;
+    let body = response.find("\r\n\r\n").map_or("", |i| &response[i + 4..]);
+    Some((status, body.to_string()))
+}
+
+/// Whether an HTTP response satisfies the check, or why not.
+fn check_response(check: &HealthCheck,
+                  status: u16,
+                  body: &str)
+                  -> Result<HealthCheckResult, String> {
+    let status_ok = match check.status {
+        Some(expected) => status == expected,
+        None => status >= 200 && status < 300,
+    };
+    if !status_ok {
+        return Err(format!("responded with status {}", status));
+    }
+    match check.body {
+        Some(ref expected) if !body.contains(expected.as_str()) => {
+            Err(format!("response did not contain '{}'", expected))
+        }
+        _ => Ok(HealthCheckResult::Ok),
+    }
+}
+
+/// Read an `exec` probe's exit code as a `health-check` hook's would
+/// be.
+fn exit_result(code: Option<i32>) -> HealthCheckResult {
+    match code {
+        Some(0) => HealthCheckResult::Ok,
+        Some(1) => HealthCheckResult::Warning,
+        Some(2) => HealthCheckResult::Critical,
+        _ => HealthCheckResult::Unknown,
+    }
+}
+
+#[cfg(unix)]
+fn exec(command: &str,
+        pkg: &Pkg,
+        _: Option<&str>,
+        deadline: Instant)
+        -> io::Result<HealthCheckResult> {
+    use habitat_core::os::users;
+    use std::{os::unix::process::CommandExt,
+              process::{Command,
+                        Stdio}};
+
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+       .arg(command)
+       .stdin(Stdio::null())
+       .stdout(Stdio::null())
+       .stderr(Stdio::null());
+    for (key, val) in pkg.env.iter() {
+        cmd.env(key, val);
+    }
+    if users::can_run_services_as_svc_user() {
+        let uid = users::get_uid_by_name(&pkg.svc_user).ok_or_else(|| {
+                                                           not_found("uid for user", &pkg.svc_user)
+                                                       })?;
+        let gid = users::get_gid_by_name(&pkg.svc_group).ok_or_else(|| {
+                                                            not_found("gid for group",
+                                                                      &pkg.svc_group)
+                                                        })?;
+        cmd.uid(uid).gid(gid);
+    }
+    // Run in our own process group, so that the whole group can be
+    // killed if the command takes too long.
+    cmd.before_exec(|| {
+           if unsafe { libc::setpgid(0, 0) } == 0 {
+               Ok(())
+           } else {
+               Err(io::Error::last_os_error())
+           }
+       });
+
+    let mut child = cmd.spawn()?;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(exit_result(status.code()));
+        }
+        if Instant::now() >= deadline {
+            unsafe {
+                libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+            }
+            child.wait()?;
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        }
+        thread::sleep(EXEC_POLL_INTERVAL);
+    }
+}
+
+#[cfg(unix)]
+fn not_found(what: &str, name: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound,
+                   format!("No {} '{}' could be found", what, name))
+}
+
+#[cfg(windows)]
+fn exec(command: &str,
+        pkg: &Pkg,
+        svc_encrypted_password: Option<&str>,
+        deadline: Instant)
+        -> io::Result<HealthCheckResult> {
+    use habitat_core::os::process::{self,
+                                    windows_child::Child};
+
+    let to_io_error = |err| io::Error::new(io::ErrorKind::Other, format!("{}", err));
+    let args = vec!["-NonInteractive", "-command", command];
+    let mut child = Child::spawn("pwsh.exe",
+                                 &args,
+                                 &pkg.env,
+                                 &pkg.svc_user,
+                                 svc_encrypted_password).map_err(to_io_error)?;
+    while process::is_alive(child.id()) {
+        if Instant::now() >= deadline {
+            child.kill()?;
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        }
+        thread::sleep(EXEC_POLL_INTERVAL);
+    }
+    let status = child.wait().map_err(to_io_error)?;
+    Ok(exit_result(status.code()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn deadline() -> Instant { Instant::now() + Duration::from_secs(5) }
+
+    fn http_check(status: Option<u16>, body: Option<&str>) -> HealthCheck {
+        let mut check = HealthCheck::new("http://localhost:8080/health".parse().unwrap());
+        check.status = status;
+        check.body = body.map(str::to_string);
+        check
+    }
+
+    #[test]
+    fn http_get_reads_status_and_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let n = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..n]).to_string();
+            stream.write_all(b"HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\nall good")
+                  .unwrap();
+            request
+        });
+
+        let (status, body) = http_get("127.0.0.1", port, "/health", deadline()).unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, "all good");
+        assert!(server.join()
+                      .unwrap()
+                      .starts_with("GET /health HTTP/1.0\r\n"));
+    }
+
+    #[test]
+    fn connect_fails_when_nothing_is_listening() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        assert!(connect("127.0.0.1", port, deadline()).is_err());
+    }
+
+    #[test]
+    fn parse_response_without_body() {
+        assert_eq!(parse_response(b"HTTP/1.1 204 No Content\r\n\r\n"),
+                   Some((204, String::new())));
+        assert_eq!(parse_response(b"garbage"), None);
+    }
+
+    #[test]
+    fn check_response_defaults_to_any_success() {
+        let check = http_check(None, None);
+        assert_eq!(check_response(&check, 204, ""), Ok(HealthCheckResult::Ok));
+        assert!(check_response(&check, 302, "").is_err());
+        assert!(check_response(&check, 500, "").is_err());
+    }
+
+    #[test]
+    fn check_response_with_expectations() {
+        let check = http_check(Some(503), Some("draining"));
+        assert_eq!(check_response(&check, 503, "status: draining"),
+                   Ok(HealthCheckResult::Ok));
+        assert!(check_response(&check, 503, "status: up").is_err());
+        assert!(check_response(&check, 200, "status: draining").is_err());
+    }
+}
@@ -0,0 +1,295 @@
+//! Decides whether, and when, a service whose process has exited is
+//! restarted.
+//!
+//! Restarts are spaced out with an exponential backoff: the first
+//! restart after a quiet period happens right away, and each further
+//! restart within the restart window waits twice as long as the one
+//! before it, up to a configurable cap. A service restarted more than
+//! `max_restarts` times within the window is "crash-looping"; it is
+//! still restarted, but only once per maximum backoff, until it stays
+//! up for a whole window.
+
+use super::RestartPolicy;
+use std::{collections::VecDeque,
+          time::{Duration,
+                 Instant}};
+
+/// How long to wait before the second restart within a window.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RestartConfig {
+    pub policy:       RestartPolicy,
+    /// How many restarts are allowed within `window` before the
+    /// service is considered to be crash-looping. Zero disables
+    /// crash-loop detection.
+    pub max_restarts: u32,
+    pub window:       Duration,
+    pub max_backoff:  Duration,
+}
+
+/// What became of a process exit reported to a `RestartTracker`.
+#[derive(Debug, PartialEq)]
+pub enum ExitOutcome {
+    /// The process will be restarted after `delay`. `crash_looping`
+    /// is `true` only for the exit that pushed the service into the
+    /// crash-looping state.
+    Restart {
+        delay:         Duration,
+        crash_looping: bool,
+    },
+    /// The restart policy says the process stays down.
+    StayDown,
+}
+
+#[derive(Debug, PartialEq)]
+enum State {
+    Running,
+    AwaitingRestart(Instant),
+    StayingDown,
+}
+
+#[derive(Debug)]
+pub struct RestartTracker {
+    config:        RestartConfig,
+    state:         State,
+    /// When each restart within the current window happened, oldest
+    /// first.
+    restarts:      VecDeque<Instant>,
+    crash_looping: bool,
+}
+
+impl RestartTracker {
+    pub fn new(config: RestartConfig) -> Self {
+        RestartTracker { config,
+                         state: State::Running,
+                         restarts: VecDeque::new(),
+                         crash_looping: false }
+    }
+
+    pub fn config(&self) -> RestartConfig { self.config }
+
+    pub fn crash_looping(&self) -> bool { self.crash_looping }
+
+    /// The number of restarts within the current window.
+    pub fn restarts(&self) -> u32 { self.restarts.len() as u32 }
+
+    /// Whether the exit of the current process has already been
+    /// reported through `process_exited`.
+    pub fn exit_handled(&self) -> bool { self.state != State::Running }
+
+    /// Whether a process that exited should be started now.
+    pub fn restart_due(&self, now: Instant) -> bool {
+        match self.state {
+            State::Running => true,
+            State::AwaitingRestart(at) => now >= at,
+            State::StayingDown => false,
+        }
+    }
+
+    /// Record that the process exited, with the given exit code if it
+    /// is known.
+    pub fn process_exited(&mut self, exit_code: Option<i32>, now: Instant) -> ExitOutcome {
+        self.expire_restarts(now);
+        let restart = match self.config.policy {
+            RestartPolicy::Always => true,
+            // An unknown exit code is treated as a failure.
+            RestartPolicy::OnFailure => exit_code != Some(0),
+            RestartPolicy::Never => false,
+        };
+        if !restart {
+            self.state = State::StayingDown;
+            return ExitOutcome::StayDown;
+        }
+
+        let count = self.restarts.len() as u32;
+        let entered_crash_loop = !self.crash_looping
+                                 && self.config.max_restarts > 0
+                                 && count >= self.config.max_restarts;
+        if entered_crash_loop {
+            self.crash_looping = true;
+        }
+        let delay = if self.crash_looping {
+            self.config.max_backoff
+        } else {
+            self.backoff(count)
+        };
+        self.state = State::AwaitingRestart(now + delay);
+        ExitOutcome::Restart { delay,
+                               crash_looping: entered_crash_loop }
+    }
+
+    /// Record that the process has been started again. Returns `true`
+    /// if this restarted a process that had exited, rather than, say,
+    /// reloaded a running one.
+    pub fn restarted(&mut self, now: Instant) -> bool {
+        if self.state != State::Running {
+            self.restarts.push_back(now);
+            self.state = State::Running;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record that the process was found running. Returns `true` if
+    /// this ends a crash loop, because the process has now stayed up
+    /// for a whole window.
+    pub fn process_running(&mut self, now: Instant) -> bool {
+        self.state = State::Running;
+        self.expire_restarts(now);
+        if self.crash_looping && self.restarts.is_empty() {
+            self.crash_looping = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn backoff(&self, previous_restarts: u32) -> Duration {
+        if previous_restarts == 0 {
+            return Duration::from_secs(0);
+        }
+        // Past 2^31 seconds every sensible cap has long been reached.
+        let factor = 1u32 << (previous_restarts - 1).min(31);
+        INITIAL_BACKOFF.checked_mul(factor)
+                       .map_or(self.config.max_backoff, |d| d.min(self.config.max_backoff))
+    }
+
+    fn expire_restarts(&mut self, now: Instant) {
+        while let Some(&oldest) = self.restarts.front() {
+            if now.duration_since(oldest) >= self.config.window {
+                self.restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(policy: RestartPolicy) -> RestartConfig {
+        RestartConfig { policy,
+                        max_restarts: 3,
+                        window: Duration::from_secs(300),
+                        max_backoff: Duration::from_secs(10) }
+    }
+
+    /// Exit and restart the process as soon as it is allowed to,
+    /// returning the outcome of the exit and the time of the restart.
+    fn crash(tracker: &mut RestartTracker, now: Instant) -> (ExitOutcome, Instant) {
+        let outcome = tracker.process_exited(Some(1), now);
+        let restart_at = match outcome {
+            ExitOutcome::Restart { delay, .. } => now + delay,
+            ExitOutcome::StayDown => panic!("Expected the process to be restarted"),
+        };
+        assert!(tracker.restart_due(restart_at));
+        assert!(tracker.restarted(restart_at));
+        (outcome, restart_at)
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let mut tracker = RestartTracker::new(RestartConfig { max_restarts: 0,
+                                                              ..config(RestartPolicy::Always) });
+        let mut now = Instant::now();
+        let mut delays = vec![];
+        for _ in 0..6 {
+            let (outcome, restarted_at) = crash(&mut tracker, now);
+            match outcome {
+                ExitOutcome::Restart { delay, .. } => delays.push(delay.as_secs()),
+                ExitOutcome::StayDown => unreachable!(),
+            }
+            now = restarted_at;
+        }
+        assert_eq!(delays, vec![0, 1, 2, 4, 8, 10]);
+        assert!(!tracker.crash_looping());
+    }
+
+    #[test]
+    fn restart_waits_for_the_backoff() {
+        let mut tracker = RestartTracker::new(config(RestartPolicy::Always));
+        let now = Instant::now();
+        let (_, now) = crash(&mut tracker, now);
+
+        tracker.process_exited(Some(1), now);
+        assert!(tracker.exit_handled());
+        assert!(!tracker.restart_due(now));
+        assert!(tracker.restart_due(now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn too_many_restarts_within_the_window_is_a_crash_loop() {
+        let mut tracker = RestartTracker::new(config(RestartPolicy::Always));
+        let mut now = Instant::now();
+        for _ in 0..3 {
+            let (outcome, restarted_at) = crash(&mut tracker, now);
+            match outcome {
+                ExitOutcome::Restart { crash_looping, .. } => assert!(!crash_looping),
+                ExitOutcome::StayDown => unreachable!(),
+            }
+            now = restarted_at;
+        }
+        assert!(!tracker.crash_looping());
+
+        assert_eq!(tracker.process_exited(Some(1), now),
+                   ExitOutcome::Restart { delay:         Duration::from_secs(10),
+                                          crash_looping: true, });
+        assert!(tracker.crash_looping());
+        assert_eq!(tracker.restarts(), 3);
+    }
+
+    #[test]
+    fn staying_up_for_a_window_ends_a_crash_loop() {
+        let mut tracker = RestartTracker::new(config(RestartPolicy::Always));
+        let mut now = Instant::now();
+        for _ in 0..4 {
+            now = crash(&mut tracker, now).1;
+        }
+        assert!(tracker.crash_looping());
+
+        assert!(!tracker.process_running(now + Duration::from_secs(60)));
+        assert!(tracker.crash_looping());
+        assert!(tracker.process_running(now + Duration::from_secs(300)));
+        assert!(!tracker.crash_looping());
+        assert_eq!(tracker.restarts(), 0);
+    }
+
+    #[test]
+    fn restarts_outside_the_window_are_forgotten() {
+        let mut tracker = RestartTracker::new(config(RestartPolicy::Always));
+        let mut now = Instant::now();
+        for _ in 0..3 {
+            now = crash(&mut tracker, now).1 + Duration::from_secs(301);
+        }
+        assert!(!tracker.crash_looping());
+        assert_eq!(tracker.process_exited(Some(1), now),
+                   ExitOutcome::Restart { delay:         Duration::from_secs(0),
+                                          crash_looping: false, });
+    }
+
+    #[test]
+    fn on_failure_leaves_a_clean_exit_down() {
+        let mut tracker = RestartTracker::new(config(RestartPolicy::OnFailure));
+        let now = Instant::now();
+        assert_eq!(tracker.process_exited(Some(0), now), ExitOutcome::StayDown);
+        assert!(!tracker.restart_due(now + Duration::from_secs(3600)));
+
+        let mut tracker = RestartTracker::new(config(RestartPolicy::OnFailure));
+        assert_eq!(tracker.process_exited(None, now),
+                   ExitOutcome::Restart { delay:         Duration::from_secs(0),
+                                          crash_looping: false, });
+    }
+
+    #[test]
+    fn never_leaves_any_exit_down() {
+        let mut tracker = RestartTracker::new(config(RestartPolicy::Never));
+        let now = Instant::now();
+        assert_eq!(tracker.process_exited(Some(1), now), ExitOutcome::StayDown);
+        assert!(tracker.exit_handled());
+        assert!(!tracker.restart_due(now));
+    }
+}
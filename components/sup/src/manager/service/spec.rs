@@ -1,4 +1,5 @@
 use super::{BindingMode,
+            RestartPolicy,
             Topology,
             UpdateStrategy};
 use crate::error::{Error,
@@ -8,13 +9,20 @@ use habitat_core::{fs::atomic_write,
                    package::{PackageIdent,
                              PackageInstall},
                    service::{ApplicationEnvironment,
+                             HealthCheck,
                              HealthCheckInterval,
-                             ServiceBind},
+                             ResourceLimits,
+                             ServiceBind,
+                             UpdateWindow},
                    url::DEFAULT_BLDR_URL,
                    util::{deserialize_using_from_str,
                           serialize_using_to_string},
                    ChannelIdent};
-use habitat_sup_protocol;
+use habitat_sup_protocol::{self,
+                           net::{self,
+                                 ErrCode,
+                                 NetErr,
+                                 NetResult}};
 use serde::{self,
             Deserialize};
 use std::{collections::HashSet,
@@ -33,6 +41,9 @@ use toml;
 static LOGKEY: &str = "SS";
 static DEFAULT_GROUP: &str = "default";
 const SPEC_FILE_EXT: &str = "spec";
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+const DEFAULT_RESTART_WINDOW_SECS: u64 = 300;
+const DEFAULT_MAX_RESTART_BACKOFF_SECS: u64 = 60;
 
 #[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum DesiredState {
@@ -88,12 +99,16 @@ pub fn deserialize_application_environment<'de, D>(
     }
 }
 
+fn invalid_payload(err: habitat_core::Error) -> NetErr {
+    net::err(ErrCode::InvalidPayload, err.to_string())
+}
+
 pub trait IntoServiceSpec {
-    fn into_spec(&self, spec: &mut ServiceSpec);
+    fn into_spec(&self, spec: &mut ServiceSpec) -> NetResult<()>;
 }
 
 impl IntoServiceSpec for habitat_sup_protocol::ctl::SvcLoad {
-    fn into_spec(&self, spec: &mut ServiceSpec) {
+    fn into_spec(&self, spec: &mut ServiceSpec) -> NetResult<()> {
         spec.ident = self.ident.clone().unwrap().into();
         spec.group = self.group
                          .clone()
@@ -113,6 +128,21 @@ impl IntoServiceSpec for habitat_sup_protocol::ctl::SvcLoad {
         if let Some(update_strategy) = self.update_strategy {
             spec.update_strategy = UpdateStrategy::from_i32(update_strategy).unwrap_or_default();
         }
+        if let Some(ref update_window) = self.update_window {
+            spec.update_window = Some(update_window.parse().map_err(invalid_payload)?);
+        }
+        if let Some(restart_policy) = self.restart_policy {
+            spec.restart_policy = RestartPolicy::from_i32(restart_policy).unwrap_or_default();
+        }
+        if let Some(max_restarts) = self.max_restarts {
+            spec.max_restarts = max_restarts;
+        }
+        if let Some(restart_window) = self.restart_window {
+            spec.restart_window = restart_window;
+        }
+        if let Some(max_restart_backoff) = self.max_restart_backoff {
+            spec.max_restart_backoff = max_restart_backoff;
+        }
         if let Some(ref list) = self.binds {
             spec.binds =
                 list.binds
@@ -135,6 +165,13 @@ impl IntoServiceSpec for habitat_sup_protocol::ctl::SvcLoad {
         if let Some(ref interval) = self.health_check_interval {
             spec.health_check_interval = interval.seconds.into()
         }
+        if let Some(ref limits) = self.resource_limits {
+            spec.resource_limits = limits.clone().into();
+        }
+        if let Some(ref check) = self.health_check {
+            spec.health_check = Some(check.parse().map_err(invalid_payload)?);
+        }
+        Ok(())
     }
 }
 
@@ -152,6 +189,15 @@ pub struct ServiceSpec {
     pub channel: ChannelIdent,
     pub topology: Topology,
     pub update_strategy: UpdateStrategy,
+    pub update_window: Option<UpdateWindow>,
+    pub restart_policy: RestartPolicy,
+    /// How many restarts within `restart_window` before the service is
+    /// considered to be crash-looping.
+    pub max_restarts: u32,
+    /// In seconds.
+    pub restart_window: u64,
+    /// The longest to wait between restarts, in seconds.
+    pub max_restart_backoff: u64,
     pub binds: Vec<ServiceBind>,
     pub binding_mode: BindingMode,
     pub config_from: Option<PathBuf>,
@@ -160,6 +206,10 @@ pub struct ServiceSpec {
     pub desired_state: DesiredState,
     pub health_check_interval: HealthCheckInterval,
     pub svc_encrypted_password: Option<String>,
+    /// Overrides the limits declared by the service's package.
+    pub resource_limits: ResourceLimits,
+    /// Overrides the health check declared by the service's package.
+    pub health_check: Option<HealthCheck>,
 }
 
 impl ServiceSpec {
@@ -267,12 +317,19 @@ impl Default for ServiceSpec {
                       channel:                 ChannelIdent::stable(),
                       topology:                Topology::default(),
                       update_strategy:         UpdateStrategy::default(),
+                      update_window:           None,
+                      restart_policy:          RestartPolicy::default(),
+                      max_restarts:            DEFAULT_MAX_RESTARTS,
+                      restart_window:          DEFAULT_RESTART_WINDOW_SECS,
+                      max_restart_backoff:     DEFAULT_MAX_RESTART_BACKOFF_SECS,
                       binds:                   Vec::default(),
                       binding_mode:            BindingMode::Strict,
                       config_from:             None,
                       desired_state:           DesiredState::default(),
                       health_check_interval:   HealthCheckInterval::default(),
-                      svc_encrypted_password:  None, }
+                      svc_encrypted_password:  None,
+                      resource_limits:         ResourceLimits::default(),
+                      health_check:            None, }
     }
 }
 
@@ -303,7 +360,8 @@ mod test {
 
     use habitat_core::{package::PackageIdent,
                        service::{ApplicationEnvironment,
-                                 HealthCheckInterval}};
+                                 HealthCheckInterval,
+                                 HealthCheckProbe}};
 
     use super::*;
     use crate::error::Error::*;
@@ -338,12 +396,24 @@ mod test {
             bldr_url = "http://example.com/depot"
             topology = "leader"
             update_strategy = "rolling"
+            update_window = "0 2 * * sat 4h"
+            restart_policy = "on-failure"
+            max_restarts = 3
+            restart_window = 120
+            max_restart_backoff = 30
             binds = ["cache:redis.cache@acmecorp", "db:postgres.app@acmecorp"]
             config_from = "/only/for/development"
 
             [health_check_interval]
             secs = 5
             nanos = 0
+
+            [resource_limits]
+            memory_bytes = 1073741824
+
+            [health_check]
+            probe = "tcp://localhost:6379"
+            timeout = 3
             "#;
         let spec = ServiceSpec::from_str(toml).unwrap();
 
@@ -355,6 +425,12 @@ mod test {
         assert_eq!(spec.bldr_url, String::from("http://example.com/depot"));
         assert_eq!(spec.topology, Topology::Leader);
         assert_eq!(spec.update_strategy, UpdateStrategy::Rolling);
+        assert_eq!(spec.update_window,
+                   Some(UpdateWindow::from_str("0 2 * * sat 4h").unwrap()));
+        assert_eq!(spec.restart_policy, RestartPolicy::OnFailure);
+        assert_eq!(spec.max_restarts, 3);
+        assert_eq!(spec.restart_window, 120);
+        assert_eq!(spec.max_restart_backoff, 30);
         assert_eq!(spec.binds,
                    vec![ServiceBind::from_str("cache:redis.cache@acmecorp").unwrap(),
                         ServiceBind::from_str("db:postgres.app@acmecorp").unwrap(),]);
@@ -362,6 +438,14 @@ mod test {
                    Some(PathBuf::from("/only/for/development")));
         assert_eq!(spec.health_check_interval,
                    HealthCheckInterval::from_str("5").unwrap());
+        assert_eq!(spec.resource_limits,
+                   ResourceLimits { memory_bytes: Some(1 << 30),
+                                    ..Default::default() });
+        let check = spec.health_check.unwrap();
+        assert_eq!(check.probe,
+                   HealthCheckProbe::Tcp { host: "localhost".to_string(),
+                                           port: 6379, });
+        assert_eq!(check.timeout, 3);
     }
 
     #[test]
@@ -418,6 +502,9 @@ mod test {
 
     #[test]
     fn service_spec_to_toml_string() {
+        let mut health_check =
+            HealthCheck::new(HealthCheckProbe::from_str("http://localhost:8080/health").unwrap());
+        health_check.status = Some(200);
         let spec =
             ServiceSpec { ident:
                               PackageIdent::from_str("origin/name/1.2.3/20170223130020").unwrap(),
@@ -428,6 +515,12 @@ mod test {
                           channel:                 ChannelIdent::unstable(),
                           topology:                Topology::Leader,
                           update_strategy:         UpdateStrategy::AtOnce,
+                          update_window:
+                              Some(UpdateWindow::from_str("0 2 * * sat 4h").unwrap()),
+                          restart_policy:          RestartPolicy::Never,
+                          max_restarts:            3,
+                          restart_window:          120,
+                          max_restart_backoff:     30,
                           binds:                   vec![
                 ServiceBind::from_str("cache:redis.cache@acmecorp").unwrap(),
                 ServiceBind::from_str("db:postgres.app@acmecorp").unwrap(),
@@ -436,7 +529,11 @@ mod test {
                           health_check_interval:   HealthCheckInterval::from_str("123").unwrap(),
                           config_from:             Some(PathBuf::from("/only/for/development")),
                           desired_state:           DesiredState::Down,
-                          svc_encrypted_password:  None, };
+                          svc_encrypted_password:  None,
+                          resource_limits:         ResourceLimits { cpu_millis:   Some(500),
+                                                                    memory_bytes: None,
+                                                                    pids:         Some(64), },
+                          health_check:            Some(health_check), };
         let toml = spec.to_toml_string().unwrap();
 
         assert!(toml.contains(r#"ident = "origin/name/1.2.3/20170223130020""#,));
@@ -446,6 +543,11 @@ mod test {
         assert!(toml.contains(r#"channel = "unstable""#));
         assert!(toml.contains(r#"topology = "leader""#));
         assert!(toml.contains(r#"update_strategy = "at-once""#));
+        assert!(toml.contains(r#"update_window = "0 2 * * sat 4h""#));
+        assert!(toml.contains(r#"restart_policy = "never""#));
+        assert!(toml.contains(r#"max_restarts = 3"#));
+        assert!(toml.contains(r#"restart_window = 120"#));
+        assert!(toml.contains(r#"max_restart_backoff = 30"#));
         assert!(toml.contains(r#""cache:redis.cache@acmecorp""#));
         assert!(toml.contains(r#""db:postgres.app@acmecorp""#));
         assert!(toml.contains(r#"desired_state = "down""#));
@@ -454,6 +556,13 @@ mod test {
         assert!(toml.contains(r#"[health_check_interval]"#));
         assert!(toml.contains(r#"secs = 123"#));
         assert!(toml.contains(r#"nanos = 0"#));
+        assert!(toml.contains(r#"[resource_limits]"#));
+        assert!(toml.contains(r#"cpu_millis = 500"#));
+        assert!(toml.contains(r#"[health_check]"#));
+        assert!(toml.contains(r#"probe = "http://localhost:8080/health""#));
+        assert!(toml.contains(r#"status = 200"#));
+        assert!(toml.contains(r#"pids = 64"#));
+        assert!(!toml.contains(r#"memory_bytes"#));
     }
 
     #[test]
@@ -484,12 +593,20 @@ mod test {
             bldr_url = "http://example.com/depot"
             topology = "leader"
             update_strategy = "rolling"
+            update_window = "0 2 * * sat 4h"
+            restart_policy = "on-failure"
+            max_restarts = 3
+            restart_window = 120
+            max_restart_backoff = 30
             binds = ["cache:redis.cache@acmecorp", "db:postgres.app@acmecorp"]
             config_from = "/only/for/development"
 
             [health_check_interval]
             secs = 5
             nanos = 0
+
+            [resource_limits]
+            memory_bytes = 1073741824
             "#;
         file_from_str(&path, toml);
         let spec = ServiceSpec::from_file(path).unwrap();
@@ -502,6 +619,12 @@ mod test {
         assert_eq!(spec.application_environment,
                    Some(ApplicationEnvironment::from_str("theinternet.preprod").unwrap(),));
         assert_eq!(spec.update_strategy, UpdateStrategy::Rolling);
+        assert_eq!(spec.update_window,
+                   Some(UpdateWindow::from_str("0 2 * * sat 4h").unwrap()));
+        assert_eq!(spec.restart_policy, RestartPolicy::OnFailure);
+        assert_eq!(spec.max_restarts, 3);
+        assert_eq!(spec.restart_window, 120);
+        assert_eq!(spec.max_restart_backoff, 30);
         assert_eq!(spec.binds,
                    vec![ServiceBind::from_str("cache:redis.cache@acmecorp").unwrap(),
                         ServiceBind::from_str("db:postgres.app@acmecorp").unwrap(),]);
@@ -514,6 +637,9 @@ mod test {
                    "Strict is the default mode, if nothing was previously specified.");
         assert_eq!(spec.health_check_interval,
                    HealthCheckInterval::from_str("5").unwrap());
+        assert_eq!(spec.resource_limits,
+                   ResourceLimits { memory_bytes: Some(1 << 30),
+                                    ..Default::default() });
     }
 
     #[test]
@@ -601,6 +727,12 @@ mod test {
                           channel:                 ChannelIdent::unstable(),
                           topology:                Topology::Leader,
                           update_strategy:         UpdateStrategy::AtOnce,
+                          update_window:
+                              Some(UpdateWindow::from_str("0 2 * * sat 4h").unwrap()),
+                          restart_policy:          RestartPolicy::Never,
+                          max_restarts:            3,
+                          restart_window:          120,
+                          max_restart_backoff:     30,
                           binds:                   vec![
                 ServiceBind::from_str("cache:redis.cache@acmecorp").unwrap(),
                 ServiceBind::from_str("db:postgres.app@acmecorp").unwrap(),
@@ -609,7 +741,10 @@ mod test {
                           health_check_interval:   HealthCheckInterval::from_str("23").unwrap(),
                           config_from:             Some(PathBuf::from("/only/for/development")),
                           desired_state:           DesiredState::Down,
-                          svc_encrypted_password:  None, };
+                          svc_encrypted_password:  None,
+                          resource_limits:         ResourceLimits { cpu_millis:   Some(500),
+                                                                    memory_bytes: None,
+                                                                    pids:         Some(64), }, };
         spec.to_file(&path).unwrap();
         let toml = string_from_file(path);
 
@@ -620,6 +755,11 @@ mod test {
         assert!(toml.contains(r#"channel = "unstable""#));
         assert!(toml.contains(r#"topology = "leader""#));
         assert!(toml.contains(r#"update_strategy = "at-once""#));
+        assert!(toml.contains(r#"update_window = "0 2 * * sat 4h""#));
+        assert!(toml.contains(r#"restart_policy = "never""#));
+        assert!(toml.contains(r#"max_restarts = 3"#));
+        assert!(toml.contains(r#"restart_window = 120"#));
+        assert!(toml.contains(r#"max_restart_backoff = 30"#));
         assert!(toml.contains(r#""cache:redis.cache@acmecorp""#));
         assert!(toml.contains(r#""db:postgres.app@acmecorp""#));
         assert!(toml.contains(r#"desired_state = "down""#));
@@ -628,6 +768,10 @@ mod test {
         assert!(toml.contains(r#"[health_check_interval]"#));
         assert!(toml.contains(r#"secs = 23"#));
         assert!(toml.contains(r#"nanos = 0"#));
+        assert!(toml.contains(r#"[resource_limits]"#));
+        assert!(toml.contains(r#"cpu_millis = 500"#));
+        assert!(toml.contains(r#"pids = 64"#));
+        assert!(!toml.contains(r#"memory_bytes"#));
     }
 
     #[test]
@@ -12,6 +12,7 @@ use crate::{error::{Error,
 use futures::{future,
               Future};
 use habitat_common::{outputln,
+                     service_log::LogConfig,
                      templating::package::Pkg,
                      types::UserInfo};
 #[cfg(unix)]
@@ -19,7 +20,8 @@ use habitat_core::os::users;
 use habitat_core::{fs,
                    os::process::{self,
                                  Pid},
-                   service::ServiceGroup};
+                   service::{ResourceLimits,
+                             ServiceGroup}};
 use habitat_launcher_client::LauncherCli;
 use serde::{ser::SerializeStruct,
             Serialize,
@@ -35,6 +37,21 @@ use time::Timespec;
 
 static LOGKEY: &'static str = "SV";
 
+/// Why a process exited on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExitReason {
+    Exited,
+    /// Killed for exceeding the service's memory limit.
+    OomKilled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct ProcessExit {
+    pub code:   i32,
+    pub reason: ExitReason,
+}
+
 #[derive(Debug)]
 pub struct Supervisor {
     pub preamble:      String,
@@ -42,6 +59,11 @@ pub struct Supervisor {
     pub state_entered: Timespec,
     pid:               Option<Pid>,
     pid_file:          PathBuf,
+    /// The process that was last found dead, until its exit code has
+    /// been asked for.
+    exited_pid:        Option<Pid>,
+    /// How the last process to exit on its own did so, if known.
+    last_exit:         Option<ProcessExit>,
 }
 
 impl Supervisor {
@@ -50,9 +72,14 @@ impl Supervisor {
                      state:         ProcessState::Down,
                      state_entered: time::get_time(),
                      pid:           None,
-                     pid_file:      fs::svc_pid_file(service_group.service()), }
+                     pid_file:      fs::svc_pid_file(service_group.service()),
+                     exited_pid:    None,
+                     last_exit:     None, }
     }
 
+    /// The PID of the service's process, while it's running.
+    pub fn pid(&self) -> Option<Pid> { self.pid }
+
     /// Check if the child process is running
     pub fn check_process(&mut self) -> bool {
         let pid = match self.pid {
@@ -73,12 +100,40 @@ impl Supervisor {
             }
         }
         debug!("Could not find a live process with pid {:?}", self.pid);
+        if pid.is_some() {
+            self.exited_pid = pid;
+        }
         self.change_state(ProcessState::Down);
         self.cleanup_pidfile();
         self.pid = None;
         false
     }
 
+    /// How the process most recently found dead by `check_process`
+    /// exited, if the Launcher still knows.
+    ///
+    /// Older Launchers forget a process as soon as it is reaped, and
+    /// so never know.
+    pub fn process_exit(&mut self, launcher: &LauncherCli) -> Option<ProcessExit> {
+        let exit = self.exited_pid
+                       .take()
+                       .and_then(|pid| launcher.process_exit(pid).ok())
+                       .and_then(|reply| reply)
+                       .map(|reply| {
+                           let reason = if reply.oom_killed {
+                               ExitReason::OomKilled
+                           } else {
+                               ExitReason::Exited
+                           };
+                           ProcessExit { code: reply.exit_code,
+                                         reason }
+                       });
+        if exit.is_some() {
+            self.last_exit = exit;
+        }
+        exit
+    }
+
     // NOTE: the &self argument is only used to get access to
     // self.preamble, and even then only for Linux :/
     #[cfg(unix)]
@@ -132,7 +187,9 @@ impl Supervisor {
                  pkg: &Pkg,
                  group: &ServiceGroup,
                  launcher: &LauncherCli,
-                 svc_password: Option<&str>)
+                 svc_password: Option<&str>,
+                 limits: ResourceLimits,
+                 log: LogConfig)
                  -> Result<()> {
         let user_info = self.user_info(&pkg)?;
         outputln!(preamble self.preamble,
@@ -159,11 +216,20 @@ impl Supervisor {
         // Launcher versions on Linux (and current Windows versions)
         // will use these, while newer versions will prefer the UID
         // and GID, ignoring the names.
+        if !limits.is_empty() && !launcher.enforces_resource_limits() {
+            outputln!(preamble self.preamble,
+                      "WARNING: Resource limits are set, but the Launcher cannot enforce them; \
+                       the service will run unconstrained");
+        }
+
         let pid = launcher.spawn(&group,
                                  &pkg.svc_run,
                                  user_info,
                                  svc_password, // Windows optional
-                                 (*pkg.env).clone())?;
+                                 (*pkg.env).clone(),
+                                 limits,
+                                 &fs::svc_logs_path(&pkg.name),
+                                 log)?;
         self.pid = Some(pid);
         self.create_pidfile()?;
         self.change_state(ProcessState::Up);
@@ -208,7 +274,9 @@ impl Supervisor {
                    pkg: &Pkg,
                    group: &ServiceGroup,
                    launcher: &LauncherCli,
-                   svc_password: Option<&str>)
+                   svc_password: Option<&str>,
+                   limits: ResourceLimits,
+                   log: LogConfig)
                    -> Result<()> {
         match self.pid {
             Some(pid) => {
@@ -226,7 +294,7 @@ impl Supervisor {
                     }
                 }
             }
-            None => self.start(pkg, group, launcher, svc_password),
+            None => self.start(pkg, group, launcher, svc_password, limits, log),
         }
     }
 
@@ -274,6 +342,7 @@ impl Serialize for Supervisor {
         where S: Serializer
     {
         let mut strukt = serializer.serialize_struct("supervisor", 5)?;
+        strukt.serialize_field("last_exit", &self.last_exit)?;
         strukt.serialize_field("pid", &self.pid)?;
         strukt.serialize_field("state", &self.state)?;
         strukt.serialize_field("state_entered", &self.state_entered.sec)?;
@@ -1,10 +1,15 @@
 use crate::{census::CensusRing,
+            event,
             manager::{periodic::Periodic,
-                      service::{Service,
+                      service::{HealthCheckResult,
+                                PendingUpdate,
+                                Service,
                                 Topology,
                                 UpdateStrategy}},
             util};
-use habitat_butterfly;
+use habitat_butterfly::{self,
+                        rumor::service::{CanaryRelease,
+                                         CanaryStatus}};
 use habitat_common::{outputln,
                      ui::UI};
 use habitat_core::{env as henv,
@@ -12,11 +17,15 @@ use habitat_core::{env as henv,
                    package::{PackageIdent,
                              PackageInstall,
                              PackageTarget},
-                   service::ServiceGroup,
+                   service::{ServiceGroup,
+                             UpdateWindow},
                    ChannelIdent};
+use habitat_sup_protocol::ctl::UpdaterStatus;
 use std::{cmp::{Ordering,
                 PartialOrd},
-          collections::HashMap,
+          collections::{HashMap,
+                        HashSet},
+          fmt,
           num::ParseIntError,
           result,
           str::FromStr,
@@ -35,13 +44,26 @@ static LOGKEY: &'static str = "SU";
 const PERIOD_BYPASS_CHECK_ENVVAR: &str = "HAB_UPDATE_STRATEGY_FREQUENCY_BYPASS_CHECK";
 lazy_static! {
     static ref MIN_ALLOWED_PERIOD: Duration = Duration::seconds(60);
+    static ref DEFAULT_CANARY_SOAK_PERIOD: Duration = Duration::minutes(5);
+    static ref DEFAULT_ROLLBACK_WINDOW: Duration = Duration::minutes(5);
+    /// How long a freshly-updated service may report a critical
+    /// health check before the update is considered to have failed.
+    static ref SUSTAINED_CRITICAL_PERIOD: Duration = Duration::seconds(60);
 }
 
+/// How many times a freshly-updated service may die before the update
+/// is considered to have failed.
+const MAX_UNEXPECTED_EXITS: u32 = 3;
+
 type UpdaterStateList = HashMap<ServiceGroup, UpdaterState>;
 
 enum UpdaterState {
     AtOnce(Receiver<PackageInstall>, Sender<()>),
     Rolling(RollingState),
+    /// Canary updates move through the same states as rolling updates,
+    /// but followers only start updating once the update leader's
+    /// release has passed its soak period.
+    Canary(RollingState),
 }
 
 enum RollingState {
@@ -65,11 +87,89 @@ enum FollowerState {
     Updating(Receiver<PackageInstall>, Sender<()>),
 }
 
+impl fmt::Display for UpdaterState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            UpdaterState::AtOnce(..) => write!(f, "at-once: polling"),
+            UpdaterState::Rolling(ref state) => write!(f, "rolling: {}", state),
+            UpdaterState::Canary(ref state) => write!(f, "canary: {}", state),
+        }
+    }
+}
+
+impl fmt::Display for RollingState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match *self {
+            RollingState::AwaitingElection => "awaiting election",
+            RollingState::InElection => "in election",
+            RollingState::Leader(LeaderState::Polling(..)) => "leader, polling",
+            RollingState::Leader(LeaderState::Waiting) => "leader, waiting for followers",
+            RollingState::Follower(FollowerState::Waiting) => "follower, waiting",
+            RollingState::Follower(FollowerState::Updating(..)) => "follower, updating",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A release installed by the update leader of a service group using
+/// the canary strategy, and how it has fared so far.
+struct Canary {
+    /// The release that was running before the canary was installed;
+    /// this is what we roll back to if the canary turns out to be bad.
+    previous:     PackageIdent,
+    release:      PackageIdent,
+    /// When the canary was first seen to be healthy. The soak period
+    /// is measured from this point.
+    soak_started: Option<SteadyTime>,
+    status:       CanaryStatus,
+    /// Set whenever `status` changes, so that the new verdict can be
+    /// gossiped to the rest of the service group.
+    regossip:     bool,
+}
+
+/// A recently-applied update that is still being watched for failure.
+struct UpdateWatch {
+    /// The release that was running before the update.
+    previous:       PackageIdent,
+    release:        PackageIdent,
+    /// When the updated service was first seen running `release`. The
+    /// rollback window is measured from this point.
+    started:        Option<SteadyTime>,
+    /// When the updated service started reporting a critical health
+    /// check, if it currently is.
+    critical_since: Option<SteadyTime>,
+}
+
+/// A service that was returned to an earlier release because an
+/// update failed.
+#[derive(Clone, Debug)]
+pub struct Rollback {
+    /// The release that failed.
+    pub from: PackageIdent,
+    /// The release the service was returned to. The service stays on
+    /// this release until a newer one is found.
+    pub to:   PackageIdent,
+}
+
 /// The ServiceUpdater is in charge of updating a Service when a more recent version of a package
 /// has been published to a depot or installed to the local package cache.
 /// To use an update strategy, the supervisor must be configured to watch a depot for new versions.
 pub struct ServiceUpdater {
-    states: UpdaterStateList,
+    states:    UpdaterStateList,
+    // Unlike `states`, which is reset whenever a service restarts,
+    // the following must survive the restart that an update (or a
+    // rollback) causes.
+    canaries:  HashMap<ServiceGroup, Canary>,
+    watches:   HashMap<ServiceGroup, UpdateWatch>,
+    /// Releases that failed on this Supervisor. The updater will never
+    /// move a service to one of these.
+    rejected:  HashSet<PackageIdent>,
+    /// Services that have been rolled back, keyed by the identifier of
+    /// their spec.
+    rollbacks: HashMap<PackageIdent, Rollback>,
+    /// Releases that have been installed but are being held until the
+    /// service's update window opens.
+    held:      HashMap<ServiceGroup, PackageIdent>,
 
     butterfly: habitat_butterfly::Server,
 }
@@ -77,9 +177,81 @@ pub struct ServiceUpdater {
 impl ServiceUpdater {
     pub fn new(butterfly: habitat_butterfly::Server) -> Self {
         ServiceUpdater { states: UpdaterStateList::default(),
+                         canaries: HashMap::default(),
+                         watches: HashMap::default(),
+                         rejected: HashSet::default(),
+                         rollbacks: HashMap::default(),
+                         held: HashMap::default(),
                          butterfly }
     }
 
+    /// The most recent rollback of a service, if it is still pinned
+    /// to the release it was rolled back to. Services are unpinned
+    /// once a newer release is found.
+    pub fn rollback(&self, spec_ident: &PackageIdent) -> Option<&Rollback> {
+        self.rollbacks.get(spec_ident)
+    }
+
+    /// The update being held for the given service until its update
+    /// window opens, if any.
+    pub fn pending_update(&self, service: &Service) -> Option<PendingUpdate> {
+        self.held.get(&service.service_group).map(|ident| {
+                                                 let until =
+                                                     service.update_window
+                                                            .as_ref()
+                                                            .and_then(UpdateWindow::next_opening)
+                                                            .map(|t| t.sec as u64);
+                                                 PendingUpdate { ident: ident.clone(),
+                                                                 until }
+                                             })
+    }
+
+    /// What the updater is doing for each service group it tracks,
+    /// for `hab sup debug`.
+    pub fn statuses(&self) -> Vec<UpdaterStatus> {
+        let mut service_groups: Vec<&ServiceGroup> = self.states
+                                                         .keys()
+                                                         .chain(self.held.keys())
+                                                         .chain(self.watches.keys())
+                                                         .collect();
+        service_groups.sort_by_key(|sg| sg.to_string());
+        service_groups.dedup();
+        service_groups.into_iter()
+                      .map(|sg| {
+                          UpdaterStatus { service_group:   sg.to_string(),
+                                          state:           self.states
+                                                               .get(sg)
+                                                               .map_or_else(|| "idle".to_string(),
+                                                                            ToString::to_string),
+                                          held_release:    self.held
+                                                               .get(sg)
+                                                               .map(ToString::to_string),
+                                          watched_release: self.watches
+                                                               .get(sg)
+                                                               .map(|w| w.release.to_string()), }
+                      })
+                      .collect()
+    }
+
+    /// The canary verdict to gossip for the given service group.
+    pub fn canary_release(&self, service_group: &ServiceGroup) -> Option<CanaryRelease> {
+        self.canaries
+            .get(service_group)
+            .map(|canary| CanaryRelease::new(&canary.release, canary.status))
+    }
+
+    /// Returns `true` (once) if the canary verdict for the given
+    /// service group has changed and should be gossiped again.
+    pub fn take_regossip(&mut self, service_group: &ServiceGroup) -> bool {
+        match self.canaries.get_mut(service_group) {
+            Some(canary) if canary.regossip => {
+                canary.regossip = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Register a new `Service` for updates. Returns `true` if the
     /// `ServiceUpdater` was modified (i.e., the given service has an
     /// `UpdateStrategy` that is not `None`).
@@ -87,11 +259,14 @@ impl ServiceUpdater {
         match service.update_strategy {
             UpdateStrategy::None => false,
             UpdateStrategy::AtOnce => {
+                let rejected = self.rejected.clone();
                 self.states
                     .entry(service.service_group.clone())
                     .or_insert_with(|| {
                         let (kill_tx, kill_rx) = channel();
-                        let rx = Worker::new(service).start(&service.service_group, None, kill_rx);
+                        let rx = Worker::new(service, rejected).start(&service.service_group,
+                                                                      None,
+                                                                      kill_rx);
                         UpdaterState::AtOnce(rx, kill_tx)
                     });
                 true
@@ -102,18 +277,25 @@ impl ServiceUpdater {
                     .or_insert(UpdaterState::Rolling(RollingState::AwaitingElection));
                 true
             }
+            UpdateStrategy::Canary => {
+                self.states
+                    .entry(service.service_group.clone())
+                    .or_insert(UpdaterState::Canary(RollingState::AwaitingElection));
+                true
+            }
         }
     }
 
     /// Remove a `Service` from updates, e.g. if the service was unloaded.
     pub fn remove(&mut self, service: &Service) {
+        self.held.remove(&service.service_group);
         match self.states.remove(&service.service_group) {
             Some(UpdaterState::AtOnce(_rx, kill_tx)) => {
                 if kill_tx.send(()).is_err() {
                     debug!("Tried to kill the updater thread but it's already dead.");
                 }
             }
-            Some(UpdaterState::Rolling(rs)) => {
+            Some(UpdaterState::Rolling(rs)) | Some(UpdaterState::Canary(rs)) => {
                 match rs {
                     RollingState::Leader(ls) => {
                         match ls {
@@ -163,6 +345,27 @@ impl ServiceUpdater {
                                      -> Option<PackageIdent> {
         debug!("Checking for updated package!");
 
+        if let Some(previous) = self.check_canary(service) {
+            return Some(previous);
+        }
+        if let Some(previous) = self.check_recent_update(service) {
+            return Some(previous);
+        }
+        if self.held.contains_key(&service.service_group) {
+            if !update_window_open(service) {
+                return None;
+            }
+            let release = self.held.remove(&service.service_group)?;
+            return Some(self.apply_update(service, release));
+        }
+
+        // Releases that failed here, or as a canary on another member
+        // of the group, are never updated to.
+        let mut rejected = self.rejected.clone();
+        if let Some(census_group) = census_ring.census_group_for(&service.service_group) {
+            rejected.extend(census_group.rejected_releases());
+        }
+
         // TODO (CM): can we do without this?
         let mut ident = None;
 
@@ -170,19 +373,21 @@ impl ServiceUpdater {
             Some(&mut UpdaterState::AtOnce(ref mut rx, ref mut kill_tx)) => {
                 match rx.try_recv() {
                     Ok(package) => {
-                        return Some(package.ident.clone());
+                        ident = Some(package.ident.clone());
                     }
                     Err(TryRecvError::Empty) => return None,
                     Err(TryRecvError::Disconnected) => {
                         debug!("Service Updater worker has died; restarting...");
                         let (ktx, krx) = channel();
-                        *rx = Worker::new(service).start(&service.service_group, None, krx);
+                        *rx =
+                            Worker::new(service, rejected).start(&service.service_group, None, krx);
                         *kill_tx = ktx;
                     }
                 }
             }
 
-            Some(&mut UpdaterState::Rolling(ref mut st @ RollingState::AwaitingElection)) => {
+            Some(&mut UpdaterState::Rolling(ref mut st @ RollingState::AwaitingElection))
+            | Some(&mut UpdaterState::Canary(ref mut st @ RollingState::AwaitingElection)) => {
                 if let Some(census_group) = census_ring.census_group_for(&service.service_group) {
                     if service.topology == Topology::Leader {
                         debug!("Rolling Update, determining proper suitability because we're in \
@@ -208,7 +413,8 @@ impl ServiceUpdater {
                     }
                 }
             }
-            Some(&mut UpdaterState::Rolling(ref mut st @ RollingState::InElection)) => {
+            Some(&mut UpdaterState::Rolling(ref mut st @ RollingState::InElection))
+            | Some(&mut UpdaterState::Canary(ref mut st @ RollingState::InElection)) => {
                 if let Some(census_group) = census_ring.census_group_for(&service.service_group) {
                     match (census_group.me(), census_group.update_leader()) {
                         (Some(me), Some(leader)) => {
@@ -227,7 +433,8 @@ impl ServiceUpdater {
                     }
                 }
             }
-            Some(&mut UpdaterState::Rolling(RollingState::Leader(ref mut state))) => {
+            Some(&mut UpdaterState::Rolling(RollingState::Leader(ref mut state)))
+            | Some(&mut UpdaterState::Canary(RollingState::Leader(ref mut state))) => {
                 match *state {
                     LeaderState::Polling(ref mut rx, ref mut kill_tx) => {
                         match rx.try_recv() {
@@ -239,7 +446,9 @@ impl ServiceUpdater {
                             Err(TryRecvError::Disconnected) => {
                                 debug!("Service Updater worker has died; restarting...");
                                 let (ktx, krx) = channel();
-                                *rx = Worker::new(service).start(&service.service_group, None, krx);
+                                *rx = Worker::new(service, rejected).start(&service.service_group,
+                                                                           None,
+                                                                           krx);
                                 *kill_tx = ktx;
                             }
                         }
@@ -253,10 +462,16 @@ impl ServiceUpdater {
                                     debug!("Update leader still waiting for followers...");
                                     return None;
                                 }
+                                if self.canaries
+                                       .get(&service.service_group)
+                                       .map_or(false, |c| c.status == CanaryStatus::Soaking)
+                                {
+                                    debug!("Update leader still soaking its canary...");
+                                    return None;
+                                }
                                 let (kill_tx, kill_rx) = channel();
-                                let rx = Worker::new(service).start(&service.service_group,
-                                                                    None,
-                                                                    kill_rx);
+                                let worker = Worker::new(service, rejected);
+                                let rx = worker.start(&service.service_group, None, kill_rx);
                                 *state = LeaderState::Polling(rx, kill_tx);
                             }
                             None => {
@@ -270,7 +485,8 @@ impl ServiceUpdater {
                     *state = LeaderState::Waiting;
                 }
             }
-            Some(&mut UpdaterState::Rolling(RollingState::Follower(ref mut state))) => {
+            Some(&mut UpdaterState::Rolling(RollingState::Follower(ref mut state)))
+            | Some(&mut UpdaterState::Canary(RollingState::Follower(ref mut state))) => {
                 match *state {
                     FollowerState::Waiting => {
                         match census_ring.census_group_for(&service.service_group) {
@@ -284,15 +500,23 @@ impl ServiceUpdater {
                                             debug!("We're not in an update");
                                             return None;
                                         }
+                                        if service.update_strategy == UpdateStrategy::Canary
+                                           && !canary_passed(leader.canary(), leader.pkg.as_ref())
+                                        {
+                                            debug!("We're in an update but the canary has not \
+                                                    passed");
+                                            return None;
+                                        }
                                         if leader.pkg != peer.pkg {
                                             debug!("We're in an update but it's not our turn");
                                             return None;
                                         }
                                         debug!("We're in an update and it's our turn");
                                         let (kill_tx, kill_rx) = channel();
-                                        let rx = Worker::new(service).start(&service.service_group,
-                                                                            leader.pkg.clone(),
-                                                                            kill_rx);
+                                        let worker = Worker::new(service, rejected);
+                                        let rx = worker.start(&service.service_group,
+                                                              leader.pkg.clone(),
+                                                              kill_rx);
                                         *state = FollowerState::Updating(rx, kill_tx);
                                     }
                                     _ => return None,
@@ -317,9 +541,8 @@ impl ServiceUpdater {
                                         let package =
                                             census_group.update_leader().unwrap().pkg.clone();
                                         let (ktx, krx) = channel();
-                                        *rx = Worker::new(service).start(&service.service_group,
-                                                                         package,
-                                                                         krx);
+                                        let worker = Worker::new(service, rejected);
+                                        *rx = worker.start(&service.service_group, package, krx);
                                         *kill_tx = ktx;
                                     }
                                 }
@@ -337,7 +560,167 @@ impl ServiceUpdater {
             }
             None => {}
         }
-        ident
+        let release = ident?;
+        event::service_update_detected(service, &release);
+        if !update_window_open(service) {
+            outputln!(preamble service.service_group,
+                      "Holding update to {} until the update window ({}) opens",
+                      release,
+                      service.update_window
+                             .as_ref()
+                             .map(ToString::to_string)
+                             .unwrap_or_default());
+            self.held.insert(service.service_group.clone(), release);
+            return None;
+        }
+        Some(self.apply_update(service, release))
+    }
+
+    /// Start tracking an update of `service` to `release`. Returns the
+    /// release to restart the service with.
+    fn apply_update(&mut self, service: &Service, release: PackageIdent) -> PackageIdent {
+        let is_canary_leader = match self.states.get(&service.service_group) {
+            Some(UpdaterState::Canary(RollingState::Leader(_))) => true,
+            _ => false,
+        };
+        if is_canary_leader {
+            outputln!(preamble service.service_group,
+                      "Installing {} as a canary release", release);
+            self.canaries.insert(service.service_group.clone(),
+                                 Canary { previous:     service.pkg.ident.clone(),
+                                          release:      release.clone(),
+                                          soak_started: None,
+                                          status:       CanaryStatus::Soaking,
+                                          regossip:     true, });
+        }
+        self.rollbacks.remove(&service.spec_ident);
+        self.watches.insert(service.service_group.clone(),
+                            UpdateWatch { previous:       service.pkg.ident.clone(),
+                                          release:        release.clone(),
+                                          started:        None,
+                                          critical_since: None, });
+        release
+    }
+
+    /// Track the health of a soaking canary release. Returns the
+    /// release to roll back to if the canary turned out to be
+    /// unhealthy.
+    fn check_canary(&mut self, service: &Service) -> Option<PackageIdent> {
+        let previous = {
+            let canary = self.canaries.get_mut(&service.service_group)?;
+            if canary.status != CanaryStatus::Soaking || service.pkg.ident != canary.release {
+                return None;
+            }
+
+            match service.health_check_result() {
+                HealthCheckResult::Ok => {
+                    let now = SteadyTime::now();
+                    let started = *canary.soak_started.get_or_insert(now);
+                    let soak_period: Duration = CanarySoakPeriod::configured_value().into();
+                    if now - started >= soak_period {
+                        outputln!(preamble service.service_group,
+                                  "Canary release {} passed its soak period", canary.release);
+                        canary.status = CanaryStatus::Passed;
+                        canary.regossip = true;
+                    }
+                    return None;
+                }
+                // The health check hasn't produced a result yet
+                HealthCheckResult::Unknown => return None,
+                HealthCheckResult::Warning | HealthCheckResult::Critical => canary.previous.clone(),
+            }
+        };
+        Some(self.roll_back(service, previous, "the canary release reported unhealthy"))
+    }
+
+    /// Watch a recently-updated service for a crash loop or a
+    /// sustained critical health check. Returns the release to roll
+    /// back to if the update failed.
+    fn check_recent_update(&mut self, service: &Service) -> Option<PackageIdent> {
+        let now = SteadyTime::now();
+        let (previous, reason) = {
+            let watch = self.watches.get_mut(&service.service_group)?;
+            if service.pkg.ident != watch.release {
+                // Still waiting for the service to restart
+                return None;
+            }
+            if watch.started.is_none() {
+                event::service_update_applied(service, &watch.previous);
+            }
+            let started = *watch.started.get_or_insert(now);
+
+            if service.unexpected_exits() >= MAX_UNEXPECTED_EXITS {
+                (watch.previous.clone(),
+                 format!("the service exited unexpectedly {} times",
+                         service.unexpected_exits()))
+            } else if service.health_check_result() == HealthCheckResult::Critical {
+                let critical_since = *watch.critical_since.get_or_insert(now);
+                if now - critical_since < *SUSTAINED_CRITICAL_PERIOD {
+                    return None;
+                }
+                (watch.previous.clone(),
+                 format!("the health check has been critical for {} seconds",
+                         (now - critical_since).num_seconds()))
+            } else {
+                watch.critical_since = None;
+                let window: Duration = UpdateRollbackWindow::configured_value().into();
+                if now - started >= window {
+                    debug!("Update of {} to {} has settled",
+                           service.service_group, watch.release);
+                    self.watches.remove(&service.service_group);
+                }
+                return None;
+            }
+        };
+        Some(self.roll_back(service, previous, &reason))
+    }
+
+    /// Give up on the release `service` is currently running: reject
+    /// it, and pin the service to `previous` until a newer release is
+    /// found. Returns the release to restart the service with.
+    fn roll_back(&mut self,
+                 service: &Service,
+                 previous: PackageIdent,
+                 reason: &str)
+                 -> PackageIdent {
+        let failed = service.pkg.ident.clone();
+        outputln!(preamble service.service_group,
+                  "Update to {} failed because {}; rolling back to {}",
+                  failed,
+                  reason,
+                  previous);
+        event::service_update_rolled_back(service, &previous, reason);
+
+        if let Some(canary) = self.canaries.get_mut(&service.service_group) {
+            if canary.release == failed {
+                canary.status = CanaryStatus::Failed;
+                canary.regossip = true;
+            }
+        }
+        self.watches.remove(&service.service_group);
+        self.rejected.insert(failed.clone());
+        self.rollbacks.insert(service.spec_ident.clone(),
+                              Rollback { from: failed,
+                                         to:   previous.clone(), });
+        previous
+    }
+}
+
+/// Whether updates may be applied to `service` right now.
+fn update_window_open(service: &Service) -> bool {
+    service.update_window
+           .as_ref()
+           .map_or(true, UpdateWindow::is_open)
+}
+
+/// Whether a canary verdict gossiped by an update leader allows the
+/// rest of the group to move to the leader's release.
+fn canary_passed(canary: Option<&CanaryRelease>, leader_pkg: Option<&PackageIdent>) -> bool {
+    match (canary, leader_pkg) {
+        (Some(canary), Some(pkg)) => {
+            canary.status == CanaryStatus::Passed && canary.pkg == pkg.to_string()
+        }
+        _ => false,
     }
 }
 
@@ -382,11 +765,66 @@ impl EnvConfig for ServiceUpdatePeriod {
     const ENVVAR: &'static str = "HAB_UPDATE_STRATEGY_FREQUENCY_MS";
 }
 
+/// How long, in milliseconds, a canary release must report healthy
+/// before the rest of its service group is updated to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CanarySoakPeriod(Duration);
+
+impl Default for CanarySoakPeriod {
+    fn default() -> Self { CanarySoakPeriod(*DEFAULT_CANARY_SOAK_PERIOD) }
+}
+
+impl FromStr for CanarySoakPeriod {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        let raw = s.parse::<u32>()?;
+        Ok(CanarySoakPeriod(Duration::milliseconds(i64::from(raw))))
+    }
+}
+
+impl Into<Duration> for CanarySoakPeriod {
+    fn into(self) -> Duration { self.0 }
+}
+
+impl EnvConfig for CanarySoakPeriod {
+    const ENVVAR: &'static str = "HAB_UPDATE_STRATEGY_CANARY_SOAK_MS";
+}
+
+/// How long, in milliseconds, a service is watched for failure after
+/// an update before the update is considered successful.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UpdateRollbackWindow(Duration);
+
+impl Default for UpdateRollbackWindow {
+    fn default() -> Self { UpdateRollbackWindow(*DEFAULT_ROLLBACK_WINDOW) }
+}
+
+impl FromStr for UpdateRollbackWindow {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        let raw = s.parse::<u32>()?;
+        Ok(UpdateRollbackWindow(Duration::milliseconds(i64::from(raw))))
+    }
+}
+
+impl Into<Duration> for UpdateRollbackWindow {
+    fn into(self) -> Duration { self.0 }
+}
+
+impl EnvConfig for UpdateRollbackWindow {
+    const ENVVAR: &'static str = "HAB_UPDATE_ROLLBACK_WINDOW_MS";
+}
+
 struct Worker {
     current:     PackageIdent,
     spec_ident:  PackageIdent,
     builder_url: String,
     channel:     ChannelIdent,
+    /// Releases that must never be installed, even if they are the
+    /// newest available.
+    rejected:    HashSet<PackageIdent>,
 }
 
 impl Periodic for Worker {
@@ -403,11 +841,12 @@ impl Periodic for Worker {
 }
 
 impl Worker {
-    fn new(service: &Service) -> Self {
-        Worker { current:     service.pkg.ident.clone(),
-                 spec_ident:  service.spec_ident.clone(),
+    fn new(service: &Service, rejected: HashSet<PackageIdent>) -> Self {
+        Worker { current: service.pkg.ident.clone(),
+                 spec_ident: service.spec_ident.clone(),
                  builder_url: service.bldr_url.clone(),
-                 channel:     service.channel.clone(), }
+                 channel: service.channel.clone(),
+                 rejected }
     }
 
     /// Start a new update worker.
@@ -529,7 +968,10 @@ impl Worker {
                                          &self.channel)
                 {
                     Ok(maybe_newer_package) => {
-                        if self.current < *maybe_newer_package.ident() {
+                        if self.rejected.contains(maybe_newer_package.ident()) {
+                            debug!("Package found {} has been rejected as a canary",
+                                   maybe_newer_package.ident());
+                        } else if self.current < *maybe_newer_package.ident() {
                             outputln!("Updating from {} to {}",
                                       self.current,
                                       maybe_newer_package.ident());
@@ -556,6 +998,7 @@ impl Worker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helpers::butterfly_server;
     use habitat_common::locked_env_var;
 
     #[test]
@@ -565,13 +1008,16 @@ mod tests {
 
     locked_env_var!(HAB_UPDATE_STRATEGY_FREQUENCY_MS, lock_period_var);
     locked_env_var!(HAB_UPDATE_STRATEGY_FREQUENCY_BYPASS_CHECK, lock_bypass_var);
+    locked_env_var!(HAB_UPDATE_STRATEGY_CANARY_SOAK_MS, lock_soak_var);
+    locked_env_var!(HAB_UPDATE_ROLLBACK_WINDOW_MS, lock_rollback_window_var);
 
     fn worker() -> Worker {
         Worker { current:     "core/testing/1.0.0/20181109125930".parse()
                                                                  .expect("Can't parse ident!"),
                  spec_ident:  "core/testing".parse().expect("Can't parse ident!"),
                  builder_url: String::from("https://bldr.habitat.sh"),
-                 channel:     ChannelIdent::stable(), }
+                 channel:     ChannelIdent::stable(),
+                 rejected:    HashSet::new(), }
     }
 
     #[test]
@@ -654,4 +1100,92 @@ mod tests {
         assert!(expected_period < *MIN_ALLOWED_PERIOD);
         assert_eq!(expected_period, worker.update_period());
     }
+
+    #[test]
+    fn canary_soak_period_defaults_properly() {
+        let soak = lock_soak_var();
+        soak.unset();
+        assert_eq!(CanarySoakPeriod::configured_value(),
+                   CanarySoakPeriod(*DEFAULT_CANARY_SOAK_PERIOD));
+    }
+
+    #[test]
+    fn canary_soak_period_can_be_overridden_by_env_var() {
+        let soak = lock_soak_var();
+        soak.set("30000");
+        assert_eq!(CanarySoakPeriod::configured_value(),
+                   CanarySoakPeriod(Duration::milliseconds(30_000)));
+    }
+
+    #[test]
+    fn canary_soak_period_must_not_be_negative() {
+        assert!(CanarySoakPeriod::from_str("-1").is_err());
+        assert!(CanarySoakPeriod::from_str("0").is_ok());
+    }
+
+    #[test]
+    fn update_rollback_window_defaults_properly() {
+        let window = lock_rollback_window_var();
+        window.unset();
+        assert_eq!(UpdateRollbackWindow::configured_value(),
+                   UpdateRollbackWindow(*DEFAULT_ROLLBACK_WINDOW));
+    }
+
+    #[test]
+    fn update_rollback_window_can_be_overridden_by_env_var() {
+        let window = lock_rollback_window_var();
+        window.set("600000");
+        assert_eq!(UpdateRollbackWindow::configured_value(),
+                   UpdateRollbackWindow(Duration::minutes(10)));
+    }
+
+    #[test]
+    fn followers_only_proceed_once_the_leaders_canary_has_passed() {
+        let release: PackageIdent = "core/testing/1.0.1/20181109130000".parse().unwrap();
+        let other: PackageIdent = "core/testing/1.0.2/20181110130000".parse().unwrap();
+
+        assert!(!canary_passed(None, Some(&release)));
+        assert!(!canary_passed(Some(&CanaryRelease::new(&release, CanaryStatus::Soaking)),
+                               Some(&release)));
+        assert!(!canary_passed(Some(&CanaryRelease::new(&release, CanaryStatus::Failed)),
+                               Some(&release)));
+        assert!(!canary_passed(Some(&CanaryRelease::new(&other, CanaryStatus::Passed)),
+                               Some(&release)));
+        assert!(canary_passed(Some(&CanaryRelease::new(&release, CanaryStatus::Passed)),
+                              Some(&release)));
+    }
+    #[test]
+    fn statuses_cover_every_tracked_service_group_once() {
+        let mut updater = ServiceUpdater::new(butterfly_server());
+        let redis: ServiceGroup = "redis.default".parse().unwrap();
+        let nginx: ServiceGroup = "nginx.default".parse().unwrap();
+        let release: PackageIdent = "core/redis/5.0.0/20190101000000".parse().unwrap();
+        updater.states.insert(redis.clone(),
+                              UpdaterState::Rolling(RollingState::AwaitingElection));
+        updater.held.insert(redis, release.clone());
+        updater.watches.insert(nginx,
+                               UpdateWatch { previous:       release.clone(),
+                                             release:        release.clone(),
+                                             started:        None,
+                                             critical_since: None, });
+
+        let statuses = updater.statuses();
+
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].service_group, "nginx.default");
+        assert_eq!(statuses[0].state, "idle");
+        assert_eq!(statuses[0].held_release, None);
+        assert_eq!(statuses[0].watched_release, Some(release.to_string()));
+        assert_eq!(statuses[1].service_group, "redis.default");
+        assert_eq!(statuses[1].state, "rolling: awaiting election");
+        assert_eq!(statuses[1].held_release, Some(release.to_string()));
+        assert_eq!(statuses[1].watched_release, None);
+    }
+
+    #[test]
+    fn an_updater_tracking_nothing_has_no_statuses() {
+        let updater = ServiceUpdater::new(butterfly_server());
+
+        assert!(updater.statuses().is_empty());
+    }
 }
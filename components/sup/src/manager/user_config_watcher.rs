@@ -43,6 +43,8 @@ impl Serviceable for Service {
 // WorkerState contains the channels the worker uses to communicate
 // with the Watcher.
 struct WorkerState {
+    // The file the worker watches.
+    path: PathBuf,
     // This receiver is used by the watcher to be notified when a
     // worker has events.  The channel is a SyncChannel with buffer
     // size 1, as we are only interested in the fact that there were
@@ -101,11 +103,12 @@ impl UserConfigWatcher {
             let (running_tx, running_rx) = channel();
             let (watching_tx, watching_rx) = sync_channel(1);
 
-            Worker::run(user_toml_path, events_tx, running_rx, watching_tx)?;
+            Worker::run(user_toml_path.clone(), events_tx, running_rx, watching_tx)?;
 
             outputln!(preamble service.service_group(), "Watching {}", USER_CONFIG_FILE);
 
-            let state = WorkerState { have_events:      events_rx,
+            let state = WorkerState { path:             user_toml_path,
+                                      have_events:      events_rx,
                                       stop_running:     running_tx,
                                       started_watching: watching_rx, };
 
@@ -131,6 +134,19 @@ impl UserConfigWatcher {
         }
     }
 
+    /// The file watched for each service, sorted by service name.
+    pub fn watched_paths(&self) -> Vec<(ServiceName, PathBuf)> {
+        let mut paths: Vec<(ServiceName, PathBuf)> =
+            self.states
+                .lock()
+                .expect("states lock was poisoned")
+                .iter()
+                .map(|(name, state)| (name.clone(), state.path.clone()))
+                .collect();
+        paths.sort();
+        paths
+    }
+
     /// Checks whether the watcher for the specified service has observed any events.
     ///
     /// This also consumes the events.
@@ -270,6 +286,17 @@ mod tests {
         assert!(!ucm.have_events_for(&service));
     }
 
+    #[test]
+    fn watched_paths_name_each_services_config_file() {
+        let service = TestService::default();
+        let mut ucm = UserConfigWatcher::new();
+        ucm.add(&service).expect("adding service");
+
+        assert_eq!(ucm.watched_paths(),
+                   vec![(service.name.clone(),
+                         service.user_config_path().get_path().join(USER_CONFIG_FILE))]);
+    }
+
     #[test]
     fn events_present_after_adding_config() {
         let service = TestService::default();
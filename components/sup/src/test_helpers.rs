@@ -1,8 +1,13 @@
 use std::{fs::File,
           io::Read,
+          net::SocketAddr,
           path::PathBuf};
 
 use crate::json;
+use habitat_butterfly::{member::Member,
+                        server::{Server,
+                                 Suitability},
+                        trace::Trace};
 use serde_json;
 use valico::json_schema;
 
@@ -61,3 +66,23 @@ pub fn validate_string(input: &str, schema: &str) -> json_schema::ValidationStat
         serde_json::from_str(input).expect("Could not parse input as JSON");
     schema.validate(&input_json)
 }
+
+#[derive(Debug)]
+struct ZeroSuitability;
+impl Suitability for ZeroSuitability {
+    fn get(&self, _service_group: &str) -> u64 { 0 }
+}
+
+/// A gossip server for tests that need one to construct the state
+/// around it, but never start it.
+pub fn butterfly_server() -> Server {
+    let addr: SocketAddr = "127.0.0.1:0".parse().expect("Can't parse SocketAddr");
+    Server::new(addr,
+                addr,
+                Member::default(),
+                Trace::default(),
+                None,
+                None,
+                None,
+                Box::new(ZeroSuitability)).expect("Could not create a butterfly server")
+}
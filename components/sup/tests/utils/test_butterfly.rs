@@ -27,9 +27,10 @@ impl Client {
         let gossip_addr =
             format!("127.0.0.1:{}", port).parse::<SocketAddr>()
                                          .expect("Could not parse Butterfly gossip address!");
-        let c = ButterflyClient::new(&gossip_addr.to_string(), None).expect("Could not create \
-                                                                             Butterfly Client \
-                                                                             for test!");
+        let c =
+            ButterflyClient::new(&gossip_addr.to_string(), None, None).expect("Could not create \
+                                                                               Butterfly Client \
+                                                                               for test!");
         Client { butterfly_client: c,
                  package_name:     package_name.to_string(),
                  service_group:    service_group.to_string(), }
@@ -58,7 +59,8 @@ impl Client {
                                                    None).unwrap(),
                                  incarnation,
                                  config,
-                                 false)
+                                 false,
+                                 None)
             .expect("Cannot send the service configuration");
     }
 